@@ -0,0 +1,124 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+  jobs::KubeJob,
+  models::{RowHealth, TableRow},
+  nodes::KubeNode,
+  pods::KubePod,
+};
+
+/// pod statuses that are alerted
+const POD_FAILURES: [&str; 2] = ["CrashLoopBackOff", "ImagePullBackOff"];
+
+/// problems of pods, jobs and nodes noticed while polling, an alert is sent when one starts. The
+/// problems found on the first poll of a kind are taken as known
+#[derive(Default)]
+pub struct Alerts {
+  /// send alerts as desktop notifications
+  pub desktop: bool,
+  /// URL alerts are posted to as Slack compatible JSON
+  pub webhook: Option<String>,
+  /// current problems by kind
+  problems: BTreeMap<&'static str, BTreeSet<String>>,
+}
+
+impl Alerts {
+  pub fn is_enabled(&self) -> bool {
+    self.desktop || self.webhook.is_some()
+  }
+
+  /// record the current problems of a kind, returns the ones that weren't there on the last poll
+  pub fn update(&mut self, kind: &'static str, problems: BTreeSet<String>) -> Vec<String> {
+    if !self.is_enabled() {
+      return vec![];
+    }
+    match self.problems.insert(kind, problems) {
+      Some(previous) => self.problems[kind].difference(&previous).cloned().collect(),
+      None => vec![],
+    }
+  }
+
+  pub fn update_pods(&mut self, pods: &[KubePod]) -> Vec<String> {
+    let problems = pods
+      .iter()
+      .filter(|pod| POD_FAILURES.contains(&pod.status.as_str()))
+      .map(|pod| format!("Pod {}/{} is in {}", pod.namespace, pod.name, pod.status))
+      .collect();
+    self.update("pods", problems)
+  }
+
+  pub fn update_jobs(&mut self, jobs: &[KubeJob]) -> Vec<String> {
+    let problems = jobs
+      .iter()
+      .filter(|job| job.status_health() == RowHealth::Failed)
+      .map(|job| format!("Job {}/{} failed", job.namespace, job.name))
+      .collect();
+    self.update("jobs", problems)
+  }
+
+  pub fn update_nodes(&mut self, nodes: &[KubeNode]) -> Vec<String> {
+    let problems = nodes
+      .iter()
+      .flat_map(|node| {
+        let not_ready =
+          (node.status == "Not Ready").then(|| format!("Node {} is not ready", node.name));
+        node
+          .pressures()
+          .into_iter()
+          .map(move |pressure| format!("Node {} has {}", node.name, pressure))
+          .chain(not_ready)
+      })
+      .collect();
+    self.update("nodes", problems)
+  }
+}
+
+/// show a desktop notification without waiting for the notification daemon
+pub fn notify_desktop(message: String) {
+  std::thread::spawn(move || {
+    let _ = notify_rust::Notification::new()
+      .summary("kdash")
+      .body(&message)
+      .show();
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_alerts() {
+    let pod = |name: &str, status: &str| {
+      let mut pod = KubePod::default();
+      pod.namespace = "web".into();
+      pod.name = name.into();
+      pod.status = status.into();
+      pod
+    };
+    let mut alerts = Alerts::default();
+    assert!(alerts
+      .update_pods(&[pod("api-0", "CrashLoopBackOff")])
+      .is_empty());
+
+    alerts.webhook = Some("https://hooks.example.com/kdash".into());
+    // known on the first poll
+    assert!(alerts
+      .update_pods(&[pod("api-0", "CrashLoopBackOff")])
+      .is_empty());
+    assert_eq!(
+      alerts.update_pods(&[
+        pod("api-0", "CrashLoopBackOff"),
+        pod("api-1", "ImagePullBackOff"),
+        pod("api-2", "Running"),
+      ]),
+      vec!["Pod web/api-1 is in ImagePullBackOff"]
+    );
+    // a pod recovering and failing again is notified again
+    assert!(alerts.update_pods(&[pod("api-0", "Running")]).is_empty());
+    assert_eq!(
+      alerts.update_pods(&[pod("api-0", "CrashLoopBackOff")]),
+      vec!["Pod web/api-0 is in CrashLoopBackOff"]
+    );
+  }
+}
@@ -0,0 +1,326 @@
+use super::{models::StatefulList, utils::fuzzy_score, ActiveBlock};
+
+/// what a palette command does, commands taking an argument get it passed when run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandKind {
+  Resource(ActiveBlock),
+  Namespace,
+  Context,
+  Contexts,
+  Utilization,
+  MetricsHistory,
+  Fleet,
+  Help,
+  Quit,
+}
+
+/// command name, aliases and what it does, the name is what gets suggested
+const COMMANDS: [(&str, &[&str], CommandKind); 27] = [
+  (
+    "pods",
+    &["po", "pod"],
+    CommandKind::Resource(ActiveBlock::Pods),
+  ),
+  (
+    "services",
+    &["svc", "service"],
+    CommandKind::Resource(ActiveBlock::Services),
+  ),
+  (
+    "nodes",
+    &["no", "node"],
+    CommandKind::Resource(ActiveBlock::Nodes),
+  ),
+  (
+    "configmaps",
+    &["cm", "configmap"],
+    CommandKind::Resource(ActiveBlock::ConfigMaps),
+  ),
+  (
+    "statefulsets",
+    &["sts", "statefulset"],
+    CommandKind::Resource(ActiveBlock::StatefulSets),
+  ),
+  (
+    "replicasets",
+    &["rs", "replicaset"],
+    CommandKind::Resource(ActiveBlock::ReplicaSets),
+  ),
+  (
+    "deployments",
+    &["deploy", "deployment"],
+    CommandKind::Resource(ActiveBlock::Deployments),
+  ),
+  ("jobs", &["job"], CommandKind::Resource(ActiveBlock::Jobs)),
+  (
+    "daemonsets",
+    &["ds", "daemonset"],
+    CommandKind::Resource(ActiveBlock::DaemonSets),
+  ),
+  (
+    "cronjobs",
+    &["cj", "cronjob"],
+    CommandKind::Resource(ActiveBlock::CronJobs),
+  ),
+  (
+    "secrets",
+    &["secret"],
+    CommandKind::Resource(ActiveBlock::Secrets),
+  ),
+  (
+    "replicationcontrollers",
+    &["rc"],
+    CommandKind::Resource(ActiveBlock::RplCtrl),
+  ),
+  (
+    "storageclasses",
+    &["sc", "storageclass"],
+    CommandKind::Resource(ActiveBlock::StorageClasses),
+  ),
+  (
+    "roles",
+    &["role"],
+    CommandKind::Resource(ActiveBlock::Roles),
+  ),
+  (
+    "rolebindings",
+    &["rb", "rolebinding"],
+    CommandKind::Resource(ActiveBlock::RoleBindings),
+  ),
+  (
+    "clusterroles",
+    &["cr", "clusterrole"],
+    CommandKind::Resource(ActiveBlock::ClusterRoles),
+  ),
+  (
+    "clusterrolebindings",
+    &["crb", "clusterrolebinding"],
+    CommandKind::Resource(ActiveBlock::ClusterRoleBinding),
+  ),
+  (
+    "helmreleases",
+    &["helm", "releases"],
+    CommandKind::Resource(ActiveBlock::HelmReleases),
+  ),
+  ("namespace", &["ns"], CommandKind::Namespace),
+  ("context", &["ctx"], CommandKind::Context),
+  ("contexts", &["ctxs"], CommandKind::Contexts),
+  ("utilization", &["util"], CommandKind::Utilization),
+  ("metrics", &["history"], CommandKind::MetricsHistory),
+  ("fleet", &["clusters"], CommandKind::Fleet),
+  ("help", &["h"], CommandKind::Help),
+  ("quit", &["q"], CommandKind::Quit),
+  ("all", &[], CommandKind::Namespace),
+];
+
+const MAX_SUGGESTIONS: usize = 10;
+
+/// k9s style `:` command input with fuzzy completion of commands, namespaces and contexts
+pub struct CommandPalette {
+  pub input: String,
+  pub suggestions: StatefulList<String>,
+}
+
+impl CommandPalette {
+  pub fn new() -> Self {
+    CommandPalette {
+      input: String::new(),
+      suggestions: StatefulList::with_items(vec![]),
+    }
+  }
+
+  /// refresh the suggestions for the current input, namespaces and contexts are completed as
+  /// arguments of the `ns` and `ctx` commands
+  pub fn update_suggestions(&mut self, namespaces: &[String], contexts: &[String]) {
+    let mut scored: Vec<(i64, String)> = match self.input.split_once(' ') {
+      Some((word, arg)) => {
+        let candidates = match find_command(word) {
+          Some(CommandKind::Namespace) => namespaces,
+          Some(CommandKind::Context) => contexts,
+          _ => &[],
+        };
+        candidates
+          .iter()
+          .filter_map(|c| fuzzy_score(arg.trim(), c).map(|s| (s, format!("{} {}", word, c))))
+          .collect()
+      }
+      None => COMMANDS
+        .iter()
+        .filter(|(name, ..)| *name != "all")
+        .filter_map(|(name, aliases, _)| {
+          std::iter::once(name)
+            .chain(aliases.iter())
+            .filter_map(|n| fuzzy_score(&self.input, n))
+            .max()
+            .map(|s| (s, name.to_string()))
+        })
+        .collect(),
+    };
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    scored.truncate(MAX_SUGGESTIONS);
+    self.suggestions = StatefulList::with_items(scored.into_iter().map(|(_, s)| s).collect());
+  }
+
+  /// replace the input with the selected suggestion
+  pub fn complete(&mut self) {
+    if let Some(suggestion) = self.selected_suggestion() {
+      self.input = suggestion;
+    }
+  }
+
+  /// the command for the input, falling back to the selected suggestion for partial input like
+  /// `deplo` or `ctx pr`
+  pub fn command(&self) -> Option<(CommandKind, Option<String>)> {
+    let typed = parse_command(&self.input);
+    let complete = match &typed {
+      Some((_, None)) => true,
+      Some(_) => {
+        self.suggestions.items.is_empty()
+          || self
+            .suggestions
+            .items
+            .iter()
+            .any(|s| s == self.input.trim())
+      }
+      None => false,
+    };
+    if complete {
+      return typed;
+    }
+    self
+      .selected_suggestion()
+      .and_then(|suggestion| parse_command(&suggestion))
+      .or(typed)
+  }
+
+  fn selected_suggestion(&self) -> Option<String> {
+    self
+      .suggestions
+      .state
+      .selected()
+      .and_then(|i| self.suggestions.items.get(i).cloned())
+  }
+}
+
+fn find_command(word: &str) -> Option<CommandKind> {
+  let word = word.to_lowercase();
+  COMMANDS
+    .iter()
+    .find(|(name, aliases, _)| *name == word || aliases.contains(&word.as_str()))
+    .map(|(_, _, kind)| *kind)
+}
+
+/// the resource table for a name or alias like `deployments` or `deploy`
+pub fn find_resource(word: &str) -> Option<ActiveBlock> {
+  match find_command(word)? {
+    CommandKind::Resource(block) => Some(block),
+    _ => None,
+  }
+}
+
+/// parse input like `deploy`, `ns kube-system` or `ctx prod`, `all` selects all namespaces
+pub fn parse_command(input: &str) -> Option<(CommandKind, Option<String>)> {
+  let input = input.trim();
+  let (word, arg) = match input.split_once(' ') {
+    Some((word, arg)) => (word, Some(arg.trim().to_owned())),
+    None => (input, None),
+  };
+  let kind = find_command(word)?;
+  match (kind, arg) {
+    (CommandKind::Namespace, Some(ns)) if ns == "all" => Some((kind, None)),
+    (CommandKind::Namespace, arg) => Some((kind, arg)),
+    (CommandKind::Context, Some(ctx)) => Some((kind, Some(ctx))),
+    (CommandKind::Context, None) => None,
+    (_, None) => Some((kind, None)),
+    (_, Some(_)) => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_find_resource() {
+    assert_eq!(find_resource("Deploy"), Some(ActiveBlock::Deployments));
+    assert_eq!(find_resource("secrets"), Some(ActiveBlock::Secrets));
+    assert_eq!(find_resource("ns"), None);
+    assert_eq!(find_resource("foo"), None);
+  }
+
+  #[test]
+  fn test_parse_command() {
+    assert_eq!(
+      parse_command("deploy"),
+      Some((CommandKind::Resource(ActiveBlock::Deployments), None))
+    );
+    assert_eq!(
+      parse_command(" PO "),
+      Some((CommandKind::Resource(ActiveBlock::Pods), None))
+    );
+    assert_eq!(
+      parse_command("ns kube-system"),
+      Some((CommandKind::Namespace, Some("kube-system".into())))
+    );
+    assert_eq!(
+      parse_command("ns all"),
+      Some((CommandKind::Namespace, None))
+    );
+    assert_eq!(parse_command("all"), Some((CommandKind::Namespace, None)));
+    assert_eq!(
+      parse_command("ctx prod"),
+      Some((CommandKind::Context, Some("prod".into())))
+    );
+    assert_eq!(parse_command("ctx"), None);
+    assert_eq!(parse_command("pods foo"), None);
+    assert_eq!(parse_command("deplo"), None);
+  }
+
+  #[test]
+  fn test_update_suggestions() {
+    let namespaces = vec!["default".to_owned(), "kube-system".to_owned()];
+    let contexts = vec!["prod".to_owned(), "dev".to_owned()];
+    let mut palette = CommandPalette::new();
+
+    palette.input = "dpl".into();
+    palette.update_suggestions(&namespaces, &contexts);
+    assert_eq!(palette.suggestions.items, vec!["deployments".to_owned()]);
+    assert_eq!(
+      palette.command(),
+      Some((CommandKind::Resource(ActiveBlock::Deployments), None))
+    );
+
+    palette.input = "ns ks".into();
+    palette.update_suggestions(&namespaces, &contexts);
+    assert_eq!(palette.suggestions.items, vec!["ns kube-system".to_owned()]);
+    palette.complete();
+    assert_eq!(palette.input, "ns kube-system");
+
+    palette.input = "ctx ".into();
+    palette.update_suggestions(&namespaces, &contexts);
+    assert_eq!(
+      palette.suggestions.items,
+      vec!["ctx dev".to_owned(), "ctx prod".to_owned()]
+    );
+    assert_eq!(
+      palette.command(),
+      Some((CommandKind::Context, Some("dev".into())))
+    );
+
+    // typed commands win over suggestions
+    palette.input = "all".into();
+    palette.update_suggestions(&namespaces, &contexts);
+    assert_eq!(palette.command(), Some((CommandKind::Namespace, None)));
+    palette.input = "ns new".into();
+    palette.update_suggestions(&namespaces, &contexts);
+    assert_eq!(
+      palette.command(),
+      Some((CommandKind::Namespace, Some("new".into())))
+    );
+
+    palette.input = "pods x".into();
+    palette.update_suggestions(&namespaces, &contexts);
+    assert!(palette.suggestions.items.is_empty());
+    assert_eq!(palette.command(), None);
+  }
+}
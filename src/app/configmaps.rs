@@ -1,8 +1,13 @@
 use std::collections::BTreeMap;
 
-use k8s_openapi::{api::core::v1::ConfigMap, chrono::Utc};
+use k8s_openapi::{
+  api::core::v1::ConfigMap, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, TableRow},
+  utils,
+};
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct KubeConfigMap {
@@ -31,6 +36,22 @@ impl KubeResource<ConfigMap> for KubeConfigMap {
   }
 }
 
+impl TableRow for KubeConfigMap {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Data" => self.data.len().to_string(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use k8s_openapi::chrono::Utc;
@@ -0,0 +1,53 @@
+use crate::{cmd::IoCmdEvent, config::ConfirmPolicy};
+
+/// question asked before running an action that changes the cluster, when open it takes all key
+/// presses
+#[derive(Debug, PartialEq, Eq)]
+pub struct Confirmation {
+  /// like `Roll back web/frontend to revision 3?`
+  pub question: String,
+  /// name to type to confirm, for the paranoid policy
+  pub typed_name: Option<String>,
+  pub input: String,
+  pub action: IoCmdEvent,
+}
+
+impl Confirmation {
+  /// the paranoid policy asks to type the name of the resource
+  pub fn new(policy: ConfirmPolicy, question: String, name: &str, action: IoCmdEvent) -> Self {
+    Confirmation {
+      question,
+      typed_name: (policy == ConfirmPolicy::Paranoid).then(|| name.to_owned()),
+      input: String::new(),
+      action,
+    }
+  }
+
+  /// whether the name was typed, always true when none needs to be
+  pub fn is_confirmed(&self) -> bool {
+    self
+      .typed_name
+      .as_ref()
+      .is_none_or(|name| self.input.trim() == name)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_confirmation() {
+    let action = || IoCmdEvent::OpenUrl("https://example.com".into());
+    let asked = Confirmation::new(ConfirmPolicy::Always, "Open?".into(), "web", action());
+    assert!(asked.is_confirmed());
+
+    let mut confirmation =
+      Confirmation::new(ConfirmPolicy::Paranoid, "Open?".into(), "web", action());
+    assert!(!confirmation.is_confirmed());
+    confirmation.input = "we".into();
+    assert!(!confirmation.is_confirmed());
+    confirmation.input = "web".into();
+    assert!(confirmation.is_confirmed());
+  }
+}
@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+/// wait before the first retry after the API server became unreachable, doubled on every failure
+const FIRST_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(60);
+
+/// whether the API server is reachable, requests wait with an exponential backoff while it isn't
+#[derive(Debug, Default)]
+pub struct Connection {
+  /// requests that failed in a row
+  failures: u32,
+  retry_at: Option<Instant>,
+  /// the poll at `retry_at` was made
+  retried: bool,
+}
+
+impl Connection {
+  pub fn failed(&mut self, now: Instant) {
+    self.failures += 1;
+    let delay = FIRST_RETRY
+      .saturating_mul(2u32.saturating_pow(self.failures - 1))
+      .min(MAX_RETRY);
+    self.retry_at = Some(now + delay);
+    self.retried = false;
+  }
+
+  pub fn reached(&mut self) {
+    *self = Connection::default();
+  }
+
+  pub fn is_disconnected(&self) -> bool {
+    self.failures > 0
+  }
+
+  /// whether requests are held back until the next retry
+  pub fn is_waiting(&self, now: Instant) -> bool {
+    self.retry_at.is_some_and(|at| now < at)
+  }
+
+  /// whether it's time to retry and the retry wasn't made yet, it is only true once per failure
+  pub fn take_retry(&mut self, now: Instant) -> bool {
+    let due = self.is_disconnected() && !self.retried && !self.is_waiting(now);
+    if due {
+      self.retried = true;
+    }
+    due
+  }
+
+  /// like `disconnected, retrying in 8s`, None while connected
+  pub fn status(&self, now: Instant) -> Option<String> {
+    if !self.is_disconnected() {
+      return None;
+    }
+    let status = match self.retry_at {
+      Some(at) if now < at => format!(
+        "disconnected, retrying in {}s",
+        (at - now).as_secs_f64().ceil()
+      ),
+      _ => "disconnected, retrying".into(),
+    };
+    Some(status)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_connection_backoff() {
+    let now = Instant::now();
+    let mut connection = Connection::default();
+    assert_eq!(connection.status(now), None);
+    assert!(!connection.take_retry(now));
+
+    connection.failed(now);
+    assert!(connection.is_waiting(now));
+    assert_eq!(
+      connection.status(now).as_deref(),
+      Some("disconnected, retrying in 1s")
+    );
+    let later = now + Duration::from_secs(1);
+    assert!(!connection.is_waiting(later));
+    assert!(connection.take_retry(later));
+    assert!(!connection.take_retry(later));
+    assert_eq!(
+      connection.status(later).as_deref(),
+      Some("disconnected, retrying")
+    );
+
+    // the wait doubles up to a minute
+    connection.failed(later);
+    assert_eq!(
+      connection.status(later).as_deref(),
+      Some("disconnected, retrying in 2s")
+    );
+    for _ in 0..10 {
+      connection.failed(later);
+    }
+    assert_eq!(
+      connection.status(later).as_deref(),
+      Some("disconnected, retrying in 60s")
+    );
+
+    connection.reached();
+    assert!(!connection.is_disconnected());
+    assert!(!connection.is_waiting(later));
+  }
+}
@@ -0,0 +1,117 @@
+use super::{models::StatefulList, utils::fuzzy_score};
+
+/// contexts kept in the recently used list
+const MAX_RECENT_CONTEXTS: usize = 20;
+
+/// fuzzy search over the contexts of the kubeconfig, the recently used ones first
+pub struct ContextSwitcher {
+  pub input: String,
+  /// matching context names and whether each is the active one
+  pub matches: StatefulList<(String, bool)>,
+}
+
+impl ContextSwitcher {
+  pub fn new() -> Self {
+    ContextSwitcher {
+      input: String::new(),
+      matches: StatefulList::with_items(vec![]),
+    }
+  }
+
+  /// list the contexts matching the input, best matches first and recently used ones first
+  /// among equal matches. Without input all contexts are listed with the recently used ones
+  /// first, and the one used before the active one is selected to switch back quickly
+  pub fn update_matches(&mut self, contexts: &[String], recent: &[String], active: Option<&str>) {
+    let recency = |name: &str| {
+      recent
+        .iter()
+        .position(|r| r == name)
+        .unwrap_or(recent.len())
+    };
+    let mut scored: Vec<(i64, usize, &String)> = contexts
+      .iter()
+      .filter_map(|name| {
+        let score = if self.input.is_empty() {
+          Some(0)
+        } else {
+          fuzzy_score(&self.input, name)
+        };
+        score.map(|s| (s, recency(name), name))
+      })
+      .collect();
+    // stable, contexts never used keep the kubeconfig order
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    self.matches = StatefulList::with_items(
+      scored
+        .into_iter()
+        .map(|(_, _, name)| (name.clone(), Some(name.as_str()) == active))
+        .collect(),
+    );
+    if self.input.is_empty() {
+      if let Some(i) = self
+        .matches
+        .items
+        .iter()
+        .position(|(_, is_active)| !is_active)
+      {
+        self.matches.state.select(Some(i));
+      }
+    }
+  }
+
+  pub fn selected_context(&self) -> Option<&str> {
+    self
+      .matches
+      .state
+      .selected()
+      .and_then(|i| self.matches.items.get(i))
+      .map(|(name, _)| name.as_str())
+  }
+}
+
+/// move a context switched to to the front of the recently used ones
+pub fn add_recent_context(recent: &mut Vec<String>, name: &str) {
+  recent.retain(|r| r != name);
+  recent.insert(0, name.to_owned());
+  recent.truncate(MAX_RECENT_CONTEXTS);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_update_matches() {
+    let contexts = ["dev", "staging", "prod-eu", "prod-us"].map(String::from);
+    let mut recent = vec![];
+    add_recent_context(&mut recent, "prod-us");
+    add_recent_context(&mut recent, "staging");
+    add_recent_context(&mut recent, "prod-us");
+    assert_eq!(recent, vec!["prod-us", "staging"]);
+
+    let mut switcher = ContextSwitcher::new();
+    switcher.update_matches(&contexts, &recent, Some("prod-us"));
+    assert_eq!(
+      switcher.matches.items,
+      vec![
+        ("prod-us".into(), true),
+        ("staging".into(), false),
+        ("dev".into(), false),
+        ("prod-eu".into(), false),
+      ]
+    );
+    assert_eq!(switcher.selected_context(), Some("staging"));
+
+    switcher.input = "prd".into();
+    switcher.update_matches(&contexts, &recent, Some("prod-us"));
+    assert_eq!(
+      switcher.matches.items,
+      vec![("prod-us".into(), true), ("prod-eu".into(), false)]
+    );
+    assert_eq!(switcher.selected_context(), Some("prod-us"));
+
+    switcher.input = "qa".into();
+    switcher.update_matches(&contexts, &recent, None);
+    assert_eq!(switcher.selected_context(), None);
+  }
+}
@@ -1,6 +1,11 @@
-use k8s_openapi::{api::batch::v1::CronJob, chrono::Utc};
+use k8s_openapi::{
+  api::batch::v1::CronJob, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, RowHealth, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeCronJob {
@@ -47,6 +52,33 @@ impl KubeResource<CronJob> for KubeCronJob {
   }
 }
 
+impl TableRow for KubeCronJob {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn status_health(&self) -> RowHealth {
+    if self.suspend {
+      RowHealth::Done
+    } else {
+      RowHealth::Healthy
+    }
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Schedule" => self.schedule.to_owned(),
+      "Last Scheduled" => self.last_schedule.to_string(),
+      "Suspend" => self.suspend.to_string(),
+      "Active" => self.active.to_string(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -0,0 +1,274 @@
+use jsonpath_lib::Compiled;
+use k8s_openapi::{apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc};
+use kube::core::{ApiResource, DynamicObject, GroupVersionKind};
+use serde_json::Value;
+
+use super::{
+  models::{KubeResource, TableRow},
+  utils,
+};
+
+/// a table of a custom resource declared in `custom_views` in the config
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomView {
+  /// title of the table, listed under More
+  pub name: String,
+  pub resource: ApiResource,
+  pub namespaced: bool,
+  pub columns: Vec<CustomColumn>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomColumn {
+  pub header: String,
+  /// JSONPath into the object, starting with `$`
+  pub path: String,
+  /// values are cut to it when set
+  pub width: Option<usize>,
+}
+
+impl CustomColumn {
+  /// a column for a path like kubectl custom columns take, `.spec.replicas` or
+  /// `{.spec.replicas}`. Fails when the path is no valid JSONPath
+  pub fn new(header: &str, path: &str, width: Option<usize>) -> Result<Self, String> {
+    let path = path.trim();
+    let path = path
+      .strip_prefix('{')
+      .and_then(|p| p.strip_suffix('}'))
+      .unwrap_or(path);
+    let path = match path.strip_prefix('$') {
+      Some(_) => path.to_owned(),
+      None if path.starts_with('.') || path.starts_with('[') => format!("${}", path),
+      None => format!("$.{}", path),
+    };
+    Compiled::compile(&path)?;
+    Ok(CustomColumn {
+      header: header.to_owned(),
+      path,
+      width,
+    })
+  }
+
+  /// the text of the column for an object as JSON, cut to the width
+  pub fn value(&self, json: &Value) -> String {
+    let value = column_value(json, &self.path);
+    match self.width {
+      Some(width) if value.chars().count() > width => {
+        let cut: String = value.chars().take(width.saturating_sub(1)).collect();
+        format!("{}…", cut)
+      }
+      _ => value,
+    }
+  }
+}
+
+impl CustomView {
+  /// the resource of a group, version and kind. The plural is guessed from the kind when not
+  /// given, the way kube-rs does
+  pub fn new(
+    name: &str,
+    group: &str,
+    version: &str,
+    kind: &str,
+    plural: Option<&str>,
+    namespaced: bool,
+    columns: Vec<CustomColumn>,
+  ) -> Self {
+    let gvk = GroupVersionKind::gvk(group, version, kind);
+    let resource = match plural {
+      Some(plural) => ApiResource::from_gvk_with_plural(&gvk, plural),
+      None => ApiResource::from_gvk(&gvk),
+    };
+    CustomView {
+      name: name.to_owned(),
+      resource,
+      namespaced,
+      columns,
+    }
+  }
+
+  /// the configured columns between the name and the age
+  pub fn headers(&self) -> Vec<&str> {
+    let namespace = self.namespaced.then_some("Namespace");
+    namespace
+      .into_iter()
+      .chain(["Name"])
+      .chain(self.columns.iter().map(|c| c.header.as_str()))
+      .chain(["Age"])
+      .collect()
+  }
+
+  /// the resource as kubectl takes it, like `certificates.cert-manager.io`
+  pub fn kubectl_kind(&self) -> String {
+    if self.resource.group.is_empty() {
+      self.resource.plural.clone()
+    } else {
+      format!("{}.{}", self.resource.plural, self.resource.group)
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct KubeCustomObject {
+  /// the resource of the view as kubectl takes it, to describe the object
+  pub resource: String,
+  pub namespace: String,
+  pub name: String,
+  pub age: String,
+  /// header and value of the configured columns
+  pub cells: Vec<(String, String)>,
+  k8s_obj: DynamicObject,
+}
+
+impl KubeCustomObject {
+  pub fn from_api(view: &CustomView, object: DynamicObject) -> Self {
+    let json = serde_json::to_value(&object).unwrap_or_default();
+    let cells = view
+      .columns
+      .iter()
+      .map(|column| (column.header.clone(), column.value(&json)))
+      .collect();
+    KubeCustomObject {
+      resource: view.kubectl_kind(),
+      namespace: object.metadata.namespace.clone().unwrap_or_default(),
+      name: object.metadata.name.clone().unwrap_or_default(),
+      age: utils::to_age(object.metadata.creation_timestamp.as_ref(), Utc::now()),
+      cells,
+      k8s_obj: utils::sanitize_obj(object),
+    }
+  }
+}
+
+/// the values the path selects joined by commas, the items of lists too. `<none>` like kubectl
+/// when there are none
+fn column_value(json: &Value, path: &str) -> String {
+  let values = Compiled::compile(path)
+    .ok()
+    .and_then(|compiled| compiled.select(json).ok())
+    .unwrap_or_default();
+  let values: Vec<String> = values
+    .into_iter()
+    .flat_map(|value| match value {
+      Value::Array(items) => items.iter().collect(),
+      value => vec![value],
+    })
+    .filter(|value| !value.is_null())
+    .map(|value| match value {
+      Value::String(text) => text.to_owned(),
+      value => value.to_string(),
+    })
+    .collect();
+  if values.is_empty() {
+    "<none>".into()
+  } else {
+    values.join(",")
+  }
+}
+
+impl KubeResource<DynamicObject> for KubeCustomObject {
+  fn get_k8s_obj(&self) -> &DynamicObject {
+    &self.k8s_obj
+  }
+}
+
+impl TableRow for KubeCustomObject {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => self
+        .cells
+        .iter()
+        .find(|(header, _)| header == column)
+        .map(|(_, value)| value.to_owned())
+        .unwrap_or_default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_custom_object() {
+    let view = CustomView::new(
+      "Certificates",
+      "cert-manager.io",
+      "v1",
+      "Certificate",
+      None,
+      true,
+      vec![
+        CustomColumn::new(
+          "Ready",
+          r#".status.conditions[?(@.type=="Ready")].status"#,
+          None,
+        )
+        .unwrap(),
+        CustomColumn::new("Hosts", "{.spec.dnsNames}", Some(12)).unwrap(),
+        CustomColumn::new("Issuer", "spec.issuerRef.name", None).unwrap(),
+        CustomColumn::new("Renewal", "$.status.renewalTime", None).unwrap(),
+      ],
+    );
+    assert_eq!(view.resource.plural, "certificates");
+    assert_eq!(view.kubectl_kind(), "certificates.cert-manager.io");
+    assert_eq!(
+      view.headers(),
+      vec![
+        "Namespace",
+        "Name",
+        "Ready",
+        "Hosts",
+        "Issuer",
+        "Renewal",
+        "Age"
+      ]
+    );
+    assert!(CustomColumn::new("Broken", ".spec[", None).is_err());
+
+    let object: DynamicObject = serde_yaml::from_str(
+      r#"
+apiVersion: cert-manager.io/v1
+kind: Certificate
+metadata:
+  name: web-tls
+  namespace: web
+spec:
+  dnsNames: [example.com, www.example.com]
+  issuerRef:
+    name: letsencrypt
+status:
+  conditions:
+    - type: Issuing
+      status: "False"
+    - type: Ready
+      status: "True"
+"#,
+    )
+    .unwrap();
+    let certificate = KubeCustomObject::from_api(&view, object);
+    assert_eq!(
+      view
+        .headers()
+        .iter()
+        .map(|header| certificate.column_value(header))
+        .collect::<Vec<_>>(),
+      vec![
+        "web",
+        "web-tls",
+        "True",
+        "example.com…",
+        "letsencrypt",
+        "<none>",
+        ""
+      ]
+    );
+    assert_eq!(certificate.resource, "certificates.cert-manager.io");
+  }
+}
@@ -1,6 +1,11 @@
-use k8s_openapi::{api::apps::v1::DaemonSet, chrono::Utc};
+use k8s_openapi::{
+  api::apps::v1::DaemonSet, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, RowHealth, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeDaemonSet {
@@ -46,6 +51,30 @@ impl KubeResource<DaemonSet> for KubeDaemonSet {
   }
 }
 
+impl TableRow for KubeDaemonSet {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn status_health(&self) -> RowHealth {
+    RowHealth::from_replicas(self.ready, self.desired)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Desired" => self.desired.to_string(),
+      "Current" => self.current.to_string(),
+      "Ready" => self.ready.to_string(),
+      "Up-to-date" => self.up_to_date.to_string(),
+      "Available" => self.available.to_string(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -0,0 +1,211 @@
+use std::{env, path::PathBuf};
+
+use super::models::ManifestFormat;
+use crate::cmd::IoCmdEvent;
+
+/// what an export prompt saves
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExportTarget {
+  /// the manifest of a resource
+  Manifest {
+    kind: String,
+    name: String,
+    namespace: Option<String>,
+    format: ManifestFormat,
+    /// leave `metadata.managedFields` out of the file
+    strip_managed_fields: bool,
+  },
+  /// a resource table as shown, filtered and sorted, as CSV
+  Table {
+    title: String,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+  },
+}
+
+/// path input for saving a manifest or table, when open it takes all key presses
+pub struct ExportPrompt {
+  pub path: String,
+  pub target: ExportTarget,
+}
+
+impl ExportPrompt {
+  pub fn new(kind: &str, name: &str, namespace: Option<String>, format: ManifestFormat) -> Self {
+    ExportPrompt {
+      path: format!("./{}-{}.{}", kind, name, format.extension()),
+      target: ExportTarget::Manifest {
+        kind: kind.to_owned(),
+        name: name.to_owned(),
+        namespace,
+        format,
+        strip_managed_fields: true,
+      },
+    }
+  }
+
+  pub fn table(title: &str, headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+    ExportPrompt {
+      path: format!("./{}.csv", title.to_lowercase().replace(' ', "-")),
+      target: ExportTarget::Table {
+        title: title.to_owned(),
+        headers,
+        rows,
+      },
+    }
+  }
+
+  /// the command writing the file to the path, `~/` is the home directory. Manifests are
+  /// fetched again as the cached resources have no managedFields
+  pub fn export_event(&self) -> IoCmdEvent {
+    let path = match (self.path.trim().strip_prefix("~/"), env::var_os("HOME")) {
+      (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+      _ => PathBuf::from(self.path.trim()),
+    };
+    match &self.target {
+      ExportTarget::Manifest {
+        kind,
+        name,
+        namespace,
+        format,
+        strip_managed_fields,
+      } => IoCmdEvent::ExportManifest {
+        kind: kind.clone(),
+        value: name.clone(),
+        ns: namespace.clone(),
+        format: *format,
+        managed_fields: !strip_managed_fields,
+        path,
+      },
+      ExportTarget::Table {
+        title,
+        headers,
+        rows,
+      } => IoCmdEvent::ExportTable {
+        title: title.clone(),
+        csv: to_csv(headers, rows),
+        path,
+      },
+    }
+  }
+}
+
+/// the path of a saved file from the `export_path` template with the placeholders like `{name}`
+/// replaced and the extension appended. Slashes in the values are replaced to keep each one in a
+/// single directory
+pub fn template_path(template: &str, values: &[(&str, &str)], extension: &str) -> String {
+  let path = values
+    .iter()
+    .fold(template.to_owned(), |path, (key, value)| {
+      path.replace(&format!("{{{}}}", key), &value.replace('/', "-"))
+    });
+  format!("{}.{}", path, extension)
+}
+
+/// the rows under the headers as CSV, cells with separators, quotes or line breaks are quoted
+fn to_csv(headers: &[String], rows: &[Vec<String>]) -> String {
+  let line = |cells: &[String]| {
+    let cells = cells
+      .iter()
+      .map(|cell| {
+        if cell.contains([',', '"', '\n', '\r']) {
+          format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+          cell.clone()
+        }
+      })
+      .collect::<Vec<_>>();
+    format!("{}\n", cells.join(","))
+  };
+  std::iter::once(line(headers))
+    .chain(rows.iter().map(|row| line(row)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_export_event() {
+    let mut prompt = ExportPrompt::new("pod", "web", Some("prod".into()), ManifestFormat::Yaml);
+    assert_eq!(prompt.path, "./pod-web.yaml");
+    assert_eq!(
+      prompt.export_event(),
+      IoCmdEvent::ExportManifest {
+        kind: "pod".into(),
+        value: "web".into(),
+        ns: Some("prod".into()),
+        format: ManifestFormat::Yaml,
+        managed_fields: false,
+        path: "./pod-web.yaml".into(),
+      }
+    );
+
+    if let ExportTarget::Manifest {
+      strip_managed_fields,
+      ..
+    } = &mut prompt.target
+    {
+      *strip_managed_fields = false;
+    }
+    prompt.path = "~/web.yaml ".into();
+    match prompt.export_event() {
+      IoCmdEvent::ExportManifest {
+        managed_fields,
+        path,
+        ..
+      } => {
+        assert!(managed_fields);
+        assert!(path.ends_with("web.yaml"));
+        assert!(!path.starts_with("~"));
+      }
+      event => panic!("unexpected event {:?}", event),
+    }
+
+    let json = ExportPrompt::new("node", "kind", None, ManifestFormat::Json);
+    assert_eq!(json.path, "./node-kind.json");
+  }
+
+  #[test]
+  fn test_template_path() {
+    let values = [
+      ("context", "arn:aws:eks:eu-west-1:1234:cluster/prod"),
+      ("namespace", "web"),
+      ("kind", "pod"),
+      ("name", "api-0"),
+    ];
+    assert_eq!(
+      template_path(
+        "~/kdash/{context}/{namespace}/{kind}-{name}",
+        &values,
+        "yaml"
+      ),
+      "~/kdash/arn:aws:eks:eu-west-1:1234:cluster-prod/web/pod-api-0.yaml"
+    );
+    assert_eq!(
+      template_path("./{name}-{unknown}", &values, "csv"),
+      "./api-0-{unknown}.csv"
+    );
+  }
+
+  #[test]
+  fn test_export_table() {
+    let prompt = ExportPrompt::table(
+      "Stateful Sets",
+      vec!["Name".into(), "Ports".into()],
+      vec![
+        vec!["web".into(), "80/TCP,443/TCP".into()],
+        vec!["say \"hi\"".into(), "".into()],
+      ],
+    );
+    assert_eq!(prompt.path, "./stateful-sets.csv");
+    assert_eq!(
+      prompt.export_event(),
+      IoCmdEvent::ExportTable {
+        title: "Stateful Sets".into(),
+        csv: "Name,Ports\nweb,\"80/TCP,443/TCP\"\n\"say \"\"hi\"\"\",\n".into(),
+        path: "./stateful-sets.csv".into(),
+      }
+    );
+  }
+}
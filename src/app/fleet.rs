@@ -0,0 +1,350 @@
+use k8s_openapi::{
+  api::core::v1::{Event, Node, Pod},
+  chrono::{DateTime, Utc},
+};
+use kube::api::ObjectList;
+
+use super::{
+  metrics::{CapacityUsage, ClusterCapacity},
+  models::{RowHealth, TableRow},
+  pods::KubePod,
+  utils,
+};
+
+/// warning events kept in the fleet view, the newest of all contexts
+const MAX_FLEET_EVENTS: usize = 500;
+
+/// basic health of a context in `fleet_contexts`, compared side by side in the fleet view
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClusterHealth {
+  pub context: String,
+  /// why the cluster couldn't be polled
+  pub error: Option<String>,
+  pub nodes: usize,
+  pub ready_nodes: usize,
+  pub pods: usize,
+  /// pods in a failed state like CrashLoopBackOff, or running but not ready
+  pub failing_pods: usize,
+  pub pending_pods: usize,
+  pub capacity: ClusterCapacity,
+}
+
+impl ClusterHealth {
+  pub fn from_api(context: &str, nodes: &ObjectList<Node>, pods: &ObjectList<Pod>) -> Self {
+    let ready_nodes = nodes
+      .iter()
+      .filter(|node| {
+        node
+          .status
+          .as_ref()
+          .and_then(|st| st.conditions.as_ref())
+          .is_some_and(|conds| {
+            conds
+              .iter()
+              .any(|c| c.type_ == "Ready" && c.status == "True")
+          })
+      })
+      .count();
+    let pending_pods = pods
+      .iter()
+      .filter(|pod| {
+        pod
+          .status
+          .as_ref()
+          .and_then(|st| st.phase.as_deref())
+          .is_some_and(|phase| phase == "Pending")
+      })
+      .count();
+    let failing_pods = pods
+      .iter()
+      .filter(|pod| KubePod::from((*pod).clone()).status_health() == RowHealth::Failed)
+      .count();
+    ClusterHealth {
+      context: context.to_owned(),
+      error: None,
+      nodes: nodes.items.len(),
+      ready_nodes,
+      pods: pods.items.len(),
+      failing_pods,
+      pending_pods,
+      capacity: ClusterCapacity::from_api(nodes, Some(pods)),
+    }
+  }
+
+  pub fn unreachable(context: &str, error: String) -> Self {
+    ClusterHealth {
+      context: context.to_owned(),
+      error: Some(error),
+      ..ClusterHealth::default()
+    }
+  }
+}
+
+/// share of the allocatable amount not requested by pods, what is left to schedule
+fn headroom(usage: &CapacityUsage) -> String {
+  if usage.allocatable > 0f64 {
+    format!(
+      "{:.0}%",
+      (1f64 - usage.requested_ratio()).max(0f64) * 100f64
+    )
+  } else {
+    "-".into()
+  }
+}
+
+impl TableRow for ClusterHealth {
+  /// unreachable clusters and ones with nodes not ready fail, failing pods or an over-committed
+  /// cluster are pending
+  fn status_health(&self) -> RowHealth {
+    if self.error.is_some() || self.ready_nodes < self.nodes {
+      RowHealth::Failed
+    } else if self.failing_pods > 0
+      || self.capacity.cpu.is_overcommitted()
+      || self.capacity.mem.is_overcommitted()
+    {
+      RowHealth::Pending
+    } else {
+      RowHealth::Healthy
+    }
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    if let Some(error) = &self.error {
+      return match column {
+        "Context" => self.context.to_owned(),
+        "Status" => format!("Unreachable: {}", error),
+        _ => "-".into(),
+      };
+    }
+    match column {
+      "Context" => self.context.to_owned(),
+      "Status" => match self.status_health() {
+        RowHealth::Failed => "Nodes Not Ready".into(),
+        RowHealth::Pending => "Degraded".into(),
+        _ => "Healthy".into(),
+      },
+      "Nodes" => format!("{}/{}", self.ready_nodes, self.nodes),
+      "Pods" => self.pods.to_string(),
+      "Failing" => self.failing_pods.to_string(),
+      "Pending" => self.pending_pods.to_string(),
+      "CPU Headroom" => headroom(&self.capacity.cpu),
+      "Mem Headroom" => headroom(&self.capacity.mem),
+      _ => String::default(),
+    }
+  }
+}
+
+/// a warning event of a context in the fleet view
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FleetEvent {
+  pub context: String,
+  pub namespace: String,
+  /// kind and name of the object the event is about, like `Pod/api-0`
+  pub object: String,
+  pub reason: String,
+  pub message: String,
+  pub count: i32,
+  pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl FleetEvent {
+  pub fn from_api(context: &str, event: &Event) -> Self {
+    let object = &event.involved_object;
+    FleetEvent {
+      context: context.to_owned(),
+      namespace: object.namespace.clone().unwrap_or_default(),
+      object: format!(
+        "{}/{}",
+        object.kind.as_deref().unwrap_or_default(),
+        object.name.as_deref().unwrap_or_default()
+      ),
+      reason: event.reason.clone().unwrap_or_default(),
+      message: event
+        .message
+        .as_deref()
+        .unwrap_or_default()
+        .replace('\n', " "),
+      count: event.count.unwrap_or(1),
+      last_seen: event
+        .last_timestamp
+        .as_ref()
+        .map(|time| time.0)
+        .or_else(|| event.event_time.as_ref().map(|time| time.0))
+        .or_else(|| {
+          event
+            .metadata
+            .creation_timestamp
+            .as_ref()
+            .map(|time| time.0)
+        }),
+    }
+  }
+}
+
+/// the warning events of all contexts in one list, newest first
+pub fn merge_fleet_events(mut events: Vec<FleetEvent>) -> Vec<FleetEvent> {
+  events.sort_by_key(|event| std::cmp::Reverse(event.last_seen));
+  events.truncate(MAX_FLEET_EVENTS);
+  events
+}
+
+impl TableRow for FleetEvent {
+  fn created_at(&self) -> Option<DateTime<Utc>> {
+    self.last_seen
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Context" => self.context.to_owned(),
+      "Namespace" => self.namespace.to_owned(),
+      "Object" => self.object.to_owned(),
+      "Reason" => self.reason.to_owned(),
+      "Message" => self.message.to_owned(),
+      "Count" => self.count.to_string(),
+      "Last Seen" => self
+        .last_seen
+        .map_or_else(|| "-".into(), |time| utils::live_age(time, Utc::now())),
+      _ => String::default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use kube::api::ListMeta;
+
+  use super::*;
+
+  #[test]
+  fn test_cluster_health() {
+    let nodes: ObjectList<Node> = ObjectList {
+      metadata: ListMeta::default(),
+      items: serde_yaml::from_str(
+        r#"
+- metadata:
+    name: node-0
+  status:
+    allocatable:
+      cpu: "4"
+      memory: 8Gi
+    conditions:
+      - type: Ready
+        status: "True"
+- metadata:
+    name: node-1
+  status:
+    allocatable:
+      cpu: "4"
+      memory: 8Gi
+    conditions:
+      - type: Ready
+        status: "True"
+"#,
+      )
+      .unwrap(),
+    };
+    let pods: ObjectList<Pod> = ObjectList {
+      metadata: ListMeta::default(),
+      items: serde_yaml::from_str(
+        r#"
+- metadata:
+    name: api-0
+    namespace: web
+  spec:
+    nodeName: node-0
+    containers:
+      - name: api
+        resources:
+          requests:
+            cpu: "2"
+            memory: 4Gi
+  status:
+    phase: Running
+    containerStatuses:
+      - name: api
+        image: api
+        imageID: ""
+        ready: false
+        restartCount: 12
+        state:
+          waiting:
+            reason: CrashLoopBackOff
+- metadata:
+    name: api-1
+    namespace: web
+  spec:
+    containers:
+      - name: api
+  status:
+    phase: Pending
+"#,
+      )
+      .unwrap(),
+    };
+    let health = ClusterHealth::from_api("prod", &nodes, &pods);
+    let columns = [
+      "Context",
+      "Status",
+      "Nodes",
+      "Pods",
+      "Failing",
+      "Pending",
+      "CPU Headroom",
+      "Mem Headroom",
+    ];
+    assert_eq!(
+      columns.map(|c| health.column_value(c)),
+      ["prod", "Degraded", "2/2", "2", "1", "1", "75%", "75%"].map(String::from)
+    );
+
+    let unreachable = ClusterHealth::unreachable("dev", "connection refused".into());
+    assert_eq!(unreachable.status_health(), RowHealth::Failed);
+    assert_eq!(
+      unreachable.column_value("Status"),
+      "Unreachable: connection refused"
+    );
+    assert_eq!(unreachable.column_value("Nodes"), "-");
+  }
+
+  #[test]
+  fn test_merge_fleet_events() {
+    let events: Vec<Event> = serde_yaml::from_str(
+      r#"
+- metadata:
+    name: api-0.1
+  involvedObject:
+    kind: Pod
+    name: api-0
+    namespace: web
+  reason: BackOff
+  message: "Back-off restarting failed container\nagain"
+  count: 12
+  type: Warning
+  lastTimestamp: "2023-03-01T10:00:00Z"
+- metadata:
+    name: node-0.1
+    creationTimestamp: "2023-03-01T11:00:00Z"
+  involvedObject:
+    kind: Node
+    name: node-0
+  reason: NodeNotReady
+  type: Warning
+"#,
+    )
+    .unwrap();
+    let merged = merge_fleet_events(vec![
+      FleetEvent::from_api("prod", &events[0]),
+      FleetEvent::from_api("staging", &events[1]),
+    ]);
+    assert_eq!(merged[0].context, "staging");
+    assert_eq!(merged[0].object, "Node/node-0");
+    assert_eq!(merged[0].count, 1);
+    assert_eq!(merged[1].namespace, "web");
+    assert_eq!(
+      merged[1].message,
+      "Back-off restarting failed container again"
+    );
+    assert_eq!(merged[1].column_value("Count"), "12");
+    assert_eq!(FleetEvent::default().column_value("Last Seen"), "-");
+  }
+}
@@ -0,0 +1,434 @@
+use std::{cmp::Reverse, collections::BTreeMap, io::Read};
+
+use flate2::read::GzDecoder;
+use k8s_openapi::{
+  api::core::v1::Secret, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+
+use super::{
+  models::{KubeResource, RowHealth, TableRow},
+  utils,
+};
+
+/// type of the secrets Helm 3 keeps a revision of a release in
+pub const HELM_RELEASE_TYPE: &str = "helm.sh/release.v1";
+
+/// the parts of the release payload shown, the rest is the manifest and values
+#[derive(Debug, Default, Deserialize)]
+struct Release {
+  #[serde(default)]
+  info: ReleaseInfo,
+  #[serde(default)]
+  chart: Chart,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ReleaseInfo {
+  /// like `Install complete` or `Rollback to 2`
+  #[serde(default)]
+  description: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Chart {
+  #[serde(default)]
+  metadata: ChartMetadata,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChartMetadata {
+  #[serde(default)]
+  name: String,
+  #[serde(default)]
+  version: String,
+  #[serde(default)]
+  app_version: String,
+}
+
+/// the values of the release payload, decoded when they are shown
+#[derive(Debug, Default, Deserialize)]
+struct ReleaseValues {
+  /// the values supplied on install or upgrade
+  #[serde(default)]
+  config: Value,
+  #[serde(default)]
+  chart: ChartValues,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChartValues {
+  /// the defaults of the chart
+  #[serde(default)]
+  values: Value,
+}
+
+/// the manifests the chart rendered for a revision, decoded when they are diffed
+#[derive(Debug, Default, Deserialize)]
+struct ReleaseManifest {
+  #[serde(default)]
+  manifest: String,
+}
+
+/// an object of the manifest of a release, to tell what uninstalling it deletes
+#[derive(Debug, Default, Deserialize)]
+struct ManifestObject {
+  #[serde(default)]
+  kind: String,
+  #[serde(default)]
+  metadata: ObjectMeta,
+}
+
+impl ManifestObject {
+  /// Helm leaves the objects annotated with the keep resource policy behind on uninstall
+  fn is_kept(&self) -> bool {
+    self
+      .metadata
+      .annotations
+      .as_ref()
+      .and_then(|annotations| annotations.get("helm.sh/resource-policy"))
+      .is_some_and(|policy| policy == "keep")
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KubeHelmRelease {
+  pub name: String,
+  pub namespace: String,
+  /// chart name and version, like `nginx-13.2.1`
+  pub chart: String,
+  pub app_version: String,
+  pub revision: u32,
+  pub status: String,
+  pub description: String,
+  pub age: String,
+  k8s_obj: Secret,
+}
+
+impl From<Secret> for KubeHelmRelease {
+  fn from(secret: Secret) -> Self {
+    let labels = secret.metadata.labels.clone().unwrap_or_default();
+    let label = |key: &str| labels.get(key).cloned().unwrap_or_default();
+    let release = secret
+      .data
+      .as_ref()
+      .and_then(|data| data.get("release"))
+      .and_then(|release| decode_release::<Release>(&release.0))
+      .unwrap_or_default();
+    let chart = release.chart.metadata;
+    KubeHelmRelease {
+      name: label("name"),
+      namespace: secret.metadata.namespace.clone().unwrap_or_default(),
+      chart: if chart.name.is_empty() {
+        String::default()
+      } else {
+        format!("{}-{}", chart.name, chart.version)
+      },
+      app_version: chart.app_version,
+      revision: label("version").parse().unwrap_or_default(),
+      status: label("status"),
+      description: release.info.description,
+      age: utils::to_age(secret.metadata.creation_timestamp.as_ref(), Utc::now()),
+      k8s_obj: utils::sanitize_obj(secret),
+    }
+  }
+}
+
+impl KubeHelmRelease {
+  /// the user supplied values and the computed ones, the chart defaults merged with them, as two
+  /// YAML documents. None when the release can't be decoded
+  pub fn values_yaml(&self) -> Option<String> {
+    let release: ReleaseValues = self.decode()?;
+    let user = match release.config {
+      Value::Null => Value::Object(Default::default()),
+      config => config,
+    };
+    let computed = coalesce(release.chart.values, user.clone());
+    Some(format!(
+      "# user supplied values\n{}---\n# computed values\n{}",
+      serde_yaml::to_string(&sort_keys(user)).ok()?,
+      serde_yaml::to_string(&sort_keys(computed)).ok()?
+    ))
+  }
+
+  /// the manifests rendered and applied by the revision, as `helm get manifest` shows them
+  pub fn manifest(&self) -> Option<String> {
+    self
+      .decode::<ReleaseManifest>()
+      .map(|release| release.manifest)
+  }
+
+  /// the objects uninstalling the release deletes, like `Deployment/frontend`
+  pub fn deleted_on_uninstall(&self) -> Vec<String> {
+    let manifest = self.manifest().unwrap_or_default();
+    serde_yaml::Deserializer::from_str(&manifest)
+      // documents with only comments are empty
+      .filter_map(|document| {
+        Option::<ManifestObject>::deserialize(document)
+          .ok()
+          .flatten()
+      })
+      .filter(|object| !object.kind.is_empty() && !object.is_kept())
+      .map(|object| {
+        format!(
+          "{}/{}",
+          object.kind,
+          object.metadata.name.unwrap_or_default()
+        )
+      })
+      .collect()
+  }
+
+  fn decode<T: DeserializeOwned>(&self) -> Option<T> {
+    self
+      .k8s_obj
+      .data
+      .as_ref()
+      .and_then(|data| data.get("release"))
+      .and_then(|release| decode_release(&release.0))
+  }
+}
+
+/// merge the user supplied values over the chart defaults like Helm does, maps are merged key by
+/// key and a null removes the default
+fn coalesce(defaults: Value, user: Value) -> Value {
+  match (defaults, user) {
+    (Value::Object(mut defaults), Value::Object(user)) => {
+      for (key, value) in user {
+        let default = defaults.remove(&key);
+        if !value.is_null() {
+          let merged = match default {
+            Some(default) => coalesce(default, value),
+            None => value,
+          };
+          defaults.insert(key, merged);
+        }
+      }
+      Value::Object(defaults)
+    }
+    (defaults, Value::Null) => defaults,
+    (_, user) => user,
+  }
+}
+
+/// order the keys of maps like `helm get values` does
+fn sort_keys(value: Value) -> Value {
+  match value {
+    Value::Object(map) => {
+      let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+      entries.sort_by(|a, b| a.0.cmp(&b.0));
+      Value::Object(
+        entries
+          .into_iter()
+          .map(|(key, nested)| (key, sort_keys(nested)))
+          .collect(),
+      )
+    }
+    Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+    value => value,
+  }
+}
+
+/// Helm stores the release as base64 of the gzipped JSON, on top of the base64 of the secret
+fn decode_release<T: DeserializeOwned>(data: &[u8]) -> Option<T> {
+  let gzipped = base64::decode(data).ok()?;
+  let mut json = String::new();
+  GzDecoder::new(gzipped.as_slice())
+    .read_to_string(&mut json)
+    .ok()?;
+  serde_json::from_str(&json).ok()
+}
+
+/// one row per release, the latest revision of each as `helm list` shows
+pub fn latest_releases(releases: Vec<KubeHelmRelease>) -> Vec<KubeHelmRelease> {
+  let mut latest: BTreeMap<(String, String), KubeHelmRelease> = BTreeMap::new();
+  for release in releases {
+    let key = (release.namespace.clone(), release.name.clone());
+    match latest.get(&key) {
+      Some(known) if known.revision >= release.revision => {}
+      _ => {
+        latest.insert(key, release);
+      }
+    }
+  }
+  latest.into_values().collect()
+}
+
+/// the revisions of a release, newest first as `helm history` lists them last
+pub fn release_history(
+  releases: &[KubeHelmRelease],
+  namespace: &str,
+  name: &str,
+) -> Vec<KubeHelmRelease> {
+  let mut history: Vec<KubeHelmRelease> = releases
+    .iter()
+    .filter(|r| r.namespace == namespace && r.name == name)
+    .cloned()
+    .collect();
+  history.sort_by_key(|r| Reverse(r.revision));
+  history
+}
+
+impl KubeResource<Secret> for KubeHelmRelease {
+  fn get_k8s_obj(&self) -> &Secret {
+    &self.k8s_obj
+  }
+}
+
+impl TableRow for KubeHelmRelease {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  /// the revisions of a release share its name, they are marked apart in the history
+  fn mark_key(&self) -> (String, String) {
+    (
+      self.namespace.to_owned(),
+      format!("{}.v{}", self.name, self.revision),
+    )
+  }
+
+  fn status_health(&self) -> RowHealth {
+    match self.status.as_str() {
+      "deployed" => RowHealth::Healthy,
+      "superseded" | "uninstalled" => RowHealth::Done,
+      "failed" => RowHealth::Failed,
+      // pending-install, pending-upgrade, pending-rollback and uninstalling
+      _ => RowHealth::Pending,
+    }
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Chart" => self.chart.to_owned(),
+      "App Version" => self.app_version.to_owned(),
+      "Revision" => self.revision.to_string(),
+      "Status" => self.status.to_owned(),
+      "Description" => self.description.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Write;
+
+  use flate2::{write::GzEncoder, Compression};
+  use k8s_openapi::ByteString;
+
+  use super::*;
+
+  fn release_secret(name: &str, revision: u32, status: &str, chart_version: &str) -> Secret {
+    let json = format!(
+      r#"{{"name":"{}","version":{},"info":{{"description":"Upgrade complete"}},"chart":{{"metadata":{{"name":"nginx","version":"{}","appVersion":"1.25.0"}},"values":{{"replicaCount":1,"image":{{"tag":"1.25.0","pullPolicy":"IfNotPresent"}},"metrics":{{"enabled":false}}}}}},"config":{{"replicaCount":3,"image":{{"tag":"1.25.1"}},"metrics":null}},"manifest":"---\n# Source: nginx/templates/deployment.yaml\nkind: Deployment\nmetadata:\n  name: {}\n---\n# Source: nginx/templates/pvc.yaml\nkind: PersistentVolumeClaim\nmetadata:\n  name: data\n  annotations:\n    helm.sh/resource-policy: keep\n---\n# Source: nginx/templates/empty.yaml\n---\nkind: Service\nmetadata:\n  name: {}\n"}}"#,
+      name, revision, chart_version, name, name
+    );
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(json.as_bytes()).unwrap();
+    let release = base64::encode(encoder.finish().unwrap());
+    let mut secret: Secret = serde_yaml::from_str(&format!(
+      r#"
+metadata:
+  name: sh.helm.release.v1.{name}.v{revision}
+  namespace: web
+  labels:
+    name: {name}
+    owner: helm
+    status: {status}
+    version: "{revision}"
+type: helm.sh/release.v1
+"#,
+    ))
+    .unwrap();
+    secret.data = Some(BTreeMap::from([(
+      "release".to_owned(),
+      ByteString(release.into_bytes()),
+    )]));
+    secret
+  }
+
+  #[test]
+  fn test_helm_release_from_api() {
+    let release = KubeHelmRelease::from(release_secret("frontend", 3, "deployed", "13.2.1"));
+    assert_eq!(release.name, "frontend");
+    assert_eq!(release.namespace, "web");
+    assert_eq!(release.chart, "nginx-13.2.1");
+    assert_eq!(release.app_version, "1.25.0");
+    assert_eq!(release.revision, 3);
+    assert_eq!(release.description, "Upgrade complete");
+    assert_eq!(
+      release.deleted_on_uninstall(),
+      vec!["Deployment/frontend", "Service/frontend"]
+    );
+    assert_eq!(release.status_health(), RowHealth::Healthy);
+
+    let mut broken = release_secret("api", 1, "failed", "1.0.0");
+    broken.data = Some(BTreeMap::from([(
+      "release".to_owned(),
+      ByteString(b"not a release".to_vec()),
+    )]));
+    let broken = KubeHelmRelease::from(broken);
+    assert_eq!(broken.chart, "");
+    assert_eq!(broken.values_yaml(), None);
+    assert_eq!(broken.manifest(), None);
+    assert!(broken.deleted_on_uninstall().is_empty());
+    assert_eq!(broken.status_health(), RowHealth::Failed);
+  }
+
+  #[test]
+  fn test_values_yaml() {
+    let release = KubeHelmRelease::from(release_secret("frontend", 3, "deployed", "13.2.1"));
+    assert_eq!(
+      release.values_yaml().unwrap(),
+      r#"# user supplied values
+image:
+  tag: 1.25.1
+metrics: null
+replicaCount: 3
+---
+# computed values
+image:
+  pullPolicy: IfNotPresent
+  tag: 1.25.1
+replicaCount: 3
+"#
+    );
+  }
+
+  #[test]
+  fn test_latest_releases() {
+    let revisions: Vec<KubeHelmRelease> = [
+      release_secret("frontend", 2, "superseded", "13.2.0"),
+      release_secret("frontend", 10, "deployed", "13.2.1"),
+      release_secret("frontend", 9, "superseded", "13.1.0"),
+      release_secret("api", 1, "pending-install", "1.0.0"),
+    ]
+    .into_iter()
+    .map(KubeHelmRelease::from)
+    .collect();
+    assert_eq!(
+      release_history(&revisions, "web", "frontend")
+        .iter()
+        .map(|r| r.revision)
+        .collect::<Vec<_>>(),
+      vec![10, 9, 2]
+    );
+    assert!(release_history(&revisions, "default", "frontend").is_empty());
+
+    let releases = latest_releases(revisions);
+    assert_eq!(
+      releases
+        .iter()
+        .map(|r| (r.name.as_str(), r.revision, r.chart.as_str()))
+        .collect::<Vec<_>>(),
+      vec![("api", 1, "nginx-1.0.0"), ("frontend", 10, "nginx-13.2.1")]
+    );
+    assert_eq!(releases[0].status_health(), RowHealth::Pending);
+  }
+}
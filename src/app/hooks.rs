@@ -0,0 +1,377 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fs,
+  path::Path,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+  },
+};
+
+use anyhow::Result;
+use k8s_openapi::api::core::v1::Event;
+use kube::{Resource, ResourceExt};
+use mlua::{
+  serde::SerializeOptions, FromLuaMulti, Function, HookTriggers, Lua, LuaOptions, LuaSerdeExt,
+  StdLib, VmState,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+  custom_views::CustomColumn,
+  models::{item_key, RowHealth, TableRow},
+};
+
+/// the Lua script from `hooks` in the config. `on_row(resource)` decorates the table rows of the
+/// objects and `on_event(event)` turns events into alerts, both are optional
+pub struct Hooks {
+  lua: Lua,
+  /// header of the column the `on_row` hook fills
+  pub column: String,
+  /// instructions left to the running call, counted in steps of `HOOK_STEP`
+  steps_left: Arc<AtomicU32>,
+}
+
+/// instructions run between two checks of the budget
+const HOOK_STEP: u32 = 1000;
+/// steps a single call of a hook may take, so a script looping forever can't hang the UI
+const HOOK_STEPS: u32 = 10_000;
+/// bytes the Lua state of the hooks may allocate, so a script can't exhaust the memory
+const HOOK_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// what the `on_row` hook returned for an object
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RowDecoration {
+  /// colors the row like the statuses do
+  pub style: Option<RowHealth>,
+  /// text of the hook column
+  pub column: Option<String>,
+  /// texts of the extra columns from the config
+  #[serde(skip)]
+  pub extra: Vec<String>,
+}
+
+/// what the config adds to the rows of a table, the rows by namespace and name
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Decorations {
+  /// headers of the extra columns from the config
+  pub extra_columns: Vec<String>,
+  /// header of the hook column, None when no row has text for it
+  pub column: Option<String>,
+  pub rows: BTreeMap<(String, String), RowDecoration>,
+}
+
+impl Decorations {
+  /// the headers of the extra columns and of the hook column, shown after the ones of the table
+  pub fn columns(&self) -> impl Iterator<Item = &str> + Clone {
+    self
+      .extra_columns
+      .iter()
+      .map(String::as_str)
+      .chain(self.column.as_deref())
+  }
+
+  /// the text of an extra column, of the hook column or of a column of the item
+  pub fn cell<T: TableRow>(&self, item: &T, column: &str) -> String {
+    let row = || self.rows.get(&item_key(item));
+    if let Some(i) = self.extra_columns.iter().position(|c| c == column) {
+      row()
+        .and_then(|row| row.extra.get(i).cloned())
+        .unwrap_or_default()
+    } else if self.column.as_deref() == Some(column) {
+      row().and_then(|row| row.column.clone()).unwrap_or_default()
+    } else {
+      item.column_value(column)
+    }
+  }
+
+  /// add the extra columns from the config, evaluated on the objects
+  pub fn with_extra_columns<K>(mut self, columns: &[CustomColumn], objects: &[K]) -> Self
+  where
+    K: Resource + Serialize,
+  {
+    if columns.is_empty() {
+      return self;
+    }
+    for object in objects {
+      let json = serde_json::to_value(object).unwrap_or_default();
+      let row = self.rows.entry(object_key(object)).or_default();
+      row.extra = columns.iter().map(|column| column.value(&json)).collect();
+    }
+    self.extra_columns = columns.iter().map(|c| c.header.clone()).collect();
+    self
+  }
+
+  /// the style the hook gave the row, or the health of the item
+  pub fn health<T: TableRow>(&self, item: &T) -> RowHealth {
+    self
+      .rows
+      .get(&item_key(item))
+      .and_then(|row| row.style)
+      .unwrap_or_else(|| item.health())
+  }
+}
+
+/// namespace and name of an object, like the rows of it are keyed
+fn object_key<K: Resource>(object: &K) -> (String, String) {
+  (
+    object.namespace().unwrap_or_default(),
+    object.meta().name.clone().unwrap_or_default(),
+  )
+}
+
+impl Hooks {
+  /// a Lua state with only the string, table, math and utf8 libraries, without access to files,
+  /// processes or modules, and with limited memory
+  pub fn new(script: &str, name: &str, column: &str) -> Result<Self> {
+    let lua = Lua::new_with(
+      StdLib::STRING | StdLib::TABLE | StdLib::MATH | StdLib::UTF8,
+      LuaOptions::new(),
+    )?;
+    lua.set_memory_limit(HOOK_MEMORY_LIMIT)?;
+    let steps_left = Arc::new(AtomicU32::new(HOOK_STEPS));
+    let steps = Arc::clone(&steps_left);
+    lua.set_hook(
+      HookTriggers::new().every_nth_instruction(HOOK_STEP),
+      move |_, _| {
+        let left = steps.load(Ordering::Relaxed);
+        if left == 0 {
+          return Err(mlua::Error::runtime(
+            "the hook ran too long and was stopped",
+          ));
+        }
+        steps.store(left - 1, Ordering::Relaxed);
+        Ok(VmState::Continue)
+      },
+    )?;
+    lua.load(script).set_name(name).exec()?;
+    Ok(Hooks {
+      lua,
+      column: column.to_owned(),
+      steps_left,
+    })
+  }
+
+  pub fn from_file(path: &Path, column: &str) -> Result<Self> {
+    let script = fs::read_to_string(path)?;
+    Hooks::new(&script, &format!("@{}", path.display()), column)
+  }
+
+  fn function(&self, name: &str) -> Result<Option<Function>> {
+    Ok(self.lua.globals().get(name)?)
+  }
+
+  pub fn has_on_event(&self) -> bool {
+    self.function("on_event").is_ok_and(|f| f.is_some())
+  }
+
+  /// call a hook with the full budget of instructions
+  fn call<R: FromLuaMulti>(&self, hook: &Function, value: mlua::Value) -> Result<R> {
+    self.steps_left.store(HOOK_STEPS, Ordering::Relaxed);
+    Ok(hook.call(value)?)
+  }
+
+  /// the objects as Lua tables, fields that are not set are nil
+  fn to_lua<T: Serialize>(&self, object: &T) -> Result<mlua::Value> {
+    let options = SerializeOptions::new()
+      .serialize_none_to_null(false)
+      .serialize_unit_to_null(false);
+    Ok(self.lua.to_value_with(object, options)?)
+  }
+
+  /// what `on_row` returns for each object, nothing without the hook
+  pub fn on_row<K: Resource + Serialize>(&self, objects: &[K]) -> Result<Decorations> {
+    let on_row = match self.function("on_row")? {
+      Some(on_row) => on_row,
+      None => return Ok(Decorations::default()),
+    };
+    let mut rows = BTreeMap::new();
+    for object in objects {
+      let value = self.call(&on_row, self.to_lua(object)?)?;
+      if let Some(row) = self.lua.from_value::<Option<RowDecoration>>(value)? {
+        rows.insert(object_key(object), row);
+      }
+    }
+    Ok(Decorations {
+      extra_columns: vec![],
+      column: rows
+        .values()
+        .any(|row| row.column.is_some())
+        .then(|| self.column.clone()),
+      rows,
+    })
+  }
+
+  /// the alerts `on_event` returns for the events, nothing without the hook
+  pub fn on_event(&self, events: &[Event]) -> Result<BTreeSet<String>> {
+    let on_event = match self.function("on_event")? {
+      Some(on_event) => on_event,
+      None => return Ok(BTreeSet::new()),
+    };
+    let mut alerts = BTreeSet::new();
+    for event in events {
+      if let Some(alert) = self.call::<Option<String>>(&on_event, self.to_lua(event)?)? {
+        alerts.insert(alert);
+      }
+    }
+    Ok(alerts)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use k8s_openapi::api::core::v1::Pod;
+
+  use super::*;
+  use crate::app::pods::KubePod;
+
+  #[test]
+  fn test_hooks() {
+    let hooks = Hooks::new(
+      r#"
+function on_row(pod)
+  if pod.metadata.labels == nil or pod.metadata.labels.cost == nil then
+    return { style = "failed", column = "no cost label" }
+  end
+end
+
+function on_event(event)
+  if event.reason == "OOMKilling" then
+    return event.involvedObject.name .. " ran out of memory"
+  end
+end
+"#,
+      "test",
+      "Conventions",
+    )
+    .unwrap();
+    let pods: Vec<Pod> = serde_yaml::from_str(
+      r#"
+- metadata:
+    name: api-0
+    namespace: web
+    labels:
+      cost: team-a
+    annotations:
+      example.com/team: payments
+  spec:
+    nodeName: node-0
+    containers: []
+  status:
+    phase: Running
+- metadata:
+    name: api-1
+    namespace: web
+  status:
+    phase: Running
+"#,
+    )
+    .unwrap();
+    let decorations = hooks.on_row(&pods).unwrap();
+    assert_eq!(decorations.column.as_deref(), Some("Conventions"));
+    let rows: Vec<KubePod> = pods.iter().cloned().map(KubePod::from).collect();
+    assert_eq!(decorations.cell(&rows[0], "Conventions"), "");
+    assert_eq!(decorations.health(&rows[0]), RowHealth::Healthy);
+    assert_eq!(decorations.cell(&rows[1], "Conventions"), "no cost label");
+    assert_eq!(decorations.cell(&rows[1], "Name"), "api-1");
+    assert_eq!(decorations.health(&rows[1]), RowHealth::Failed);
+
+    let extra = [
+      CustomColumn::new("Node Name", ".spec.nodeName", None).unwrap(),
+      CustomColumn::new("Team", ".metadata.annotations['example.com/team']", None).unwrap(),
+    ];
+    let decorations = decorations.with_extra_columns(&extra, &pods);
+    assert_eq!(
+      decorations.columns().collect::<Vec<_>>(),
+      vec!["Node Name", "Team", "Conventions"]
+    );
+    assert_eq!(decorations.cell(&rows[0], "Node Name"), "node-0");
+    assert_eq!(decorations.cell(&rows[0], "Team"), "payments");
+    assert_eq!(decorations.cell(&rows[1], "Team"), "<none>");
+    assert_eq!(decorations.cell(&rows[1], "Conventions"), "no cost label");
+
+    assert!(hooks.has_on_event());
+    let events: Vec<Event> = serde_yaml::from_str(
+      r#"
+- metadata:
+    name: node-0.1
+  involvedObject:
+    kind: Node
+    name: node-0
+  reason: OOMKilling
+- metadata:
+    name: node-0.2
+  involvedObject:
+    kind: Node
+    name: node-0
+  reason: NodeReady
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+      hooks.on_event(&events).unwrap(),
+      BTreeSet::from(["node-0 ran out of memory".to_owned()])
+    );
+
+    let invalid = Hooks::new(
+      "function on_row(pod) return { style = 'red' } end",
+      "test",
+      "Notes",
+    )
+    .unwrap();
+    assert!(!invalid.has_on_event());
+    assert!(invalid.on_row(&[Pod::default()]).is_err());
+    assert!(Hooks::new("function on_row(", "test", "Notes").is_err());
+  }
+
+  #[test]
+  fn test_hooks_sandbox() {
+    // only the string, table, math and utf8 libraries are there
+    let hooks = Hooks::new(
+      "function on_event(event) return type(os) .. type(io) .. type(require) .. type(string) end",
+      "test",
+      "Notes",
+    )
+    .unwrap();
+    assert_eq!(
+      hooks.on_event(&[Event::default()]).unwrap(),
+      BTreeSet::from(["nilnilniltable".to_owned()])
+    );
+
+    // a hook looping forever is stopped, and the next call gets a new budget
+    let looping_hooks = Hooks::new(
+      r#"
+function on_event(event)
+  if event.reason == "Loop" then
+    while true do end
+  end
+  return "done"
+end
+"#,
+      "test",
+      "Notes",
+    )
+    .unwrap();
+    let looping = Event {
+      reason: Some("Loop".into()),
+      ..Event::default()
+    };
+    assert!(looping_hooks.on_event(&[looping]).is_err());
+    assert_eq!(
+      looping_hooks.on_event(&[Event::default()]).unwrap(),
+      BTreeSet::from(["done".to_owned()])
+    );
+    assert!(Hooks::new("while true do end", "test", "Notes").is_err());
+
+    // a hook allocating too much fails instead of taking the memory
+    let allocating_hooks = Hooks::new(
+      r#"function on_event(event) return string.rep("x", 1e10) end"#,
+      "test",
+      "Notes",
+    )
+    .unwrap();
+    assert!(allocating_hooks.on_event(&[Event::default()]).is_err());
+    assert!(Hooks::new(r#"local s = string.rep("x", 1e10)"#, "test", "Notes").is_err());
+  }
+}
@@ -1,6 +1,11 @@
-use k8s_openapi::{api::batch::v1::Job, chrono::Utc};
+use k8s_openapi::{
+  api::batch::v1::Job, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, RowHealth, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeJob {
@@ -57,6 +62,45 @@ impl KubeResource<Job> for KubeJob {
   }
 }
 
+impl TableRow for KubeJob {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn status_health(&self) -> RowHealth {
+    let condition = |type_: &str| {
+      self
+        .k8s_obj
+        .status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .is_some_and(|conditions| {
+          conditions
+            .iter()
+            .any(|c| c.type_ == type_ && c.status == "True")
+        })
+    };
+    if condition("Failed") {
+      RowHealth::Failed
+    } else if condition("Complete") {
+      RowHealth::Done
+    } else {
+      RowHealth::Healthy
+    }
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Completions" => self.completions.to_owned(),
+      "Duration" => self.duration.to_string(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
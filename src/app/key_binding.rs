@@ -1,10 +1,13 @@
-use std::fmt;
+use std::{collections::BTreeMap, fmt, sync::OnceLock};
 
-use crate::event::Key;
+use anyhow::{anyhow, Result};
+
+use crate::{config::KeyList, event::Key};
 
 // using a macro so that we can automatically generate an iterable vector for bindings. This beats reflection :)
 macro_rules! generate_keybindings {
   ($($field:ident),+) => {
+    #[derive(Clone)]
     pub struct KeyBindings { $(pub $field: KeyBinding),+ }
     impl KeyBindings {
       pub fn as_iter(&self) -> Vec<&KeyBinding> {
@@ -12,6 +15,19 @@ macro_rules! generate_keybindings {
             $(&self.$field),+
         ]
       }
+
+      fn as_named_iter(&self) -> Vec<(&'static str, &KeyBinding)> {
+        vec![
+            $((stringify!($field), &self.$field)),+
+        ]
+      }
+
+      fn get_mut(&mut self, name: &str) -> Option<&mut KeyBinding> {
+        match name {
+          $(stringify!($field) => Some(&mut self.$field),)+
+          _ => None,
+        }
+      }
     }
   };
 }
@@ -21,27 +37,68 @@ generate_keybindings! {
   quit,
   esc,
   help,
+  search,
   submit,
   refresh,
+  refresh_tab,
+  command_palette,
+  find_resource,
   toggle_theme,
+  cycle_layout,
   cycle_main_views,
   jump_to_current_context,
   jump_to_all_context,
+  switch_context,
   jump_to_utilization,
+  jump_to_metrics_history,
+  jump_to_fleet,
+  fleet_events,
   copy_to_clipboard,
   pg_up,
   pg_down,
+  half_page_up,
+  half_page_down,
+  scroll_to_top,
+  scroll_to_bottom,
   up,
   down,
   left,
   right,
+  scroll_left,
+  scroll_right,
+  next_match,
+  previous_match,
   toggle_info,
   log_auto_scroll,
   select_all_namespace,
   jump_to_namespace,
+  pin_namespace,
   describe_resource,
   resource_yaml,
+  export_yaml,
+  export_csv,
+  pipe_manifest,
+  diff_last_applied,
+  attach,
+  open_url,
+  copy_kubectl,
+  replay_back,
+  replay_forward,
+  toggle_json,
   decode_secret,
+  helm_rollback,
+  helm_uninstall,
+  helm_uninstall_keep_history,
+  helm_values,
+  cycle_sort,
+  cycle_timestamp_format,
+  pick_columns,
+  cycle_split_pane,
+  mark_row,
+  mark_all_rows,
+  copy_name,
+  copy_namespaced_name,
+  copy_cell,
   jump_to_pods,
   jump_to_services,
   jump_to_nodes,
@@ -59,12 +116,42 @@ generate_keybindings! {
 pub enum HContext {
   General,
   Overview,
+  Table,
+  Logs,
+  Detail,
   Utilization,
 }
 
+/// order of the groups on the help page
+const HELP_GROUPS: [HContext; 6] = [
+  HContext::General,
+  HContext::Overview,
+  HContext::Table,
+  HContext::Logs,
+  HContext::Detail,
+  HContext::Utilization,
+];
+
 impl fmt::Display for HContext {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "{:?}", self)
+    match self {
+      HContext::General => write!(f, "Global"),
+      HContext::Table => write!(f, "Resource tables"),
+      HContext::Detail => write!(f, "Describe/YAML"),
+      _ => write!(f, "{:?}", self),
+    }
+  }
+}
+
+impl HContext {
+  /// general bindings are active everywhere, so they clash with bindings of any context. The
+  /// overview, table, logs and detail bindings are all handled on the home route
+  fn overlaps(self, other: HContext) -> bool {
+    match (self, other) {
+      (HContext::General, _) | (_, HContext::General) => true,
+      (HContext::Utilization, o) | (o, HContext::Utilization) => o == HContext::Utilization,
+      _ => true,
+    }
   }
 }
 
@@ -76,6 +163,90 @@ pub struct KeyBinding {
   pub context: HContext,
 }
 
+impl KeyBinding {
+  pub fn matches(&self, key: Key) -> bool {
+    self.key == key || self.alt == Some(key)
+  }
+
+  fn keys(&self) -> Vec<Key> {
+    std::iter::once(self.key).chain(self.alt).collect()
+  }
+}
+
+impl KeyBindings {
+  /// the default bindings with the keys from the config file applied, fails on unknown binding
+  /// names and on keys used by more than one binding in the same context
+  pub fn with_overrides(overrides: &BTreeMap<String, KeyList>) -> Result<KeyBindings> {
+    let mut bindings = DEFAULT_KEYBINDING;
+    for (name, keys) in overrides {
+      let binding = bindings
+        .get_mut(name)
+        .ok_or_else(|| anyhow!("Unknown keybinding '{}' in config", name))?;
+      binding.key = keys.0[0];
+      binding.alt = keys.0.get(1).copied();
+    }
+    let conflicts = bindings.conflicts();
+    if conflicts.is_empty() {
+      Ok(bindings)
+    } else {
+      Err(anyhow!("Conflicting keybindings: {}", conflicts.join(", ")))
+    }
+  }
+
+  /// whether the binding runs an action changing the cluster, those are hidden and refused in
+  /// read-only mode
+  pub fn is_mutating(&self, binding: &KeyBinding) -> bool {
+    [
+      &self.attach,
+      &self.pipe_manifest,
+      &self.diff_last_applied,
+      &self.helm_rollback,
+      &self.helm_uninstall,
+      &self.helm_uninstall_keep_history,
+    ]
+    .into_iter()
+    .any(|mutating| std::ptr::eq(mutating, binding))
+  }
+
+  /// name of the binding using the key where the bindings of the context work too
+  pub fn used_by(&self, key: Key, context: HContext) -> Option<&'static str> {
+    self
+      .as_named_iter()
+      .into_iter()
+      .find(|(_, binding)| binding.context.overlaps(context) && binding.matches(key))
+      .map(|(name, _)| name)
+  }
+
+  fn conflicts(&self) -> Vec<String> {
+    let bindings = self.as_named_iter();
+    let mut conflicts = vec![];
+    for (i, (name, binding)) in bindings.iter().enumerate() {
+      for (other_name, other) in &bindings[i + 1..] {
+        if !binding.context.overlaps(other.context) {
+          continue;
+        }
+        for key in binding.keys() {
+          if other.matches(key) {
+            conflicts.push(format!("{} is used by {} and {}", key, name, other_name));
+          }
+        }
+      }
+    }
+    conflicts
+  }
+}
+
+static KEY_BINDINGS: OnceLock<KeyBindings> = OnceLock::new();
+
+pub fn set_key_bindings(bindings: KeyBindings) {
+  let _ = KEY_BINDINGS.set(bindings);
+}
+
+/// the active bindings, the defaults unless the config file changed them
+pub fn key_bindings() -> &'static KeyBindings {
+  KEY_BINDINGS.get().unwrap_or(&DEFAULT_KEYBINDING)
+}
+
 pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
   quit: KeyBinding {
     key: Key::Ctrl('c'),
@@ -85,7 +256,7 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
   },
   esc: KeyBinding {
     key: Key::Esc,
-    alt: None,
+    alt: Some(Key::Backspace),
     desc: "Close child page/Go back",
     context: HContext::General,
   },
@@ -95,6 +266,24 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Help page",
     context: HContext::General,
   },
+  search: KeyBinding {
+    key: Key::Char('/'),
+    alt: None,
+    desc: "Search the help page, describe and YAML views or filter the table",
+    context: HContext::General,
+  },
+  command_palette: KeyBinding {
+    key: Key::Char(':'),
+    alt: None,
+    desc: "Command palette (:deploy, :ns, :ctx)",
+    context: HContext::General,
+  },
+  find_resource: KeyBinding {
+    key: Key::Ctrl('f'),
+    alt: None,
+    desc: "Find any resource by name",
+    context: HContext::General,
+  },
   submit: KeyBinding {
     key: Key::Enter,
     alt: None,
@@ -107,12 +296,24 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Refresh data",
     context: HContext::General,
   },
+  refresh_tab: KeyBinding {
+    key: Key::Char('R'),
+    alt: None,
+    desc: "Poll the current view now",
+    context: HContext::General,
+  },
   toggle_theme: KeyBinding {
     key: Key::Char('t'),
     alt: None,
     desc: "Toggle theme",
     context: HContext::General,
   },
+  cycle_layout: KeyBinding {
+    key: Key::Char('z'),
+    alt: None,
+    desc: "Cycle normal, compact and wide layout",
+    context: HContext::General,
+  },
   jump_to_current_context: KeyBinding {
     key: Key::Char('A'),
     alt: None,
@@ -125,12 +326,36 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Switch to all contexts view",
     context: HContext::General,
   },
+  switch_context: KeyBinding {
+    key: Key::Char('X'),
+    alt: None,
+    desc: "Switch context, recently used first",
+    context: HContext::General,
+  },
   jump_to_utilization: KeyBinding {
     key: Key::Char('U'),
     alt: None,
     desc: "Switch to resource utilization view",
     context: HContext::General,
   },
+  jump_to_metrics_history: KeyBinding {
+    key: Key::Char('M'),
+    alt: None,
+    desc: "Switch to metrics history view",
+    context: HContext::General,
+  },
+  jump_to_fleet: KeyBinding {
+    key: Key::Char('F'),
+    alt: None,
+    desc: "Switch to fleet health view",
+    context: HContext::General,
+  },
+  fleet_events: KeyBinding {
+    key: Key::Char('W'),
+    alt: None,
+    desc: "Focus the warning events of the fleet view",
+    context: HContext::General,
+  },
   cycle_main_views: KeyBinding {
     key: Key::Tab,
     alt: None,
@@ -141,7 +366,7 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     key: Key::Char('c'),
     alt: None,
     desc: "Copy log/output to clipboard",
-    context: HContext::General,
+    context: HContext::Detail,
   },
   down: KeyBinding {
     key: Key::Down,
@@ -167,6 +392,30 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Scroll page down",
     context: HContext::General,
   },
+  half_page_up: KeyBinding {
+    key: Key::Ctrl('u'),
+    alt: None,
+    desc: "Scroll half a page up",
+    context: HContext::General,
+  },
+  half_page_down: KeyBinding {
+    key: Key::Ctrl('d'),
+    alt: None,
+    desc: "Scroll half a page down",
+    context: HContext::General,
+  },
+  scroll_to_top: KeyBinding {
+    key: Key::Home,
+    alt: Some(Key::Char('g')),
+    desc: "First row/Scroll to top, letter keys are pressed twice (gg)",
+    context: HContext::Table,
+  },
+  scroll_to_bottom: KeyBinding {
+    key: Key::End,
+    alt: Some(Key::Char('G')),
+    desc: "Last row/Scroll to bottom",
+    context: HContext::Table,
+  },
   left: KeyBinding {
     key: Key::Left,
     alt: Some(Key::Char('h')),
@@ -179,6 +428,30 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Previous resource tab",
     context: HContext::Overview,
   },
+  scroll_left: KeyBinding {
+    key: Key::Char('H'),
+    alt: None,
+    desc: "Scroll table columns or describe view left",
+    context: HContext::Table,
+  },
+  scroll_right: KeyBinding {
+    key: Key::Char('L'),
+    alt: None,
+    desc: "Scroll table columns or describe view right",
+    context: HContext::Table,
+  },
+  next_match: KeyBinding {
+    key: Key::Char(']'),
+    alt: None,
+    desc: "Jump to the next search match",
+    context: HContext::Detail,
+  },
+  previous_match: KeyBinding {
+    key: Key::Char('['),
+    alt: None,
+    desc: "Jump to the previous search match",
+    context: HContext::Detail,
+  },
   toggle_info: KeyBinding {
     key: Key::Char('i'),
     alt: None,
@@ -189,7 +462,7 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     key: Key::Char('s'),
     alt: None,
     desc: "Toggle log auto scroll",
-    context: HContext::Overview,
+    context: HContext::Logs,
   },
   jump_to_namespace: KeyBinding {
     key: Key::Char('n'),
@@ -197,6 +470,12 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     desc: "Select namespace block",
     context: HContext::Overview,
   },
+  pin_namespace: KeyBinding {
+    key: Key::Char('P'),
+    alt: None,
+    desc: "Pin/Unpin selected namespace, select pinned ones with 1-9",
+    context: HContext::Overview,
+  },
   select_all_namespace: KeyBinding {
     key: Key::Char('a'),
     alt: None,
@@ -207,19 +486,157 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
     key: Key::Char('d'),
     alt: None,
     desc: "Describe resource",
-    context: HContext::Overview,
+    context: HContext::Table,
   },
   resource_yaml: KeyBinding {
     key: Key::Char('y'),
     alt: None,
     desc: "Get resource YAML",
-    context: HContext::Overview,
+    context: HContext::Table,
+  },
+  export_yaml: KeyBinding {
+    key: Key::Char('e'),
+    alt: None,
+    desc: "Save the manifest of the resource to a file",
+    context: HContext::Table,
+  },
+  export_csv: KeyBinding {
+    key: Key::Char('E'),
+    alt: None,
+    desc: "Save the table as shown to a CSV file",
+    context: HContext::Table,
+  },
+  pipe_manifest: KeyBinding {
+    key: Key::Char('|'),
+    alt: None,
+    desc: "Pipe the manifest of the resource to the configured command",
+    context: HContext::Table,
+  },
+  diff_last_applied: KeyBinding {
+    key: Key::Char('D'),
+    alt: None,
+    desc: "Diff the resource against its last applied configuration, or two Helm revisions",
+    context: HContext::Table,
+  },
+  attach: KeyBinding {
+    key: Key::Char('I'),
+    alt: None,
+    desc: "Attach to the stdin and stdout of the selected container",
+    context: HContext::Table,
+  },
+  open_url: KeyBinding {
+    key: Key::Char('O'),
+    alt: None,
+    desc: "Open the address of a LoadBalancer service in the browser",
+    context: HContext::Table,
+  },
+  copy_kubectl: KeyBinding {
+    key: Key::Char('K'),
+    alt: None,
+    desc: "Copy the kubectl command for the current view",
+    context: HContext::General,
+  },
+  replay_back: KeyBinding {
+    key: Key::Char('<'),
+    alt: None,
+    desc: "Step back through a replayed recording",
+    context: HContext::General,
+  },
+  replay_forward: KeyBinding {
+    key: Key::Char('>'),
+    alt: None,
+    desc: "Step forward through a replayed recording",
+    context: HContext::General,
+  },
+  toggle_json: KeyBinding {
+    key: Key::Char('J'),
+    alt: None,
+    desc: "Toggle YAML and JSON for manifests",
+    context: HContext::Detail,
   },
   decode_secret: KeyBinding {
     key: Key::Char('x'),
     alt: None,
     desc: "Decode secret",
-    context: HContext::Overview,
+    context: HContext::Table,
+  },
+  helm_rollback: KeyBinding {
+    key: Key::Char('b'),
+    alt: None,
+    desc: "Roll back a Helm release to the selected revision",
+    context: HContext::Table,
+  },
+  helm_uninstall: KeyBinding {
+    key: Key::Char('u'),
+    alt: None,
+    desc: "Uninstall a Helm release",
+    context: HContext::Table,
+  },
+  helm_uninstall_keep_history: KeyBinding {
+    key: Key::Alt('u'),
+    alt: None,
+    desc: "Uninstall a Helm release keeping its history",
+    context: HContext::Table,
+  },
+  helm_values: KeyBinding {
+    key: Key::Char('V'),
+    alt: None,
+    desc: "Show the user supplied and computed values of a Helm release",
+    context: HContext::Table,
+  },
+  cycle_sort: KeyBinding {
+    key: Key::Char('o'),
+    alt: None,
+    desc: "Cycle table sort column and order",
+    context: HContext::Table,
+  },
+  cycle_timestamp_format: KeyBinding {
+    key: Key::Char('T'),
+    alt: None,
+    desc: "Cycle relative, local and UTC timestamps",
+    context: HContext::Table,
+  },
+  pick_columns: KeyBinding {
+    key: Key::Char('v'),
+    alt: None,
+    desc: "Pick table columns",
+    context: HContext::Table,
+  },
+  cycle_split_pane: KeyBinding {
+    key: Key::Char('p'),
+    alt: None,
+    desc: "Cycle split pane with describe, YAML, logs or nothing",
+    context: HContext::Table,
+  },
+  mark_row: KeyBinding {
+    key: Key::Char(' '),
+    alt: None,
+    desc: "Mark/Unmark table row",
+    context: HContext::Table,
+  },
+  mark_all_rows: KeyBinding {
+    key: Key::Ctrl('a'),
+    alt: None,
+    desc: "Mark/Unmark all table rows",
+    context: HContext::Table,
+  },
+  copy_name: KeyBinding {
+    key: Key::Char('Y'),
+    alt: None,
+    desc: "Copy name of selected resource",
+    context: HContext::Table,
+  },
+  copy_namespaced_name: KeyBinding {
+    key: Key::Ctrl('y'),
+    alt: None,
+    desc: "Copy namespace/name of selected resource",
+    context: HContext::Table,
+  },
+  copy_cell: KeyBinding {
+    key: Key::Alt('y'),
+    alt: None,
+    desc: "Pick a cell of selected row to copy",
+    context: HContext::Table,
   },
   jump_to_pods: KeyBinding {
     key: Key::Char('1'),
@@ -289,30 +706,141 @@ pub const DEFAULT_KEYBINDING: KeyBindings = KeyBindings {
   },
 };
 
-pub fn get_help_docs() -> Vec<Vec<String>> {
-  let items = DEFAULT_KEYBINDING.as_iter();
+/// help page rows grouped by context, a group starts with a single column row holding its
+/// title. Only bindings whose key, description or group contain the query are listed, and none
+/// changing the cluster in read-only mode
+pub fn get_help_docs(query: &str, read_only: bool) -> Vec<Vec<String>> {
+  let query = query.to_lowercase();
+  let bindings = key_bindings();
+  let items = bindings.as_iter();
 
-  items.iter().map(|it| help_row(it)).collect()
+  HELP_GROUPS
+    .iter()
+    .flat_map(|group| {
+      let rows: Vec<Vec<String>> = items
+        .iter()
+        .filter(|it| it.context == *group && !(read_only && bindings.is_mutating(it)))
+        .map(|it| help_row(it))
+        .filter(|row| {
+          query.is_empty()
+            || row.iter().any(|cell| cell.to_lowercase().contains(&query))
+            || group.to_string().to_lowercase().contains(&query)
+        })
+        .collect();
+      if rows.is_empty() {
+        rows
+      } else {
+        std::iter::once(vec![group.to_string()])
+          .chain(rows)
+          .collect()
+      }
+    })
+    .collect()
 }
 
 fn help_row(item: &KeyBinding) -> Vec<String> {
   vec![
-    if item.alt.is_some() {
-      format!("{} | {}", item.key, item.alt.unwrap())
-    } else {
-      item.key.to_string()
+    match item.alt {
+      Some(alt) => format!("{} | {}", item.key, alt),
+      None => item.key.to_string(),
     },
     String::from(item.desc),
-    item.context.to_string(),
   ]
 }
 
 #[cfg(test)]
 mod tests {
-  use super::DEFAULT_KEYBINDING;
+  use super::*;
 
   #[test]
   fn test_as_iter() {
     assert!(DEFAULT_KEYBINDING.as_iter().len() >= 28);
   }
+
+  #[test]
+  fn test_get_help_docs() {
+    let docs = get_help_docs("", false);
+    assert_eq!(docs[0], vec!["Global".to_string()]);
+    assert_eq!(
+      docs[1],
+      vec!["<Ctrl+c> | <q>".to_string(), "Quit".to_string()]
+    );
+    // every binding is listed once plus a title row for every group
+    assert_eq!(
+      docs.len(),
+      DEFAULT_KEYBINDING.as_iter().len() + HELP_GROUPS.len()
+    );
+
+    assert_eq!(
+      get_help_docs("SORT", false),
+      vec![
+        vec!["Resource tables".to_string()],
+        vec![
+          "<o>".to_string(),
+          "Cycle table sort column and order".to_string()
+        ],
+      ]
+    );
+    // matching a group lists all of its bindings
+    assert_eq!(
+      get_help_docs("utilization", false),
+      vec![
+        vec!["Global".to_string()],
+        vec![
+          "<U>".to_string(),
+          "Switch to resource utilization view".to_string()
+        ],
+        vec!["Utilization".to_string()],
+        vec!["<g>".to_string(), "Cycle through grouping".to_string()],
+      ]
+    );
+    assert!(get_help_docs("nothing like this", false).is_empty());
+    // the actions changing the cluster are left out in read-only mode
+    assert_eq!(get_help_docs("", true).len(), docs.len() - 6);
+    assert_eq!(get_help_docs("uninstall", false).len(), 3);
+    assert!(get_help_docs("uninstall", true).is_empty());
+  }
+
+  #[test]
+  fn test_default_keybindings_have_no_conflicts() {
+    assert_eq!(DEFAULT_KEYBINDING.conflicts(), Vec::<String>::new());
+  }
+
+  #[test]
+  fn test_with_overrides() {
+    let mut overrides = BTreeMap::new();
+    overrides.insert("quit".into(), KeyList(vec![Key::Ctrl('q')]));
+    overrides.insert("up".into(), KeyList(vec![Key::Up, Key::Char('w')]));
+    let bindings = KeyBindings::with_overrides(&overrides).unwrap();
+    assert_eq!(bindings.quit.key, Key::Ctrl('q'));
+    assert_eq!(bindings.quit.alt, None);
+    assert!(bindings.up.matches(Key::Char('w')));
+    assert!(!bindings.up.matches(Key::Char('k')));
+    assert_eq!(bindings.down.key, DEFAULT_KEYBINDING.down.key);
+
+    overrides.insert("qiut".into(), KeyList(vec![Key::Char('Q')]));
+    assert_eq!(
+      KeyBindings::with_overrides(&overrides)
+        .err()
+        .unwrap()
+        .to_string(),
+      "Unknown keybinding 'qiut' in config"
+    );
+  }
+
+  #[test]
+  fn test_with_overrides_conflicts() {
+    let mut overrides = BTreeMap::new();
+    // general binding clashes with an overview one
+    overrides.insert("toggle_theme".into(), KeyList(vec![Key::Char('d')]));
+    // overview and utilization bindings don't clash
+    overrides.insert("cycle_group_by".into(), KeyList(vec![Key::Char('o')]));
+    assert_eq!(
+      KeyBindings::with_overrides(&overrides)
+        .err()
+        .unwrap()
+        .to_string(),
+      "Conflicting keybindings: <d> is used by toggle_theme and describe_resource"
+    );
+  }
 }
@@ -1,7 +1,22 @@
 // Based on https://github.com/davidB/kubectl-view-allocations
 
-use kube::api::ObjectMeta;
-use kubectl_view_allocations::metrics::Usage;
+use std::{
+  collections::{BTreeMap, HashMap, VecDeque},
+  str::FromStr,
+  time::Instant,
+};
+
+use k8s_openapi::{
+  api::core::v1::{Node, Pod},
+  apimachinery::pkg::api::resource::Quantity,
+};
+use kube::api::{ObjectList, ObjectMeta};
+use kubectl_view_allocations::{
+  metrics::{PodMetrics, Usage},
+  qty::Qty,
+  tree::provide_prefix,
+  QtyByQualifier,
+};
 use serde::{Deserialize, Serialize};
 use tokio::sync::MutexGuard;
 
@@ -65,6 +80,406 @@ impl KubeNodeMetrics {
   }
 }
 
+/// average CPU and memory usage of the nodes as ratios, shown in the context info gauges
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct NodeUsage {
+  pub cpu: f64,
+  pub mem: f64,
+}
+
+impl NodeUsage {
+  pub fn from_node_metrics(node_metrics: &[KubeNodeMetrics]) -> Self {
+    // covert percent value from metrics to ratio that gauge can understand
+    let average = |f: fn(&KubeNodeMetrics) -> f64| {
+      if node_metrics.is_empty() {
+        0f64
+      } else {
+        node_metrics.iter().map(f).sum::<f64>() / node_metrics.len() as f64 / 100f64
+      }
+    };
+    NodeUsage {
+      cpu: average(|nm| nm.cpu_percent),
+      mem: average(|nm| nm.mem_percent),
+    }
+  }
+}
+
+/// a row of the resource utilization table, formatted when the metrics are fetched
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct UtilizationRow {
+  /// the resource or group with the tree lines before it
+  pub name: String,
+  pub utilization: String,
+  pub requested: String,
+  pub limit: String,
+  pub allocatable: String,
+  pub free: String,
+  /// more is requested or used than the limit
+  pub over_limit: bool,
+  /// nothing is requested or there is no limit
+  pub unbounded: bool,
+}
+
+/// the rows of the utilization table from the quantities grouped by `make_qualifiers`, groups
+/// without quantities are left out
+pub fn utilization_rows(data: &[(Vec<String>, Option<QtyByQualifier>)]) -> Vec<UtilizationRow> {
+  let prefixes = provide_prefix(data, |parent, item| parent.0.len() + 1 == item.0.len());
+  data
+    .iter()
+    .zip(prefixes.iter())
+    .filter_map(|((k, oqtys), prefix)| {
+      oqtys.as_ref().map(|qtys| UtilizationRow {
+        name: format!(
+          "{} {}",
+          prefix,
+          k.last().map(|x| x.as_str()).unwrap_or("???")
+        ),
+        utilization: qty_cell(&qtys.utilization, &qtys.allocatable),
+        requested: qty_cell(&qtys.requested, &qtys.allocatable),
+        limit: qty_cell(&qtys.limit, &qtys.allocatable),
+        allocatable: qty_cell(&qtys.allocatable, &None),
+        free: qty_cell(&qtys.calc_free(), &None),
+        over_limit: qtys.requested > qtys.limit || qtys.utilization > qtys.limit,
+        unbounded: is_empty(&qtys.requested) || is_empty(&qtys.limit),
+      })
+    })
+    .collect()
+}
+
+fn qty_cell(oqty: &Option<Qty>, o100: &Option<Qty>) -> String {
+  match oqty {
+    None => "__".into(),
+    Some(ref qty) => match o100 {
+      None => format!("{}", qty.adjust_scale()),
+      Some(q100) => format!("{} ({:.0}%)", qty.adjust_scale(), qty.calc_percentage(q100)),
+    },
+  }
+}
+
+fn is_empty(oqty: &Option<Qty>) -> bool {
+  match oqty {
+    Some(qty) => qty.is_zero(),
+    None => true,
+  }
+}
+
+/// Amounts of a single resource (CPU in cores, memory in bytes) summed over the cluster
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct CapacityUsage {
+  pub capacity: f64,
+  pub allocatable: f64,
+  pub requested: f64,
+  pub used: f64,
+}
+
+impl CapacityUsage {
+  /// ratio of requested to allocatable, anything above 1 means the cluster is over-committed
+  pub fn requested_ratio(&self) -> f64 {
+    ratio(self.requested, self.allocatable)
+  }
+
+  pub fn used_ratio(&self) -> f64 {
+    ratio(self.used, self.allocatable)
+  }
+
+  pub fn is_overcommitted(&self) -> bool {
+    self.requested > self.allocatable
+  }
+}
+
+/// Cluster wide capacity vs allocatable vs requested vs used for CPU and memory
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct ClusterCapacity {
+  pub cpu: CapacityUsage,
+  pub mem: CapacityUsage,
+  /// the pods were not listed, as in large cluster mode, so the requests are not known
+  pub requests_unknown: bool,
+}
+
+impl ClusterCapacity {
+  /// capacity of the nodes and the requests of the pods, without pods the requests are unknown
+  pub fn from_api(nodes: &ObjectList<Node>, pods: Option<&ObjectList<Pod>>) -> Self {
+    let mut capacity = ClusterCapacity {
+      requests_unknown: pods.is_none(),
+      ..ClusterCapacity::default()
+    };
+
+    nodes
+      .iter()
+      .filter_map(|n| n.status.as_ref())
+      .for_each(|st| {
+        capacity.cpu.capacity += get_quantity(&st.capacity, "cpu");
+        capacity.mem.capacity += get_quantity(&st.capacity, "memory");
+        capacity.cpu.allocatable += get_quantity(&st.allocatable, "cpu");
+        capacity.mem.allocatable += get_quantity(&st.allocatable, "memory");
+      });
+
+    pods
+      .iter()
+      .flat_map(|pods| pods.iter())
+      .filter(|pod| is_scheduled_and_active(pod))
+      .filter_map(|pod| pod.spec.as_ref())
+      .for_each(|spec| {
+        // effective request of a pod is the higher of the sum of its containers and its largest init container
+        let requests = |c: &k8s_openapi::api::core::v1::Container, key: &str| {
+          c.resources
+            .as_ref()
+            .map_or(0f64, |r| get_quantity(&r.requests, key))
+        };
+        for key in ["cpu", "memory"] {
+          let sum = spec
+            .containers
+            .iter()
+            .map(|c| requests(c, key))
+            .sum::<f64>();
+          let init = spec
+            .init_containers
+            .iter()
+            .flatten()
+            .map(|c| requests(c, key))
+            .fold(0f64, f64::max);
+          let overhead = get_quantity(&spec.overhead, key);
+          let total = sum.max(init) + overhead;
+          if key == "cpu" {
+            capacity.cpu.requested += total;
+          } else {
+            capacity.mem.requested += total;
+          }
+        }
+      });
+
+    capacity
+  }
+
+  /// update the actual usage from node metrics, keeping the rest intact
+  pub fn set_usage(&mut self, node_metrics: &[NodeMetrics]) {
+    self.cpu.used = node_metrics
+      .iter()
+      .map(|m| parse_quantity(m.usage.cpu.trim_matches('"')))
+      .sum();
+    self.mem.used = node_metrics
+      .iter()
+      .map(|m| parse_quantity(m.usage.memory.trim_matches('"')))
+      .sum();
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.cpu.capacity == 0f64 && self.mem.capacity == 0f64
+  }
+}
+
+/// kubelet evicts pods by default when less than 10% of the node filesystem is available
+pub const DISK_EVICTION_THRESHOLD: f64 = 0.10;
+/// start warning a little before the eviction threshold is reached
+pub const DISK_WARNING_THRESHOLD: f64 = 0.15;
+
+/// Subset of the kubelet stats summary API (`/api/v1/nodes/<node>/proxy/stats/summary`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStatsSummary {
+  pub node: NodeStats,
+  #[serde(default)]
+  pub pods: Vec<PodStats>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStats {
+  pub node_name: String,
+  pub fs: Option<FsStats>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodStats {
+  pub pod_ref: PodReference,
+  #[serde(rename = "ephemeral-storage")]
+  pub ephemeral_storage: Option<FsStats>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PodReference {
+  pub name: String,
+  pub namespace: String,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsStats {
+  pub available_bytes: Option<u64>,
+  pub capacity_bytes: Option<u64>,
+  pub used_bytes: Option<u64>,
+}
+
+impl FsStats {
+  pub fn used_percent(&self) -> Option<f64> {
+    match (self.used_bytes, self.capacity_bytes) {
+      (Some(used), Some(cap)) if cap > 0 => Some(utils::to_percent(used as f64, cap as f64)),
+      _ => None,
+    }
+  }
+
+  fn available_ratio(&self) -> Option<f64> {
+    match (self.available_bytes, self.capacity_bytes) {
+      (Some(available), Some(cap)) if cap > 0 => Some(available as f64 / cap as f64),
+      _ => None,
+    }
+  }
+
+  /// available space is getting close to the disk eviction threshold
+  pub fn is_near_eviction(&self) -> bool {
+    self
+      .available_ratio()
+      .is_some_and(|r| r < DISK_WARNING_THRESHOLD)
+  }
+
+  /// available space is below the disk eviction threshold, pods are likely being evicted
+  pub fn is_past_eviction(&self) -> bool {
+    self
+      .available_ratio()
+      .is_some_and(|r| r < DISK_EVICTION_THRESHOLD)
+  }
+}
+
+/// Ephemeral storage usage per node (by name) and per pod (by namespace/name) from the summary API
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct EphemeralStorage {
+  pub nodes: HashMap<String, FsStats>,
+  pub pods: HashMap<String, u64>,
+}
+
+impl EphemeralStorage {
+  pub fn add_summary(&mut self, summary: NodeStatsSummary) {
+    if let Some(fs) = summary.node.fs {
+      self.nodes.insert(summary.node.node_name, fs);
+    }
+    for pod in summary.pods {
+      if let Some(used) = pod.ephemeral_storage.and_then(|es| es.used_bytes) {
+        self.pods.insert(
+          format!("{}/{}", pod.pod_ref.namespace, pod.pod_ref.name),
+          used,
+        );
+      }
+    }
+  }
+
+  pub fn get_pod_usage(&self, namespace: &str, name: &str) -> Option<u64> {
+    self.pods.get(&format!("{}/{}", namespace, name)).copied()
+  }
+}
+
+/// max points kept per series, an hour worth of data at the default poll rate
+const MAX_HISTORY_POINTS: usize = 720;
+
+/// Metrics collected during the current session, as (seconds since start, value) points
+#[derive(Clone, Debug)]
+pub struct MetricsHistory {
+  started: Instant,
+  /// percentage of allocatable
+  pub cluster_cpu: VecDeque<(f64, f64)>,
+  pub cluster_mem: VecDeque<(f64, f64)>,
+  /// namespace/name of the pod tracked by the pod series
+  pub pod: Option<String>,
+  /// millicores
+  pub pod_cpu: VecDeque<(f64, f64)>,
+  /// mebibytes
+  pub pod_mem: VecDeque<(f64, f64)>,
+}
+
+impl Default for MetricsHistory {
+  fn default() -> Self {
+    MetricsHistory {
+      started: Instant::now(),
+      cluster_cpu: VecDeque::new(),
+      cluster_mem: VecDeque::new(),
+      pod: None,
+      pod_cpu: VecDeque::new(),
+      pod_mem: VecDeque::new(),
+    }
+  }
+}
+
+impl MetricsHistory {
+  pub fn elapsed(&self) -> f64 {
+    self.started.elapsed().as_secs_f64()
+  }
+
+  pub fn add_cluster_point(&mut self, at: f64, capacity: &ClusterCapacity) {
+    if capacity.cpu.allocatable > 0f64 {
+      push_point(
+        &mut self.cluster_cpu,
+        (at, capacity.cpu.used_ratio() * 100f64),
+      );
+    }
+    if capacity.mem.allocatable > 0f64 {
+      push_point(
+        &mut self.cluster_mem,
+        (at, capacity.mem.used_ratio() * 100f64),
+      );
+    }
+  }
+
+  /// record usage of a pod, the pod series start over when a different pod is tracked
+  pub fn add_pod_point(&mut self, at: f64, pod: String, metrics: &PodMetrics) {
+    if self.pod.as_ref() != Some(&pod) {
+      self.pod = Some(pod);
+      self.pod_cpu.clear();
+      self.pod_mem.clear();
+    }
+    let cpu = metrics
+      .containers
+      .iter()
+      .map(|c| parse_quantity(&c.usage.cpu))
+      .sum::<f64>();
+    let mem = metrics
+      .containers
+      .iter()
+      .map(|c| parse_quantity(&c.usage.memory))
+      .sum::<f64>();
+    push_point(&mut self.pod_cpu, (at, cpu * 1000f64));
+    push_point(&mut self.pod_mem, (at, mem / (1024f64 * 1024f64)));
+  }
+}
+
+fn push_point(series: &mut VecDeque<(f64, f64)>, point: (f64, f64)) {
+  if series.len() >= MAX_HISTORY_POINTS {
+    series.pop_front();
+  }
+  series.push_back(point);
+}
+
+fn is_scheduled_and_active(pod: &Pod) -> bool {
+  let scheduled = pod
+    .spec
+    .as_ref()
+    .is_some_and(|spec| spec.node_name.is_some());
+  let finished = pod
+    .status
+    .as_ref()
+    .and_then(|st| st.phase.as_deref())
+    .is_some_and(|phase| phase == "Succeeded" || phase == "Failed");
+  scheduled && !finished
+}
+
+fn get_quantity(quantities: &Option<BTreeMap<String, Quantity>>, key: &str) -> f64 {
+  quantities
+    .as_ref()
+    .and_then(|q| q.get(key))
+    .map_or(0f64, |q| parse_quantity(&q.0))
+}
+
+pub fn parse_quantity(q: &str) -> f64 {
+  Qty::from_str(q).map_or(0f64, |q| f64::from(&q))
+}
+
+fn ratio(value: f64, total: f64) -> f64 {
+  if total > 0f64 {
+    value / total
+  } else {
+    0f64
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use tokio::sync::Mutex;
@@ -72,6 +487,191 @@ mod tests {
   use super::*;
   use crate::app::test_utils::load_resource_from_file;
 
+  #[test]
+  #[allow(clippy::float_cmp)]
+  fn test_node_usage() {
+    assert_eq!(NodeUsage::from_node_metrics(&[]), NodeUsage::default());
+    let node_metrics = [
+      KubeNodeMetrics {
+        cpu_percent: 80f64,
+        mem_percent: 10f64,
+        ..KubeNodeMetrics::default()
+      },
+      KubeNodeMetrics {
+        cpu_percent: 60f64,
+        mem_percent: 30f64,
+        ..KubeNodeMetrics::default()
+      },
+    ];
+    let usage = NodeUsage::from_node_metrics(&node_metrics);
+    assert_eq!(usage.cpu, 0.7f64);
+    assert_eq!(usage.mem, 0.2f64);
+  }
+
+  #[test]
+  fn test_utilization_rows() {
+    let qty = |s: &str| Some(Qty::from_str(s).unwrap());
+    let data = vec![
+      (vec!["cpu".to_owned()], None),
+      (
+        vec!["cpu".to_owned(), "web".to_owned()],
+        Some(QtyByQualifier {
+          limit: qty("1"),
+          requested: qty("500m"),
+          allocatable: qty("2"),
+          utilization: qty("1500m"),
+        }),
+      ),
+    ];
+    let rows = utilization_rows(&data);
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].name.ends_with(" web"));
+    assert_eq!(rows[0].requested, "500.0m (25%)");
+    assert_eq!(rows[0].free, "1.0");
+    assert!(rows[0].over_limit);
+    assert!(!rows[0].unbounded);
+  }
+
+  #[test]
+  #[allow(clippy::float_cmp)]
+  fn test_cluster_capacity_from_api() {
+    let nodes = load_resource_from_file("nodes");
+    let pods = load_resource_from_file("pods");
+    let node_metrics: ObjectList<NodeMetrics> = load_resource_from_file("node_metrics");
+
+    let mut capacity = ClusterCapacity::from_api(&nodes, Some(&pods));
+    assert!(!capacity.is_empty());
+    assert!(!capacity.requests_unknown);
+    assert_eq!(capacity.cpu.capacity, 8f64);
+    assert_eq!(capacity.cpu.allocatable, 8f64);
+    assert_eq!(capacity.mem.capacity, 32734444f64 * 1024f64);
+    assert_eq!(capacity.mem.allocatable, 32734444f64 * 1024f64);
+    // only running and pending pods that are scheduled on a node count
+    assert_eq!(capacity.cpu.requested, 0.6f64);
+    assert_eq!(capacity.mem.requested, 476f64 * 1024f64 * 1024f64);
+    assert!(!capacity.cpu.is_overcommitted());
+    assert_eq!(capacity.cpu.requested_ratio(), 0.075f64);
+
+    capacity.set_usage(&node_metrics.items);
+    assert!((capacity.cpu.used - 0.264f64).abs() < 0.001);
+    assert_eq!(capacity.mem.used, (582980f64 + 282980f64) * 1024f64);
+    assert_eq!(capacity.cpu.allocatable, 8f64);
+
+    // large cluster mode lists no pods
+    let without_pods = ClusterCapacity::from_api(&nodes, None);
+    assert!(without_pods.requests_unknown);
+    assert_eq!(without_pods.cpu.requested, 0f64);
+    assert_eq!(without_pods.cpu.allocatable, 8f64);
+  }
+
+  #[test]
+  #[allow(clippy::float_cmp)]
+  fn test_metrics_history() {
+    let mut history = MetricsHistory::default();
+    let capacity = ClusterCapacity {
+      cpu: CapacityUsage {
+        allocatable: 4f64,
+        used: 1f64,
+        ..CapacityUsage::default()
+      },
+      ..ClusterCapacity::default()
+    };
+    history.add_cluster_point(1f64, &capacity);
+    assert_eq!(history.cluster_cpu, VecDeque::from(vec![(1f64, 25f64)]));
+    // no allocatable memory known yet so nothing is recorded
+    assert!(history.cluster_mem.is_empty());
+
+    for i in 0..MAX_HISTORY_POINTS {
+      history.add_cluster_point(i as f64 + 2f64, &capacity);
+    }
+    assert_eq!(history.cluster_cpu.len(), MAX_HISTORY_POINTS);
+    assert_eq!(history.cluster_cpu.front(), Some(&(2f64, 25f64)));
+
+    let pod_metrics: PodMetrics = serde_yaml::from_str(
+      r#"
+metadata:
+  name: web
+  namespace: default
+timestamp: "2021-05-10T21:48:20Z"
+window: 30s
+containers:
+  - name: app
+    usage:
+      cpu: 150000000n
+      memory: 10240Ki
+  - name: sidecar
+    usage:
+      cpu: 50m
+      memory: 6Mi
+"#,
+    )
+    .unwrap();
+    history.add_pod_point(1f64, "default/web".into(), &pod_metrics);
+    history.add_pod_point(2f64, "default/web".into(), &pod_metrics);
+    assert_eq!(history.pod_cpu.len(), 2);
+    assert!((history.pod_cpu[0].1 - 200f64).abs() < 0.001);
+    assert_eq!(history.pod_mem[0].1, 16f64);
+
+    history.add_pod_point(3f64, "default/api".into(), &pod_metrics);
+    assert_eq!(history.pod, Some("default/api".into()));
+    assert_eq!(history.pod_cpu.len(), 1);
+  }
+
+  #[test]
+  #[allow(clippy::float_cmp)]
+  fn test_ephemeral_storage_from_summary() {
+    let summary: NodeStatsSummary = serde_json::from_str(
+      r#"{
+        "node": {
+          "nodeName": "node-1",
+          "fs": { "availableBytes": 12, "capacityBytes": 100, "usedBytes": 88 }
+        },
+        "pods": [
+          {
+            "podRef": { "name": "web", "namespace": "default", "uid": "123" },
+            "ephemeral-storage": { "usedBytes": 4096 }
+          },
+          { "podRef": { "name": "api", "namespace": "default", "uid": "456" } }
+        ]
+      }"#,
+    )
+    .unwrap();
+
+    let mut storage = EphemeralStorage::default();
+    storage.add_summary(summary);
+
+    let fs = storage.nodes.get("node-1").unwrap();
+    assert_eq!(fs.used_percent(), Some(88f64));
+    assert!(fs.is_near_eviction());
+    assert!(!fs.is_past_eviction());
+    assert_eq!(storage.get_pod_usage("default", "web"), Some(4096));
+    assert_eq!(storage.get_pod_usage("default", "api"), None);
+
+    let low_fs = FsStats {
+      available_bytes: Some(5),
+      capacity_bytes: Some(100),
+      used_bytes: None,
+    };
+    assert!(low_fs.is_past_eviction());
+    assert_eq!(low_fs.used_percent(), None);
+    assert!(!FsStats::default().is_near_eviction());
+  }
+
+  #[test]
+  #[allow(clippy::float_cmp)]
+  fn test_capacity_usage_ratios() {
+    let usage = CapacityUsage {
+      capacity: 4f64,
+      allocatable: 2f64,
+      requested: 3f64,
+      used: 1f64,
+    };
+    assert!(usage.is_overcommitted());
+    assert_eq!(usage.requested_ratio(), 1.5f64);
+    assert_eq!(usage.used_ratio(), 0.5f64);
+    assert_eq!(CapacityUsage::default().requested_ratio(), 0f64);
+  }
+
   #[tokio::test]
   async fn test_kube_node_metrics_from_api() {
     let node_metrics = load_resource_from_file("node_metrics");
@@ -1,56 +1,124 @@
+pub(crate) mod alerts;
+pub(crate) mod command_palette;
 pub(crate) mod configmaps;
+pub(crate) mod confirmation;
+pub(crate) mod connection;
+pub(crate) mod context_switcher;
 pub(crate) mod contexts;
 pub(crate) mod cronjobs;
+pub(crate) mod custom_views;
 pub(crate) mod daemonsets;
 pub(crate) mod deployments;
+pub(crate) mod export;
+pub(crate) mod fleet;
+pub(crate) mod helm;
+pub(crate) mod hooks;
 pub(crate) mod jobs;
 pub(crate) mod key_binding;
 pub(crate) mod metrics;
 pub(crate) mod models;
 pub(crate) mod nodes;
+pub(crate) mod notifications;
 pub(crate) mod ns;
+pub(crate) mod plugins;
 pub(crate) mod pods;
+pub(crate) mod recording;
 pub(crate) mod replicasets;
 pub(crate) mod replication_controllers;
+pub(crate) mod resource_finder;
 pub(crate) mod roles;
 pub(crate) mod secrets;
 pub(crate) mod statefulsets;
 pub(crate) mod storageclass;
 pub(crate) mod svcs;
+pub(crate) mod table_filter;
 mod utils;
 
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc, time::Instant};
+
 use anyhow::anyhow;
+use k8s_openapi::chrono::Local;
 use kube::config::Kubeconfig;
-use kubectl_view_allocations::{GroupBy, QtyByQualifier};
+use kubectl_view_allocations::GroupBy;
+use serde::Serialize;
 use tokio::sync::mpsc::Sender;
 use tui::layout::Rect;
 
 use self::{
+  alerts::Alerts,
+  command_palette::{CommandKind, CommandPalette},
   configmaps::KubeConfigMap,
+  confirmation::Confirmation,
+  connection::Connection,
+  context_switcher::{add_recent_context, ContextSwitcher},
   contexts::KubeContext,
   cronjobs::KubeCronJob,
+  custom_views::{CustomColumn, CustomView, KubeCustomObject},
   daemonsets::KubeDaemonSet,
   deployments::KubeDeployment,
+  export::{template_path, ExportPrompt},
+  fleet::{ClusterHealth, FleetEvent},
+  helm::{latest_releases, release_history, KubeHelmRelease},
+  hooks::Hooks,
   jobs::KubeJob,
-  key_binding::DEFAULT_KEYBINDING,
-  metrics::KubeNodeMetrics,
-  models::{LogsState, ScrollableTxt, StatefulList, StatefulTable, TabRoute, TabsState},
+  key_binding::key_bindings,
+  metrics::{
+    ClusterCapacity, EphemeralStorage, KubeNodeMetrics, MetricsHistory, NodeUsage, UtilizationRow,
+  },
+  models::{
+    KubeResource, LogLimits, LogsState, ManifestFormat, ResourceTable, ScrollableTxt, StatefulList,
+    StatefulTable, TabRoute, TabsState, TimestampFormat,
+  },
   nodes::KubeNode,
+  notifications::{NotificationLevel, Notifications},
   ns::KubeNs,
-  pods::{KubeContainer, KubePod},
+  plugins::{plugin_help_docs, Plugin, PluginRun},
+  pods::{AttachRequest, KubeContainer, KubePod},
+  recording::Replay,
   replicasets::KubeReplicaSet,
   replication_controllers::KubeReplicationController,
+  resource_finder::{FinderMatch, ResourceFinder, SEARCHED_BLOCKS},
   roles::{KubeClusterRole, KubeClusterRoleBinding, KubeRole, KubeRoleBinding},
   secrets::KubeSecret,
   statefulsets::KubeStatefulSet,
   storageclass::KubeStorageClass,
   svcs::KubeSvc,
+  table_filter::{add_to_history, FilterInput, TableFilter},
 };
 use super::{
   cmd::IoCmdEvent,
+  config::{ConfirmConfig, ConfirmPolicy, PluginOutput},
+  event::Key,
   network::{stream::IoStreamEvent, IoEvent},
+  state::UiState,
 };
 
+/// what the pane next to a resource table shows for the selected item
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum SplitPane {
+  Describe,
+  Yaml,
+  Logs,
+}
+
+/// how much room the tables get, compact hides the header and info blocks, wide shows extra
+/// table columns on large terminals
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum LayoutMode {
+  Normal,
+  Compact,
+  Wide,
+}
+
+/// terminal width from which the wide layout shows extra columns
+pub const WIDE_LAYOUT_MIN_WIDTH: u16 = 160;
+
+/// command diffing manifests when `diff_command` isn't set
+const DEFAULT_DIFF_COMMAND: &str = "diff -u";
+const DEFAULT_LARGE_CLUSTER_THRESHOLD: usize = 5000;
+/// the disk usage is read from the kubelet of every node, so only every so many metrics polls
+const EPHEMERAL_STORAGE_POLL_FACTOR: u64 = 5;
+
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum ActiveBlock {
   Help,
@@ -78,7 +146,15 @@ pub enum ActiveBlock {
   RoleBindings,
   ClusterRoles,
   ClusterRoleBinding,
+  HelmReleases,
+  HelmHistory,
   More,
+  MetricsHistory,
+  Columns,
+  Fleet,
+  FleetEvents,
+  /// a table from `custom_views` in the config, by its index there
+  CustomView(usize),
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -86,6 +162,8 @@ pub enum RouteId {
   Home,
   Contexts,
   Utilization,
+  MetricsHistory,
+  Fleet,
   HelpMenu,
 }
 
@@ -115,9 +193,20 @@ pub struct Data {
   pub contexts: StatefulTable<KubeContext>,
   pub active_context: Option<KubeContext>,
   pub node_metrics: Vec<KubeNodeMetrics>,
+  /// average usage of the nodes, kept with `node_metrics`
+  pub node_usage: NodeUsage,
+  pub cluster_capacity: ClusterCapacity,
+  pub metrics_history: MetricsHistory,
+  pub ephemeral_storage: EphemeralStorage,
+  /// set while listing node metrics fails so it is only reported once
+  pub metrics_server_unreachable: bool,
   pub logs: LogsState,
   pub describe_out: ScrollableTxt,
-  pub metrics: StatefulTable<(Vec<String>, Option<QtyByQualifier>)>,
+  /// the describe or YAML output of the split pane, apart from the describe and YAML views
+  pub split_pane_out: ScrollableTxt,
+  /// the logs of the container selected next to the split pane
+  pub split_pane_logs: LogsState,
+  pub metrics: StatefulTable<UtilizationRow>,
   pub namespaces: StatefulTable<KubeNs>,
   pub nodes: StatefulTable<KubeNode>,
   pub pods: StatefulTable<KubePod>,
@@ -137,6 +226,13 @@ pub struct Data {
   pub role_bindings: StatefulTable<KubeRoleBinding>,
   pub cluster_roles: StatefulTable<KubeClusterRole>,
   pub cluster_role_binding: StatefulTable<KubeClusterRoleBinding>,
+  pub helm_releases: StatefulTable<KubeHelmRelease>,
+  /// every revision of the releases, the table shows the latest of each
+  pub helm_revisions: Vec<KubeHelmRelease>,
+  /// the revisions of the selected release
+  pub helm_history: StatefulTable<KubeHelmRelease>,
+  /// the objects of each custom view, by the index of the view
+  pub custom_objects: BTreeMap<usize, StatefulTable<KubeCustomObject>>,
 }
 
 /// selected data items
@@ -145,6 +241,8 @@ pub struct Selected {
   pub pod: Option<String>,
   pub container: Option<String>,
   pub context: Option<String>,
+  /// namespace and name of the release whose history is open
+  pub helm_release: Option<(String, String)>,
 }
 
 /// Holds main application state
@@ -158,23 +256,121 @@ pub struct App {
   pub main_tabs: TabsState,
   pub context_tabs: TabsState,
   pub more_resources_menu: StatefulList<(String, ActiveBlock)>,
+  pub column_picker: StatefulList<(String, bool)>,
+  /// the `:` command input, when open it takes all key presses
+  pub command_palette: Option<CommandPalette>,
+  /// fuzzy search over all cached resources, when open it takes all key presses
+  pub resource_finder: Option<ResourceFinder>,
+  /// fuzzy search over the contexts to switch to, when open it takes all key presses
+  pub context_switcher: Option<ContextSwitcher>,
+  /// header and value of the cells of the selected row to copy one of, takes all key presses
+  pub cell_picker: Option<StatefulList<(String, String)>>,
+  /// name filter input of the focused resource table, takes all key presses
+  pub filter_input: Option<FilterInput>,
+  /// where to save the manifest of the selected resource, takes all key presses
+  pub export_prompt: Option<ExportPrompt>,
+  /// filters used before, newest first
+  pub filter_history: Vec<String>,
+  /// contexts switched to, most recent first
+  pub recent_contexts: Vec<String>,
+  /// namespace each context was left in, None for all namespaces
+  pub context_namespaces: BTreeMap<String, Option<String>>,
+  /// contexts compared in the fleet view
+  pub fleet_contexts: Vec<String>,
+  pub fleet: StatefulTable<ClusterHealth>,
+  /// warning events of the fleet contexts, newest first
+  pub fleet_events: StatefulTable<FleetEvent>,
+  /// name and filter of the filters saved in the config
+  pub saved_filters: Vec<(String, String)>,
+  pub split_pane: Option<SplitPane>,
+  /// the describe command, YAML or container last shown in the split pane
+  split_pane_item: Option<String>,
+  /// a log stream is filling the split pane
+  pub split_pane_streaming: bool,
   pub show_info_bar: bool,
+  pub layout_mode: LayoutMode,
+  /// how the Age columns show creation times
+  pub timestamp_format: TimestampFormat,
+  /// whether manifests are shown and exported as YAML or JSON
+  pub manifest_format: ManifestFormat,
+  /// namespaces listed first in the namespaces block, in the order their number keys select them
+  pub pinned_namespaces: Vec<String>,
   pub is_loading: bool,
   pub is_streaming: bool,
+  /// the state changed since the screen was last drawn, the screen is only drawn again when it did
+  pub redraw: bool,
   pub is_routing: bool,
   pub tick_until_poll: u64,
+  /// resources polled every so many ticks instead of `tick_until_poll`
+  pub resource_poll_ticks: Vec<(ActiveBlock, u64)>,
+  /// resource tabs kept up to date while other tabs are shown
+  pub pinned_tabs: Vec<ActiveBlock>,
+  pub tick_until_metrics_poll: u64,
   pub tick_count: u64,
   pub enhanced_graphics: bool,
+  /// draw borders, gauges and markers with ASCII only
+  pub ascii_graphics: bool,
+  /// actions that change resources in the cluster are disabled
+  pub read_only: bool,
   pub table_cols: u16,
   pub size: Rect,
   pub api_error: String,
-  pub dialog: Option<String>,
-  pub confirm: bool,
+  /// whether the API server is reachable, polls wait for the next retry while it isn't
+  pub connection: Connection,
+  /// number of pods from which on large cluster mode is used, 0 to never use it
+  pub large_cluster_threshold: usize,
+  /// leave out the work that gets slow with many objects, the pod counts of nodes and disk usage
+  pub large_cluster: bool,
+  /// transient results of background operations, shown on top of the current view
+  pub notifications: Notifications,
+  /// confirmation asked before each action that changes resources
+  pub confirm_policies: ConfirmConfig,
+  /// the confirmation asked for the action about to run, when open it takes all key presses
+  pub confirmation: Option<Confirmation>,
+  /// shell command the manifest of the selected resource is piped to
+  pub pipe_command: Option<String>,
+  /// container to attach to, taken by the UI loop which hands it the terminal until it detaches
+  pub attach_request: Option<AttachRequest>,
+  /// external commands run on the selected resource with a key
+  pub plugins: Vec<Plugin>,
+  /// plugin taken by the UI loop like `attach_request`, to run with the terminal
+  pub plugin_request: Option<PluginRun>,
+  /// name of the plugin and its captured output, shown in a popup that takes all key presses
+  pub plugin_output: Option<(String, ScrollableTxt)>,
+  /// tables of custom resources from the config, listed under More
+  pub custom_views: Vec<CustomView>,
+  /// columns from the config added to the tables of resources
+  pub extra_columns: Vec<(ActiveBlock, Vec<CustomColumn>)>,
+  /// Lua hooks decorating table rows and turning events into alerts
+  pub hooks: Option<Arc<Hooks>>,
+  /// file the lists received from the cluster are appended to
+  pub recording: Option<PathBuf>,
+  /// recording shown instead of the cluster
+  pub replay: Option<Replay>,
+  /// template of the paths files are saved to, without the extension
+  pub export_path: Option<String>,
+  /// command diffing two manifest files given as its last arguments
+  pub diff_command: String,
+  /// copy with OSC 52 escape sequences through the terminal instead of the system clipboard
+  pub osc52_clipboard: bool,
+  /// failing pods and nodes, for desktop notifications
+  pub alerts: Alerts,
   pub light_theme: bool,
   pub refresh: bool,
   pub log_auto_scroll: bool,
+  /// lines and bytes of a log kept while tailing it
+  pub log_limits: LogLimits,
   pub utilization_group_by: Vec<GroupBy>,
   pub help_docs: StatefulTable<Vec<String>>,
+  /// filter for the help page and whether it is being typed
+  pub help_query: String,
+  pub is_help_searching: bool,
+  /// whether a search in the describe or YAML view is being typed
+  pub is_detail_searching: bool,
+  /// vim style count typed before a scroll key, like the 15 of `15j`
+  pub pending_count: usize,
+  /// the scroll to top key was pressed once, letter keys have to be pressed twice
+  pub pending_top: bool,
   pub data: Data,
 }
 
@@ -186,18 +382,26 @@ impl Default for Data {
       contexts: StatefulTable::new(),
       active_context: None,
       node_metrics: vec![],
+      node_usage: NodeUsage::default(),
+      cluster_capacity: ClusterCapacity::default(),
+      metrics_history: MetricsHistory::default(),
+      ephemeral_storage: EphemeralStorage::default(),
+      metrics_server_unreachable: false,
       namespaces: StatefulTable::new(),
       selected: Selected {
         ns: None,
         pod: None,
         container: None,
         context: None,
+        helm_release: None,
       },
       logs: LogsState::new(String::default()),
       describe_out: ScrollableTxt::new(),
+      split_pane_out: ScrollableTxt::new(),
+      split_pane_logs: LogsState::new(String::default()),
       metrics: StatefulTable::new(),
       nodes: StatefulTable::new(),
-      pods: StatefulTable::new(),
+      pods: StatefulTable::new().with_hidden_columns(&["Node", "IP"]),
       containers: StatefulTable::new(),
       services: StatefulTable::new(),
       config_maps: StatefulTable::new(),
@@ -214,10 +418,207 @@ impl Default for Data {
       role_bindings: StatefulTable::new(),
       cluster_roles: StatefulTable::new(),
       cluster_role_binding: StatefulTable::new(),
+      helm_releases: StatefulTable::new(),
+      helm_revisions: vec![],
+      helm_history: StatefulTable::new(),
+      custom_objects: BTreeMap::new(),
+    }
+  }
+}
+
+impl Data {
+  pub fn set_node_metrics(&mut self, node_metrics: Vec<KubeNodeMetrics>) {
+    self.node_metrics = node_metrics;
+    self.update_node_usage();
+  }
+
+  /// average the usage of the nodes again, after their percentages were set from the nodes
+  pub fn update_node_usage(&mut self) {
+    self.node_usage = NodeUsage::from_node_metrics(&self.node_metrics);
+  }
+
+  /// show the latest revision of each release, and the revisions of the one whose history is open
+  pub fn set_helm_revisions(&mut self, revisions: Vec<KubeHelmRelease>) {
+    if let Some((namespace, name)) = &self.selected.helm_release {
+      self
+        .helm_history
+        .set_sorted_items(release_history(&revisions, namespace, name));
+    }
+    self
+      .helm_releases
+      .set_sorted_items(latest_releases(revisions.clone()));
+    self.helm_revisions = revisions;
+  }
+
+  /// the resource table drawn for the given block, if it is one
+  pub fn resource_table(&mut self, block: ActiveBlock) -> Option<&mut dyn ResourceTable> {
+    match block {
+      ActiveBlock::Pods => Some(&mut self.pods),
+      ActiveBlock::Containers => Some(&mut self.containers),
+      ActiveBlock::Services => Some(&mut self.services),
+      ActiveBlock::Nodes => Some(&mut self.nodes),
+      ActiveBlock::ConfigMaps => Some(&mut self.config_maps),
+      ActiveBlock::StatefulSets => Some(&mut self.stateful_sets),
+      ActiveBlock::ReplicaSets => Some(&mut self.replica_sets),
+      ActiveBlock::Deployments => Some(&mut self.deployments),
+      ActiveBlock::Jobs => Some(&mut self.jobs),
+      ActiveBlock::DaemonSets => Some(&mut self.daemon_sets),
+      ActiveBlock::CronJobs => Some(&mut self.cronjobs),
+      ActiveBlock::Secrets => Some(&mut self.secrets),
+      ActiveBlock::RplCtrl => Some(&mut self.rpl_ctrls),
+      ActiveBlock::StorageClasses => Some(&mut self.storage_classes),
+      ActiveBlock::Roles => Some(&mut self.roles),
+      ActiveBlock::RoleBindings => Some(&mut self.role_bindings),
+      ActiveBlock::ClusterRoles => Some(&mut self.cluster_roles),
+      ActiveBlock::ClusterRoleBinding => Some(&mut self.cluster_role_binding),
+      ActiveBlock::HelmReleases => Some(&mut self.helm_releases),
+      ActiveBlock::HelmHistory => Some(&mut self.helm_history),
+      ActiveBlock::CustomView(i) => Some(
+        self
+          .custom_objects
+          .entry(i)
+          .or_insert_with(StatefulTable::new),
+      ),
+      _ => None,
+    }
+  }
+
+  /// the describe command and YAML of the item selected in the given block
+  pub fn selected_details(&self, block: ActiveBlock) -> Option<(IoCmdEvent, String)> {
+    match block {
+      ActiveBlock::Pods => get_details(&self.pods, "pod", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::Services => get_details(&self.services, "service", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::Nodes => get_details(&self.nodes, "node", |r| (r.name.to_owned(), None)),
+      ActiveBlock::ConfigMaps => get_details(&self.config_maps, "configmap", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::StatefulSets => get_details(&self.stateful_sets, "statefulset", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::ReplicaSets => get_details(&self.replica_sets, "replicaset", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::Deployments => get_details(&self.deployments, "deployment", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::Jobs => get_details(&self.jobs, "job", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::DaemonSets => get_details(&self.daemon_sets, "daemonset", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::CronJobs => get_details(&self.cronjobs, "cronjob", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::Secrets => get_details(&self.secrets, "secret", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::RplCtrl => get_details(&self.rpl_ctrls, "replicationcontroller", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::StorageClasses => get_details(&self.storage_classes, "storageclass", |r| {
+        (r.name.to_owned(), None)
+      }),
+      ActiveBlock::Roles => get_details(&self.roles, "roles", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::RoleBindings => get_details(&self.role_bindings, "rolebindings", |r| {
+        (r.name.to_owned(), Some(r.namespace.to_owned()))
+      }),
+      ActiveBlock::ClusterRoles => get_details(&self.cluster_roles, "clusterroles", |r| {
+        (r.name.to_owned(), None)
+      }),
+      ActiveBlock::ClusterRoleBinding => {
+        get_details(&self.cluster_role_binding, "clusterrolebinding", |r| {
+          (r.name.to_owned(), None)
+        })
+      }
+      // the secret keeping the latest revision of the release
+      ActiveBlock::HelmReleases => get_details(&self.helm_releases, "secret", |r| {
+        (
+          r.get_k8s_obj().metadata.name.clone().unwrap_or_default(),
+          Some(r.namespace.to_owned()),
+        )
+      }),
+      ActiveBlock::CustomView(index) => {
+        let table = self.custom_objects.get(&index)?;
+        let res = table.state.selected().and_then(|i| table.items.get(i))?;
+        get_details(table, &res.resource, |r| {
+          (
+            r.name.to_owned(),
+            (!r.namespace.is_empty()).then(|| r.namespace.to_owned()),
+          )
+        })
+      }
+      _ => None,
     }
   }
 }
 
+fn block_title(block: ActiveBlock) -> &'static str {
+  match block {
+    ActiveBlock::Help => "Help",
+    ActiveBlock::Pods => "Pods",
+    ActiveBlock::Containers => "Containers",
+    ActiveBlock::Logs => "Logs",
+    ActiveBlock::Services => "Services",
+    ActiveBlock::Nodes => "Nodes",
+    ActiveBlock::Deployments => "Deployments",
+    ActiveBlock::ConfigMaps => "ConfigMaps",
+    ActiveBlock::StatefulSets => "StatefulSets",
+    ActiveBlock::ReplicaSets => "ReplicaSets",
+    ActiveBlock::Namespaces => "Namespaces",
+    ActiveBlock::Describe => "Describe",
+    ActiveBlock::Yaml => "YAML",
+    ActiveBlock::Contexts => "Contexts",
+    ActiveBlock::Utilization => "Utilization",
+    ActiveBlock::Jobs => "Jobs",
+    ActiveBlock::DaemonSets => "DaemonSets",
+    ActiveBlock::CronJobs => "CronJobs",
+    ActiveBlock::Secrets => "Secrets",
+    ActiveBlock::RplCtrl => "ReplicationControllers",
+    ActiveBlock::StorageClasses => "StorageClasses",
+    ActiveBlock::Roles => "Roles",
+    ActiveBlock::RoleBindings => "RoleBindings",
+    ActiveBlock::ClusterRoles => "ClusterRoles",
+    ActiveBlock::ClusterRoleBinding => "ClusterRoleBindings",
+    ActiveBlock::HelmReleases => "HelmReleases",
+    ActiveBlock::HelmHistory => "History",
+    ActiveBlock::More => "More",
+    ActiveBlock::MetricsHistory => "Metrics History",
+    ActiveBlock::Columns => "Columns",
+    ActiveBlock::Fleet => "Fleet",
+    ActiveBlock::FleetEvents => "Warning Events",
+    ActiveBlock::CustomView(_) => "Custom Resources",
+  }
+}
+
+fn get_details<T, S, F>(
+  table: &StatefulTable<T>,
+  kind: &str,
+  name_and_ns: F,
+) -> Option<(IoCmdEvent, String)>
+where
+  T: KubeResource<S>,
+  S: Serialize,
+  F: Fn(&T) -> (String, Option<String>),
+{
+  let res = table.state.selected().and_then(|i| table.items.get(i))?;
+  let (value, ns) = name_and_ns(res);
+  Some((
+    IoCmdEvent::GetDescribe {
+      kind: kind.to_owned(),
+      value,
+      ns,
+    },
+    res.resource_to_yaml(),
+  ))
+}
+
 impl Default for App {
   fn default() -> Self {
     App {
@@ -231,7 +632,7 @@ impl Default for App {
         TabRoute {
           title: format!(
             "Active Context {}",
-            DEFAULT_KEYBINDING.jump_to_current_context.key
+            key_bindings().jump_to_current_context.key
           ),
           route: Route {
             active_block: ActiveBlock::Pods,
@@ -239,92 +640,103 @@ impl Default for App {
           },
         },
         TabRoute {
-          title: format!(
-            "All Contexts {}",
-            DEFAULT_KEYBINDING.jump_to_all_context.key
-          ),
+          title: format!("All Contexts {}", key_bindings().jump_to_all_context.key),
           route: Route {
             active_block: ActiveBlock::Contexts,
             id: RouteId::Contexts,
           },
         },
         TabRoute {
-          title: format!("Utilization {}", DEFAULT_KEYBINDING.jump_to_utilization.key),
+          title: format!("Utilization {}", key_bindings().jump_to_utilization.key),
           route: Route {
             active_block: ActiveBlock::Utilization,
             id: RouteId::Utilization,
           },
         },
+        TabRoute {
+          title: format!(
+            "Metrics History {}",
+            key_bindings().jump_to_metrics_history.key
+          ),
+          route: Route {
+            active_block: ActiveBlock::MetricsHistory,
+            id: RouteId::MetricsHistory,
+          },
+        },
+        TabRoute {
+          title: format!("Fleet {}", key_bindings().jump_to_fleet.key),
+          route: Route {
+            active_block: ActiveBlock::Fleet,
+            id: RouteId::Fleet,
+          },
+        },
       ]),
       context_tabs: TabsState::new(vec![
         TabRoute {
-          title: format!("Pods {}", DEFAULT_KEYBINDING.jump_to_pods.key),
+          title: format!("Pods {}", key_bindings().jump_to_pods.key),
           route: Route {
             active_block: ActiveBlock::Pods,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("Services {}", DEFAULT_KEYBINDING.jump_to_services.key),
+          title: format!("Services {}", key_bindings().jump_to_services.key),
           route: Route {
             active_block: ActiveBlock::Services,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("Nodes {}", DEFAULT_KEYBINDING.jump_to_nodes.key),
+          title: format!("Nodes {}", key_bindings().jump_to_nodes.key),
           route: Route {
             active_block: ActiveBlock::Nodes,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("ConfigMaps {}", DEFAULT_KEYBINDING.jump_to_configmaps.key),
+          title: format!("ConfigMaps {}", key_bindings().jump_to_configmaps.key),
           route: Route {
             active_block: ActiveBlock::ConfigMaps,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!(
-            "StatefulSets {}",
-            DEFAULT_KEYBINDING.jump_to_statefulsets.key
-          ),
+          title: format!("StatefulSets {}", key_bindings().jump_to_statefulsets.key),
           route: Route {
             active_block: ActiveBlock::StatefulSets,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("ReplicaSets {}", DEFAULT_KEYBINDING.jump_to_replicasets.key),
+          title: format!("ReplicaSets {}", key_bindings().jump_to_replicasets.key),
           route: Route {
             active_block: ActiveBlock::ReplicaSets,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("Deployments {}", DEFAULT_KEYBINDING.jump_to_deployments.key),
+          title: format!("Deployments {}", key_bindings().jump_to_deployments.key),
           route: Route {
             active_block: ActiveBlock::Deployments,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("Jobs {}", DEFAULT_KEYBINDING.jump_to_jobs.key),
+          title: format!("Jobs {}", key_bindings().jump_to_jobs.key),
           route: Route {
             active_block: ActiveBlock::Jobs,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("DaemonSets {}", DEFAULT_KEYBINDING.jump_to_daemonsets.key),
+          title: format!("DaemonSets {}", key_bindings().jump_to_daemonsets.key),
           route: Route {
             active_block: ActiveBlock::DaemonSets,
             id: RouteId::Home,
           },
         },
         TabRoute {
-          title: format!("More {}", DEFAULT_KEYBINDING.jump_to_more_resources.key),
+          title: format!("More {}", key_bindings().jump_to_more_resources.key),
           route: Route {
             active_block: ActiveBlock::More,
             id: RouteId::Home,
@@ -345,32 +757,84 @@ impl Default for App {
           "Cluster Role Bindings".into(),
           ActiveBlock::ClusterRoleBinding,
         ),
+        ("Helm Releases".into(), ActiveBlock::HelmReleases),
         // ("Service Accounts".into(), ActiveBlock::RplCtrl),
         // ("Ingresses".into(), ActiveBlock::RplCtrl),
         // ("Network Policies".into(), ActiveBlock::RplCtrl),
       ]),
+      column_picker: StatefulList::with_items(vec![]),
+      command_palette: None,
+      resource_finder: None,
+      context_switcher: None,
+      cell_picker: None,
+      export_prompt: None,
+      filter_input: None,
+      filter_history: vec![],
+      recent_contexts: vec![],
+      context_namespaces: BTreeMap::new(),
+      fleet_contexts: vec![],
+      fleet: StatefulTable::new(),
+      fleet_events: StatefulTable::new(),
+      saved_filters: vec![],
+      split_pane: None,
+      split_pane_item: None,
+      split_pane_streaming: false,
       show_info_bar: true,
+      layout_mode: LayoutMode::Normal,
+      timestamp_format: TimestampFormat::Relative,
+      manifest_format: ManifestFormat::Yaml,
+      pinned_namespaces: vec![],
       is_loading: false,
       is_streaming: false,
+      redraw: true,
       is_routing: false,
       tick_until_poll: 0,
+      resource_poll_ticks: vec![],
+      pinned_tabs: vec![],
+      tick_until_metrics_poll: 0,
       tick_count: 0,
       enhanced_graphics: false,
+      ascii_graphics: false,
+      read_only: false,
       table_cols: 0,
       size: Rect::default(),
       api_error: String::new(),
-      dialog: None,
-      confirm: false,
+      connection: Connection::default(),
+      large_cluster_threshold: DEFAULT_LARGE_CLUSTER_THRESHOLD,
+      large_cluster: false,
+      confirm_policies: ConfirmConfig::default(),
+      confirmation: None,
+      pipe_command: None,
+      export_path: None,
+      recording: None,
+      replay: None,
+      attach_request: None,
+      plugins: vec![],
+      plugin_request: None,
+      plugin_output: None,
+      custom_views: vec![],
+      extra_columns: vec![],
+      hooks: None,
+      diff_command: DEFAULT_DIFF_COMMAND.into(),
+      osc52_clipboard: false,
+      alerts: Alerts::default(),
       light_theme: false,
       refresh: true,
       log_auto_scroll: true,
+      log_limits: LogLimits::default(),
       utilization_group_by: vec![
         GroupBy::resource,
         GroupBy::node,
         GroupBy::namespace,
         GroupBy::pod,
       ],
-      help_docs: StatefulTable::with_items(key_binding::get_help_docs()),
+      help_docs: StatefulTable::with_items(key_binding::get_help_docs("", false)),
+      help_query: String::new(),
+      notifications: Notifications::default(),
+      is_help_searching: false,
+      is_detail_searching: false,
+      pending_count: 0,
+      pending_top: false,
       data: Data::default(),
     }
   }
@@ -383,6 +847,7 @@ impl App {
     io_cmd_tx: Sender<IoCmdEvent>,
     enhanced_graphics: bool,
     tick_until_poll: u64,
+    tick_until_metrics_poll: u64,
   ) -> Self {
     App {
       io_tx: Some(io_tx),
@@ -390,12 +855,14 @@ impl App {
       io_cmd_tx: Some(io_cmd_tx),
       enhanced_graphics,
       tick_until_poll,
+      tick_until_metrics_poll,
       ..App::default()
     }
   }
 
   pub fn reset(&mut self) {
     self.tick_count = 0;
+    self.large_cluster = false;
     self.api_error = String::new();
     self.data = Data::default();
     self.route_home();
@@ -403,9 +870,10 @@ impl App {
 
   // Send a network event to the network thread
   pub async fn dispatch(&mut self, action: IoEvent) {
-    // `is_loading` will be set to false again after the async action has finished in network/mod.rs
-    self.is_loading = true;
     if let Some(io_tx) = &self.io_tx {
+      // `is_loading` will be set to false again after the async action has finished in network/mod.rs
+      self.is_loading = true;
+      self.redraw = true;
       if let Err(e) = io_tx.send(action).await {
         self.is_loading = false;
         self.handle_error(anyhow!(e));
@@ -415,9 +883,10 @@ impl App {
 
   // Send a stream event to the stream network thread
   pub async fn dispatch_stream(&mut self, action: IoStreamEvent) {
-    // `is_loading` will be set to false again after the async action has finished in network/stream.rs
-    self.is_loading = true;
     if let Some(io_stream_tx) = &self.io_stream_tx {
+      // `is_loading` will be set to false again after the async action has finished in network/stream.rs
+      self.is_loading = true;
+      self.redraw = true;
       if let Err(e) = io_stream_tx.send(action).await {
         self.is_loading = false;
         self.handle_error(anyhow!(e));
@@ -427,9 +896,13 @@ impl App {
 
   // Send a cmd event to the cmd runner thread
   pub async fn dispatch_cmd(&mut self, action: IoCmdEvent) {
-    // `is_loading` will be set to false again after the async action has finished in network/stream.rs
-    self.is_loading = true;
+    if action.is_mutating() && !self.ensure_writable() {
+      return;
+    }
     if let Some(io_cmd_tx) = &self.io_cmd_tx {
+      // `is_loading` will be set to false again after the async action has finished in network/stream.rs
+      self.is_loading = true;
+      self.redraw = true;
       if let Err(e) = io_cmd_tx.send(action).await {
         self.is_loading = false;
         self.handle_error(anyhow!(e));
@@ -448,10 +921,483 @@ impl App {
     self.data.contexts.set_items(contexts);
   }
 
+  /// switch to large cluster mode when a list has as many pods as the threshold, it stays on until
+  /// the context changes
+  pub fn count_pods(&mut self, count: usize) {
+    if !self.large_cluster
+      && self.large_cluster_threshold > 0
+      && count >= self.large_cluster_threshold
+    {
+      self.large_cluster = true;
+      self.notify(
+        NotificationLevel::Info,
+        format!(
+          "Large cluster mode: {} pods or more, pod counts of nodes and disk usage are left out",
+          self.large_cluster_threshold
+        ),
+      );
+    }
+  }
+
   pub fn handle_error(&mut self, e: anyhow::Error) {
     self.api_error = e.to_string();
   }
 
+  /// the kubectl command showing what the current view shows, like
+  /// `kubectl logs -f -n web pod/api-0 -c app --tail=10`, for sharing with people not in kdash
+  pub fn kubectl_command(&self) -> Option<String> {
+    let route = self.get_current_route();
+    let (verb, ns, target, mut flags) = match route.active_block {
+      ActiveBlock::Containers | ActiveBlock::Logs => {
+        let pod = self.data.pods.get_selected_item_copy()?;
+        let container = match route.active_block {
+          ActiveBlock::Logs => self.data.selected.container.clone()?,
+          _ => self.data.containers.get_selected_item_copy()?.name,
+        };
+        (
+          "logs -f",
+          Some(pod.namespace),
+          format!("pod/{}", pod.name),
+          vec![format!("-c {}", container), "--tail=10".into()],
+        )
+      }
+      block => {
+        let table_block = match block {
+          ActiveBlock::Describe | ActiveBlock::Yaml => self.get_prev_route().active_block,
+          block => block,
+        };
+        let (kind, name, ns) = match self.data.selected_details(table_block)? {
+          (IoCmdEvent::GetDescribe { kind, value, ns }, _) => (kind, value, ns),
+          _ => return None,
+        };
+        let (verb, flags) = match block {
+          ActiveBlock::Describe => ("describe", vec![]),
+          ActiveBlock::Yaml => (
+            "get",
+            vec![format!("-o {}", self.manifest_format.extension())],
+          ),
+          _ => ("get", vec![]),
+        };
+        (verb, ns, format!("{}/{}", kind, name), flags)
+      }
+    };
+    if let Some(context) = &self.data.selected.context {
+      flags.push(format!("--context {}", context));
+    }
+    let ns = ns.map(|ns| format!("-n {} ", ns)).unwrap_or_default();
+    Some(
+      format!("kubectl {} {}{} {}", verb, ns, target, flags.join(" "))
+        .trim_end()
+        .to_owned(),
+    )
+  }
+
+  /// show a recording instead of the cluster, which isn't called at all while replaying
+  pub fn start_replay(&mut self, replay: Replay) -> anyhow::Result<()> {
+    self.io_tx = None;
+    self.io_stream_tx = None;
+    self.io_cmd_tx = None;
+    replay.apply(self)?;
+    self.replay = Some(replay);
+    Ok(())
+  }
+
+  /// move through the replayed recording by a number of records and show the cluster there
+  pub fn step_replay(&mut self, records: isize) {
+    if let Some(mut replay) = self.replay.take() {
+      if replay.step(records) {
+        if let Err(e) = replay.apply(self) {
+          self.handle_error(e);
+        }
+      }
+      self.replay = Some(replay);
+    }
+  }
+
+  /// whether actions changing the cluster may run, tells they are disabled in read-only mode
+  pub fn ensure_writable(&mut self) -> bool {
+    if self.read_only {
+      self.notify(
+        NotificationLevel::Failure,
+        "Changing the cluster is disabled in read-only mode",
+      );
+    }
+    !self.read_only
+  }
+
+  /// request attaching to the stdin and stdout of the container selected in the containers view,
+  /// like `kubectl attach -it`
+  pub fn attach_to_container(&mut self) {
+    if !self.ensure_writable() {
+      return;
+    }
+    let (pod, container) = match (
+      self.data.pods.get_selected_item_copy(),
+      self.data.containers.get_selected_item_copy(),
+    ) {
+      (Some(pod), Some(container)) => (pod, container),
+      _ => return,
+    };
+    self.attach_request = Some(AttachRequest {
+      context: self.data.selected.context.clone(),
+      namespace: pod.namespace,
+      pod: pod.name,
+      container: container.name,
+      tty: container.tty,
+    });
+  }
+
+  /// list the custom views under More after the built in resources
+  pub fn set_custom_views(&mut self, views: Vec<CustomView>) {
+    let items = views
+      .iter()
+      .enumerate()
+      .map(|(i, view)| (view.name.clone(), ActiveBlock::CustomView(i)));
+    self.more_resources_menu.items.extend(items);
+    self.custom_views = views;
+  }
+
+  /// the extra columns from the config of a resource table
+  pub fn extra_columns(&self, block: ActiveBlock) -> Vec<CustomColumn> {
+    self
+      .extra_columns
+      .iter()
+      .find(|(b, _)| *b == block)
+      .map(|(_, columns)| columns.clone())
+      .unwrap_or_default()
+  }
+
+  /// the title of a block, the name of the view for custom ones
+  fn block_name(&self, block: ActiveBlock) -> String {
+    match block {
+      ActiveBlock::CustomView(i) => self
+        .custom_views
+        .get(i)
+        .map_or_else(|| block_title(block).into(), |view| view.name.clone()),
+      _ => block_title(block).into(),
+    }
+  }
+
+  pub fn has_plugin(&self, key: Key) -> bool {
+    let block = self.get_current_route().active_block;
+    self.plugins.iter().any(|plugin| plugin.matches(key, block))
+  }
+
+  /// run the plugin of the key on the selected resource of the table, with the terminal or with
+  /// its output captured into a popup
+  pub async fn run_plugin(&mut self, key: Key) {
+    let block = self.get_current_route().active_block;
+    let plugin = match self
+      .plugins
+      .iter()
+      .find(|plugin| plugin.matches(key, block))
+    {
+      Some(plugin) => plugin.clone(),
+      None => return,
+    };
+    if !plugin.read_only_safe && !self.ensure_writable() {
+      return;
+    }
+    let (namespace, name) = match self
+      .data
+      .resource_table(block)
+      .and_then(|table| table.selected_key())
+    {
+      Some(selected) => selected,
+      None => return,
+    };
+    let context = self
+      .data
+      .active_context
+      .as_ref()
+      .map(|context| context.name.clone())
+      .unwrap_or_default();
+    let run = plugin.run_on(&namespace, &name, &context);
+    match plugin.output {
+      PluginOutput::Terminal => self.plugin_request = Some(run),
+      PluginOutput::Popup => {
+        self.plugin_output = Some((plugin.name, ScrollableTxt::with_string("Running...".into())));
+        self.dispatch_cmd(IoCmdEvent::RunPlugin(run)).await;
+      }
+    }
+  }
+
+  /// show the revisions of a release in place of the releases table
+  pub fn open_helm_history(&mut self, release: &KubeHelmRelease) {
+    self.data.selected.helm_release = Some((release.namespace.clone(), release.name.clone()));
+    let history = release_history(&self.data.helm_revisions, &release.namespace, &release.name);
+    self.data.helm_history.set_sorted_items(history);
+    self.push_navigation_stack(RouteId::Home, ActiveBlock::HelmHistory);
+  }
+
+  /// show the values of a release or a revision of it in the YAML view
+  pub fn show_helm_values(&mut self, release: &KubeHelmRelease) {
+    match release.values_yaml() {
+      Some(values) => {
+        self.data.describe_out = ScrollableTxt::with_string(values);
+        self.push_navigation_stack(RouteId::Home, ActiveBlock::Yaml);
+      }
+      None => self.notify(
+        NotificationLevel::Failure,
+        format!("Unable to decode the release {}", release.name),
+      ),
+    }
+  }
+
+  /// diff the manifests of the two revisions marked in the history of a release, or of the
+  /// selected revision and the one before it when none are marked
+  pub async fn diff_helm_revisions(&mut self) {
+    if !self.ensure_writable() {
+      return;
+    }
+    let history = &self.data.helm_history;
+    let revisions = match history.marked_items().as_slice() {
+      [] => match history.get_selected_item_copy() {
+        Some(selected) => history
+          .items
+          .iter()
+          .filter(|r| r.revision < selected.revision)
+          .max_by_key(|r| r.revision)
+          .map(|previous| (previous.clone(), selected.clone()))
+          .ok_or_else(|| {
+            format!(
+              "Revision {} of {} has no earlier revision to diff with",
+              selected.revision, selected.name
+            )
+          }),
+        None => return,
+      },
+      [first, second] if first.revision < second.revision => {
+        Ok(((*first).clone(), (*second).clone()))
+      }
+      [first, second] => Ok(((*second).clone(), (*first).clone())),
+      _ => Err("Mark two revisions to diff them".into()),
+    };
+    let (old, new) = match revisions {
+      Ok(revisions) => revisions,
+      Err(message) => {
+        self.notify(NotificationLevel::Info, message);
+        return;
+      }
+    };
+    match (old.manifest(), new.manifest()) {
+      (Some(old_manifest), Some(new_manifest)) => {
+        self.data.describe_out = ScrollableTxt::new();
+        self.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
+        self
+          .dispatch_cmd(IoCmdEvent::DiffHelmRevisions {
+            command: self.diff_command.clone(),
+            name: new.name.clone(),
+            old_revision: old.revision,
+            old: old_manifest,
+            new_revision: new.revision,
+            new: new_manifest,
+          })
+          .await;
+      }
+      _ => self.notify(
+        NotificationLevel::Failure,
+        format!("Unable to decode the release {}", new.name),
+      ),
+    }
+  }
+
+  /// roll the open release back to the revision selected in its history, like `helm rollback`
+  pub async fn rollback_helm_release(&mut self) {
+    let revision = match self.data.helm_history.get_selected_item_copy() {
+      Some(revision) => revision,
+      None => return,
+    };
+    if revision.status == "deployed" {
+      self.notify(
+        NotificationLevel::Info,
+        format!(
+          "Revision {} is the deployed revision of {}",
+          revision.revision, revision.name
+        ),
+      );
+      return;
+    }
+    let question = format!(
+      "Roll back {}/{} to revision {} ({})?",
+      revision.namespace, revision.name, revision.revision, revision.chart
+    );
+    let action = IoCmdEvent::HelmRollback {
+      name: revision.name.clone(),
+      namespace: revision.namespace.clone(),
+      revision: revision.revision,
+      context: self.data.selected.context.clone(),
+    };
+    self
+      .run_confirmed(
+        self.confirm_policies.rollback,
+        question,
+        &revision.name,
+        action,
+      )
+      .await;
+  }
+
+  /// uninstall the selected release like `helm uninstall`, its revisions are kept with
+  /// `keep_history` so it can still be rolled back
+  pub async fn uninstall_helm_release(&mut self, keep_history: bool) {
+    let release = match self.data.helm_releases.get_selected_item_copy() {
+      Some(release) => release,
+      None => return,
+    };
+    if release.status == "uninstalled" {
+      self.notify(
+        NotificationLevel::Info,
+        format!("{} is already uninstalled", release.name),
+      );
+      return;
+    }
+    let question = format!(
+      "Uninstall {}/{} ({}){}?",
+      release.namespace,
+      release.name,
+      release.chart,
+      if keep_history {
+        " keeping its history"
+      } else {
+        ""
+      }
+    );
+    let action = IoCmdEvent::HelmUninstall {
+      name: release.name.clone(),
+      namespace: release.namespace.clone(),
+      keep_history,
+      context: self.data.selected.context.clone(),
+      deleted: release.deleted_on_uninstall(),
+    };
+    self
+      .run_confirmed(
+        self.confirm_policies.delete,
+        question,
+        &release.name,
+        action,
+      )
+      .await;
+  }
+
+  /// run an action changing the cluster, after asking when the policy says so
+  async fn run_confirmed(
+    &mut self,
+    policy: ConfirmPolicy,
+    question: String,
+    name: &str,
+    action: IoCmdEvent,
+  ) {
+    if !self.ensure_writable() {
+      return;
+    }
+    if policy == ConfirmPolicy::Never {
+      self.dispatch_cmd(action).await;
+    } else {
+      self.confirmation = Some(Confirmation::new(policy, question, name, action));
+    }
+  }
+
+  /// run the action of the open confirmation, if the name asked for was typed
+  pub async fn confirm_action(&mut self) {
+    match self.confirmation.take() {
+      Some(confirmation) if confirmation.is_confirmed() => {
+        self.dispatch_cmd(confirmation.action).await;
+      }
+      Some(confirmation) => {
+        self.notify(
+          NotificationLevel::Failure,
+          format!(
+            "Type {} to confirm",
+            confirmation.typed_name.as_deref().unwrap_or_default()
+          ),
+        );
+        self.confirmation = Some(confirmation);
+      }
+      None => {}
+    }
+  }
+
+  /// ask where to save the manifest of the item selected in the resource table, which is the
+  /// one behind the describe and YAML views
+  pub fn open_export(&mut self) {
+    let block = match self.get_current_route().active_block {
+      ActiveBlock::Describe | ActiveBlock::Yaml => self.get_prev_route().active_block,
+      block => block,
+    };
+    if let Some((IoCmdEvent::GetDescribe { kind, value, ns }, _)) =
+      self.data.selected_details(block)
+    {
+      let path = self.template_path(
+        &kind,
+        &value,
+        ns.as_deref().unwrap_or("cluster"),
+        self.manifest_format.extension(),
+      );
+      let mut prompt = ExportPrompt::new(&kind, &value, ns, self.manifest_format);
+      if let Some(path) = path {
+        prompt.path = path;
+      }
+      self.export_prompt = Some(prompt);
+    }
+  }
+
+  /// ask where to save the focused resource table as CSV, with the shown columns and the items
+  /// filtered and sorted like they are drawn
+  pub fn open_table_export(&mut self) {
+    let block = self.get_current_route().active_block;
+    let title = self.block_name(block);
+    if let Some(table) = self.data.resource_table(block) {
+      let (headers, rows) = table.rows();
+      if !headers.is_empty() {
+        let mut prompt = ExportPrompt::table(&title, headers, rows);
+        let kind = title.to_lowercase().replace(' ', "-");
+        let ns = self.data.selected.ns.clone();
+        if let Some(path) =
+          self.template_path(&kind, "table", ns.as_deref().unwrap_or("all"), "csv")
+        {
+          prompt.path = path;
+        }
+        self.export_prompt = Some(prompt);
+      }
+    }
+  }
+
+  /// the path of a saved file from the `export_path` template, None without one
+  fn template_path(&self, kind: &str, name: &str, ns: &str, extension: &str) -> Option<String> {
+    let template = self.export_path.as_ref()?;
+    let context = self
+      .data
+      .active_context
+      .as_ref()
+      .map_or("default", |context| context.name.as_str());
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    Some(template_path(
+      template,
+      &[
+        ("context", context),
+        ("namespace", ns),
+        ("kind", kind),
+        ("name", name),
+        ("timestamp", &timestamp),
+      ],
+      extension,
+    ))
+  }
+
+  /// save the file of the export prompt and close it
+  pub async fn save_export(&mut self) {
+    if let Some(prompt) = self.export_prompt.take() {
+      self.dispatch_cmd(prompt.export_event()).await;
+    }
+  }
+
+  pub fn notify(&mut self, level: NotificationLevel, message: impl Into<String>) {
+    self
+      .notifications
+      .push(level, message.into(), Instant::now());
+  }
+
   pub fn push_navigation_stack(&mut self, id: RouteId, active_block: ActiveBlock) {
     self.push_navigation_route(Route { id, active_block });
   }
@@ -511,170 +1457,789 @@ impl App {
     self.push_navigation_route(route);
   }
 
-  pub async fn dispatch_container_logs(&mut self, id: String) {
-    self.data.logs = LogsState::new(id);
-    self.push_navigation_stack(RouteId::Home, ActiveBlock::Logs);
-    self.dispatch_stream(IoStreamEvent::GetPodLogs(true)).await;
-  }
-
-  pub fn refresh(&mut self) {
-    self.refresh = true;
+  pub fn route_metrics_history(&mut self) {
+    let route = self.main_tabs.set_index(3).route.clone();
+    self.push_navigation_route(route);
   }
 
-  pub async fn cache_all_resource_data(&mut self) {
-    self.dispatch(IoEvent::GetNamespaces).await;
-    self.dispatch(IoEvent::GetPods).await;
-    self.dispatch(IoEvent::GetServices).await;
-    self.dispatch(IoEvent::GetNodes).await;
-    self.dispatch(IoEvent::GetConfigMaps).await;
-    self.dispatch(IoEvent::GetStatefulSets).await;
-    self.dispatch(IoEvent::GetReplicaSets).await;
-    self.dispatch(IoEvent::GetDeployments).await;
-    self.dispatch(IoEvent::GetJobs).await;
-    self.dispatch(IoEvent::GetDaemonSets).await;
-    self.dispatch(IoEvent::GetCronJobs).await;
-    self.dispatch(IoEvent::GetSecrets).await;
-    self.dispatch(IoEvent::GetReplicationControllers).await;
-    self.dispatch(IoEvent::GetStorageClasses).await;
-    self.dispatch(IoEvent::GetRoles).await;
-    self.dispatch(IoEvent::GetRoleBindings).await;
-    self.dispatch(IoEvent::GetClusterRoles).await;
-    self.dispatch(IoEvent::GetClusterRoleBinding).await;
-    self.dispatch(IoEvent::GetMetrics).await;
+  pub fn route_fleet(&mut self) {
+    let route = self.main_tabs.set_index(4).route.clone();
+    self.push_navigation_route(route);
   }
 
-  pub async fn dispatch_by_active_block(&mut self, active_block: ActiveBlock) {
-    match active_block {
-      ActiveBlock::Pods | ActiveBlock::Containers => {
-        self.dispatch(IoEvent::GetPods).await;
-      }
-      ActiveBlock::Services => {
-        self.dispatch(IoEvent::GetServices).await;
-      }
-      ActiveBlock::ConfigMaps => {
-        self.dispatch(IoEvent::GetConfigMaps).await;
-      }
-      ActiveBlock::StatefulSets => {
-        self.dispatch(IoEvent::GetStatefulSets).await;
-      }
-      ActiveBlock::ReplicaSets => {
-        self.dispatch(IoEvent::GetReplicaSets).await;
-      }
-      ActiveBlock::Deployments => {
-        self.dispatch(IoEvent::GetDeployments).await;
-      }
-      ActiveBlock::Jobs => {
-        self.dispatch(IoEvent::GetJobs).await;
-      }
-      ActiveBlock::DaemonSets => {
-        self.dispatch(IoEvent::GetDaemonSets).await;
-      }
-      ActiveBlock::CronJobs => {
-        self.dispatch(IoEvent::GetCronJobs).await;
+  /// show a resource table, resources in the more menu are routed through it so that going back
+  /// works the same as when picked from the menu
+  pub fn route_resource(&mut self, block: ActiveBlock) {
+    self.main_tabs.set_index(0);
+    let tab = self
+      .context_tabs
+      .items
+      .iter()
+      .position(|t| t.route.active_block == block);
+    match tab {
+      Some(index) => {
+        let route = self.context_tabs.set_index(index).route.clone();
+        self.push_navigation_route(route);
       }
-      ActiveBlock::Secrets => {
-        self.dispatch(IoEvent::GetSecrets).await;
+      None => {
+        let more = self.context_tabs.items.len() - 1;
+        let route = self.context_tabs.set_index(more).route.clone();
+        self.push_navigation_route(route);
+        self.push_navigation_stack(RouteId::Home, block);
       }
-      ActiveBlock::RplCtrl => {
-        self.dispatch(IoEvent::GetReplicationControllers).await;
-      }
-      ActiveBlock::StorageClasses => {
-        self.dispatch(IoEvent::GetStorageClasses).await;
-      }
-      ActiveBlock::Roles => {
-        self.dispatch(IoEvent::GetRoles).await;
-      }
-      ActiveBlock::RoleBindings => {
-        self.dispatch(IoEvent::GetRoleBindings).await;
-      }
-      ActiveBlock::ClusterRoles => {
-        self.dispatch(IoEvent::GetClusterRoles).await;
-      }
-      ActiveBlock::ClusterRoleBinding => {
-        self.dispatch(IoEvent::GetClusterRoleBinding).await;
-      }
-      ActiveBlock::Logs => {
-        if !self.is_streaming {
-          // do not tail to avoid duplicates
-          self.dispatch_stream(IoStreamEvent::GetPodLogs(false)).await;
+    }
+  }
+
+  /// where the user is, from the context down to the shown view of an item
+  pub fn breadcrumbs(&mut self) -> Vec<String> {
+    let route = self.get_current_route().clone();
+    let mut crumbs = vec![self
+      .data
+      .active_context
+      .as_ref()
+      .map_or_else(|| "no context".into(), |ctx| ctx.name.clone())];
+    match route.id {
+      RouteId::Home => {
+        crumbs.push(
+          self
+            .data
+            .selected
+            .ns
+            .clone()
+            .unwrap_or_else(|| "all namespaces".into()),
+        );
+        let prev_block = self.get_prev_route().active_block;
+        match route.active_block {
+          ActiveBlock::Containers | ActiveBlock::Logs => {
+            crumbs.push(block_title(ActiveBlock::Pods).into());
+            crumbs.extend(self.data.selected.pod.clone());
+            if route.active_block == ActiveBlock::Logs {
+              crumbs.extend(self.data.selected.container.clone());
+            }
+            crumbs.push(block_title(route.active_block).into());
+          }
+          ActiveBlock::HelmHistory => {
+            crumbs.push(block_title(ActiveBlock::HelmReleases).into());
+            crumbs.extend(
+              self
+                .data
+                .selected
+                .helm_release
+                .as_ref()
+                .map(|(_, name)| name.clone()),
+            );
+            crumbs.push(block_title(route.active_block).into());
+          }
+          ActiveBlock::Describe | ActiveBlock::Yaml => {
+            crumbs.push(self.block_name(prev_block));
+            crumbs.extend(
+              self
+                .data
+                .resource_table(prev_block)
+                .and_then(|t| t.selected_key())
+                .map(|(_, name)| name),
+            );
+            crumbs.push(match route.active_block {
+              ActiveBlock::Yaml => self.manifest_format.title().into(),
+              block => block_title(block).into(),
+            });
+          }
+          ActiveBlock::Namespaces | ActiveBlock::Columns => {
+            crumbs.push(self.block_name(prev_block));
+            crumbs.push(block_title(route.active_block).into());
+          }
+          block => crumbs.push(self.block_name(block)),
         }
       }
-      _ => {}
+      RouteId::Contexts => crumbs.push("Contexts".into()),
+      RouteId::Utilization => crumbs.push("Utilization".into()),
+      RouteId::MetricsHistory => crumbs.push("Metrics History".into()),
+      RouteId::Fleet => crumbs.push("Fleet".into()),
+      RouteId::HelpMenu => crumbs.push("Help".into()),
     }
+    crumbs
   }
 
-  pub async fn on_tick(&mut self, first_render: bool) {
-    // Make one time requests on first render or refresh
-    if self.refresh {
-      if !first_render {
-        self.dispatch(IoEvent::RefreshClient).await;
-        self.dispatch_stream(IoStreamEvent::RefreshClient).await;
-      }
-      self.dispatch(IoEvent::GetKubeConfig).await;
-      // call these once to pre-load data
-      self.cache_all_resource_data().await;
-      self.refresh = false;
+  pub fn update_help_docs(&mut self) {
+    let mut docs = key_binding::get_help_docs(&self.help_query, self.read_only);
+    docs.extend(plugin_help_docs(
+      &self.plugins,
+      &self.help_query,
+      self.read_only,
+    ));
+    self.help_docs.set_items(docs);
+  }
+
+  pub fn open_command_palette(&mut self) {
+    self.command_palette = Some(CommandPalette::new());
+    self.update_command_suggestions();
+  }
+
+  pub fn update_command_suggestions(&mut self) {
+    let namespaces: Vec<String> = self
+      .data
+      .namespaces
+      .items
+      .iter()
+      .map(|ns| ns.name.clone())
+      .collect();
+    let contexts: Vec<String> = self
+      .data
+      .contexts
+      .items
+      .iter()
+      .map(|ctx| ctx.name.clone())
+      .collect();
+    if let Some(palette) = self.command_palette.as_mut() {
+      palette.update_suggestions(&namespaces, &contexts);
     }
-    // make network requests only in intervals to avoid hogging up the network
-    if self.tick_count % self.tick_until_poll == 0 || self.is_routing {
-      // make periodic network calls based on active route and active block to avoid hogging
-      match self.get_current_route().id {
-        RouteId::Home => {
-          if self.data.clis.is_empty() {
-            self.dispatch_cmd(IoCmdEvent::GetCliInfo).await;
-          }
-          self.dispatch(IoEvent::GetNamespaces).await;
-          self.dispatch(IoEvent::GetNodes).await;
+  }
 
-          let active_block = self.get_current_route().active_block;
-          if active_block == ActiveBlock::Namespaces {
-            self
-              .dispatch_by_active_block(self.get_prev_route().active_block)
-              .await;
+  /// run the command from the palette and close it
+  pub async fn run_command(&mut self) {
+    let command = self.command_palette.take().map(|p| (p.command(), p.input));
+    match command {
+      Some((Some((kind, arg)), _)) => match kind {
+        CommandKind::Resource(block) => self.route_resource(block),
+        CommandKind::Namespace => {
+          self.data.selected.ns = arg;
+          self.cache_all_resource_data().await;
+        }
+        CommandKind::Context => {
+          let ctx = arg.unwrap_or_default();
+          if self.data.contexts.items.iter().any(|c| c.name == ctx) {
+            self.switch_context(ctx);
           } else {
-            self.dispatch_by_active_block(active_block).await;
+            self.handle_error(anyhow!("Unknown context '{}'", ctx));
           }
         }
-        RouteId::Utilization => {
-          self.dispatch(IoEvent::GetMetrics).await;
+        CommandKind::Contexts => self.route_contexts(),
+        CommandKind::Utilization => self.route_utilization(),
+        CommandKind::MetricsHistory => self.route_metrics_history(),
+        CommandKind::Fleet => self.route_fleet(),
+        CommandKind::Help => {
+          if self.get_current_route().active_block != ActiveBlock::Help {
+            self.push_navigation_stack(RouteId::HelpMenu, ActiveBlock::Help);
+          }
         }
-        _ => {}
+        CommandKind::Quit => self.should_quit = true,
+      },
+      Some((None, input)) if !input.trim().is_empty() => {
+        self.handle_error(anyhow!("Unknown command '{}'", input.trim()));
       }
-      self.is_routing = false;
+      _ => {}
     }
+  }
 
-    self.tick_count += 1;
+  /// open the filter input for the focused resource table
+  pub fn open_filter(&mut self) {
+    let block = self.get_current_route().active_block;
+    if let Some(table) = self.data.resource_table(block) {
+      self.filter_input = Some(FilterInput::new(
+        block,
+        table.filter(),
+        &self.saved_filters,
+        &self.filter_history,
+      ));
+    }
   }
-}
 
-/// utility methods for tests
-#[cfg(test)]
-#[macro_use]
-mod test_utils {
-  use std::{fmt, fs};
+  /// filter the table by the input while it is typed, keys typed in quick succession are
+  /// applied together on a tick
+  pub fn type_filter_input(&mut self) {
+    if self
+      .filter_input
+      .as_mut()
+      .is_some_and(|input| input.typed(Instant::now()))
+    {
+      self.apply_filter_input();
+    }
+  }
 
-  use k8s_openapi::{
-    apimachinery::pkg::apis::meta::v1::Time,
-    chrono::{DateTime, TimeZone, Utc},
-  };
-  use kube::{api::ObjectList, Resource};
-  use serde::{de::DeserializeOwned, Serialize};
+  /// apply the input held back while typing once typing paused
+  pub fn apply_pending_filter_input(&mut self, now: Instant) {
+    if self
+      .filter_input
+      .as_mut()
+      .is_some_and(|input| input.take_pending(now))
+    {
+      self.apply_filter_input();
+      self.redraw = true;
+    }
+  }
 
-  use super::models::KubeResource;
+  /// filter the table by the input
+  pub fn apply_filter_input(&mut self) {
+    if let Some(input) = &self.filter_input {
+      let (block, filter) = (input.block, input.input.clone());
+      if let Some(table) = self.data.resource_table(block) {
+        table.set_filter(&filter);
+      }
+    }
+  }
 
-  pub fn convert_resource_from_file<K: Serialize, T>(filename: &str) -> (Vec<T>, Vec<K>)
-  where
-    <K as Resource>::DynamicType: Default,
-    K: Clone + DeserializeOwned + fmt::Debug,
-    K: Resource,
-    T: KubeResource<K> + From<K>,
-  {
-    let res_list = load_resource_from_file(filename);
-    let original_res_list = res_list.items.clone();
+  /// whether each resource tab has a filtered table, the more tab counts all of its tables
+  pub fn filtered_tabs(&mut self) -> Vec<bool> {
+    let tab_blocks: Vec<Vec<ActiveBlock>> = self
+      .context_tabs
+      .items
+      .iter()
+      .map(|tab| match tab.route.active_block {
+        ActiveBlock::More => self
+          .more_resources_menu
+          .items
+          .iter()
+          .map(|(_, block)| *block)
+          .collect(),
+        block => vec![block],
+      })
+      .collect();
+    tab_blocks
+      .into_iter()
+      .map(|blocks| {
+        blocks.into_iter().any(|block| {
+          self
+            .data
+            .resource_table(block)
+            .is_some_and(|t| !t.filter().is_empty())
+        })
+      })
+      .collect()
+  }
 
-    let resources: Vec<T> = res_list.into_iter().map(K::into).collect::<Vec<_>>();
+  /// close the filter input, keeping the typed filter or restoring the one from before
+  pub fn close_filter(&mut self, keep: bool) {
+    if let Some(input) = self.filter_input.take() {
+      let previous_selectors = TableFilter::parse(&input.previous)
+        .ok()
+        .map(|f| (f.field_selector(), f.label_selector()))
+        .unwrap_or_default();
+      let filter = if keep { input.input } else { input.previous };
+      if let Some(table) = self.data.resource_table(input.block) {
+        table.set_filter(&filter);
+        // list the resources again right away when the API has to filter them differently
+        if (table.field_selector(), table.label_selector()) != previous_selectors {
+          self.tick_count = 0;
+        }
+      }
+      if keep {
+        add_to_history(&mut self.filter_history, &filter);
+      }
+    }
+  }
 
-    (resources, original_res_list)
+  pub fn open_resource_finder(&mut self) {
+    self.resource_finder = Some(ResourceFinder::new());
+  }
+
+  pub fn open_context_switcher(&mut self) {
+    self.context_switcher = Some(ContextSwitcher::new());
+    self.update_context_matches();
+  }
+
+  pub fn update_context_matches(&mut self) {
+    let contexts: Vec<String> = self
+      .data
+      .contexts
+      .items
+      .iter()
+      .map(|c| c.name.clone())
+      .collect();
+    let active = self.data.active_context.as_ref().map(|c| c.name.as_str());
+    if let Some(switcher) = self.context_switcher.as_mut() {
+      switcher.update_matches(&contexts, &self.recent_contexts, active);
+    }
+  }
+
+  /// switch to the context picked in the switcher and close it
+  pub fn switch_to_picked_context(&mut self) {
+    let picked = self
+      .context_switcher
+      .take()
+      .and_then(|s| s.selected_context().map(str::to_owned));
+    let active = self.data.active_context.as_ref().map(|c| c.name.as_str());
+    if let Some(name) = picked.filter(|name| Some(name.as_str()) != active) {
+      self.switch_context(name);
+    }
+  }
+
+  pub fn update_finder_matches(&mut self) {
+    let mut candidates = vec![];
+    for (block, kind) in SEARCHED_BLOCKS {
+      if let Some(table) = self.data.resource_table(block) {
+        candidates.extend(
+          table
+            .item_names()
+            .into_iter()
+            .map(|(namespace, name)| FinderMatch {
+              block,
+              kind,
+              namespace,
+              name,
+            }),
+        );
+      }
+    }
+    if let Some(finder) = self.resource_finder.as_mut() {
+      finder.update_matches(candidates);
+    }
+  }
+
+  /// show the table of the match picked in the finder with its row selected and close the finder
+  pub fn jump_to_finder_match(&mut self) {
+    let selected = self
+      .resource_finder
+      .take()
+      .and_then(|f| f.selected_match().cloned());
+    if let Some(m) = selected {
+      self.route_resource(m.block);
+      if let Some(table) = self.data.resource_table(m.block) {
+        // look the item up again as the table might have been refreshed or sorted meanwhile
+        let item = (m.namespace, m.name);
+        if let Some(index) = table.item_names().iter().position(|i| *i == item) {
+          table.select_row(index);
+        }
+      }
+    }
+  }
+
+  pub async fn dispatch_container_logs(&mut self, id: String) {
+    self.data.logs = LogsState::new(id).with_limits(self.log_limits);
+    self.push_navigation_stack(RouteId::Home, ActiveBlock::Logs);
+    self.dispatch_stream(IoStreamEvent::GetPodLogs(true)).await;
+  }
+
+  pub fn refresh(&mut self) {
+    self.refresh = true;
+  }
+
+  /// switch to a context from the kubeconfig and reload all data, in the namespace the context
+  /// was left in or else its default namespace
+  pub fn switch_context(&mut self, name: String) {
+    if let Some(shown) = &self.data.active_context {
+      if shown.name != name {
+        add_recent_context(&mut self.recent_contexts, &shown.name);
+        self
+          .context_namespaces
+          .insert(shown.name.clone(), self.data.selected.ns.clone());
+      }
+    }
+    add_recent_context(&mut self.recent_contexts, &name);
+    self.data.selected.ns = match self.context_namespaces.get(&name) {
+      Some(ns) => ns.clone(),
+      None => self
+        .data
+        .contexts
+        .items
+        .iter()
+        .find(|c| c.name == name)
+        .and_then(|c| c.namespace.clone()),
+    };
+    self.data.selected.context = Some(name);
+    self.refresh();
+  }
+
+  /// list the namespaces with the pinned ones first
+  pub fn set_namespaces(&mut self, mut items: Vec<KubeNs>) {
+    let pinned = &self.pinned_namespaces;
+    items.sort_by_key(|ns| {
+      pinned
+        .iter()
+        .position(|p| *p == ns.name)
+        .unwrap_or(pinned.len())
+    });
+    self.data.namespaces.set_items(items);
+  }
+
+  /// pin the selected namespace or unpin it when it already is
+  pub fn toggle_pinned_namespace(&mut self) {
+    if let Some(ns) = self.data.namespaces.get_selected_item_copy() {
+      if self.pinned_namespaces.contains(&ns.name) {
+        self.pinned_namespaces.retain(|p| *p != ns.name);
+      } else {
+        self.pinned_namespaces.push(ns.name.clone());
+      }
+      let items = std::mem::take(&mut self.data.namespaces.items);
+      self.set_namespaces(items);
+      // keep the selection on the toggled namespace as it moves
+      if let Some(i) = self
+        .data
+        .namespaces
+        .items
+        .iter()
+        .position(|n| n.name == ns.name)
+      {
+        self.data.namespaces.state.select(Some(i));
+      }
+    }
+  }
+
+  /// the state to restore on the next start
+  pub fn ui_state(&mut self) -> UiState {
+    let mut sorts = BTreeMap::new();
+    for (block, _) in SEARCHED_BLOCKS {
+      if let Some(sort) = self.data.resource_table(block).and_then(|t| t.table_sort()) {
+        sorts.insert(block_title(block).to_owned(), sort);
+      }
+    }
+    let mut context_namespaces = self.context_namespaces.clone();
+    if let Some(context) = &self.data.active_context {
+      context_namespaces.insert(context.name.clone(), self.data.selected.ns.clone());
+    }
+    UiState {
+      context: self.data.active_context.as_ref().map(|c| c.name.clone()),
+      namespace: self.data.selected.ns.clone(),
+      resource_tab: self.context_tabs.index,
+      sorts,
+      filter_history: self.filter_history.clone(),
+      recent_contexts: self.recent_contexts.clone(),
+      context_namespaces,
+    }
+  }
+
+  pub fn restore_ui_state(&mut self, state: UiState) {
+    self.data.selected.context = state.context;
+    self.data.selected.ns = state.namespace;
+    self.filter_history = state.filter_history;
+    self.recent_contexts = state.recent_contexts;
+    self.context_namespaces = state.context_namespaces;
+    for (block, _) in SEARCHED_BLOCKS {
+      if let Some(sort) = state.sorts.get(block_title(block)) {
+        if let Some(table) = self.data.resource_table(block) {
+          table.set_table_sort(Some(sort.clone()));
+        }
+      }
+    }
+    if state.resource_tab < self.context_tabs.items.len() {
+      let route = self
+        .context_tabs
+        .set_index(state.resource_tab)
+        .route
+        .clone();
+      self.push_navigation_route(route);
+    }
+  }
+
+  pub fn cycle_layout_mode(&mut self) {
+    self.layout_mode = match self.layout_mode {
+      LayoutMode::Normal => LayoutMode::Compact,
+      LayoutMode::Compact => LayoutMode::Wide,
+      LayoutMode::Wide => LayoutMode::Normal,
+    };
+  }
+
+  /// the info blocks above the resource tabs are left out in the compact layout
+  pub fn is_info_bar_shown(&self) -> bool {
+    self.show_info_bar && self.layout_mode != LayoutMode::Compact
+  }
+
+  /// apply the layout and timestamp format to the resource tables, the optional table columns
+  /// are shown when the wide layout has room for them
+  pub fn update_table_display(&mut self, width: u16) {
+    let wide = self.layout_mode == LayoutMode::Wide && width >= WIDE_LAYOUT_MIN_WIDTH;
+    let timestamp_format = self.timestamp_format;
+    for (block, _) in SEARCHED_BLOCKS {
+      if let Some(table) = self.data.resource_table(block) {
+        table.set_wide(wide);
+        table.set_timestamp_format(timestamp_format);
+      }
+    }
+  }
+
+  /// cycle the split pane through describe, YAML, logs and off
+  pub fn cycle_split_pane(&mut self) {
+    self.split_pane = match self.split_pane {
+      None => Some(SplitPane::Describe),
+      Some(SplitPane::Describe) => Some(SplitPane::Yaml),
+      Some(SplitPane::Yaml) => Some(SplitPane::Logs),
+      Some(SplitPane::Logs) => None,
+    };
+    self.split_pane_item = None;
+  }
+
+  /// the split pane is drawn only next to resource tables with a selected item
+  pub fn is_split_pane_shown(&self) -> bool {
+    self.split_pane.is_some() && self.split_pane_item.is_some()
+  }
+
+  /// switch manifests between YAML and JSON, showing the one in the YAML view or split pane again
+  pub fn toggle_manifest_format(&mut self) {
+    self.manifest_format = self.manifest_format.next();
+    if self.get_current_route().active_block == ActiveBlock::Yaml {
+      if let Some((_, yaml)) = self
+        .data
+        .selected_details(self.get_prev_route().active_block)
+      {
+        self.data.describe_out = ScrollableTxt::with_string(self.manifest_format.render(yaml));
+      }
+    } else if self.split_pane == Some(SplitPane::Yaml) {
+      self.split_pane_item = None;
+    }
+  }
+
+  /// keep the split pane in sync with the item selected in the active resource table
+  pub async fn update_split_pane(&mut self) {
+    if self.split_pane == Some(SplitPane::Logs) {
+      return self.update_split_pane_logs().await;
+    }
+    let details = match self.split_pane {
+      Some(_) if self.get_current_route().id == RouteId::Home => self
+        .data
+        .selected_details(self.get_current_route().active_block),
+      _ => None,
+    };
+    match (self.split_pane, details) {
+      (Some(SplitPane::Describe), Some((IoCmdEvent::GetDescribe { kind, value, ns }, _))) => {
+        let describe = IoCmdEvent::DescribeInSplitPane { kind, value, ns };
+        let item = format!("{:?}", describe);
+        if self.split_pane_item.as_ref() != Some(&item) {
+          self.split_pane_item = Some(item);
+          self.data.split_pane_out = ScrollableTxt::new();
+          self.dispatch_cmd(describe).await;
+        }
+      }
+      (Some(SplitPane::Yaml), Some((_, yaml))) => {
+        if self.split_pane_item.as_ref() != Some(&yaml) {
+          self.data.split_pane_out =
+            ScrollableTxt::with_string(self.manifest_format.render(yaml.clone()));
+          self.split_pane_item = Some(yaml);
+        }
+      }
+      _ => self.split_pane_item = None,
+    }
+  }
+
+  /// stream the logs of the selected pod's first container, or of the selected container, into
+  /// the split pane, the stream is started again when it ends while the container stays selected
+  async fn update_split_pane_logs(&mut self) {
+    let route = self.get_current_route();
+    let selected_pod = match route.id {
+      RouteId::Home => self.data.pods.get_selected_item_copy(),
+      _ => None,
+    };
+    let target = match (route.active_block, selected_pod) {
+      (ActiveBlock::Pods, Some(pod)) => pod
+        .containers
+        .first()
+        .map(|c| (pod.namespace.clone(), pod.name.clone(), c.name.clone())),
+      (ActiveBlock::Containers, Some(pod)) => self
+        .data
+        .containers
+        .get_selected_item_copy()
+        .map(|c| (pod.namespace, c.pod_name, c.name)),
+      _ => None,
+    };
+    let (namespace, pod, container) = match target {
+      Some(target) => target,
+      None => {
+        self.split_pane_item = None;
+        // ends a stream still running for the container shown before
+        self.data.split_pane_logs = LogsState::new(String::default());
+        return;
+      }
+    };
+    let id = format!("{}/{}/{}", namespace, pod, container);
+    let tail = if self.split_pane_item.as_ref() != Some(&id) {
+      self.split_pane_item = Some(id.clone());
+      self.data.split_pane_logs = LogsState::new(id).with_limits(self.log_limits);
+      true
+    } else if !self.split_pane_streaming && self.is_poll_due(ActiveBlock::Logs) {
+      // do not tail to avoid duplicates
+      false
+    } else {
+      return;
+    };
+    self
+      .dispatch_stream(IoStreamEvent::GetSplitPaneLogs {
+        namespace,
+        pod,
+        container,
+        tail,
+      })
+      .await;
+  }
+
+  /// fetch the namespaces, nodes and the live tabs, the other tabs are fetched when opened
+  pub async fn cache_all_resource_data(&mut self) {
+    self.dispatch(IoEvent::GetNamespaces).await;
+    self.dispatch(IoEvent::GetNodes).await;
+    for block in self.live_tabs() {
+      if let Some(event) = IoEvent::list(block) {
+        self.dispatch(event).await;
+      }
+    }
+    self.dispatch(IoEvent::GetMetrics).await;
+  }
+
+  /// the resource tabs kept up to date, the one shown or under the view shown and the pinned
+  /// ones. Nodes are always kept up to date
+  pub fn live_tabs(&self) -> Vec<ActiveBlock> {
+    let open = self
+      .navigation_stack
+      .iter()
+      .rev()
+      .map(|route| match route.active_block {
+        ActiveBlock::Containers => ActiveBlock::Pods,
+        ActiveBlock::HelmHistory => ActiveBlock::HelmReleases,
+        block => block,
+      })
+      .find(|block| *block != ActiveBlock::Nodes && IoEvent::list(*block).is_some());
+    let mut tabs: Vec<ActiveBlock> = open.into_iter().collect();
+    for block in &self.pinned_tabs {
+      if !tabs.contains(block) {
+        tabs.push(*block);
+      }
+    }
+    tabs
+  }
+
+  pub async fn dispatch_by_active_block(&mut self, active_block: ActiveBlock) {
+    match active_block {
+      // nodes are polled on their own interval
+      ActiveBlock::Nodes => {}
+      ActiveBlock::Logs if !self.is_streaming => {
+        // do not tail to avoid duplicates
+        self.dispatch_stream(IoStreamEvent::GetPodLogs(false)).await;
+      }
+      block => {
+        if let Some(event) = IoEvent::list(block) {
+          self.dispatch(event).await;
+        }
+      }
+    }
+  }
+
+  /// whether the data of the block is polled on this tick, containers come with the pods and the
+  /// history of a Helm release with the releases
+  fn is_poll_due(&self, block: ActiveBlock) -> bool {
+    let block = match block {
+      ActiveBlock::Containers => ActiveBlock::Pods,
+      ActiveBlock::HelmHistory => ActiveBlock::HelmReleases,
+      block => block,
+    };
+    let ticks = self
+      .resource_poll_ticks
+      .iter()
+      .find(|(b, _)| *b == block)
+      .map_or(self.tick_until_poll, |(_, ticks)| *ticks);
+    self.is_routing || self.is_tick_due(ticks)
+  }
+
+  /// whether a poll made every so many ticks is due, with 0 ticks only on the first tick
+  fn is_tick_due(&self, ticks: u64) -> bool {
+    if ticks == 0 {
+      self.tick_count == 0
+    } else {
+      self.tick_count.is_multiple_of(ticks)
+    }
+  }
+
+  /// poll the current view on the next tick instead of waiting for its interval
+  pub fn refresh_current_view(&mut self) {
+    self.is_routing = true;
+  }
+
+  pub async fn on_tick(&mut self, first_render: bool) {
+    // Make one time requests on first render or refresh
+    if self.refresh {
+      if !first_render {
+        self.dispatch(IoEvent::RefreshClient).await;
+        self.dispatch_stream(IoStreamEvent::RefreshClient).await;
+      }
+      self.dispatch(IoEvent::GetKubeConfig).await;
+      // call these once to pre-load data
+      self.cache_all_resource_data().await;
+      self.refresh = false;
+    }
+    self.apply_pending_filter_input(Instant::now());
+    // poll the current view right away when the API server is retried
+    if self.connection.take_retry(Instant::now()) {
+      self.is_routing = true;
+    }
+    // make network requests only in intervals to avoid hogging up the network
+    let poll_resources = self.is_tick_due(self.tick_until_poll) || self.is_routing;
+    // metrics have their own interval as metrics-server can struggle on big clusters
+    let poll_metrics = self.is_tick_due(self.tick_until_metrics_poll) || self.is_routing;
+    // make periodic network calls based on active route and active block to avoid hogging
+    match self.get_current_route().id {
+      RouteId::Home => {
+        if poll_metrics {
+          self.dispatch(IoEvent::GetNodeMetrics).await;
+        }
+        let storage_ticks = self.tick_until_metrics_poll * EPHEMERAL_STORAGE_POLL_FACTOR;
+        if !self.large_cluster && self.is_tick_due(storage_ticks) {
+          self.dispatch(IoEvent::GetEphemeralStorage).await;
+        }
+        if poll_resources {
+          if self.data.clis.is_empty() {
+            self.dispatch_cmd(IoCmdEvent::GetCliInfo).await;
+          }
+          self.dispatch(IoEvent::GetNamespaces).await;
+        }
+        if self.is_poll_due(ActiveBlock::Nodes) {
+          self.dispatch(IoEvent::GetNodes).await;
+        }
+
+        let mut active_block = self.get_current_route().active_block;
+        if active_block == ActiveBlock::Namespaces {
+          active_block = self.get_prev_route().active_block;
+        }
+        if self.is_poll_due(active_block) {
+          self.dispatch_by_active_block(active_block).await;
+        }
+        for block in self.pinned_tabs.clone() {
+          if block != active_block && self.is_poll_due(block) {
+            self.dispatch_by_active_block(block).await;
+          }
+        }
+      }
+      RouteId::Utilization if poll_metrics => {
+        self.dispatch(IoEvent::GetMetrics).await;
+      }
+      RouteId::MetricsHistory if poll_metrics => {
+        self.dispatch(IoEvent::GetNodeMetrics).await;
+      }
+      RouteId::Fleet if poll_resources && !self.fleet_contexts.is_empty() => {
+        self.dispatch(IoEvent::GetFleetHealth).await;
+      }
+      _ => {}
+    }
+    self.is_routing = false;
+    self.update_split_pane().await;
+    self.notifications.prune(Instant::now());
+
+    self.tick_count += 1;
+  }
+}
+
+/// utility methods for tests
+#[cfg(test)]
+#[macro_use]
+mod test_utils {
+  use std::{fmt, fs};
+
+  use k8s_openapi::{
+    apimachinery::pkg::apis::meta::v1::Time,
+    chrono::{DateTime, TimeZone, Utc},
+  };
+  use kube::{api::ObjectList, Resource};
+  use serde::{de::DeserializeOwned, Serialize};
+
+  use super::models::KubeResource;
+
+  pub fn convert_resource_from_file<K, T>(filename: &str) -> (Vec<T>, Vec<K>)
+  where
+    <K as Resource>::DynamicType: Default,
+    K: Serialize + Clone + DeserializeOwned + fmt::Debug,
+    K: Resource,
+    T: KubeResource<K> + From<K>,
+  {
+    let res_list = load_resource_from_file(filename);
+    let original_res_list = res_list.items.clone();
+
+    let resources: Vec<T> = res_list.into_iter().map(K::into).collect::<Vec<_>>();
+
+    (resources, original_res_list)
   }
 
   pub fn load_resource_from_file<K>(filename: &str) -> ObjectList<K>
@@ -713,7 +2278,599 @@ mod test_utils {
 mod tests {
   use tokio::sync::mpsc;
 
-  use super::*;
+  use super::{models::Scrollable, *};
+
+  #[tokio::test]
+  async fn test_update_split_pane() {
+    let (sync_io_cmd_tx, mut sync_io_cmd_rx) = mpsc::channel::<IoCmdEvent>(500);
+    let (sync_io_stream_tx, mut sync_io_stream_rx) = mpsc::channel::<IoStreamEvent>(500);
+    let mut app = App {
+      io_cmd_tx: Some(sync_io_cmd_tx),
+      io_stream_tx: Some(sync_io_stream_tx),
+      ..App::default()
+    };
+    app.route_home();
+    let mut pod = KubePod::default();
+    pod.name = "pod-1".into();
+    pod.namespace = "ns".into();
+    pod.containers = vec![KubeContainer {
+      name: "app".into(),
+      pod_name: "pod-1".into(),
+      ..KubeContainer::default()
+    }];
+    app.data.pods.set_items(vec![pod.clone()]);
+
+    app.update_split_pane().await;
+    assert!(!app.is_split_pane_shown());
+
+    app.cycle_split_pane();
+    app.update_split_pane().await;
+    assert!(app.is_split_pane_shown());
+    assert_eq!(
+      sync_io_cmd_rx.recv().await.unwrap(),
+      IoCmdEvent::DescribeInSplitPane {
+        kind: "pod".into(),
+        value: "pod-1".into(),
+        ns: Some("ns".into()),
+      }
+    );
+    // nothing is fetched again while the selection stays the same
+    app.update_split_pane().await;
+    assert!(sync_io_cmd_rx.try_recv().is_err());
+
+    app.cycle_split_pane();
+    app.update_split_pane().await;
+    assert_eq!(app.data.split_pane_out.get_txt(), pod.resource_to_yaml());
+    // the describe view keeps its own output
+    assert!(app.data.describe_out.get_txt().is_empty());
+
+    app.cycle_split_pane();
+    app.update_split_pane().await;
+    assert!(app.is_split_pane_shown());
+    assert_eq!(
+      sync_io_stream_rx.recv().await.unwrap(),
+      IoStreamEvent::GetSplitPaneLogs {
+        namespace: "ns".into(),
+        pod: "pod-1".into(),
+        container: "app".into(),
+        tail: true,
+      }
+    );
+    assert_eq!(app.data.split_pane_logs.id, "ns/pod-1/app");
+    // a running stream is not started again
+    app.split_pane_streaming = true;
+    app.update_split_pane().await;
+    assert!(sync_io_stream_rx.try_recv().is_err());
+
+    // not shown next to blocks without a selectable resource
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::More);
+    app.update_split_pane().await;
+    assert!(!app.is_split_pane_shown());
+
+    app.cycle_split_pane();
+    assert_eq!(app.split_pane, None);
+  }
+
+  #[test]
+  fn test_kubectl_command() {
+    let mut app = App::default();
+    app.route_home();
+    assert_eq!(app.kubectl_command(), None);
+
+    let mut pod = KubePod::default();
+    pod.name = "api-0".into();
+    pod.namespace = "web".into();
+    app.data.pods.set_items(vec![pod]);
+    assert_eq!(
+      app.kubectl_command().as_deref(),
+      Some("kubectl get -n web pod/api-0")
+    );
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
+    assert_eq!(
+      app.kubectl_command().as_deref(),
+      Some("kubectl describe -n web pod/api-0")
+    );
+    app.pop_navigation_stack();
+
+    app.data.selected.context = Some("prod".into());
+    app.data.selected.container = Some("app".into());
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Logs);
+    assert_eq!(
+      app.kubectl_command().as_deref(),
+      Some("kubectl logs -f -n web pod/api-0 -c app --tail=10 --context prod")
+    );
+    app.pop_navigation_stack();
+
+    app.manifest_format = ManifestFormat::Json;
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Yaml);
+    assert_eq!(
+      app.kubectl_command().as_deref(),
+      Some("kubectl get -n web pod/api-0 -o json --context prod")
+    );
+  }
+
+  #[test]
+  fn test_attach_to_container() {
+    let mut app = App::default();
+    app.attach_to_container();
+    assert_eq!(app.attach_request, None);
+
+    let mut pod = KubePod::default();
+    pod.name = "api-0".into();
+    pod.namespace = "web".into();
+    app.data.pods.set_items(vec![pod]);
+    app.data.containers.set_items(vec![KubeContainer {
+      name: "app".into(),
+      tty: true,
+      ..KubeContainer::default()
+    }]);
+    app.data.selected.context = Some("prod".into());
+    app.read_only = true;
+    app.attach_to_container();
+    assert_eq!(app.attach_request, None);
+
+    app.read_only = false;
+    app.attach_to_container();
+    assert_eq!(
+      app.attach_request.unwrap(),
+      AttachRequest {
+        context: Some("prod".into()),
+        namespace: "web".into(),
+        pod: "api-0".into(),
+        container: "app".into(),
+        tty: true,
+      }
+    );
+  }
+
+  #[test]
+  fn test_pinned_namespaces() {
+    let mut app = App::default();
+    let namespaces = ["default", "kube-system", "web"]
+      .iter()
+      .map(|name| {
+        let mut ns = KubeNs::default();
+        ns.name = name.to_string();
+        ns
+      })
+      .collect::<Vec<_>>();
+    app.pinned_namespaces = vec!["web".into(), "missing".into()];
+    app.set_namespaces(namespaces);
+    let names = |a: &App| {
+      a.data
+        .namespaces
+        .items
+        .iter()
+        .map(|ns| ns.name.clone())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&app), vec!["web", "default", "kube-system"]);
+
+    app.data.namespaces.state.select(Some(2));
+    app.toggle_pinned_namespace();
+    assert_eq!(app.pinned_namespaces, vec!["web", "missing", "kube-system"]);
+    assert_eq!(names(&app), vec!["web", "kube-system", "default"]);
+    assert_eq!(app.data.namespaces.state.selected(), Some(1));
+
+    app.data.namespaces.state.select(Some(0));
+    app.toggle_pinned_namespace();
+    assert_eq!(names(&app), vec!["kube-system", "web", "default"]);
+    assert_eq!(app.data.namespaces.state.selected(), Some(1));
+  }
+
+  #[test]
+  fn test_filtered_tabs() {
+    let mut app = App::default();
+    assert!(!app.filtered_tabs().iter().any(|f| *f));
+    app.data.services.set_filter("api");
+    app.data.roles.set_filter("-l app=web");
+    let filtered = app.filtered_tabs();
+    assert_eq!(filtered.len(), app.context_tabs.items.len());
+    assert!(!filtered[0]);
+    assert!(filtered[1]);
+    // roles are under the more tab
+    assert!(filtered[filtered.len() - 1]);
+    // switching tabs keeps the filter of each table
+    app.context_tabs.next();
+    app.context_tabs.next();
+    assert_eq!(app.data.services.filter(), "api");
+  }
+
+  #[test]
+  fn test_large_cluster() {
+    let mut app = App::default();
+    app.count_pods(4999);
+    assert!(!app.large_cluster);
+    app.count_pods(5000);
+    assert!(app.large_cluster);
+    assert_eq!(app.notifications.iter().count(), 1);
+    // notified once
+    app.count_pods(6000);
+    assert_eq!(app.notifications.iter().count(), 1);
+    app.reset();
+    assert!(!app.large_cluster);
+
+    app.large_cluster_threshold = 0;
+    app.count_pods(100_000);
+    assert!(!app.large_cluster);
+  }
+
+  #[test]
+  fn test_ui_state() {
+    let mut app = App::default();
+    app.route_home();
+    app.data.active_context = Some(KubeContext {
+      name: "prod".into(),
+      ..KubeContext::default()
+    });
+    app.data.selected.ns = Some("web".into());
+    app.route_resource(ActiveBlock::Deployments);
+    app.data.pods.set_columns(&["Name", "Age"]);
+    app.data.pods.sort_by_next_column();
+    let state = app.ui_state();
+    assert_eq!(state.context, Some("prod".into()));
+    assert_eq!(state.namespace, Some("web".into()));
+    assert_eq!(state.context_namespaces["prod"], Some("web".into()));
+    assert_eq!(state.resource_tab, 6);
+    assert_eq!(state.sorts.keys().collect::<Vec<_>>(), vec!["Pods"]);
+
+    let mut restored = App::default();
+    restored.route_home();
+    restored.restore_ui_state(state);
+    assert_eq!(restored.data.selected.context, Some("prod".into()));
+    assert_eq!(restored.data.selected.ns, Some("web".into()));
+    assert_eq!(
+      restored.get_current_route().active_block,
+      ActiveBlock::Deployments
+    );
+    assert_eq!(restored.data.pods.sort, app.data.pods.sort);
+  }
+
+  #[test]
+  fn test_context_namespaces() {
+    let contexts = |active: &str| {
+      ["dev", "prod"]
+        .iter()
+        .map(|name| KubeContext {
+          name: (*name).into(),
+          namespace: Some("default".into()),
+          is_active: *name == active,
+          ..KubeContext::default()
+        })
+        .collect::<Vec<_>>()
+    };
+    let mut app = App::default();
+    app.set_contexts(contexts("dev"));
+    app.data.selected.ns = Some("web".into());
+
+    app.switch_context("prod".into());
+    assert_eq!(app.data.selected.ns, Some("default".into()));
+    assert_eq!(app.context_namespaces["dev"], Some("web".into()));
+
+    // the client connected to prod
+    app.set_contexts(contexts("prod"));
+    app.switch_context("dev".into());
+    assert_eq!(app.data.selected.ns, Some("web".into()));
+    assert_eq!(app.context_namespaces["prod"], Some("default".into()));
+  }
+
+  #[tokio::test]
+  async fn test_rollback_helm_release() {
+    let revision = |number: u32, status: &str| {
+      let mut release = KubeHelmRelease::default();
+      release.name = "frontend".into();
+      release.namespace = "web".into();
+      release.chart = format!("nginx-13.{}.0", number);
+      release.revision = number;
+      release.status = status.into();
+      release
+    };
+    let mut app = App::default();
+    app
+      .data
+      .set_helm_revisions(vec![revision(1, "superseded"), revision(2, "deployed")]);
+    let latest = app.data.helm_releases.items[0].clone();
+    assert_eq!(latest.revision, 2);
+
+    app.open_helm_history(&latest);
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::HelmHistory
+    );
+    assert_eq!(
+      app.breadcrumbs(),
+      vec![
+        "no context",
+        "all namespaces",
+        "HelmReleases",
+        "frontend",
+        "History"
+      ]
+    );
+    // the deployed revision is listed first and can't be rolled back to
+    app.rollback_helm_release().await;
+    assert!(app.confirmation.is_none());
+
+    app.data.helm_history.state.select(Some(1));
+    app.read_only = true;
+    app.rollback_helm_release().await;
+    assert!(app.confirmation.is_none());
+
+    app.read_only = false;
+    app.confirm_policies.rollback = ConfirmPolicy::Paranoid;
+    app.rollback_helm_release().await;
+    let confirmation = app.confirmation.as_ref().unwrap();
+    assert_eq!(
+      confirmation.question,
+      "Roll back web/frontend to revision 1 (nginx-13.1.0)?"
+    );
+    assert_eq!(
+      confirmation.action,
+      IoCmdEvent::HelmRollback {
+        name: "frontend".into(),
+        namespace: "web".into(),
+        revision: 1,
+        context: None,
+      }
+    );
+    // kept open until the name is typed
+    app.confirm_action().await;
+    assert!(app.confirmation.is_some());
+    app.confirmation.as_mut().unwrap().input = "frontend".into();
+    app.confirm_action().await;
+    assert!(app.confirmation.is_none());
+
+    // a new revision shows up in the open history
+    app.data.set_helm_revisions(vec![
+      revision(1, "superseded"),
+      revision(2, "superseded"),
+      revision(3, "deployed"),
+    ]);
+    assert_eq!(app.data.helm_history.items.len(), 3);
+    assert_eq!(app.data.helm_releases.items[0].revision, 3);
+
+    // the first revision has none before it to diff with
+    app.data.helm_history.state.select(Some(2));
+    app.diff_helm_revisions().await;
+    let last_message = |notified: &App| {
+      notified
+        .notifications
+        .iter()
+        .last()
+        .unwrap()
+        .message
+        .clone()
+    };
+    assert_eq!(
+      last_message(&app),
+      "Revision 1 of frontend has no earlier revision to diff with"
+    );
+    // the revisions share a name but are marked apart
+    app.data.helm_history.toggle_mark();
+    app.data.helm_history.state.select(Some(0));
+    app.data.helm_history.toggle_mark();
+    assert_eq!(app.data.helm_history.marked_items().len(), 2);
+    app.data.helm_history.state.select(Some(1));
+    app.data.helm_history.toggle_mark();
+    app.diff_helm_revisions().await;
+    assert_eq!(last_message(&app), "Mark two revisions to diff them");
+    // the diff command could change the cluster
+    app.read_only = true;
+    app.diff_helm_revisions().await;
+    assert_eq!(
+      last_message(&app),
+      "Changing the cluster is disabled in read-only mode"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_uninstall_helm_release() {
+    let release = |name: &str, status: &str| {
+      let mut release = KubeHelmRelease::default();
+      release.name = name.into();
+      release.namespace = "web".into();
+      release.chart = "nginx-13.2.1".into();
+      release.revision = 1;
+      release.status = status.into();
+      release
+    };
+    let mut app = App::default();
+    app.data.set_helm_revisions(vec![
+      release("api", "uninstalled"),
+      release("frontend", "deployed"),
+    ]);
+    // kept history of an uninstalled release
+    app.uninstall_helm_release(false).await;
+    assert!(app.confirmation.is_none());
+
+    app.data.helm_releases.state.select(Some(1));
+    app.read_only = true;
+    app.uninstall_helm_release(false).await;
+    assert!(app.confirmation.is_none());
+
+    app.read_only = false;
+    app.uninstall_helm_release(true).await;
+    let confirmation = app.confirmation.as_ref().unwrap();
+    assert_eq!(
+      confirmation.question,
+      "Uninstall web/frontend (nginx-13.2.1) keeping its history?"
+    );
+    assert_eq!(
+      confirmation.action,
+      IoCmdEvent::HelmUninstall {
+        name: "frontend".into(),
+        namespace: "web".into(),
+        keep_history: true,
+        context: None,
+        deleted: vec![],
+      }
+    );
+  }
+
+  #[test]
+  fn test_context_switcher() {
+    let mut app = App::default();
+    app.set_contexts(
+      ["dev", "staging", "prod"]
+        .iter()
+        .map(|name| KubeContext {
+          name: (*name).into(),
+          is_active: *name == "dev",
+          ..KubeContext::default()
+        })
+        .collect(),
+    );
+    app.recent_contexts = vec!["prod".into()];
+    app.open_context_switcher();
+    let switcher = app.context_switcher.as_mut().unwrap();
+    assert_eq!(switcher.selected_context(), Some("prod"));
+    switcher.input = "stg".into();
+    app.update_context_matches();
+    app.switch_to_picked_context();
+    assert!(app.context_switcher.is_none());
+    assert_eq!(app.data.selected.context, Some("staging".into()));
+    assert_eq!(app.recent_contexts, vec!["staging", "dev", "prod"]);
+
+    assert_eq!(app.context_namespaces["dev"], None);
+
+    // picking the active context switches nothing
+    app.refresh = false;
+    app.open_context_switcher();
+    app.context_switcher.as_mut().unwrap().input = "dev".into();
+    app.update_context_matches();
+    app.switch_to_picked_context();
+    assert!(!app.refresh);
+  }
+
+  #[test]
+  fn test_breadcrumbs() {
+    let mut app = App::default();
+    app.route_home();
+    assert_eq!(
+      app.breadcrumbs(),
+      vec!["no context", "all namespaces", "Pods"]
+    );
+
+    app.data.active_context = Some(KubeContext {
+      name: "prod".into(),
+      ..KubeContext::default()
+    });
+    app.data.selected.ns = Some("web".into());
+    let mut pod = KubePod::default();
+    pod.name = "nginx".into();
+    app.data.pods.set_items(vec![pod]);
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
+    assert_eq!(
+      app.breadcrumbs(),
+      vec!["prod", "web", "Pods", "nginx", "Describe"]
+    );
+
+    app.data.selected.pod = Some("nginx".into());
+    app.data.selected.container = Some("app".into());
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Containers);
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Logs);
+    assert_eq!(
+      app.breadcrumbs(),
+      vec!["prod", "web", "Pods", "nginx", "app", "Logs"]
+    );
+
+    app.route_resource(ActiveBlock::CronJobs);
+    assert_eq!(app.breadcrumbs(), vec!["prod", "web", "CronJobs"]);
+
+    app.route_utilization();
+    assert_eq!(app.breadcrumbs(), vec!["prod", "Utilization"]);
+  }
+
+  #[test]
+  fn test_jump_to_finder_match() {
+    let mut app = App::default();
+    let mut pods = vec![KubePod::default(), KubePod::default()];
+    pods[0].name = "redis".into();
+    pods[1].name = "nginx".into();
+    app.data.pods.set_items(pods);
+    let mut secret = KubeSecret::default();
+    secret.name = "nginx".into();
+    secret.namespace = "web".into();
+    app.data.secrets.set_items(vec![secret]);
+
+    app.open_resource_finder();
+    app.resource_finder.as_mut().unwrap().input = "ngx".into();
+    app.update_finder_matches();
+    let matches = &app.resource_finder.as_ref().unwrap().matches.items;
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0].kind, "Pod");
+    assert_eq!(matches[1].kind, "Secret");
+    assert_eq!(matches[1].namespace, "web");
+
+    app.resource_finder.as_mut().unwrap().matches.scroll_down(1);
+    app.jump_to_finder_match();
+    assert!(app.resource_finder.is_none());
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Secrets);
+    assert_eq!(app.get_prev_route().active_block, ActiveBlock::More);
+    assert_eq!(app.data.secrets.state.selected(), Some(0));
+
+    app.open_resource_finder();
+    app.resource_finder.as_mut().unwrap().input = "ngx".into();
+    app.update_finder_matches();
+    app.jump_to_finder_match();
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Pods);
+    assert_eq!(app.data.pods.state.selected(), Some(1));
+  }
+
+  #[tokio::test]
+  async fn test_run_command() {
+    let mut app = App::default();
+    app.route_utilization();
+
+    app.open_command_palette();
+    app.command_palette.as_mut().unwrap().input = "deploy".into();
+    app.update_command_suggestions();
+    app.run_command().await;
+    assert!(app.command_palette.is_none());
+    assert_eq!(app.main_tabs.index, 0);
+    assert_eq!(app.context_tabs.index, 6);
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::Deployments
+    );
+
+    // resources from the more menu go back to it
+    app.open_command_palette();
+    app.command_palette.as_mut().unwrap().input = "cj".into();
+    app.update_command_suggestions();
+    app.run_command().await;
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::CronJobs);
+    assert_eq!(app.get_prev_route().active_block, ActiveBlock::More);
+
+    app.open_command_palette();
+    app.command_palette.as_mut().unwrap().input = "ns kube-system".into();
+    app.update_command_suggestions();
+    app.run_command().await;
+    assert_eq!(app.data.selected.ns, Some("kube-system".into()));
+
+    app.data.contexts.set_items(vec![KubeContext {
+      name: "prod".into(),
+      ..KubeContext::default()
+    }]);
+    app.open_command_palette();
+    app.command_palette.as_mut().unwrap().input = "ctx pr".into();
+    app.update_command_suggestions();
+    app.run_command().await;
+    assert_eq!(app.data.selected.context, Some("prod".into()));
+    assert!(app.api_error.is_empty());
+
+    app.open_command_palette();
+    app.command_palette.as_mut().unwrap().input = "ctx dev".into();
+    app.update_command_suggestions();
+    app.run_command().await;
+    assert_eq!(app.api_error, "Unknown context 'dev'");
+
+    app.open_command_palette();
+    app.command_palette.as_mut().unwrap().input = "foo bar".into();
+    app.update_command_suggestions();
+    app.run_command().await;
+    assert_eq!(app.api_error, "Unknown command 'foo bar'");
+  }
 
   #[tokio::test]
   async fn test_on_tick_first_render() {
@@ -731,31 +2888,16 @@ mod tests {
     // test first render
     app.on_tick(true).await;
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetKubeConfig);
+    // only the open tab is fetched with the namespaces and nodes
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetServices);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodes);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetConfigMaps);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetStatefulSets);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetReplicaSets);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetDeployments);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetJobs);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetDaemonSets);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetCronJobs);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetSecrets);
-    assert_eq!(
-      sync_io_rx.recv().await.unwrap(),
-      IoEvent::GetReplicationControllers
-    );
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetStorageClasses);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetRoles);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetRoleBindings);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetClusterRoles);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetMetrics);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodeMetrics);
     assert_eq!(
       sync_io_rx.recv().await.unwrap(),
-      IoEvent::GetClusterRoleBinding
+      IoEvent::GetEphemeralStorage
     );
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetMetrics);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodes);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
@@ -788,13 +2930,9 @@ mod tests {
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::RefreshClient);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetKubeConfig);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetServices);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodes);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetConfigMaps);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetStatefulSets);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetReplicaSets);
-    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetDeployments);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetMetrics);
 
     assert_eq!(
       sync_io_stream_rx.recv().await.unwrap(),
@@ -826,6 +2964,7 @@ mod tests {
     assert_eq!(app.tick_count, 2);
     // test first render
     app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodeMetrics);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
     assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodes);
 
@@ -838,4 +2977,119 @@ mod tests {
     assert!(!app.is_routing);
     assert_eq!(app.tick_count, 3);
   }
+  #[tokio::test]
+  async fn test_on_tick_metrics_poll_interval() {
+    let (sync_io_tx, mut sync_io_rx) = mpsc::channel::<IoEvent>(500);
+    let (sync_io_cmd_tx, _sync_io_cmd_rx) = mpsc::channel::<IoCmdEvent>(500);
+
+    let mut app = App {
+      tick_until_poll: 2,
+      tick_until_metrics_poll: 6,
+      tick_count: 2,
+      refresh: false,
+      io_tx: Some(sync_io_tx),
+      io_cmd_tx: Some(sync_io_cmd_tx),
+      ..App::default()
+    };
+
+    // resources are due but metrics are not
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodes);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
+    assert!(sync_io_rx.try_recv().is_err());
+
+    // neither are due
+    app.on_tick(false).await;
+    assert!(sync_io_rx.try_recv().is_err());
+
+    // both are due
+    app.tick_count = 6;
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodeMetrics);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
+
+    // the disk usage only with every fifth metrics poll
+    app.tick_count = 30;
+    while sync_io_rx.try_recv().is_ok() {}
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodeMetrics);
+    assert_eq!(
+      sync_io_rx.recv().await.unwrap(),
+      IoEvent::GetEphemeralStorage
+    );
+
+    // only metrics are due on the utilization screen
+    app.route_utilization();
+    app.is_routing = false;
+    app.tick_count = 12;
+    while sync_io_rx.try_recv().is_ok() {}
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetMetrics);
+    assert!(sync_io_rx.try_recv().is_err());
+  }
+
+  #[tokio::test]
+  async fn test_on_tick_resource_poll_ticks() {
+    let (sync_io_tx, mut sync_io_rx) = mpsc::channel::<IoEvent>(500);
+    let (sync_io_cmd_tx, _sync_io_cmd_rx) = mpsc::channel::<IoCmdEvent>(500);
+
+    let mut app = App {
+      tick_until_poll: 2,
+      tick_until_metrics_poll: 100,
+      resource_poll_ticks: vec![(ActiveBlock::Nodes, 6), (ActiveBlock::Pods, 1)],
+      tick_count: 1,
+      refresh: false,
+      redraw: false,
+      io_tx: Some(sync_io_tx),
+      io_cmd_tx: Some(sync_io_cmd_tx),
+      ..App::default()
+    };
+
+    // pods poll every tick
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
+    assert!(sync_io_rx.try_recv().is_err());
+    // the loading indicator is drawn
+    assert!(app.redraw);
+
+    // nodes are not due with the other resources
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
+    assert!(sync_io_rx.try_recv().is_err());
+
+    app.tick_count = 6;
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodes);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetPods);
+
+    // forcing a poll of the current view skips the intervals
+    app.route_resource(ActiveBlock::Services);
+    app.is_routing = false;
+    app.tick_count = 7;
+    while sync_io_rx.try_recv().is_ok() {}
+    app.refresh_current_view();
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodeMetrics);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNodes);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetServices);
+    assert!(!app.is_routing);
+
+    // pinned tabs are polled under other tabs
+    app.pinned_tabs = vec![ActiveBlock::Deployments, ActiveBlock::Services];
+    assert_eq!(
+      app.live_tabs(),
+      vec![ActiveBlock::Services, ActiveBlock::Deployments]
+    );
+    app.tick_count = 8;
+    while sync_io_rx.try_recv().is_ok() {}
+    app.on_tick(false).await;
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetNamespaces);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetServices);
+    assert_eq!(sync_io_rx.recv().await.unwrap(), IoEvent::GetDeployments);
+    assert!(sync_io_rx.try_recv().is_err());
+  }
 }
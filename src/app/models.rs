@@ -1,6 +1,13 @@
-use std::collections::VecDeque;
+use std::{
+  collections::{BTreeSet, VecDeque},
+  ops::Range,
+};
 
-use serde::Serialize;
+use k8s_openapi::{
+  apimachinery::pkg::apis::meta::v1::ObjectMeta,
+  chrono::{DateTime, Local, Utc},
+};
+use serde::{Deserialize, Serialize};
 use tui::{
   backend::Backend,
   layout::Rect,
@@ -10,7 +17,7 @@ use tui::{
   Frame,
 };
 
-use super::Route;
+use super::{hooks::Decorations, table_filter::TableFilter, utils, Route};
 
 pub trait KubeResource<T: Serialize> {
   fn get_k8s_obj(&self) -> &T;
@@ -22,12 +29,294 @@ pub trait KubeResource<T: Serialize> {
       Err(_) => "".into(),
     }
   }
+
+  /// the last applied and the live manifest as YAML, for diffing them. None when the resource
+  /// wasn't created or updated with `kubectl apply`
+  fn last_applied_manifests(&self) -> Option<(String, String)> {
+    last_applied_manifests(&self.resource_to_yaml())
+  }
+}
+
+/// annotation `kubectl apply` keeps the applied manifest in
+const LAST_APPLIED: &str = "kubectl.kubernetes.io/last-applied-configuration";
+/// metadata set by the API server, left out of the live manifest in a diff
+const SERVER_METADATA: [&str; 6] = [
+  "creationTimestamp",
+  "generation",
+  "managedFields",
+  "resourceVersion",
+  "selfLink",
+  "uid",
+];
+
+/// the manifest in the last applied annotation and the live one without its status, metadata set
+/// by the API server and the annotation itself. Keys are sorted on both sides like they are in
+/// the annotation
+fn last_applied_manifests(yaml: &str) -> Option<(String, String)> {
+  let mut live: serde_json::Value = serde_yaml::from_str(yaml).ok()?;
+  let applied: serde_json::Value =
+    serde_json::from_str(live["metadata"]["annotations"][LAST_APPLIED].as_str()?).ok()?;
+  if let Some(manifest) = live.as_object_mut() {
+    manifest.remove("status");
+  }
+  if let Some(metadata) = live.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+    for key in SERVER_METADATA {
+      metadata.remove(key);
+    }
+    let no_annotations = metadata
+      .get_mut("annotations")
+      .and_then(|a| a.as_object_mut())
+      .map(|annotations| {
+        annotations.remove(LAST_APPLIED);
+        annotations.is_empty()
+      })
+      .unwrap_or_default();
+    if no_annotations {
+      metadata.remove("annotations");
+    }
+  }
+  Some((
+    serde_yaml::to_string(&sort_keys(applied)).ok()?,
+    serde_yaml::to_string(&sort_keys(live)).ok()?,
+  ))
+}
+
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+  match value {
+    serde_json::Value::Object(object) => {
+      let mut entries: Vec<_> = object.into_iter().collect();
+      entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+      entries
+        .into_iter()
+        .map(|(key, item)| (key, sort_keys(item)))
+        .collect()
+    }
+    serde_json::Value::Array(items) => items.into_iter().map(sort_keys).collect(),
+    value => value,
+  }
+}
+
+/// resources shown as the rows of a table with named columns
+pub trait TableRow {
+  /// text of the cell under the given column header
+  fn column_value(&self, column: &str) -> String;
+  /// metadata of the kubernetes object shown in the row
+  fn meta(&self) -> Option<&ObjectMeta> {
+    None
+  }
+  /// changes whenever a cell of the row might, the drawn cells are reused while it stays the same.
+  /// Rows without one are built on every draw
+  fn version(&self) -> Option<String> {
+    self.meta().and_then(|meta| meta.resource_version.clone())
+  }
+  /// creation time of the resource, shown in the Age column
+  fn created_at(&self) -> Option<DateTime<Utc>> {
+    self
+      .meta()
+      .and_then(|meta| meta.creation_timestamp.as_ref())
+      .map(|time| time.0)
+  }
+  /// health going by the status of the resource
+  fn status_health(&self) -> RowHealth {
+    RowHealth::Healthy
+  }
+  /// namespace and name the row is marked by, kept across refreshes
+  fn mark_key(&self) -> (String, String) {
+    item_key(self)
+  }
+  /// health the row is colored by, resources being deleted are pending
+  fn health(&self) -> RowHealth {
+    if self
+      .meta()
+      .is_some_and(|meta| meta.deletion_timestamp.is_some())
+    {
+      RowHealth::Pending
+    } else {
+      self.status_health()
+    }
+  }
+}
+
+/// health of the resource in a table row
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RowHealth {
+  Healthy,
+  /// starting, updating or terminating
+  Pending,
+  Failed,
+  /// completed or suspended
+  Done,
+}
+
+impl RowHealth {
+  /// pending until as many replicas are ready as desired
+  pub fn from_replicas(ready: i32, desired: i32) -> RowHealth {
+    if ready < desired {
+      RowHealth::Pending
+    } else {
+      RowHealth::Healthy
+    }
+  }
+
+  /// health of a ready column like `1/3`
+  pub fn from_ready(ready: &str) -> RowHealth {
+    match ready.split_once('/') {
+      Some((ready, desired)) => RowHealth::from_replicas(
+        ready.trim().parse().unwrap_or_default(),
+        desired.trim().parse().unwrap_or_default(),
+      ),
+      None => RowHealth::Healthy,
+    }
+  }
+}
+
+/// how the YAML view, the split pane and exports show manifests
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ManifestFormat {
+  #[default]
+  Yaml,
+  Json,
+}
+
+impl ManifestFormat {
+  pub fn next(self) -> ManifestFormat {
+    match self {
+      ManifestFormat::Yaml => ManifestFormat::Json,
+      ManifestFormat::Json => ManifestFormat::Yaml,
+    }
+  }
+
+  /// name used in titles
+  pub fn title(self) -> &'static str {
+    match self {
+      ManifestFormat::Yaml => "YAML",
+      ManifestFormat::Json => "JSON",
+    }
+  }
+
+  /// file extension and kubectl output format
+  pub fn extension(self) -> &'static str {
+    match self {
+      ManifestFormat::Yaml => "yaml",
+      ManifestFormat::Json => "json",
+    }
+  }
+
+  /// the manifest given as YAML in this format
+  pub fn render(self, yaml: String) -> String {
+    match self {
+      ManifestFormat::Yaml => yaml,
+      ManifestFormat::Json => serde_yaml::from_str::<serde_yaml::Value>(&yaml)
+        .ok()
+        .and_then(|manifest| serde_json::to_string_pretty(&manifest).ok())
+        .unwrap_or(yaml),
+    }
+  }
+}
+
+/// how the Age column shows when a resource was created
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampFormat {
+  /// age like `5m` or `2d`
+  #[default]
+  Relative,
+  /// creation time in the local time zone
+  Local,
+  /// creation time in UTC
+  Utc,
+}
+
+impl TimestampFormat {
+  pub fn next(self) -> TimestampFormat {
+    match self {
+      TimestampFormat::Relative => TimestampFormat::Local,
+      TimestampFormat::Local => TimestampFormat::Utc,
+      TimestampFormat::Utc => TimestampFormat::Relative,
+    }
+  }
+
+  /// the time in this format, relative ages are counted up to `now`
+  pub fn format(self, time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match self {
+      TimestampFormat::Relative => utils::live_age(time, now),
+      TimestampFormat::Local => time
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string(),
+      TimestampFormat::Utc => time.format("%Y-%m-%d %H:%MZ").to_string(),
+    }
+  }
+}
+
+/// column and sort settings of a resource table, usable without knowing the row type
+pub trait ResourceTable {
+  /// every column of the table and whether it is shown
+  fn column_choices(&self) -> Vec<(String, bool)>;
+  /// show a hidden column or hide a shown one, the last shown column can't be hidden
+  fn toggle_column(&mut self, column: &str);
+  /// move the sort to the next column or direction and sort the items
+  fn sort_by_next_column(&mut self);
+  /// scroll the shown columns by one column
+  fn scroll_columns(&mut self, right: bool);
+  fn table_sort(&self) -> Option<TableSort>;
+  /// sort by the given column, used to restore the sort from a previous run
+  fn set_table_sort(&mut self, sort: Option<TableSort>);
+  /// show or hide the optional columns of the wide layout
+  fn set_wide(&mut self, wide: bool);
+  fn set_timestamp_format(&mut self, format: TimestampFormat);
+  fn filter(&self) -> String;
+  /// field selector of the filter for listing the resources
+  fn field_selector(&self) -> Option<String>;
+  /// label selector of the filter for listing the resources
+  fn label_selector(&self) -> Option<String>;
+  /// show only the items whose name and labels match the filter, an empty filter shows everything
+  fn set_filter(&mut self, filter: &str);
+  /// namespace (empty for cluster wide resources) and name of every item, in table order
+  fn item_names(&self) -> Vec<(String, String)>;
+  fn select_row(&mut self, index: usize);
+  /// select the first or last row
+  fn select_edge(&mut self, last: bool);
+  /// namespace and name of the selected item
+  fn selected_key(&self) -> Option<(String, String)>;
+  /// header and value of every shown column of the selected item
+  fn selected_cells(&self) -> Vec<(String, String)>;
+  /// set the column headers, the table is drawn with
+  fn set_columns(&mut self, columns: &[&str]);
+  /// headers of the shown columns and the cells of every item as drawn, in table order
+  fn rows(&self) -> (Vec<String>, Vec<Vec<String>>);
+  /// mark or unmark the selected item
+  fn toggle_mark(&mut self);
+  /// mark every item, or unmark them all when they already are
+  fn toggle_mark_all(&mut self);
+  /// styles and hook column texts of the rows from the `on_row` hook
+  fn set_decorations(&mut self, decorations: Decorations);
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TableSort {
+  pub column: String,
+  pub descending: bool,
+}
+
+/// how far a scroll key moves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollStep {
+  Line,
+  /// like vim's ctrl-d and ctrl-u
+  HalfPage,
+  Page,
 }
 
 pub trait Scrollable {
-  fn handle_scroll(&mut self, up: bool, page: bool) {
+  fn handle_scroll(&mut self, up: bool, step: ScrollStep) {
     // support page up/down
-    let inc_or_dec = if page { 10 } else { 1 };
+    let inc_or_dec = match step {
+      ScrollStep::Line => 1,
+      ScrollStep::HalfPage => 5,
+      ScrollStep::Page => 10,
+    };
     if up {
       self.scroll_up(inc_or_dec);
     } else {
@@ -87,7 +376,48 @@ impl<T> Scrollable for StatefulList<T> {
 #[derive(Clone)]
 pub struct StatefulTable<T> {
   pub state: TableState,
+  /// changed through `set_items` or `items_mut`, which have the drawn rows checked again
   pub items: Vec<T>,
+  /// index of the first row in view, mirrors the offset that `TableState` keeps private
+  offset: usize,
+  /// screen area of the table rows (excluding header) from the last draw
+  area: Rect,
+  /// column the items are sorted by, kept across refreshes
+  pub sort: Option<TableSort>,
+  /// column headers from the last draw
+  columns: Vec<String>,
+  /// columns left out when drawing the table
+  pub hidden_columns: Vec<String>,
+  /// hidden columns that the wide layout shows, until one is picked in the column picker
+  optional_columns: Vec<String>,
+  /// whether the optional columns are shown
+  wide: bool,
+  timestamp_format: TimestampFormat,
+  /// visible columns scrolled out of view to the left
+  column_offset: usize,
+  /// namespace and name of the items marked for bulk actions, kept across refreshes
+  pub marked: BTreeSet<(String, String)>,
+  /// filter the item names and labels must match, kept across refreshes
+  filter: String,
+  /// items hidden by the filter
+  filtered_out: Vec<T>,
+  /// cells of the items as last drawn, in table order
+  row_cache: Vec<CachedRow>,
+  /// columns the cached cells are for
+  cached_columns: Vec<String>,
+  /// widest cached cell of each column
+  cached_widths: Vec<usize>,
+  /// the items changed since the cells were cached
+  rows_changed: bool,
+  /// styles and the extra column of the rows from the `on_row` hook
+  decorations: Decorations,
+}
+
+/// the cells of a drawn row and the version of the item they were built from
+#[derive(Clone, Debug, Default)]
+pub struct CachedRow {
+  version: Option<String>,
+  pub cells: Vec<String>,
 }
 
 impl<T> StatefulTable<T> {
@@ -95,9 +425,33 @@ impl<T> StatefulTable<T> {
     StatefulTable {
       state: TableState::default(),
       items: Vec::new(),
+      offset: 0,
+      area: Rect::default(),
+      sort: None,
+      columns: vec![],
+      hidden_columns: vec![],
+      optional_columns: vec![],
+      wide: false,
+      timestamp_format: TimestampFormat::Relative,
+      column_offset: 0,
+      marked: BTreeSet::new(),
+      filter: String::new(),
+      filtered_out: vec![],
+      row_cache: vec![],
+      cached_columns: vec![],
+      cached_widths: vec![],
+      rows_changed: true,
+      decorations: Decorations::default(),
     }
   }
 
+  /// columns that are only shown in the wide layout or once picked in the column picker
+  pub fn with_hidden_columns(mut self, columns: &[&str]) -> StatefulTable<T> {
+    self.hidden_columns = columns.iter().map(|c| c.to_string()).collect();
+    self.optional_columns = self.hidden_columns.clone();
+    self
+  }
+
   pub fn with_items(items: Vec<T>) -> StatefulTable<T> {
     let mut table = StatefulTable::new();
     if !items.is_empty() {
@@ -110,6 +464,7 @@ impl<T> StatefulTable<T> {
   pub fn set_items(&mut self, items: Vec<T>) {
     let item_len = items.len();
     self.items = items;
+    self.rows_changed = true;
     if !self.items.is_empty() {
       let i = self.state.selected().map_or(0, |i| {
         if i > 0 && i < item_len {
@@ -123,9 +478,148 @@ impl<T> StatefulTable<T> {
       self.state.select(Some(i));
     }
   }
+
+  /// record where the table rows were drawn and scroll the view offset the same way the table
+  /// widget does, so that mouse clicks can be mapped back to rows. Rows are always one line high
+  pub fn set_view(&mut self, area: Rect) {
+    self.area = area;
+    let height = area.height as usize;
+    if self.items.is_empty() || height == 0 {
+      self.offset = 0;
+      return;
+    }
+    let selected = match self.state.selected() {
+      Some(i) => i.min(self.items.len() - 1),
+      None => {
+        self.offset = 0;
+        0
+      }
+    };
+    let offset = self.offset.min(self.items.len() - 1);
+    self.offset = if selected >= offset + height {
+      selected + 1 - height
+    } else {
+      offset.min(selected)
+    };
+  }
+
+  /// the items for changing them in place
+  pub fn items_mut(&mut self) -> &mut [T] {
+    self.rows_changed = true;
+    &mut self.items
+  }
+
+  /// indexes of the items in view from the last draw, only they are drawn
+  pub fn rows_in_view(&self) -> Range<usize> {
+    let start = self.offset.min(self.items.len());
+    start..(start + self.page_size()).min(self.items.len())
+  }
+
+  /// rows that fit in the table from the last draw, 10 before it is drawn
+  pub fn page_size(&self) -> usize {
+    match self.area.height {
+      0 => 10,
+      height => height as usize,
+    }
+  }
+
+  /// position of the selected row like `item 341/2891, page 12/97`, only for tables longer than
+  /// a page
+  pub fn position(&self) -> Option<String> {
+    let page_size = self.page_size();
+    let len = self.items.len();
+    if len <= page_size {
+      return None;
+    }
+    let selected = self.state.selected()?;
+    Some(format!(
+      "item {}/{}, page {}/{}",
+      selected + 1,
+      len,
+      selected / page_size + 1,
+      len.div_ceil(page_size)
+    ))
+  }
+
+  pub fn is_column_visible(&self, column: &str) -> bool {
+    !self.hidden_columns.iter().any(|c| c == column)
+      || (self.wide && self.optional_columns.iter().any(|c| c == column))
+  }
+
+  pub fn visible_columns(&self) -> Vec<&str> {
+    self
+      .columns
+      .iter()
+      .map(String::as_str)
+      .filter(|c| self.is_column_visible(c))
+      .collect()
+  }
+
+  /// visible columns that are scrolled into view
+  pub fn shown_columns(&self) -> Vec<&str> {
+    let columns = self.visible_columns();
+    let offset = self.column_offset.min(columns.len().saturating_sub(1));
+    columns.into_iter().skip(offset).collect()
+  }
+
+  /// whether visible columns are scrolled out of view to the left
+  pub fn is_column_scrolled(&self) -> bool {
+    self.column_offset > 0 && self.visible_columns().len() > 1
+  }
+
+  /// sort by the next visible column: each column is sorted ascending then descending, and after
+  /// the last column the sort is cleared
+  pub fn cycle_sort(&mut self) {
+    let columns = self.visible_columns();
+    let next_column = |i: usize| {
+      columns.get(i).map(|column| TableSort {
+        column: column.to_string(),
+        descending: false,
+      })
+    };
+    let sort = match &self.sort {
+      None => next_column(0),
+      Some(sort) if !sort.descending => Some(TableSort {
+        descending: true,
+        ..sort.clone()
+      }),
+      Some(sort) => match columns.iter().position(|c| *c == sort.column) {
+        Some(i) => next_column(i + 1),
+        None => next_column(0),
+      },
+    };
+    self.sort = sort;
+  }
+
+  /// index of the item drawn at the given screen position, if any
+  pub fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+    if !is_within(self.area, column, row) {
+      return None;
+    }
+    let index = self.offset + (row - self.area.y) as usize;
+    if index < self.items.len() {
+      Some(index)
+    } else {
+      None
+    }
+  }
 }
 
 impl<T> Scrollable for StatefulTable<T> {
+  /// pages are as long as the table was drawn
+  fn handle_scroll(&mut self, up: bool, step: ScrollStep) {
+    let inc_or_dec = match step {
+      ScrollStep::Line => 1,
+      ScrollStep::HalfPage => (self.page_size() / 2).max(1),
+      ScrollStep::Page => self.page_size(),
+    };
+    if up {
+      self.scroll_up(inc_or_dec);
+    } else {
+      self.scroll_down(inc_or_dec);
+    }
+  }
+
   fn scroll_down(&mut self, increment: usize) {
     if let Some(i) = self.state.selected() {
       if (i + increment) < self.items.len() {
@@ -145,6 +639,322 @@ impl<T> Scrollable for StatefulTable<T> {
   }
 }
 
+impl<T: TableRow> StatefulTable<T> {
+  /// set items in the order of the current sort and hide the ones not matching the filter, marks
+  /// of items that are gone are dropped
+  pub fn set_sorted_items(&mut self, mut items: Vec<T>) {
+    self.filtered_out.clear();
+    // an invalid filter hides everything until it is fixed
+    let filter = TableFilter::parse(&self.filter);
+    if !filter.as_ref().is_ok_and(TableFilter::is_empty) {
+      let (shown, hidden) = items.into_iter().partition(|item| match &filter {
+        Ok(filter) => filter.matches(
+          &item_key(item).1,
+          item.meta().and_then(|meta| meta.labels.as_ref()),
+        ),
+        Err(_) => false,
+      });
+      items = shown;
+      self.filtered_out = hidden;
+    }
+    let selected = self.selected_key();
+    self.set_items(items);
+    self.sort_items();
+    // a refresh can change the order too
+    if let Some(key) = selected {
+      self.select_key(&key);
+    }
+    if !self.marked.is_empty() {
+      let keys: BTreeSet<_> = self
+        .items
+        .iter()
+        .chain(self.filtered_out.iter())
+        .map(TableRow::mark_key)
+        .collect();
+      self.marked.retain(|k| keys.contains(k));
+    }
+  }
+
+  /// text of a cell as drawn, the Age column follows the timestamp format and is recomputed on
+  /// every draw rather than frozen at the last poll
+  pub fn cell_value(&self, item: &T, column: &str) -> String {
+    match item.created_at() {
+      Some(time) if column == "Age" => self.timestamp_format.format(time, Utc::now()),
+      _ => self.decorations.cell(item, column),
+    }
+  }
+
+  /// health the row is colored by, the `on_row` hook can change it
+  pub fn health(&self, item: &T) -> RowHealth {
+    self.decorations.health(item)
+  }
+
+  /// build the cells of the items under the columns for drawing. After the items changed only
+  /// the rows whose item did are built again. Age cells are relative to now and built on every
+  /// draw, for the rows in view
+  pub fn update_row_cache(&mut self, columns: &[String]) {
+    if self.cached_columns != columns {
+      self.cached_columns = columns.to_vec();
+      self.row_cache.clear();
+      self.rows_changed = true;
+    }
+    let age = columns.iter().position(|c| c == "Age");
+    if self.rows_changed {
+      self.rows_changed = false;
+      self
+        .row_cache
+        .resize_with(self.items.len(), CachedRow::default);
+      let decorations = &self.decorations;
+      for (item, row) in self.items.iter().zip(self.row_cache.iter_mut()) {
+        let version = item.version();
+        if version.is_none() || version != row.version {
+          row.cells = columns.iter().map(|c| decorations.cell(item, c)).collect();
+          row.version = version;
+        }
+      }
+      self.update_ages(age, 0..self.items.len());
+      self.cached_widths = vec![0; columns.len()];
+      for row in &self.row_cache {
+        for (width, cell) in self.cached_widths.iter_mut().zip(&row.cells) {
+          *width = (*width).max(cell.chars().count());
+        }
+      }
+    } else {
+      self.update_ages(age, self.rows_in_view());
+    }
+  }
+
+  fn update_ages(&mut self, age: Option<usize>, rows: Range<usize>) {
+    if let Some(age) = age {
+      let now = Utc::now();
+      for (item, row) in self.items[rows.clone()]
+        .iter()
+        .zip(self.row_cache[rows].iter_mut())
+      {
+        if let Some(time) = item.created_at() {
+          row.cells[age] = self.timestamp_format.format(time, now);
+        }
+      }
+    }
+  }
+
+  /// the cells from the last `update_row_cache`, in table order
+  pub fn cached_rows(&self) -> &[CachedRow] {
+    &self.row_cache
+  }
+
+  /// the widest cell of each column from the last `update_row_cache`
+  pub fn cached_widths(&self) -> &[usize] {
+    &self.cached_widths
+  }
+
+  pub fn is_marked(&self, item: &T) -> bool {
+    !self.marked.is_empty() && self.marked.contains(&item.mark_key())
+  }
+
+  /// the marked items in table order
+  pub fn marked_items(&self) -> Vec<&T> {
+    self.items.iter().filter(|i| self.is_marked(i)).collect()
+  }
+
+  /// sort the items by the current sort, the selected item stays selected
+  pub fn sort_items(&mut self) {
+    if let Some(sort) = &self.sort {
+      let selected = self.selected_key();
+      self.rows_changed = true;
+      let decorations = &self.decorations;
+      self.items.sort_by(|a, b| {
+        let ordering = utils::compare_values(
+          &sort.column,
+          &decorations.cell(a, &sort.column),
+          &decorations.cell(b, &sort.column),
+        );
+        if sort.descending {
+          ordering.reverse()
+        } else {
+          ordering
+        }
+      });
+      if let Some(key) = selected {
+        self.select_key(&key);
+      }
+    }
+  }
+
+  /// select the item with the namespace and name, if it is there
+  fn select_key(&mut self, key: &(String, String)) {
+    if let Some(index) = self.items.iter().position(|item| item_key(item) == *key) {
+      self.state.select(Some(index));
+    }
+  }
+}
+
+impl<T: TableRow> ResourceTable for StatefulTable<T> {
+  fn column_choices(&self) -> Vec<(String, bool)> {
+    self
+      .columns
+      .iter()
+      .map(|c| (c.to_owned(), self.is_column_visible(c)))
+      .collect()
+  }
+
+  fn toggle_column(&mut self, column: &str) {
+    if !self.is_column_visible(column) {
+      self.hidden_columns.retain(|c| c != column);
+      self.optional_columns.retain(|c| c != column);
+    } else if self.visible_columns().len() > 1 {
+      // a picked column is no longer up to the layout
+      self.optional_columns.retain(|c| c != column);
+      if !self.hidden_columns.iter().any(|c| c == column) {
+        self.hidden_columns.push(column.to_owned());
+      }
+      if self.sort.as_ref().is_some_and(|s| s.column == column) {
+        self.sort = None;
+      }
+    }
+  }
+
+  fn sort_by_next_column(&mut self) {
+    self.cycle_sort();
+    self.sort_items();
+  }
+
+  fn scroll_columns(&mut self, right: bool) {
+    let max_offset = self.visible_columns().len().saturating_sub(1);
+    self.column_offset = if right {
+      (self.column_offset + 1).min(max_offset)
+    } else {
+      self.column_offset.min(max_offset).saturating_sub(1)
+    };
+  }
+
+  fn table_sort(&self) -> Option<TableSort> {
+    self.sort.clone()
+  }
+
+  fn set_table_sort(&mut self, sort: Option<TableSort>) {
+    self.sort = sort;
+    self.sort_items();
+  }
+
+  fn set_wide(&mut self, wide: bool) {
+    self.wide = wide;
+  }
+
+  fn set_timestamp_format(&mut self, format: TimestampFormat) {
+    self.timestamp_format = format;
+  }
+  fn filter(&self) -> String {
+    self.filter.clone()
+  }
+
+  fn field_selector(&self) -> Option<String> {
+    TableFilter::parse(&self.filter)
+      .ok()
+      .and_then(|f| f.field_selector())
+  }
+
+  fn label_selector(&self) -> Option<String> {
+    TableFilter::parse(&self.filter)
+      .ok()
+      .and_then(|f| f.label_selector())
+  }
+
+  fn set_filter(&mut self, filter: &str) {
+    if self.filter == filter {
+      return;
+    }
+    self.filter = filter.to_owned();
+    let mut items = std::mem::take(&mut self.items);
+    items.append(&mut self.filtered_out);
+    self.set_sorted_items(items);
+  }
+
+  fn item_names(&self) -> Vec<(String, String)> {
+    self.items.iter().map(item_key).collect()
+  }
+
+  fn select_row(&mut self, index: usize) {
+    if index < self.items.len() {
+      self.state.select(Some(index));
+    }
+  }
+
+  fn select_edge(&mut self, last: bool) {
+    if !self.items.is_empty() {
+      let index = if last { self.items.len() - 1 } else { 0 };
+      self.state.select(Some(index));
+    }
+  }
+
+  fn selected_key(&self) -> Option<(String, String)> {
+    self
+      .state
+      .selected()
+      .and_then(|i| self.items.get(i))
+      .map(item_key)
+  }
+
+  fn selected_cells(&self) -> Vec<(String, String)> {
+    match self.state.selected().and_then(|i| self.items.get(i)) {
+      Some(item) => self
+        .visible_columns()
+        .into_iter()
+        .map(|c| (c.to_owned(), self.decorations.cell(item, c)))
+        .collect(),
+      None => vec![],
+    }
+  }
+
+  /// the extra columns from the config are added after the given ones, and the hook column when
+  /// the `on_row` hook fills it
+  fn set_columns(&mut self, columns: &[&str]) {
+    let columns = columns.iter().copied().chain(self.decorations.columns());
+    if self.columns.iter().map(String::as_str).ne(columns.clone()) {
+      self.columns = columns.map(str::to_owned).collect();
+    }
+  }
+
+  fn set_decorations(&mut self, decorations: Decorations) {
+    if self.decorations != decorations {
+      self.decorations = decorations;
+      self.row_cache.clear();
+      self.rows_changed = true;
+    }
+  }
+
+  fn rows(&self) -> (Vec<String>, Vec<Vec<String>>) {
+    let columns = self.visible_columns();
+    let rows = self
+      .items
+      .iter()
+      .map(|item| columns.iter().map(|c| self.cell_value(item, c)).collect())
+      .collect();
+    (columns.into_iter().map(str::to_owned).collect(), rows)
+  }
+
+  fn toggle_mark(&mut self) {
+    if let Some(item) = self.state.selected().and_then(|i| self.items.get(i)) {
+      let key = item.mark_key();
+      if !self.marked.remove(&key) {
+        self.marked.insert(key);
+      }
+    }
+  }
+
+  fn toggle_mark_all(&mut self) {
+    if self.items.iter().all(|i| self.is_marked(i)) {
+      self.marked.clear();
+    } else {
+      self.marked = self.items.iter().map(TableRow::mark_key).collect();
+    }
+  }
+}
+
+pub(crate) fn item_key<T: TableRow + ?Sized>(item: &T) -> (String, String) {
+  (item.column_value("Namespace"), item.column_value("Name"))
+}
+
 impl<T: Clone> StatefulTable<T> {
   /// a clone of the currently selected item.
   /// for mutable ref use state.selected() and fetch from items when needed
@@ -166,11 +976,17 @@ pub struct TabRoute {
 pub struct TabsState {
   pub items: Vec<TabRoute>,
   pub index: usize,
+  /// screen areas of the tab titles from the last draw
+  title_areas: Vec<Rect>,
 }
 
 impl TabsState {
   pub fn new(items: Vec<TabRoute>) -> TabsState {
-    TabsState { items, index: 0 }
+    TabsState {
+      items,
+      index: 0,
+      title_areas: vec![],
+    }
   }
   pub fn set_index(&mut self, index: usize) -> &TabRoute {
     self.index = index;
@@ -190,31 +1006,171 @@ impl TabsState {
       self.index = self.items.len() - 1;
     }
   }
+
+  pub fn set_title_areas(&mut self, title_areas: Vec<Rect>) {
+    self.title_areas = title_areas;
+  }
+
+  /// index of the tab whose title is drawn at the given screen position, if any
+  pub fn index_at(&self, column: u16, row: u16) -> Option<usize> {
+    self
+      .title_areas
+      .iter()
+      .position(|area| is_within(*area, column, row))
+  }
+}
+
+fn is_within(area: Rect, column: u16, row: u16) -> bool {
+  column >= area.left() && column < area.right() && row >= area.top() && row < area.bottom()
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct ScrollableTxt {
   items: Vec<String>,
   pub offset: u16,
+  /// columns scrolled to the right, lines are wrapped only when this is 0
+  pub h_offset: u16,
+  /// text searched for case insensitively, occurrences are highlighted
+  search: String,
+  /// lines containing the search, in order
+  search_matches: Vec<usize>,
+  /// index in `search_matches` of the match scrolled to
+  search_index: usize,
 }
 
 impl ScrollableTxt {
   pub fn new() -> ScrollableTxt {
-    ScrollableTxt {
-      items: vec![],
-      offset: 0,
-    }
+    ScrollableTxt::with_string(String::new())
   }
 
   pub fn with_string(item: String) -> ScrollableTxt {
     let items: Vec<&str> = item.split('\n').collect();
     let items: Vec<String> = items.iter().map(|it| it.to_string()).collect();
-    ScrollableTxt { items, offset: 0 }
+    ScrollableTxt {
+      items,
+      offset: 0,
+      h_offset: 0,
+      search: String::new(),
+      search_matches: vec![],
+      search_index: 0,
+    }
   }
 
   pub fn get_txt(&self) -> String {
     self.items.join("\n")
   }
+
+  pub fn lines(&self) -> &[String] {
+    &self.items
+  }
+
+  pub fn scroll_horizontal(&mut self, right: bool, increment: u16) {
+    if right {
+      let longest = self
+        .items
+        .iter()
+        .map(|it| it.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+      // keep the end of the longest line in view
+      self.h_offset = (self.h_offset + increment).min(longest.saturating_sub(increment));
+    } else {
+      self.h_offset = self.h_offset.saturating_sub(increment);
+    }
+  }
+
+  pub fn search(&self) -> &str {
+    &self.search
+  }
+
+  /// search for the text and scroll to the first match from the current line
+  pub fn set_search(&mut self, search: String) {
+    let query = search.to_ascii_lowercase();
+    self.search_matches = if query.is_empty() {
+      vec![]
+    } else {
+      self
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.to_ascii_lowercase().contains(&query))
+        .map(|(i, _)| i)
+        .collect()
+    };
+    self.search = search;
+    let offset = self.offset as usize;
+    self.search_index = self
+      .search_matches
+      .iter()
+      .position(|line| *line >= offset)
+      .unwrap_or(0);
+    self.scroll_to_match();
+  }
+
+  /// scroll to the next or previous match, wrapping around at the ends
+  pub fn next_match(&mut self, forward: bool) {
+    let len = self.search_matches.len();
+    if len == 0 {
+      return;
+    }
+    self.search_index = if forward {
+      (self.search_index + 1) % len
+    } else {
+      (self.search_index + len - 1) % len
+    };
+    self.scroll_to_match();
+  }
+
+  /// the line of the match scrolled to
+  pub fn current_match(&self) -> Option<usize> {
+    self.search_matches.get(self.search_index).copied()
+  }
+
+  /// position of the match scrolled to like `2/15`, `None` without a search
+  pub fn search_position(&self) -> Option<String> {
+    if self.search.is_empty() {
+      None
+    } else if self.search_matches.is_empty() {
+      Some("no matches".into())
+    } else {
+      Some(format!(
+        "{}/{}",
+        self.search_index + 1,
+        self.search_matches.len()
+      ))
+    }
+  }
+
+  /// position of the view drawn at the given size, lines are counted as wrapped unless
+  /// scrolled sideways, which is only roughly the same as the paragraph word wrap
+  pub fn scroll_position(&self, width: u16, height: u16) -> String {
+    let width = usize::from(width.max(1));
+    let rows: usize = if self.h_offset == 0 {
+      self
+        .items
+        .iter()
+        .map(|line| line.chars().count().div_ceil(width).max(1))
+        .sum()
+    } else {
+      self.items.len()
+    };
+    utils::scroll_position(
+      usize::from(self.offset),
+      rows.saturating_sub(usize::from(height)),
+    )
+  }
+
+  /// scroll so the line is at the top, at most as far as scrolling down goes
+  pub fn scroll_to_line(&mut self, line: usize) {
+    self.offset = line.min(self.items.len().saturating_sub(3)) as u16;
+  }
+
+  /// the offset counts lines as drawn, so with wrapped lines the match can end up further down
+  fn scroll_to_match(&mut self) {
+    if let Some(line) = self.current_match() {
+      self.offset = line as u16;
+    }
+  }
 }
 
 impl Scrollable for ScrollableTxt {
@@ -234,6 +1190,22 @@ impl Scrollable for ScrollableTxt {
 }
 
 // TODO implement line buffer to avoid gathering too much data in memory
+/// how much of a log is kept, the oldest lines are dropped past either limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogLimits {
+  pub lines: usize,
+  pub bytes: usize,
+}
+
+impl Default for LogLimits {
+  fn default() -> Self {
+    LogLimits {
+      lines: 100_000,
+      bytes: 64 * 1024 * 1024,
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogsState {
   /// Stores the log messages to be displayed
@@ -241,7 +1213,16 @@ pub struct LogsState {
   /// (original_message, (wrapped_message, wrapped_at_width))
   #[allow(clippy::type_complexity)]
   records: VecDeque<(String, Option<(Vec<ListItem<'static>>, u16)>)>,
+  /// first wrapped line of each record wrapped at `wrapped_width`, the records after them are
+  /// wrapped on the next draw
+  line_starts: Vec<usize>,
+  wrapped_width: u16,
   wrapped_length: usize,
+  /// first wrapped line in view when not following the logs
+  offset: usize,
+  limits: LogLimits,
+  /// size of the records
+  bytes: usize,
   pub state: ListState,
   pub id: String,
 }
@@ -250,12 +1231,22 @@ impl LogsState {
   pub fn new(id: String) -> LogsState {
     LogsState {
       records: VecDeque::with_capacity(512),
+      line_starts: vec![],
+      wrapped_width: 0,
       state: ListState::default(),
       wrapped_length: 0,
+      offset: 0,
+      limits: LogLimits::default(),
+      bytes: 0,
       id,
     }
   }
 
+  pub fn with_limits(mut self, limits: LogLimits) -> LogsState {
+    self.limits = limits;
+    self
+  }
+
   /// get a plain text version of the logs
   pub fn get_plain_text(&self) -> String {
     self.records.iter().fold(String::new(), |mut acc, v| {
@@ -265,7 +1256,34 @@ impl LogsState {
     })
   }
 
-  /// Render the current state as a list widget
+  /// wrap the records added since the last draw, or all of them when the width changed
+  fn wrap_records(&mut self, width: u16, style: Style) {
+    if width != self.wrapped_width {
+      self.wrapped_width = width;
+      self.line_starts.clear();
+      self.wrapped_length = 0;
+    }
+    for r in self.records.iter_mut().skip(self.line_starts.len()) {
+      // See if we can use a cached wrapped line
+      let cached = r.1.as_ref().is_some_and(|wrapped| wrapped.1 == width);
+      if !cached {
+        r.1 = Some((
+          textwrap::wrap(r.0.as_ref(), width as usize)
+            .into_iter()
+            .map(|s| s.to_string())
+            .map(|c| Span::styled(c, style))
+            .map(ListItem::new)
+            .collect::<Vec<ListItem<'_>>>(),
+          width,
+        ));
+      }
+      self.line_starts.push(self.wrapped_length);
+      self.wrapped_length += r.1.as_ref().map_or(0, |wrapped| wrapped.0.len());
+    }
+  }
+
+  /// Render the current state as a list widget. Only the wrapped lines in view are drawn, so
+  /// long logs take as long to draw as short ones
   pub fn render_list<B: Backend>(
     &mut self,
     f: &mut Frame<'_, B>,
@@ -274,69 +1292,47 @@ impl LogsState {
     style: Style,
     follow: bool,
   ) {
-    let available_lines = logs_area.height as usize;
-    let logs_area_width = logs_area.width as usize;
-
-    let num_records = self.records.len();
-    // Keep track of the number of lines after wrapping so we can skip lines as
-    // needed below
-    let mut wrapped_lines_len = 0;
+    let height = block.inner(logs_area).height as usize;
+    self.wrap_records(logs_area.width, style);
 
-    let mut items = Vec::with_capacity(logs_area.height as usize);
-
-    let lines_to_skip = if follow {
+    let selected = if follow {
       self.unselect();
-      num_records.saturating_sub(available_lines)
+      // leaving follow mode starts at the top
+      self.offset = 0;
+      None
     } else {
-      0
+      let selected = self
+        .state
+        .selected()
+        .map(|i| i.min(self.wrapped_length.saturating_sub(1)));
+      if let Some(i) = selected {
+        if i < self.offset {
+          self.offset = i;
+        } else if i >= self.offset + height {
+          self.offset = i + 1 - height;
+        }
+      }
+      selected
     };
-
-    items.extend(
-      self
-        .records
-        .iter_mut()
-        // Only wrap the records we could potentially be displaying
-        .skip(lines_to_skip)
-        .flat_map(|r| {
-          // See if we can use a cached wrapped line
-          if let Some(wrapped) = &r.1 {
-            if wrapped.1 as usize == logs_area_width {
-              wrapped_lines_len += wrapped.0.len();
-              return wrapped.0.clone();
-            }
-          }
-
-          // If not, wrap the line and cache it
-          r.1 = Some((
-            textwrap::wrap(r.0.as_ref(), logs_area_width)
-              .into_iter()
-              .map(|s| s.to_string())
-              .map(|c| Span::styled(c, style))
-              .map(ListItem::new)
-              .collect::<Vec<ListItem<'_>>>(),
-            logs_area.width,
-          ));
-
-          wrapped_lines_len += r.1.as_ref().unwrap().0.len();
-          r.1.as_ref().unwrap().0.clone()
-        }),
-    );
-
-    let wrapped_lines_to_skip = if follow {
-      wrapped_lines_len.saturating_sub(available_lines)
+    let start = if follow {
+      self.wrapped_length.saturating_sub(height)
     } else {
-      0
+      self.offset.min(self.wrapped_length)
     };
-
-    let items = items
-      .into_iter()
-      // Wrapping could have created more lines than what we can display;
-      // skip them
-      .skip(wrapped_lines_to_skip)
+    // the record the first line in view belongs to
+    let first = self
+      .line_starts
+      .partition_point(|line| *line <= start)
+      .saturating_sub(1);
+    let items = self
+      .records
+      .iter()
+      .skip(first)
+      .flat_map(|r| r.1.iter().flat_map(|wrapped| wrapped.0.iter().cloned()))
+      .skip(start - self.line_starts.get(first).copied().unwrap_or(0))
+      .take(height)
       .collect::<Vec<_>>();
 
-    self.wrapped_length = items.len();
-
     // TODO: All this is a workaround. we should be wrapping text with paragraph, but it currently
     // doesn't support wrapping and staying scrolled to the bottom
     //
@@ -345,11 +1341,72 @@ impl LogsState {
       .block(block)
       .highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
-    f.render_stateful_widget(list, logs_area, &mut self.state);
+    let mut state = ListState::default();
+    state.select(selected.and_then(|i| i.checked_sub(start)));
+    f.render_stateful_widget(list, logs_area, &mut state);
+  }
+  /// position of the selected line, the end is shown while following the logs
+  pub fn scroll_position(&self, follow: bool, height: usize) -> String {
+    // records added since the last draw aren't wrapped yet
+    let fits = self.records.len() <= height && self.wrapped_length <= height;
+    if fits {
+      utils::scroll_position(0, 0)
+    } else if follow {
+      utils::scroll_position(1, 1)
+    } else {
+      utils::scroll_position(
+        self.state.selected().unwrap_or(0),
+        self.wrapped_length.saturating_sub(1),
+      )
+    }
+  }
+
+  /// select a wrapped line, the last one when it is past the end
+  pub fn select_line(&mut self, line: usize) {
+    self
+      .state
+      .select(Some(line.min(self.wrapped_length.saturating_sub(1))));
   }
-  /// Add a record to be displayed
+
+  /// Add a record to be displayed, dropping the oldest ones past the limits
   pub fn add_record(&mut self, record: String) {
+    self.bytes += record.len();
     self.records.push_back((record, None));
+    if self.records.len() > self.limits.lines || self.bytes > self.limits.bytes {
+      self.drop_oldest();
+    }
+  }
+
+  /// drop the oldest records down to 90% of the limits, so the lines in view are counted again
+  /// once per tenth of the limit instead of for every record added
+  fn drop_oldest(&mut self) {
+    let lines = self.limits.lines - self.limits.lines / 10;
+    let bytes = self.limits.bytes - self.limits.bytes / 10;
+    let mut dropped = 0;
+    while self.records.len() > 1 && (self.records.len() > lines || self.bytes > bytes) {
+      if let Some((record, _)) = self.records.pop_front() {
+        self.bytes -= record.len();
+        dropped += 1;
+      }
+    }
+    // keep the view on the same lines
+    let dropped_lines = if dropped < self.line_starts.len() {
+      self.line_starts[dropped]
+    } else {
+      self.wrapped_length
+    };
+    self
+      .line_starts
+      .drain(..dropped.min(self.line_starts.len()));
+    self
+      .line_starts
+      .iter_mut()
+      .for_each(|line| *line -= dropped_lines);
+    self.wrapped_length -= dropped_lines;
+    self.offset = self.offset.saturating_sub(dropped_lines);
+    if let Some(i) = self.state.selected() {
+      self.state.select(Some(i.saturating_sub(dropped_lines)));
+    }
   }
 
   fn unselect(&mut self) {
@@ -383,12 +1440,57 @@ impl Scrollable for LogsState {
 
 #[cfg(test)]
 mod tests {
-  use k8s_openapi::api::core::v1::Namespace;
+  use k8s_openapi::{
+    api::core::v1::{ConfigMap, Namespace},
+    apimachinery::pkg::apis::meta::v1::Time,
+  };
   use kube::api::ObjectMeta;
   use tui::{backend::TestBackend, buffer::Buffer, Terminal};
 
   use super::*;
-  use crate::app::{ns::KubeNs, ActiveBlock, RouteId};
+  use crate::app::{
+    configmaps::KubeConfigMap, hooks::RowDecoration, ns::KubeNs, ActiveBlock, RouteId,
+  };
+
+  #[test]
+  fn test_manifest_format() {
+    let yaml = "apiVersion: v1\nkind: Pod\nmetadata:\n  name: web\n".to_owned();
+    assert_eq!(ManifestFormat::Yaml.render(yaml.clone()), yaml);
+    assert_eq!(
+      ManifestFormat::Json.render(yaml),
+      "{\n  \"apiVersion\": \"v1\",\n  \"kind\": \"Pod\",\n  \"metadata\": {\n    \"name\": \"web\"\n  }\n}"
+    );
+    assert_eq!(ManifestFormat::Yaml.next(), ManifestFormat::Json);
+    assert_eq!(ManifestFormat::Json.next().extension(), "yaml");
+  }
+
+  #[test]
+  fn test_last_applied_manifests() {
+    let yaml = r#"apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: web
+  uid: 2f1c
+  resourceVersion: "42"
+  annotations:
+    kubectl.kubernetes.io/last-applied-configuration: |
+      {"apiVersion":"v1","data":{"port":"80"},"kind":"ConfigMap","metadata":{"annotations":{},"name":"web"}}
+data:
+  port: "8080"
+"#;
+    assert_eq!(
+      last_applied_manifests(yaml),
+      Some((
+        "apiVersion: v1\ndata:\n  port: '80'\nkind: ConfigMap\nmetadata:\n  annotations: {}\n  name: web\n"
+          .into(),
+        "apiVersion: v1\ndata:\n  port: '8080'\nkind: ConfigMap\nmetadata:\n  name: web\n".into()
+      ))
+    );
+    assert_eq!(
+      last_applied_manifests("apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: web\n"),
+      None
+    );
+  }
 
   #[test]
   fn test_kube_resource() {
@@ -461,6 +1563,430 @@ mod tests {
     assert_eq!(sft2.state.selected(), Some(0));
   }
 
+  impl TableRow for (&str, &str) {
+    fn column_value(&self, column: &str) -> String {
+      match column {
+        "Name" => self.0.into(),
+        "Age" => self.1.into(),
+        _ => String::default(),
+      }
+    }
+  }
+
+  #[test]
+  fn test_stateful_table_sort() {
+    let mut sft: StatefulTable<(&str, &str)> = StatefulTable::new();
+    let items = vec![("b", "2d"), ("c", "5h"), ("a", "1w")];
+
+    // without columns there is nothing to sort by
+    sft.cycle_sort();
+    assert_eq!(sft.sort, None);
+
+    sft.set_columns(&["Name", "Age"]);
+    sft.set_sorted_items(items.clone());
+    assert_eq!(sft.items, items);
+
+    let names = |table: &StatefulTable<(&str, &str)>| {
+      table
+        .items
+        .iter()
+        .map(|i| i.0.to_string())
+        .collect::<Vec<_>>()
+    };
+    sft.state.select(Some(1));
+    sft.cycle_sort();
+    sft.sort_items();
+    assert_eq!(names(&sft), vec!["a", "b", "c"]);
+    // the selected item moved with the sort
+    assert_eq!(sft.state.selected(), Some(2));
+    sft.cycle_sort();
+    sft.sort_items();
+    assert_eq!(names(&sft), vec!["c", "b", "a"]);
+    assert_eq!(sft.state.selected(), Some(0));
+    sft.cycle_sort();
+    assert_eq!(
+      sft.sort,
+      Some(TableSort {
+        column: "Age".into(),
+        descending: false
+      })
+    );
+    // the sort is kept when items are refreshed
+    sft.set_sorted_items(items);
+    assert_eq!(names(&sft), vec!["c", "b", "a"]);
+    sft.cycle_sort();
+    sft.sort_items();
+    assert_eq!(names(&sft), vec!["a", "b", "c"]);
+    // and cleared after the last column
+    sft.cycle_sort();
+    assert_eq!(sft.sort, None);
+  }
+
+  #[test]
+  fn test_stateful_table_columns() {
+    let mut sft: StatefulTable<(&str, &str)> = StatefulTable::new().with_hidden_columns(&["Age"]);
+    sft.set_columns(&["Name", "Age"]);
+    assert_eq!(sft.visible_columns(), vec!["Name"]);
+    assert_eq!(
+      sft.column_choices(),
+      vec![("Name".into(), true), ("Age".into(), false)]
+    );
+
+    // the last visible column stays
+    sft.toggle_column("Name");
+    assert_eq!(sft.visible_columns(), vec!["Name"]);
+
+    sft.toggle_column("Age");
+    assert_eq!(sft.visible_columns(), vec!["Name", "Age"]);
+
+    // hidden columns are skipped when sorting and hiding the sorted column clears the sort
+    sft.toggle_column("Name");
+    sft.sort_by_next_column();
+    assert_eq!(sft.sort.as_ref().map(|s| s.column.as_str()), Some("Age"));
+    sft.toggle_column("Name");
+    sft.toggle_column("Age");
+    assert_eq!(sft.sort, None);
+  }
+
+  #[test]
+  fn test_stateful_table_wide_columns() {
+    let mut sft: StatefulTable<(&str, &str)> = StatefulTable::new().with_hidden_columns(&["Age"]);
+    sft.set_columns(&["Name", "Age"]);
+    sft.set_wide(true);
+    assert_eq!(sft.visible_columns(), vec!["Name", "Age"]);
+    sft.set_wide(false);
+    assert_eq!(sft.visible_columns(), vec!["Name"]);
+
+    // a column hidden in the picker stays hidden in the wide layout
+    sft.set_wide(true);
+    sft.toggle_column("Age");
+    assert_eq!(sft.visible_columns(), vec!["Name"]);
+    // and a picked one stays shown in the normal layout
+    sft.toggle_column("Age");
+    sft.set_wide(false);
+    assert_eq!(sft.visible_columns(), vec!["Name", "Age"]);
+  }
+
+  #[test]
+  fn test_stateful_table_filter() {
+    let mut sft: StatefulTable<(&str, &str)> = StatefulTable::new();
+    sft.set_sorted_items(vec![("api-1", "1d"), ("web", "2d"), ("API-2", "3d")]);
+    sft.marked.insert((String::new(), "web".into()));
+    sft.set_filter("api");
+    assert_eq!(sft.items, vec![("api-1", "1d"), ("API-2", "3d")]);
+    // refreshed items are filtered too, and marks on hidden items are kept
+    sft.set_sorted_items(vec![("web", "2d"), ("api-3", "1m")]);
+    assert_eq!(sft.items, vec![("api-3", "1m")]);
+    assert_eq!(sft.marked.len(), 1);
+    sft.set_table_sort(Some(TableSort {
+      column: "Name".into(),
+      descending: true,
+    }));
+    sft.set_filter("~^(db|cache)$");
+    assert!(sft.items.is_empty());
+    sft.set_filter("~^api-[0-9]$");
+    assert_eq!(sft.items, vec![("api-3", "1m")]);
+    sft.set_filter("");
+    assert_eq!(sft.items, vec![("web", "2d"), ("api-3", "1m")]);
+  }
+
+  struct Created(DateTime<Utc>);
+
+  impl TableRow for Created {
+    fn column_value(&self, column: &str) -> String {
+      match column {
+        "Age" => "2d".into(),
+        _ => String::default(),
+      }
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+      Some(self.0)
+    }
+  }
+
+  #[test]
+  fn test_stateful_table_timestamp_format() {
+    let time = DateTime::parse_from_rfc3339("2021-04-27T10:13:58Z")
+      .unwrap()
+      .with_timezone(&Utc);
+    let item = Created(time);
+    let mut sft = StatefulTable::with_items(vec![]);
+    // relative ages are counted when drawn instead of using the polled value
+    let recent = Created(Utc::now() - k8s_openapi::chrono::Duration::hours(73));
+    assert_eq!(recent.column_value("Age"), "2d");
+    assert_eq!(sft.cell_value(&recent, "Age"), "3d1h");
+    sft.set_timestamp_format(TimestampFormat::Utc);
+    assert_eq!(sft.cell_value(&item, "Age"), "2021-04-27 10:13Z");
+    assert_eq!(sft.cell_value(&item, "Name"), "");
+    sft.set_timestamp_format(TimestampFormat::Local);
+    assert_eq!(
+      sft.cell_value(&item, "Age"),
+      time
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+    );
+    assert_eq!(TimestampFormat::Utc.next(), TimestampFormat::Relative);
+  }
+
+  #[test]
+  fn test_stateful_table_row_cache() {
+    let cm = |name: &str, version: Option<&str>, data: usize| {
+      KubeConfigMap::from(ConfigMap {
+        metadata: ObjectMeta {
+          name: Some(name.into()),
+          resource_version: version.map(String::from),
+          creation_timestamp: Some(Time(Utc::now() - k8s_openapi::chrono::Duration::hours(2))),
+          ..ObjectMeta::default()
+        },
+        data: Some((0..data).map(|i| (i.to_string(), String::new())).collect()),
+        ..ConfigMap::default()
+      })
+    };
+    let columns = vec!["Name".to_string(), "Data".into(), "Age".into()];
+    let cells = |sft: &StatefulTable<KubeConfigMap>| {
+      sft
+        .cached_rows()
+        .iter()
+        .map(|row| row.cells.join(" "))
+        .collect::<Vec<_>>()
+    };
+    let mut sft = StatefulTable::with_items(vec![cm("a", Some("1"), 1), cm("b", None, 1)]);
+    sft.update_row_cache(&columns);
+    assert_eq!(cells(&sft), vec!["a 1 2h", "b 1 2h"]);
+
+    // rows of unchanged versions are kept, rows without a version are built again
+    sft.set_items(vec![
+      cm("a", Some("1"), 2),
+      cm("b", None, 2),
+      cm("c", Some("3"), 2),
+    ]);
+    sft.update_row_cache(&columns);
+    assert_eq!(cells(&sft), vec!["a 1 2h", "b 2 2h", "c 2 2h"]);
+    sft.set_items(vec![cm("a", Some("2"), 2)]);
+    sft.update_row_cache(&columns);
+    assert_eq!(cells(&sft), vec!["a 2 2h"]);
+
+    // other columns build every row again
+    sft.update_row_cache(&columns[..1]);
+    assert_eq!(cells(&sft), vec!["a"]);
+
+    // the hook column follows the given ones and new decorations build the rows again
+    let note = RowDecoration {
+      style: Some(RowHealth::Failed),
+      column: Some("unowned".into()),
+      extra: vec![],
+    };
+    sft.set_decorations(Decorations {
+      extra_columns: vec![],
+      column: Some("Notes".into()),
+      rows: [(("".into(), "a".into()), note)].into(),
+    });
+    sft.set_columns(&["Name"]);
+    let shown: Vec<String> = sft.shown_columns().into_iter().map(String::from).collect();
+    assert_eq!(shown, vec!["Name", "Notes"]);
+    sft.update_row_cache(&shown);
+    assert_eq!(cells(&sft), vec!["a unowned"]);
+    assert_eq!(sft.health(&sft.items[0]), RowHealth::Failed);
+  }
+
+  #[test]
+  fn test_row_health() {
+    assert_eq!(RowHealth::from_ready("3/3"), RowHealth::Healthy);
+    assert_eq!(RowHealth::from_ready("1/3"), RowHealth::Pending);
+    assert_eq!(RowHealth::from_ready("0/0"), RowHealth::Healthy);
+    assert_eq!(RowHealth::from_ready(""), RowHealth::Healthy);
+    assert_eq!(RowHealth::from_replicas(2, 3), RowHealth::Pending);
+
+    let mut cm = ConfigMap::default();
+    assert_eq!(KubeConfigMap::from(cm.clone()).health(), RowHealth::Healthy);
+    cm.metadata.deletion_timestamp = Some(Time(Utc::now()));
+    assert_eq!(KubeConfigMap::from(cm).health(), RowHealth::Pending);
+  }
+
+  #[test]
+  fn test_scrollable_txt_search() {
+    let mut stxt =
+      ScrollableTxt::with_string("Name: api\nStatus: Running\nimage: API:1\nReady: true".into());
+    assert_eq!(stxt.search_position(), None);
+    stxt.set_search("api".into());
+    assert_eq!(stxt.search_position(), Some("1/2".into()));
+    assert_eq!(stxt.offset, 0);
+    stxt.next_match(true);
+    assert_eq!(stxt.current_match(), Some(2));
+    assert_eq!(stxt.offset, 2);
+    stxt.next_match(true);
+    assert_eq!(stxt.offset, 0);
+    stxt.next_match(false);
+    assert_eq!(stxt.search_position(), Some("2/2".into()));
+
+    // the first match from the current line is picked
+    stxt.offset = 1;
+    stxt.set_search("ready".into());
+    assert_eq!(stxt.offset, 3);
+    stxt.set_search("missing".into());
+    assert_eq!(stxt.search_position(), Some("no matches".into()));
+    assert_eq!(stxt.offset, 3);
+    stxt.set_search(String::new());
+    assert_eq!(stxt.search_position(), None);
+  }
+
+  #[test]
+  fn test_scrollable_txt_scroll_position() {
+    let mut stxt = ScrollableTxt::with_string("a\nb\nc\nd\n0123456789".into());
+    assert_eq!(stxt.scroll_position(10, 5), "All");
+    // the long line wraps into two rows
+    assert_eq!(stxt.scroll_position(5, 5), "Top");
+    stxt.offset = 1;
+    assert_eq!(stxt.scroll_position(5, 5), "Bot");
+    assert_eq!(stxt.scroll_position(5, 2), "25%");
+  }
+
+  #[test]
+  fn test_stateful_table_pages() {
+    let mut sft = StatefulTable::with_items((0..25).collect::<Vec<_>>());
+    sft.set_view(Rect::new(0, 0, 10, 10));
+    assert_eq!(sft.page_size(), 10);
+    assert_eq!(sft.position(), Some("item 1/25, page 1/3".into()));
+
+    sft.handle_scroll(false, ScrollStep::Page);
+    assert_eq!(sft.state.selected(), Some(10));
+    assert_eq!(sft.position(), Some("item 11/25, page 2/3".into()));
+    sft.handle_scroll(false, ScrollStep::Page);
+    sft.handle_scroll(false, ScrollStep::Page);
+    assert_eq!(sft.position(), Some("item 25/25, page 3/3".into()));
+
+    sft.set_view(Rect::new(0, 0, 10, 30));
+    assert_eq!(sft.position(), None);
+  }
+
+  #[test]
+  fn test_stateful_table_rows_in_view() {
+    let mut sft = StatefulTable::with_items((0..50_000).collect::<Vec<_>>());
+    assert_eq!(sft.rows_in_view(), 0..10);
+    sft.set_view(Rect::new(0, 0, 10, 20));
+    assert_eq!(sft.rows_in_view(), 0..20);
+    sft.state.select(Some(30_000));
+    sft.set_view(Rect::new(0, 0, 10, 20));
+    assert_eq!(sft.rows_in_view(), 29_981..30_001);
+    sft.set_items((0..5).collect());
+    sft.state.select(Some(0));
+    sft.set_view(Rect::new(0, 0, 10, 20));
+    assert_eq!(sft.rows_in_view(), 0..5);
+  }
+
+  #[test]
+  fn test_stateful_table_marks() {
+    let mut sft = StatefulTable::with_items(vec![("a", "1d"), ("b", "2d"), ("c", "3d")]);
+    assert_eq!(sft.marked.len(), 0);
+
+    sft.toggle_mark();
+    sft.select_row(2);
+    sft.toggle_mark();
+    assert_eq!(sft.marked.len(), 2);
+    assert!(sft.is_marked(&("a", "1d")));
+    assert!(!sft.is_marked(&("b", "2d")));
+    assert_eq!(sft.marked_items(), vec![&("a", "1d"), &("c", "3d")]);
+    sft.toggle_mark();
+    assert_eq!(sft.marked_items(), vec![&("a", "1d")]);
+
+    // marks follow the items across refreshes and are dropped for removed ones
+    sft.toggle_mark_all();
+    assert_eq!(sft.marked.len(), 3);
+    sft.set_sorted_items(vec![("c", "4d"), ("a", "2d"), ("d", "1d")]);
+    assert_eq!(sft.marked_items(), vec![&("c", "4d"), &("a", "2d")]);
+
+    sft.toggle_mark_all();
+    assert_eq!(sft.marked.len(), 3);
+    sft.toggle_mark_all();
+    assert_eq!(sft.marked.len(), 0);
+  }
+
+  #[test]
+  fn test_stateful_table_selected_cells() {
+    let mut sft: StatefulTable<(&str, &str)> = StatefulTable::new();
+    assert_eq!(sft.selected_key(), None);
+    assert!(sft.selected_cells().is_empty());
+
+    sft.set_columns(&["Name", "Age"]);
+    sft.set_items(vec![("a", "1d"), ("b", "2d")]);
+    sft.select_row(1);
+    assert_eq!(sft.selected_key(), Some((String::new(), "b".into())));
+    assert_eq!(
+      sft.selected_cells(),
+      vec![("Name".into(), "b".into()), ("Age".into(), "2d".into())]
+    );
+    sft.toggle_column("Name");
+    assert_eq!(sft.selected_cells(), vec![("Age".into(), "2d".into())]);
+  }
+
+  #[test]
+  fn test_stateful_table_scroll_columns() {
+    let mut sft: StatefulTable<(&str, &str)> = StatefulTable::new();
+    sft.set_columns(&["Name", "Age"]);
+    assert!(!sft.is_column_scrolled());
+
+    sft.scroll_columns(false);
+    assert_eq!(sft.shown_columns(), vec!["Name", "Age"]);
+    sft.scroll_columns(true);
+    assert_eq!(sft.shown_columns(), vec!["Age"]);
+    assert!(sft.is_column_scrolled());
+    // the last column stays in view
+    sft.scroll_columns(true);
+    assert_eq!(sft.shown_columns(), vec!["Age"]);
+    sft.scroll_columns(false);
+    assert_eq!(sft.shown_columns(), vec!["Name", "Age"]);
+  }
+
+  #[test]
+  fn test_scrollable_txt_horizontal() {
+    let mut stxt = ScrollableTxt::with_string("short\na much longer line".into());
+    stxt.scroll_horizontal(true, 4);
+    assert_eq!(stxt.h_offset, 4);
+    stxt.scroll_horizontal(true, 4);
+    stxt.scroll_horizontal(true, 4);
+    stxt.scroll_horizontal(true, 4);
+    stxt.scroll_horizontal(true, 4);
+    assert_eq!(stxt.h_offset, 14);
+    stxt.scroll_horizontal(false, 10);
+    stxt.scroll_horizontal(false, 10);
+    assert_eq!(stxt.h_offset, 0);
+  }
+
+  #[test]
+  fn test_stateful_table_row_at() {
+    let mut sft: StatefulTable<&str> = StatefulTable::new();
+    sft.set_items(vec!["A", "B", "C", "D", "E"]);
+
+    // not drawn yet
+    assert_eq!(sft.row_at(5, 5), None);
+
+    sft.set_view(Rect::new(2, 3, 10, 3));
+    assert_eq!(sft.row_at(2, 3), Some(0));
+    assert_eq!(sft.row_at(11, 5), Some(2));
+    assert_eq!(sft.row_at(12, 5), None);
+    assert_eq!(sft.row_at(5, 6), None);
+
+    // selecting a row out of view scrolls the view
+    sft.state.select(Some(4));
+    sft.set_view(Rect::new(2, 3, 10, 3));
+    assert_eq!(sft.row_at(2, 3), Some(2));
+    assert_eq!(sft.row_at(2, 5), Some(4));
+    // and it stays put while the selection is in view
+    sft.state.select(Some(3));
+    sft.set_view(Rect::new(2, 3, 10, 3));
+    assert_eq!(sft.row_at(2, 3), Some(2));
+    sft.state.select(Some(0));
+    sft.set_view(Rect::new(2, 3, 10, 3));
+    assert_eq!(sft.row_at(2, 3), Some(0));
+
+    // rows past the end of items
+    sft.set_view(Rect::new(2, 3, 10, 8));
+    assert_eq!(sft.row_at(2, 7), Some(4));
+    assert_eq!(sft.row_at(2, 8), None);
+  }
+
   #[test]
   fn test_handle_table_scroll() {
     let mut item: StatefulTable<&str> = StatefulTable::new();
@@ -468,22 +1994,22 @@ mod tests {
 
     assert_eq!(item.state.selected(), Some(0));
 
-    item.handle_scroll(false, false);
+    item.handle_scroll(false, ScrollStep::Line);
     assert_eq!(item.state.selected(), Some(1));
 
-    item.handle_scroll(false, false);
+    item.handle_scroll(false, ScrollStep::Line);
     assert_eq!(item.state.selected(), Some(2));
 
-    item.handle_scroll(false, false);
+    item.handle_scroll(false, ScrollStep::Line);
     assert_eq!(item.state.selected(), Some(2));
     // previous
-    item.handle_scroll(true, false);
+    item.handle_scroll(true, ScrollStep::Line);
     assert_eq!(item.state.selected(), Some(1));
     // page down
-    item.handle_scroll(false, true);
+    item.handle_scroll(false, ScrollStep::Page);
     assert_eq!(item.state.selected(), Some(2));
     // page up
-    item.handle_scroll(true, true);
+    item.handle_scroll(true, ScrollStep::Page);
     assert_eq!(item.state.selected(), Some(0));
   }
 
@@ -520,6 +2046,13 @@ mod tests {
     tab.previous();
     assert_eq!(tab.index, 0);
     assert_eq!(tab.get_active_route().active_block, ActiveBlock::Pods);
+
+    assert_eq!(tab.index_at(1, 0), None);
+    tab.set_title_areas(vec![Rect::new(1, 0, 5, 1), Rect::new(8, 0, 4, 1)]);
+    assert_eq!(tab.index_at(1, 0), Some(0));
+    assert_eq!(tab.index_at(6, 0), None);
+    assert_eq!(tab.index_at(11, 0), Some(1));
+    assert_eq!(tab.index_at(11, 1), None);
   }
 
   #[test]
@@ -622,6 +2155,7 @@ mod tests {
     ]);
 
     terminal.backend().assert_buffer(&expected3);
+    assert_eq!(log.scroll_position(true, 7), "Bot");
 
     terminal
       .draw(|f| log.render_list(f, f.size(), Block::default(), Style::default(), false))
@@ -638,9 +2172,12 @@ mod tests {
     ]);
 
     terminal.backend().assert_buffer(&expected4);
+    assert_eq!(log.scroll_position(false, 7), "Top");
+    assert_eq!(log.scroll_position(false, 20), "All");
 
     log.scroll_up(1); // to reset select state
     log.scroll_down(11);
+    assert_eq!(log.scroll_position(false, 7), "78%");
 
     terminal
       .draw(|f| log.render_list(f, f.size(), Block::default(), Style::default(), false))
@@ -665,4 +2202,69 @@ mod tests {
 
     terminal.backend().assert_buffer(&expected5);
   }
+
+  #[test]
+  fn test_logs_state_limits() {
+    let mut log = LogsState::new("1".into()).with_limits(LogLimits {
+      lines: 10,
+      bytes: 1000,
+    });
+    for i in 0..10 {
+      log.add_record(format!("record {}", i));
+    }
+    let backend = TestBackend::new(20, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| log.render_list(f, f.size(), Block::default(), Style::default(), false))
+      .unwrap();
+    log.select_line(5);
+
+    // the oldest records are dropped down to 90% of the limit, the selection stays on its line
+    log.add_record("record 10".into());
+    assert!(log.get_plain_text().starts_with("\nrecord 2\n"));
+    assert_eq!(log.state.selected(), Some(3));
+    terminal
+      .draw(|f| log.render_list(f, f.size(), Block::default(), Style::default(), false))
+      .unwrap();
+    assert_eq!(log.scroll_position(false, 3), "37%");
+
+    log.add_record("x".repeat(950));
+    assert_eq!(log.get_plain_text(), format!("\n{}", "x".repeat(950)));
+  }
+
+  #[test]
+  fn test_logs_state_long() {
+    let mut log = LogsState::new("1".into());
+    for i in 0..90_000 {
+      log.add_record(format!("record {}", i));
+    }
+    let backend = TestBackend::new(20, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| log.render_list(f, f.size(), Block::default(), Style::default(), true))
+      .unwrap();
+    terminal.backend().assert_buffer(&Buffer::with_lines(vec![
+      "record 89997        ",
+      "record 89998        ",
+      "record 89999        ",
+    ]));
+
+    // only the lines around the selected one are drawn
+    log.select_line(45_000);
+    terminal
+      .draw(|f| log.render_list(f, f.size(), Block::default(), Style::default(), false))
+      .unwrap();
+    let mut expected = Buffer::with_lines(vec![
+      "record 44998        ",
+      "record 44999        ",
+      "record 45000        ",
+    ]);
+    for col in 0..=19 {
+      expected
+        .get_mut(col, 2)
+        .set_style(Style::default().add_modifier(Modifier::BOLD));
+    }
+    terminal.backend().assert_buffer(&expected);
+    assert_eq!(log.scroll_position(false, 3), "50%");
+  }
 }
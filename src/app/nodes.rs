@@ -1,12 +1,13 @@
 use k8s_openapi::{
   api::core::v1::{Node, Pod},
+  apimachinery::pkg::apis::meta::v1::ObjectMeta,
   chrono::Utc,
 };
 use kube::api::ObjectList;
-use tokio::sync::MutexGuard;
 
 use super::{
-  models::KubeResource,
+  metrics::{self, FsStats},
+  models::{KubeResource, RowHealth, TableRow},
   utils::{self, UNKNOWN},
   App,
 };
@@ -17,13 +18,18 @@ pub struct KubeNode {
   pub status: String,
   pub role: String,
   pub version: String,
-  pub pods: i32,
+  /// None when the pods weren't listed, in large cluster mode
+  pub pods: Option<i32>,
   pub cpu: String,
   pub mem: String,
   pub cpu_a: String,
   pub mem_a: String,
   pub cpu_percent: String,
   pub mem_percent: String,
+  pub disk: String,
+  pub disk_a: String,
+  pub disk_percent: String,
+  pub disk_fs: Option<FsStats>,
   pub age: String,
   k8s_obj: Node,
 }
@@ -33,18 +39,20 @@ static NODE_LABEL_ROLE: &str = "kubernetes.io/role";
 static NONE_ROLE: &str = "<none>";
 
 impl KubeNode {
+  /// the node with the number of pods running on it, counted when the pods of all namespaces are
+  /// given
   pub fn from_api_with_pods(
     node: &Node,
-    pods_list: &ObjectList<Pod>,
-    app: &mut MutexGuard<'_, App>,
+    pods_list: Option<&ObjectList<Pod>>,
+    app: &mut App,
   ) -> Self {
     let node_name = node.metadata.name.clone().unwrap_or_default();
     let unschedulable = &node
       .spec
       .as_ref()
-      .map_or(false, |s| s.unschedulable.unwrap_or(false));
+      .is_some_and(|s| s.unschedulable.unwrap_or(false));
 
-    let (status, version, cpu_a, mem_a) = match &node.status {
+    let (status, version, cpu_a, mem_a, disk_a) = match &node.status {
       Some(node_status) => {
         let status = if *unschedulable {
           Some("Unschedulable".into())
@@ -65,34 +73,40 @@ impl KubeNode {
           .as_ref()
           .map(|i| i.kubelet_version.clone());
 
-        let (cpu, mem) = node_status.allocatable.as_ref().map_or((None, None), |a| {
-          (
-            a.get("cpu").map(|q| q.0.clone()),
-            a.get("memory").map(|q| q.0.clone()),
-          )
-        });
+        let (cpu, mem, disk) = node_status
+          .allocatable
+          .as_ref()
+          .map_or((None, None, None), |a| {
+            (
+              a.get("cpu").map(|q| q.0.clone()),
+              a.get("memory").map(|q| q.0.clone()),
+              a.get("ephemeral-storage").map(|q| q.0.clone()),
+            )
+          });
 
-        (status, version, cpu, mem)
+        (status, version, cpu, mem, disk)
       }
-      None => (None, None, None, None),
+      None => (None, None, None, None, None),
     };
 
-    let pod_count = pods_list.iter().fold(0, |acc, pod| {
-      let p_node_name = pod.spec.as_ref().and_then(|spec| spec.node_name.clone());
-      p_node_name.map_or(acc, |v| if v == node_name { acc + 1 } else { acc })
+    let pod_count = pods_list.map(|pods_list| {
+      pods_list.iter().fold(0, |acc, pod| {
+        let p_node_name = pod.spec.as_ref().and_then(|spec| spec.node_name.clone());
+        p_node_name.map_or(acc, |v| if v == node_name { acc + 1 } else { acc })
+      })
     });
 
     let role = match &node.metadata.labels {
       Some(labels) => labels
         .iter()
         .filter_map(|(k, v)| {
-          return if k.starts_with(NODE_LABEL_PREFIX) {
+          if k.starts_with(NODE_LABEL_PREFIX) {
             Some(k.trim_start_matches(NODE_LABEL_PREFIX))
           } else if k == NODE_LABEL_ROLE && !v.is_empty() {
             Some(v)
           } else {
             None
-          };
+          }
         })
         .collect::<Vec<_>>()
         .join(","),
@@ -130,7 +144,9 @@ impl KubeNode {
       ),
     };
 
-    KubeNode {
+    let disk_fs = app.data.ephemeral_storage.nodes.get(&node_name).copied();
+
+    let mut kube_node = KubeNode {
       name: node_name,
       status: status.unwrap_or_else(|| UNKNOWN.into()),
       role: if role.is_empty() {
@@ -147,8 +163,44 @@ impl KubeNode {
       mem_a: utils::mem_to_mi(mem_a.unwrap_or_default()),
       cpu_percent,
       mem_percent,
+      disk: String::default(),
+      disk_a: disk_a.map_or_else(String::default, |d| {
+        utils::bytes_to_human(metrics::parse_quantity(&d) as u64)
+      }),
+      disk_percent: String::default(),
+      disk_fs: None,
       k8s_obj: utils::sanitize_obj(node.to_owned()),
-    }
+    };
+    kube_node.set_disk_usage(disk_fs);
+    kube_node
+  }
+
+  /// set ephemeral storage usage of the node filesystem as reported by the summary API
+  pub fn set_disk_usage(&mut self, fs: Option<FsStats>) {
+    self.disk = fs
+      .and_then(|fs| fs.used_bytes)
+      .map_or_else(|| String::from("-"), utils::bytes_to_human);
+    self.disk_percent = fs
+      .and_then(|fs| fs.used_percent())
+      .map_or_else(|| String::from("-"), |p| p.to_string());
+    self.disk_fs = fs;
+  }
+
+  /// the pressure conditions that are true, like `DiskPressure`
+  pub fn pressures(&self) -> Vec<String> {
+    self
+      .k8s_obj
+      .status
+      .as_ref()
+      .and_then(|status| status.conditions.as_ref())
+      .map(|conditions| {
+        conditions
+          .iter()
+          .filter(|c| c.type_.ends_with("Pressure") && c.status == "True")
+          .map(|c| c.type_.clone())
+          .collect()
+      })
+      .unwrap_or_default()
   }
 }
 
@@ -158,6 +210,54 @@ impl KubeResource<Node> for KubeNode {
   }
 }
 
+impl TableRow for KubeNode {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  /// the pod count, metrics and disk usage come from other resources than the node
+  fn version(&self) -> Option<String> {
+    let version = self.k8s_obj.metadata.resource_version.as_ref()?;
+    Some(format!(
+      "{}/{:?}/{}/{}/{}/{}",
+      version, self.pods, self.cpu, self.mem, self.disk, self.disk_a
+    ))
+  }
+
+  fn status_health(&self) -> RowHealth {
+    if self.status != "Ready" || self.disk_fs.is_some_and(|fs| fs.is_past_eviction()) {
+      RowHealth::Failed
+    } else if self.disk_fs.is_some_and(|fs| fs.is_near_eviction()) {
+      RowHealth::Pending
+    } else {
+      RowHealth::Healthy
+    }
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Name" => self.name.to_owned(),
+      "Status" => self.status.to_owned(),
+      "Roles" => self.role.to_owned(),
+      "Version" => self.version.to_owned(),
+      "Pods" => self
+        .pods
+        .map_or_else(|| String::from("-"), |p| p.to_string()),
+      "CPU" => self.cpu.to_owned(),
+      "Mem" => self.mem.to_owned(),
+      "Disk" => self.disk.to_owned(),
+      "CPU %" => self.cpu_percent.to_owned(),
+      "Mem %" => self.mem_percent.to_owned(),
+      "Disk %" => self.disk_percent.to_owned(),
+      "CPU/A" => self.cpu_a.to_owned(),
+      "Mem/A" => self.mem_a.to_owned(),
+      "Disk/A" => self.disk_a.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use tokio::sync::Mutex;
@@ -183,10 +283,11 @@ mod tests {
 
     let nodes = nodes
       .iter()
-      .map(|it| KubeNode::from_api_with_pods(it, &pods_list, &mut app))
+      .map(|it| KubeNode::from_api_with_pods(it, Some(&pods_list), &mut app))
       .collect::<Vec<_>>();
 
     assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].pressures(), vec!["DiskPressure"]);
     assert_eq!(
       nodes[0],
       KubeNode {
@@ -196,13 +297,17 @@ mod tests {
         status: "Ready".into(),
         role: "control-plane,master".into(),
         version: "v1.20.6+k3s1".into(),
-        pods: 5,
+        pods: Some(5),
         cpu: "1414m".into(),
         mem: "590Mi".into(),
         cpu_a: "8000m".into(),
         mem_a: "31967Mi".into(),
         cpu_percent: "17".into(),
         mem_percent: "1".into(),
+        disk: "-".into(),
+        disk_a: "65.0Gi".into(),
+        disk_percent: "-".into(),
+        disk_fs: None,
       }
     );
   }
@@ -0,0 +1,110 @@
+use std::{
+  collections::VecDeque,
+  time::{Duration, Instant},
+};
+
+/// how long a notification stays on screen
+const NOTIFICATION_TTL: Duration = Duration::from_secs(5);
+/// older notifications are dropped when more than this are shown at once
+const MAX_NOTIFICATIONS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationLevel {
+  Info,
+  Success,
+  Failure,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+  pub level: NotificationLevel,
+  pub message: String,
+  shown_at: Instant,
+}
+
+/// transient messages reporting the outcome of background operations, newest last
+#[derive(Default)]
+pub struct Notifications {
+  items: VecDeque<Notification>,
+}
+
+impl Notifications {
+  /// add a notification, a message that is already shown is restarted instead of repeated
+  pub fn push(&mut self, level: NotificationLevel, message: String, now: Instant) {
+    self
+      .items
+      .retain(|n| n.level != level || n.message != message);
+    self.items.push_back(Notification {
+      level,
+      message,
+      shown_at: now,
+    });
+    while self.items.len() > MAX_NOTIFICATIONS {
+      self.items.pop_front();
+    }
+  }
+
+  /// drop notifications that have been shown long enough
+  pub fn prune(&mut self, now: Instant) {
+    self
+      .items
+      .retain(|n| now.duration_since(n.shown_at) < NOTIFICATION_TTL);
+  }
+
+  pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Notification> {
+    self.items.iter()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_notifications() {
+    let start = Instant::now();
+    let mut notifications = Notifications::default();
+    assert!(notifications.is_empty());
+
+    notifications.push(NotificationLevel::Success, "Deleted".into(), start);
+    notifications.push(NotificationLevel::Failure, "Failed".into(), start);
+    notifications.push(
+      NotificationLevel::Success,
+      "Deleted".into(),
+      start + Duration::from_secs(3),
+    );
+    assert_eq!(
+      notifications
+        .iter()
+        .map(|n| n.message.as_str())
+        .collect::<Vec<_>>(),
+      vec!["Failed", "Deleted"]
+    );
+
+    notifications.prune(start + Duration::from_secs(6));
+    assert_eq!(
+      notifications
+        .iter()
+        .map(|n| n.message.as_str())
+        .collect::<Vec<_>>(),
+      vec!["Deleted"]
+    );
+    notifications.prune(start + Duration::from_secs(8));
+    assert!(notifications.is_empty());
+
+    for i in 0..5 {
+      notifications.push(NotificationLevel::Info, i.to_string(), start);
+    }
+    assert_eq!(
+      notifications
+        .iter()
+        .map(|n| n.message.as_str())
+        .collect::<Vec<_>>(),
+      vec!["2", "3", "4"]
+    );
+  }
+}
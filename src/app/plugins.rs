@@ -0,0 +1,114 @@
+use super::ActiveBlock;
+use crate::{config::PluginOutput, event::Key};
+
+/// an external command from `plugins` in the config, run on the selected resource with a key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plugin {
+  pub name: String,
+  pub keys: Vec<Key>,
+  /// the tables the plugin runs in, every table when empty
+  pub blocks: Vec<ActiveBlock>,
+  /// shell command, the selected resource is in `$NAME`, `$NAMESPACE` and `$CONTEXT`
+  pub command: String,
+  pub output: PluginOutput,
+  /// the command only reads the cluster, so it runs in read-only mode too
+  pub read_only_safe: bool,
+}
+
+impl Plugin {
+  pub fn matches(&self, key: Key, block: ActiveBlock) -> bool {
+    self.keys.contains(&key) && (self.blocks.is_empty() || self.blocks.contains(&block))
+  }
+
+  /// the command with the selected resource, cluster scoped ones have an empty namespace
+  pub fn run_on(&self, namespace: &str, name: &str, context: &str) -> PluginRun {
+    PluginRun {
+      name: self.name.clone(),
+      command: self.command.clone(),
+      read_only_safe: self.read_only_safe,
+      env: [
+        ("NAME", name),
+        ("NAMESPACE", namespace),
+        ("CONTEXT", context),
+      ]
+      .iter()
+      .map(|(var, value)| (var.to_string(), value.to_string()))
+      .collect(),
+    }
+  }
+}
+
+/// a plugin run on a resource, the shell expands the placeholders from the environment so names
+/// need no quoting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginRun {
+  pub name: String,
+  pub command: String,
+  pub env: Vec<(String, String)>,
+  pub read_only_safe: bool,
+}
+
+/// help page rows of the plugins whose key or name contain the query, under their own title.
+/// Only the read-only safe ones are listed in read-only mode
+pub fn plugin_help_docs(plugins: &[Plugin], query: &str, read_only: bool) -> Vec<Vec<String>> {
+  let query = query.to_lowercase();
+  let rows: Vec<Vec<String>> = plugins
+    .iter()
+    .filter(|plugin| plugin.read_only_safe || !read_only)
+    .map(|plugin| {
+      let keys: Vec<String> = plugin.keys.iter().map(Key::to_string).collect();
+      vec![keys.join(" | "), plugin.name.clone()]
+    })
+    .filter(|row| {
+      query.is_empty()
+        || "plugins".contains(&query)
+        || row.iter().any(|cell| cell.to_lowercase().contains(&query))
+    })
+    .collect();
+  if rows.is_empty() {
+    rows
+  } else {
+    std::iter::once(vec!["Plugins".to_owned()])
+      .chain(rows)
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_plugin() {
+    let plugin = Plugin {
+      name: "dive".into(),
+      keys: vec![Key::Ctrl('i')],
+      blocks: vec![ActiveBlock::Pods],
+      command: "dive $NAME".into(),
+      output: PluginOutput::Terminal,
+      read_only_safe: false,
+    };
+    assert!(plugin.matches(Key::Ctrl('i'), ActiveBlock::Pods));
+    assert!(!plugin.matches(Key::Ctrl('i'), ActiveBlock::Nodes));
+    assert!(!plugin.matches(Key::Char('i'), ActiveBlock::Pods));
+    assert_eq!(
+      plugin.run_on("web", "api-0", "prod").env,
+      vec![
+        ("NAME".into(), "api-0".into()),
+        ("NAMESPACE".into(), "web".into()),
+        ("CONTEXT".into(), "prod".into()),
+      ]
+    );
+
+    let plugins = [plugin];
+    assert_eq!(
+      plugin_help_docs(&plugins, "", false),
+      vec![
+        vec!["Plugins".to_string()],
+        vec!["<Ctrl+i>".to_string(), "dive".to_string()],
+      ]
+    );
+    assert!(plugin_help_docs(&plugins, "logs", false).is_empty());
+    assert!(plugin_help_docs(&plugins, "", true).is_empty());
+  }
+}
@@ -3,11 +3,12 @@ use k8s_openapi::{
     Container, ContainerPort, ContainerState, ContainerStateWaiting, ContainerStatus, Pod, PodSpec,
     PodStatus,
   },
+  apimachinery::pkg::apis::meta::v1::ObjectMeta,
   chrono::Utc,
 };
 
 use super::{
-  models::KubeResource,
+  models::{KubeResource, RowHealth, TableRow},
   utils::{self, UNKNOWN},
 };
 
@@ -20,6 +21,10 @@ pub struct KubePod {
   pub restarts: i32,
   pub cpu: String,
   pub mem: String,
+  /// ephemeral storage used, when known from the summary API
+  pub disk: String,
+  pub node: String,
+  pub ip: String,
   pub age: String,
   pub containers: Vec<KubeContainer>,
   k8s_obj: Pod,
@@ -38,6 +43,18 @@ pub struct KubeContainer {
   pub age: String,
   pub pod_name: String,
   pub init: bool,
+  /// the container has a TTY and stdin, so attaching to it is interactive
+  pub tty: bool,
+}
+
+/// a container the UI loop attaches to with the terminal, through the API
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AttachRequest {
+  pub context: Option<String>,
+  pub namespace: String,
+  pub pod: String,
+  pub container: String,
+  pub tty: bool,
 }
 
 impl From<Pod> for KubePod {
@@ -104,6 +121,17 @@ impl From<Pod> for KubePod {
       _ => (UNKNOWN.into(), 0, 0, 0, vec![]),
     };
 
+    let node = pod
+      .spec
+      .as_ref()
+      .and_then(|spec| spec.node_name.clone())
+      .unwrap_or_default();
+    let ip = pod
+      .status
+      .as_ref()
+      .and_then(|pod_status| pod_status.pod_ip.clone())
+      .unwrap_or_default();
+
     KubePod {
       name: pod_name,
       namespace: pod.metadata.namespace.clone().unwrap_or_default(),
@@ -112,6 +140,9 @@ impl From<Pod> for KubePod {
       // TODO implement pod metrics
       cpu: String::default(),
       mem: String::default(),
+      disk: String::from("-"),
+      node,
+      ip,
       status,
       age,
       containers,
@@ -120,12 +151,82 @@ impl From<Pod> for KubePod {
   }
 }
 
+impl KubePod {
+  pub fn set_disk_usage(&mut self, used: Option<u64>) {
+    self.disk = used.map_or_else(|| String::from("-"), utils::bytes_to_human);
+  }
+}
+
 impl KubeResource<Pod> for KubePod {
   fn get_k8s_obj(&self) -> &Pod {
     &self.k8s_obj
   }
 }
 
+impl TableRow for KubePod {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  /// the disk usage comes from the node summaries, not the pod
+  fn version(&self) -> Option<String> {
+    let version = self.k8s_obj.metadata.resource_version.as_ref()?;
+    Some(format!("{}/{}", version, self.disk))
+  }
+
+  fn status_health(&self) -> RowHealth {
+    pod_status_health(&self.status, self.ready.0 == self.ready.1)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Ready" => format!("{}/{}", self.ready.0, self.ready.1),
+      "Status" => self.status.to_owned(),
+      "Restarts" => self.restarts.to_string(),
+      "Disk" => self.disk.to_owned(),
+      "Node" => self.node.to_owned(),
+      "IP" => self.ip.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
+impl TableRow for KubeContainer {
+  fn status_health(&self) -> RowHealth {
+    pod_status_health(&self.status, true)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Name" => self.name.to_owned(),
+      "Image" => self.image.to_owned(),
+      "Init" => self.init.to_string(),
+      "Ready" => self.ready.to_owned(),
+      "State" => self.status.to_owned(),
+      "Restarts" => self.restarts.to_string(),
+      "Probes(L/R)" => format!("{}/{}", self.liveliness_probe, self.readiness_probe),
+      "Ports" => self.ports.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
+/// health of a pod or container status, running ones are only healthy once ready
+fn pod_status_health(status: &str, ready: bool) -> RowHealth {
+  match status {
+    "Running" if ready => RowHealth::Healthy,
+    "Completed" | "Succeeded" => RowHealth::Done,
+    "ContainerCreating" | "PodInitializing" | "Pending" | "Initialized" | "Terminating" => {
+      RowHealth::Pending
+    }
+    _ => RowHealth::Failed,
+  }
+}
+
 impl KubeContainer {
   pub fn from_api(
     container: &Container,
@@ -155,6 +256,7 @@ impl KubeContainer {
       ports: get_container_ports(&container.ports).unwrap_or_default(),
       age,
       init,
+      tty: container.tty.unwrap_or_default() && container.stdin.unwrap_or_default(),
     }
   }
 }
@@ -326,6 +428,24 @@ mod tests {
   use super::*;
   use crate::app::test_utils::*;
 
+  #[test]
+  fn test_pod_health() {
+    let mut pod = KubePod {
+      status: "Running".into(),
+      ready: (2, 2),
+      ..KubePod::default()
+    };
+    assert_eq!(pod.health(), RowHealth::Healthy);
+    pod.ready = (1, 2);
+    assert_eq!(pod.health(), RowHealth::Failed);
+    pod.status = "CrashLoopBackOff".into();
+    assert_eq!(pod.health(), RowHealth::Failed);
+    pod.status = "Pending".into();
+    assert_eq!(pod.health(), RowHealth::Pending);
+    pod.status = "Completed".into();
+    assert_eq!(pod.health(), RowHealth::Done);
+  }
+
   #[test]
   fn test_pod_from_api() {
     let (pods, pods_list): (Vec<KubePod>, Vec<_>) = convert_resource_from_file("pods");
@@ -341,6 +461,9 @@ mod tests {
         restarts: 0,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: String::default(),
+        ip: String::default(),
         age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
         containers: vec![KubeContainer {
           name: "server".into(),
@@ -354,6 +477,7 @@ mod tests {
           age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
           pod_name: "adservice-f787c8dcd-tb6x2".into(),
           init: false,
+          tty: false,
         }],
         k8s_obj: pods_list[0].clone()
       }
@@ -368,6 +492,9 @@ mod tests {
         restarts: 896,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: "gke-hello-hipster-default-pool-9e6f6ffb-q16l".into(),
+        ip: "10.24.1.9".into(),
         age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
         containers: vec![KubeContainer {
           name: "server".into(),
@@ -381,6 +508,7 @@ mod tests {
           age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
           pod_name: "cartservice-67b89ffc69-s5qp8".into(),
           init: false,
+          tty: false,
         }],
         k8s_obj: pods_list[1].clone()
       }
@@ -395,6 +523,9 @@ mod tests {
         restarts: 3,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: "gke-hello-hipster-default-pool-9e6f6ffb-xzbc".into(),
+        ip: "10.24.0.3".into(),
         age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
         containers: vec![KubeContainer {
           name: "server".into(),
@@ -408,6 +539,7 @@ mod tests {
           age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
           pod_name: "emailservice-5f8fc7dbb4-5lqdb".into(),
           init: false,
+          tty: false,
         }],
         k8s_obj: pods_list[3].clone()
       }
@@ -422,6 +554,9 @@ mod tests {
         restarts: 0,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: "gke-hello-hipster-default-pool-9e6f6ffb-q16l".into(),
+        ip: String::default(),
         age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
         containers: vec![KubeContainer {
           name: "server".into(),
@@ -435,6 +570,7 @@ mod tests {
           age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
           pod_name: "frontend-5c4745dfdb-6k8wf".into(),
           init: false,
+          tty: false,
         }],
         k8s_obj: pods_list[4].clone()
       }
@@ -449,6 +585,9 @@ mod tests {
         restarts: 0,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: "gke-hello-hipster-default-pool-9e6f6ffb-q16l".into(),
+        ip: String::default(),
         age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
         containers: vec![KubeContainer {
           name: "server".into(),
@@ -462,6 +601,7 @@ mod tests {
           age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
           pod_name: "frontend-5c4745dfdb-qz7fg".into(),
           init: false,
+          tty: false,
         }],
         k8s_obj: pods_list[5].clone()
       }
@@ -476,6 +616,9 @@ mod tests {
         restarts: 0,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: "gke-hello-hipster-default-pool-9e6f6ffb-q16l".into(),
+        ip: String::default(),
         age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
         containers: vec![KubeContainer {
           name: "server".into(),
@@ -489,6 +632,7 @@ mod tests {
           age: utils::to_age(Some(&get_time("2021-04-27T10:13:58Z")), Utc::now()),
           pod_name: "frontend-5c4745dfdb-6k8wf".into(),
           init: false,
+          tty: false,
         }],
         k8s_obj: pods_list[6].clone()
       }
@@ -503,6 +647,9 @@ mod tests {
         restarts: 0,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: "k3d-my-kdash-cluster-server-0".into(),
+        ip: "10.42.0.20".into(),
         age: utils::to_age(Some(&get_time("2021-06-18T08:57:56Z")), Utc::now()),
         containers: vec![
           KubeContainer {
@@ -517,6 +664,7 @@ mod tests {
             age: utils::to_age(Some(&get_time("2021-06-18T08:57:56Z")), Utc::now()),
             pod_name: "pod-init-container".into(),
             init: false,
+            tty: false,
           },
           KubeContainer {
             name: "init-busybox1".into(),
@@ -530,6 +678,7 @@ mod tests {
             age: utils::to_age(Some(&get_time("2021-06-18T08:57:56Z")), Utc::now()),
             pod_name: "pod-init-container".into(),
             init: true,
+            tty: false,
           },
           KubeContainer {
             name: "init-busybox2".into(),
@@ -543,6 +692,7 @@ mod tests {
             age: utils::to_age(Some(&get_time("2021-06-18T08:57:56Z")), Utc::now()),
             pod_name: "pod-init-container".into(),
             init: true,
+            tty: false,
           }
         ],
         k8s_obj: pods_list[11].clone()
@@ -558,6 +708,9 @@ mod tests {
         restarts: 0,
         cpu: "".into(),
         mem: "".into(),
+        disk: "-".into(),
+        node: "k3d-my-kdash-cluster-server-0".into(),
+        ip: "10.42.0.21".into(),
         age: utils::to_age(Some(&get_time("2021-06-18T09:26:11Z")), Utc::now()),
         containers: vec![
           KubeContainer {
@@ -572,6 +725,7 @@ mod tests {
             age: utils::to_age(Some(&get_time("2021-06-18T09:26:11Z")), Utc::now()),
             pod_name: "pod-init-container-2".into(),
             init: false,
+            tty: false,
           },
           KubeContainer {
             name: "init-busybox1".into(),
@@ -585,6 +739,7 @@ mod tests {
             age: utils::to_age(Some(&get_time("2021-06-18T09:26:11Z")), Utc::now()),
             pod_name: "pod-init-container-2".into(),
             init: true,
+            tty: false,
           },
           KubeContainer {
             name: "init-busybox2".into(),
@@ -598,6 +753,7 @@ mod tests {
             age: utils::to_age(Some(&get_time("2021-06-18T09:26:11Z")), Utc::now()),
             pod_name: "pod-init-container-2".into(),
             init: true,
+            tty: false,
           }
         ],
         k8s_obj: pods_list[12].clone()
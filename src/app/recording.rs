@@ -0,0 +1,310 @@
+use std::{
+  collections::BTreeMap,
+  fs::{self, OpenOptions},
+  io::Write,
+  path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use k8s_openapi::{
+  api::{
+    apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet},
+    batch::v1::{CronJob, Job},
+    core::v1::{ConfigMap, Namespace, Node, Pod, ReplicationController, Secret, Service},
+    rbac::v1::{ClusterRole, ClusterRoleBinding, Role, RoleBinding},
+    storage::v1::StorageClass,
+  },
+  chrono::{DateTime, Utc},
+};
+use kube::api::{ListMeta, ObjectList};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+  models::{StatefulTable, TableRow},
+  nodes::KubeNode,
+  ActiveBlock, App,
+};
+
+/// a list of a resource kdash received, one line of a recording
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Record {
+  time: DateTime<Utc>,
+  kind: String,
+  items: Value,
+}
+
+/// name of the resource listed for a block in recordings
+fn kind(block: ActiveBlock) -> Option<&'static str> {
+  let kind = match block {
+    ActiveBlock::Namespaces => "namespaces",
+    ActiveBlock::Pods => "pods",
+    ActiveBlock::Services => "services",
+    ActiveBlock::Nodes => "nodes",
+    ActiveBlock::ConfigMaps => "configmaps",
+    ActiveBlock::StatefulSets => "statefulsets",
+    ActiveBlock::ReplicaSets => "replicasets",
+    ActiveBlock::Deployments => "deployments",
+    ActiveBlock::Jobs => "jobs",
+    ActiveBlock::DaemonSets => "daemonsets",
+    ActiveBlock::CronJobs => "cronjobs",
+    ActiveBlock::Secrets => "secrets",
+    ActiveBlock::RplCtrl => "replicationcontrollers",
+    ActiveBlock::StorageClasses => "storageclasses",
+    ActiveBlock::Roles => "roles",
+    ActiveBlock::RoleBindings => "rolebindings",
+    ActiveBlock::ClusterRoles => "clusterroles",
+    ActiveBlock::ClusterRoleBinding => "clusterrolebindings",
+    ActiveBlock::HelmReleases => "helmreleases",
+    _ => return None,
+  };
+  Some(kind)
+}
+
+/// append a list received for a block to the recording as a line of JSON
+pub fn record<K: Serialize>(path: &Path, block: ActiveBlock, items: &[K]) -> Result<()> {
+  let kind = match kind(block) {
+    Some(kind) => kind,
+    None => return Ok(()),
+  };
+  let mut items = serde_json::to_value(items)?;
+  if matches!(block, ActiveBlock::Secrets | ActiveBlock::HelmReleases) {
+    redact_secrets(&mut items);
+  }
+  let record = Record {
+    time: Utc::now(),
+    kind: kind.into(),
+    items,
+  };
+  let mut options = OpenOptions::new();
+  options.create(true).append(true);
+  // the cluster state in it is for the user only
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+  let mut file = options
+    .open(path)
+    .with_context(|| format!("Unable to open recording {}", path.display()))?;
+  writeln!(file, "{}", serde_json::to_string(&record)?)
+    .with_context(|| format!("Unable to write recording {}", path.display()))
+}
+
+/// empty the values of Secrets, the keys are kept for the tables
+fn redact_secrets(items: &mut Value) {
+  for item in items.as_array_mut().into_iter().flatten() {
+    for field in ["data", "stringData"] {
+      if let Some(Value::Object(data)) = item.get_mut(field) {
+        data
+          .values_mut()
+          .for_each(|value| *value = Value::String(String::new()));
+      }
+    }
+  }
+}
+
+/// a recording loaded for replay, the cluster at a position is the last list of every resource
+/// up to the record at that position
+pub struct Replay {
+  records: Vec<Record>,
+  position: usize,
+}
+
+impl Replay {
+  pub fn load(path: &Path) -> Result<Replay> {
+    let contents = fs::read_to_string(path)
+      .with_context(|| format!("Unable to read recording {}", path.display()))?;
+    Replay::parse(&contents).with_context(|| format!("Unable to replay {}", path.display()))
+  }
+
+  fn parse(contents: &str) -> Result<Replay> {
+    let records = contents
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .enumerate()
+      .map(|(i, line)| {
+        serde_json::from_str(line).with_context(|| format!("Invalid record on line {}", i + 1))
+      })
+      .collect::<Result<Vec<Record>>>()?;
+    if records.is_empty() {
+      return Err(anyhow!("The recording is empty"));
+    }
+    Ok(Replay {
+      records,
+      position: 0,
+    })
+  }
+
+  /// move by a number of records, returns whether the position changed
+  pub fn step(&mut self, records: isize) -> bool {
+    let position = self
+      .position
+      .saturating_add_signed(records)
+      .min(self.records.len() - 1);
+    let moved = position != self.position;
+    self.position = position;
+    moved
+  }
+
+  /// position and time of the current record, like `12/340 2022-11-02 14:03:07Z`
+  pub fn progress(&self) -> String {
+    format!(
+      "{}/{} {}",
+      self.position + 1,
+      self.records.len(),
+      self.records[self.position]
+        .time
+        .format("%Y-%m-%d %H:%M:%SZ")
+    )
+  }
+
+  /// the last items of every resource up to the position
+  fn latest(&self) -> BTreeMap<&str, &Value> {
+    self.records[..=self.position]
+      .iter()
+      .map(|record| (record.kind.as_str(), &record.items))
+      .collect()
+  }
+
+  /// show the cluster at the position in the tables. Ages are counted to now
+  pub fn apply(&self, app: &mut App) -> Result<()> {
+    let latest = self.latest();
+    for (kind, items) in &latest {
+      let data = &mut app.data;
+      match *kind {
+        "namespaces" => {
+          let items = convert::<Namespace, _>(items)?;
+          app.set_namespaces(items);
+        }
+        "pods" => set::<Pod, _>(&mut data.pods, items)?,
+        "services" => set::<Service, _>(&mut data.services, items)?,
+        "configmaps" => set::<ConfigMap, _>(&mut data.config_maps, items)?,
+        "statefulsets" => set::<StatefulSet, _>(&mut data.stateful_sets, items)?,
+        "replicasets" => set::<ReplicaSet, _>(&mut data.replica_sets, items)?,
+        "deployments" => set::<Deployment, _>(&mut data.deployments, items)?,
+        "jobs" => set::<Job, _>(&mut data.jobs, items)?,
+        "daemonsets" => set::<DaemonSet, _>(&mut data.daemon_sets, items)?,
+        "cronjobs" => set::<CronJob, _>(&mut data.cronjobs, items)?,
+        "secrets" => set::<Secret, _>(&mut data.secrets, items)?,
+        "replicationcontrollers" => set::<ReplicationController, _>(&mut data.rpl_ctrls, items)?,
+        "storageclasses" => set::<StorageClass, _>(&mut data.storage_classes, items)?,
+        "roles" => set::<Role, _>(&mut data.roles, items)?,
+        "rolebindings" => set::<RoleBinding, _>(&mut data.role_bindings, items)?,
+        "clusterroles" => set::<ClusterRole, _>(&mut data.cluster_roles, items)?,
+        "clusterrolebindings" => {
+          set::<ClusterRoleBinding, _>(&mut data.cluster_role_binding, items)?
+        }
+        "helmreleases" => data.set_helm_revisions(convert::<Secret, _>(items)?),
+        "nodes" => {
+          // the pods of the same time are counted on the nodes
+          let pods = ObjectList {
+            metadata: ListMeta::default(),
+            items: match latest.get("pods") {
+              Some(pods) => Vec::<Pod>::deserialize(*pods)?,
+              None => vec![],
+            },
+          };
+          let nodes = Vec::<Node>::deserialize(*items)?
+            .iter()
+            .map(|node| KubeNode::from_api_with_pods(node, Some(&pods), app))
+            .collect();
+          app.data.update_node_usage();
+          app.data.nodes.set_sorted_items(nodes);
+        }
+        // resources of newer versions are left out
+        _ => {}
+      }
+    }
+    Ok(())
+  }
+}
+
+fn convert<K: DeserializeOwned, T: From<K>>(items: &Value) -> Result<Vec<T>> {
+  Ok(
+    Vec::<K>::deserialize(items)?
+      .into_iter()
+      .map(T::from)
+      .collect(),
+  )
+}
+
+fn set<K: DeserializeOwned, T: From<K> + TableRow>(
+  table: &mut StatefulTable<T>,
+  items: &Value,
+) -> Result<()> {
+  table.set_sorted_items(convert::<K, T>(items)?);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+  use super::*;
+
+  fn pod(name: &str) -> Pod {
+    Pod {
+      metadata: ObjectMeta {
+        name: Some(name.into()),
+        namespace: Some("web".into()),
+        ..ObjectMeta::default()
+      },
+      ..Pod::default()
+    }
+  }
+
+  #[test]
+  fn test_replay() {
+    let path = env::temp_dir().join(format!("kdash-recording-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    record(&path, ActiveBlock::Pods, &[pod("api-0")]).unwrap();
+    record(&path, ActiveBlock::Services, &Vec::<Service>::new()).unwrap();
+    record(&path, ActiveBlock::Pods, &[pod("api-0"), pod("api-1")]).unwrap();
+    // views that aren't resource lists aren't recorded
+    record(&path, ActiveBlock::Logs, &[pod("api-2")]).unwrap();
+    let mut replay = Replay::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let mut app = App::default();
+    replay.apply(&mut app).unwrap();
+    assert_eq!(app.data.pods.items.len(), 1);
+    assert!(replay.progress().starts_with("1/3 "));
+
+    assert!(!replay.step(-1));
+    assert!(replay.step(5));
+    assert!(replay.progress().starts_with("3/3 "));
+    replay.apply(&mut app).unwrap();
+    assert_eq!(
+      app
+        .data
+        .pods
+        .items
+        .iter()
+        .map(|pod| pod.name.as_str())
+        .collect::<Vec<_>>(),
+      vec!["api-0", "api-1"]
+    );
+
+    // Secrets are recorded without their values
+    let secret: Secret = serde_json::from_str(
+      r#"{"metadata": {"name": "db"}, "data": {"password": "aHVudGVyMg=="}, "stringData": {"user": "admin"}}"#,
+    )
+    .unwrap();
+    record(&path, ActiveBlock::Secrets, &[secret]).unwrap();
+    let recorded = fs::read_to_string(&path).unwrap();
+    #[cfg(unix)]
+    assert_eq!(
+      std::os::unix::fs::PermissionsExt::mode(&fs::metadata(&path).unwrap().permissions()) & 0o777,
+      0o600
+    );
+    fs::remove_file(&path).unwrap();
+    assert!(recorded.contains(r#""data":{"password":""}"#));
+    assert!(recorded.contains(r#""stringData":{"user":""}"#));
+    replay = Replay::parse(&recorded).unwrap();
+    replay.apply(&mut app).unwrap();
+    assert_eq!(app.data.secrets.items[0].name, "db");
+
+    assert!(Replay::parse("\n").is_err());
+    assert!(Replay::parse("{\"kind\": \"pods\"}").is_err());
+  }
+}
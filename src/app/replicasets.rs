@@ -1,6 +1,11 @@
-use k8s_openapi::{api::apps::v1::ReplicaSet, chrono::Utc};
+use k8s_openapi::{
+  api::apps::v1::ReplicaSet, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, RowHealth, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeReplicaSet {
@@ -41,6 +46,28 @@ impl KubeResource<ReplicaSet> for KubeReplicaSet {
   }
 }
 
+impl TableRow for KubeReplicaSet {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn status_health(&self) -> RowHealth {
+    RowHealth::from_replicas(self.ready, self.desired)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Desired" => self.desired.to_string(),
+      "Current" => self.current.to_string(),
+      "Ready" => self.ready.to_string(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1,8 +1,13 @@
 use std::collections::BTreeMap;
 
-use k8s_openapi::{api::core::v1::ReplicationController, chrono::Utc};
+use k8s_openapi::{
+  api::core::v1::ReplicationController, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, RowHealth, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeReplicationController {
@@ -81,6 +86,31 @@ impl KubeResource<ReplicationController> for KubeReplicationController {
   }
 }
 
+impl TableRow for KubeReplicationController {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn status_health(&self) -> RowHealth {
+    RowHealth::from_replicas(self.ready, self.desired)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Desired" => self.desired.to_string(),
+      "Current" => self.current.to_string(),
+      "Ready" => self.ready.to_string(),
+      "Containers" => self.containers.to_owned(),
+      "Images" => self.images.to_owned(),
+      "Selector" => self.selector.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -0,0 +1,115 @@
+use super::{models::StatefulList, utils::fuzzy_score, ActiveBlock};
+
+/// resource tables searched by the finder with the kind shown next to matches
+pub const SEARCHED_BLOCKS: [(ActiveBlock, &str); 18] = [
+  (ActiveBlock::Pods, "Pod"),
+  (ActiveBlock::Services, "Service"),
+  (ActiveBlock::Nodes, "Node"),
+  (ActiveBlock::ConfigMaps, "ConfigMap"),
+  (ActiveBlock::StatefulSets, "StatefulSet"),
+  (ActiveBlock::ReplicaSets, "ReplicaSet"),
+  (ActiveBlock::Deployments, "Deployment"),
+  (ActiveBlock::Jobs, "Job"),
+  (ActiveBlock::DaemonSets, "DaemonSet"),
+  (ActiveBlock::CronJobs, "CronJob"),
+  (ActiveBlock::Secrets, "Secret"),
+  (ActiveBlock::RplCtrl, "ReplicationController"),
+  (ActiveBlock::StorageClasses, "StorageClass"),
+  (ActiveBlock::Roles, "Role"),
+  (ActiveBlock::RoleBindings, "RoleBinding"),
+  (ActiveBlock::ClusterRoles, "ClusterRole"),
+  (ActiveBlock::ClusterRoleBinding, "ClusterRoleBinding"),
+  (ActiveBlock::HelmReleases, "HelmRelease"),
+];
+
+const MAX_MATCHES: usize = 20;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FinderMatch {
+  pub block: ActiveBlock,
+  pub kind: &'static str,
+  pub namespace: String,
+  pub name: String,
+}
+
+/// fuzzy search over the names of all cached resources
+pub struct ResourceFinder {
+  pub input: String,
+  pub matches: StatefulList<FinderMatch>,
+}
+
+impl ResourceFinder {
+  pub fn new() -> Self {
+    ResourceFinder {
+      input: String::new(),
+      matches: StatefulList::with_items(vec![]),
+    }
+  }
+
+  /// keep the best matches for the input, nothing is listed until something is typed
+  pub fn update_matches(&mut self, candidates: Vec<FinderMatch>) {
+    let mut scored: Vec<(i64, FinderMatch)> = if self.input.is_empty() {
+      vec![]
+    } else {
+      candidates
+        .into_iter()
+        .filter_map(|c| fuzzy_score(&self.input, &c.name).map(|s| (s, c)))
+        .collect()
+    };
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    scored.truncate(MAX_MATCHES);
+    self.matches = StatefulList::with_items(scored.into_iter().map(|(_, m)| m).collect());
+  }
+
+  pub fn selected_match(&self) -> Option<&FinderMatch> {
+    self
+      .matches
+      .state
+      .selected()
+      .and_then(|i| self.matches.items.get(i))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn candidate(block: ActiveBlock, kind: &'static str, name: &str) -> FinderMatch {
+    FinderMatch {
+      block,
+      kind,
+      namespace: "default".into(),
+      name: name.into(),
+    }
+  }
+
+  #[test]
+  fn test_update_matches() {
+    let candidates = vec![
+      candidate(ActiveBlock::Pods, "Pod", "nginx-7d9f-abcde"),
+      candidate(ActiveBlock::Deployments, "Deployment", "nginx"),
+      candidate(ActiveBlock::Services, "Service", "redis"),
+    ];
+    let mut finder = ResourceFinder::new();
+
+    finder.update_matches(candidates.clone());
+    assert!(finder.matches.items.is_empty());
+    assert_eq!(finder.selected_match(), None);
+
+    finder.input = "ngx".into();
+    finder.update_matches(candidates.clone());
+    assert_eq!(
+      finder.matches.items,
+      vec![candidates[1].clone(), candidates[0].clone()]
+    );
+    assert_eq!(finder.selected_match(), Some(&candidates[1]));
+
+    finder.input = "rds".into();
+    finder.update_matches(candidates.clone());
+    assert_eq!(finder.matches.items, vec![candidates[2].clone()]);
+
+    finder.input = "mysql".into();
+    finder.update_matches(candidates);
+    assert!(finder.matches.items.is_empty());
+  }
+}
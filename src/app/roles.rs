@@ -1,9 +1,13 @@
 use k8s_openapi::{
   api::rbac::v1::{ClusterRole, ClusterRoleBinding, Role, RoleBinding},
+  apimachinery::pkg::apis::meta::v1::ObjectMeta,
   chrono::Utc,
 };
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeRole {
@@ -54,6 +58,21 @@ impl KubeResource<Role> for KubeRole {
   }
 }
 
+impl TableRow for KubeRole {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 impl From<ClusterRole> for KubeClusterRole {
   fn from(cluster_role: ClusterRole) -> Self {
     KubeClusterRole {
@@ -73,6 +92,20 @@ impl KubeResource<ClusterRole> for KubeClusterRole {
   }
 }
 
+impl TableRow for KubeClusterRole {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Name" => self.name.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 impl From<RoleBinding> for KubeRoleBinding {
   fn from(role_binding: RoleBinding) -> Self {
     KubeRoleBinding {
@@ -93,6 +126,22 @@ impl KubeResource<RoleBinding> for KubeRoleBinding {
   }
 }
 
+impl TableRow for KubeRoleBinding {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Role" => self.role.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 impl From<ClusterRoleBinding> for KubeClusterRoleBinding {
   fn from(crb: ClusterRoleBinding) -> Self {
     KubeClusterRoleBinding {
@@ -110,6 +159,21 @@ impl KubeResource<ClusterRoleBinding> for KubeClusterRoleBinding {
   }
 }
 
+impl TableRow for KubeClusterRoleBinding {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Name" => self.name.to_owned(),
+      "Role" => self.role.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use k8s_openapi::chrono::Utc;
@@ -1,8 +1,13 @@
 use std::collections::BTreeMap;
 
-use k8s_openapi::{api::core::v1::Secret, chrono::Utc, ByteString};
+use k8s_openapi::{
+  api::core::v1::Secret, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc, ByteString,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct KubeSecret {
@@ -32,6 +37,23 @@ impl KubeResource<Secret> for KubeSecret {
   }
 }
 
+impl TableRow for KubeSecret {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Type" => self.type_.to_owned(),
+      "Data" => self.data.len().to_string(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use k8s_openapi::chrono::Utc;
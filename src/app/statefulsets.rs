@@ -1,6 +1,11 @@
-use k8s_openapi::{api::apps::v1::StatefulSet, chrono::Utc};
+use k8s_openapi::{
+  api::apps::v1::StatefulSet, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, RowHealth, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeStatefulSet {
@@ -39,6 +44,27 @@ impl KubeResource<StatefulSet> for KubeStatefulSet {
   }
 }
 
+impl TableRow for KubeStatefulSet {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn status_health(&self) -> RowHealth {
+    RowHealth::from_ready(&self.ready)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Ready" => self.ready.to_owned(),
+      "Service" => self.service.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1,6 +1,11 @@
-use k8s_openapi::{api::storage::v1::StorageClass, chrono::Utc};
+use k8s_openapi::{
+  api::storage::v1::StorageClass, apimachinery::pkg::apis::meta::v1::ObjectMeta, chrono::Utc,
+};
 
-use super::{models::KubeResource, utils};
+use super::{
+  models::{KubeResource, TableRow},
+  utils,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct KubeStorageClass {
@@ -39,6 +44,24 @@ impl KubeResource<StorageClass> for KubeStorageClass {
   }
 }
 
+impl TableRow for KubeStorageClass {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Name" => self.name.to_owned(),
+      "Provisioner" => self.provisioner.to_owned(),
+      "Reclaim Policy" => self.reclaim_policy.to_owned(),
+      "Volume Binding Mode" => self.volume_binding_mode.to_owned(),
+      "Allow Volume Expansion" => self.allow_volume_expansion.to_string(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use k8s_openapi::chrono::Utc;
@@ -1,10 +1,11 @@
 use k8s_openapi::{
   api::core::v1::{Service, ServicePort},
+  apimachinery::pkg::apis::meta::v1::ObjectMeta,
   chrono::Utc,
 };
 
 use super::{
-  models::KubeResource,
+  models::{KubeResource, TableRow},
   utils::{self, UNKNOWN},
 };
 
@@ -64,12 +65,60 @@ impl From<Service> for KubeSvc {
   }
 }
 
+impl KubeSvc {
+  /// the address of a LoadBalancer service on its first port, https for port 443 or ports named
+  /// https
+  pub fn url(&self) -> Option<String> {
+    if self.type_ != "LoadBalancer" {
+      return None;
+    }
+    let host = self
+      .external_ip
+      .split(',')
+      .find(|host| !host.is_empty() && *host != "<pending>")?;
+    let port = self
+      .k8s_obj
+      .spec
+      .as_ref()
+      .and_then(|spec| spec.ports.as_ref())
+      .and_then(|ports| ports.first());
+    let url = match port {
+      Some(port) if port.port == 443 || port.name.as_deref() == Some("https") => match port.port {
+        443 => format!("https://{}", host),
+        number => format!("https://{}:{}", host, number),
+      },
+      Some(port) if port.port != 80 => format!("http://{}:{}", host, port.port),
+      _ => format!("http://{}", host),
+    };
+    Some(url)
+  }
+}
+
 impl KubeResource<Service> for KubeSvc {
   fn get_k8s_obj(&self) -> &Service {
     &self.k8s_obj
   }
 }
 
+impl TableRow for KubeSvc {
+  fn meta(&self) -> Option<&ObjectMeta> {
+    Some(&self.k8s_obj.metadata)
+  }
+
+  fn column_value(&self, column: &str) -> String {
+    match column {
+      "Namespace" => self.namespace.to_owned(),
+      "Name" => self.name.to_owned(),
+      "Type" => self.type_.to_owned(),
+      "Cluster IP" => self.cluster_ip.to_owned(),
+      "External IP" => self.external_ip.to_owned(),
+      "Ports" => self.ports.to_owned(),
+      "Age" => self.age.to_owned(),
+      _ => String::default(),
+    }
+  }
+}
+
 fn get_ports(s_ports: &Option<Vec<ServicePort>>) -> Option<String> {
   s_ports.as_ref().map(|ports| {
     ports
@@ -202,4 +251,22 @@ mod tests {
       }
     );
   }
+
+  #[test]
+  fn test_service_url() {
+    let (mut svcs, _): (Vec<KubeSvc>, Vec<_>) = convert_resource_from_file("svcs");
+    assert_eq!(svcs[0].url(), None);
+    assert_eq!(svcs[4].url(), Some("http://172.20.0.2".into()));
+
+    fn ports(svc: &mut KubeSvc) -> &mut Vec<ServicePort> {
+      svc.k8s_obj.spec.as_mut().unwrap().ports.as_mut().unwrap()
+    }
+    ports(&mut svcs[4]).remove(0);
+    assert_eq!(svcs[4].url(), Some("https://172.20.0.2".into()));
+    ports(&mut svcs[4])[0].port = 8443;
+    assert_eq!(svcs[4].url(), Some("https://172.20.0.2:8443".into()));
+
+    svcs[4].external_ip = "<pending>".into();
+    assert_eq!(svcs[4].url(), None);
+  }
 }
@@ -0,0 +1,417 @@
+use std::{
+  collections::BTreeMap,
+  fmt,
+  time::{Duration, Instant},
+};
+
+use regex::{Regex, RegexBuilder};
+
+use super::{models::StatefulList, ActiveBlock};
+
+/// filters kept in the history, older ones are dropped
+const MAX_FILTER_HISTORY: usize = 20;
+
+/// while typing the filter is applied at most this often, the last keys are applied on a tick
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// name filter typed for a resource table and applied while typing. The saved filters from the
+/// config and the filters used before, newest first, are listed below the input and recalled with
+/// up/down
+pub struct FilterInput {
+  pub input: String,
+  /// the filtered table
+  pub block: ActiveBlock,
+  /// filter of the table when the input was opened, restored on Esc
+  pub previous: String,
+  /// name and filter of the saved filters followed by the history, which has empty names
+  pub choices: StatefulList<(String, String)>,
+  /// when the input was last applied to the table
+  applied_at: Option<Instant>,
+  /// typed since it was last applied
+  pending: bool,
+}
+
+impl FilterInput {
+  pub fn new(
+    block: ActiveBlock,
+    filter: String,
+    saved: &[(String, String)],
+    history: &[String],
+  ) -> Self {
+    let mut choices = StatefulList::with_items(
+      saved
+        .iter()
+        .cloned()
+        .chain(history.iter().map(|f| (String::new(), f.clone())))
+        .collect(),
+    );
+    choices.state.select(None);
+    FilterInput {
+      input: filter.clone(),
+      block,
+      previous: filter,
+      choices,
+      applied_at: None,
+      pending: false,
+    }
+  }
+
+  /// whether the input typed at `now` is applied right away, otherwise it is held back until
+  /// `take_pending` so that filtering a long table doesn't slow down typing
+  pub fn typed(&mut self, now: Instant) -> bool {
+    self.pending = true;
+    self.take_pending(now)
+  }
+
+  /// whether input held back while typing is due to be applied
+  pub fn take_pending(&mut self, now: Instant) -> bool {
+    let due = self.pending
+      && self
+        .applied_at
+        .is_none_or(|at| now.duration_since(at) >= FILTER_DEBOUNCE);
+    if due {
+      self.pending = false;
+      self.applied_at = Some(now);
+    }
+    due
+  }
+
+  /// replace the input with the selected saved or previous filter
+  pub fn recall(&mut self) {
+    if let Some((_, filter)) = self
+      .choices
+      .state
+      .selected()
+      .and_then(|i| self.choices.items.get(i))
+    {
+      self.input = filter.clone();
+    }
+  }
+}
+
+/// a parsed table filter. Words are matched against item names, or hide the matching names when
+/// prefixed with `!` like `!kube-` or `!~^kube-`. `-l` takes a label selector like
+/// `app=web,tier!=cache` that the item labels must match, as in `api -l env=prod`. `-f` takes a
+/// field selector like `status.phase=Pending`. Both selectors are passed to the API when listing
+/// the resources
+#[derive(Default)]
+pub struct TableFilter {
+  name: Option<NameFilter>,
+  /// hide the names matching the name filter instead
+  exclude: bool,
+  labels: Vec<LabelRequirement>,
+  fields: Vec<String>,
+}
+
+impl TableFilter {
+  pub fn parse(filter: &str) -> Result<TableFilter, String> {
+    let mut words = vec![];
+    let mut labels = vec![];
+    let mut fields = vec![];
+    let mut iter = filter.split_whitespace();
+    while let Some(word) = iter.next() {
+      match word {
+        "-l" => {
+          let selector = iter.next().ok_or("missing label selector after -l")?;
+          labels.extend(parse_label_selector(selector)?);
+        }
+        "-f" => {
+          let selector = iter.next().ok_or("missing field selector after -f")?;
+          if !selector.contains('=') {
+            return Err(format!("invalid field selector '{}'", selector));
+          }
+          fields.push(selector.to_owned());
+        }
+        _ => words.push(word),
+      }
+    }
+    let words = words.join(" ");
+    let (exclude, name) = match words.strip_prefix('!') {
+      Some(name) => (true, name),
+      None => (false, words.as_str()),
+    };
+    Ok(TableFilter {
+      name: NameFilter::parse(name).map_err(|e| format!("invalid regex: {}", e))?,
+      exclude,
+      labels,
+      fields,
+    })
+  }
+
+  /// whether the filter lets every listed item through, field selectors are applied by the API
+  pub fn is_empty(&self) -> bool {
+    self.name.is_none() && self.labels.is_empty()
+  }
+
+  /// the field selectors joined for the list call
+  pub fn field_selector(&self) -> Option<String> {
+    if self.fields.is_empty() {
+      None
+    } else {
+      Some(self.fields.join(","))
+    }
+  }
+
+  /// the label selectors joined for the list call, the listed items are matched against them too
+  pub fn label_selector(&self) -> Option<String> {
+    if self.labels.is_empty() {
+      None
+    } else {
+      Some(
+        self
+          .labels
+          .iter()
+          .map(|l| l.to_string())
+          .collect::<Vec<_>>()
+          .join(","),
+      )
+    }
+  }
+
+  pub fn matches(&self, name: &str, labels: Option<&BTreeMap<String, String>>) -> bool {
+    self
+      .name
+      .as_ref()
+      .is_none_or(|n| n.matches(name) != self.exclude)
+      && self.labels.iter().all(|l| l.matches(labels))
+  }
+}
+
+/// item names case insensitively containing some text, or matching a regex when prefixed with `~`
+/// like `~^api-(foo|bar)-`
+enum NameFilter {
+  Contains(String),
+  Regex(Regex),
+}
+
+impl NameFilter {
+  fn parse(filter: &str) -> Result<Option<NameFilter>, regex::Error> {
+    if filter.is_empty() {
+      return Ok(None);
+    }
+    let name_filter = match filter.strip_prefix('~') {
+      Some(pattern) => {
+        NameFilter::Regex(RegexBuilder::new(pattern).case_insensitive(true).build()?)
+      }
+      None => NameFilter::Contains(filter.to_lowercase()),
+    };
+    Ok(Some(name_filter))
+  }
+
+  fn matches(&self, name: &str) -> bool {
+    match self {
+      NameFilter::Contains(text) => name.to_lowercase().contains(text),
+      NameFilter::Regex(regex) => regex.is_match(name),
+    }
+  }
+}
+
+/// one requirement of an equality based label selector
+#[derive(Debug, PartialEq, Eq)]
+enum LabelRequirement {
+  Equals(String, String),
+  NotEquals(String, String),
+  Exists(String),
+  NotExists(String),
+}
+
+impl LabelRequirement {
+  fn matches(&self, labels: Option<&BTreeMap<String, String>>) -> bool {
+    let value = |key: &String| labels.and_then(|l| l.get(key));
+    match self {
+      LabelRequirement::Equals(key, v) => value(key) == Some(v),
+      LabelRequirement::NotEquals(key, v) => value(key) != Some(v),
+      LabelRequirement::Exists(key) => value(key).is_some(),
+      LabelRequirement::NotExists(key) => value(key).is_none(),
+    }
+  }
+}
+
+impl fmt::Display for LabelRequirement {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LabelRequirement::Equals(key, value) => write!(f, "{}={}", key, value),
+      LabelRequirement::NotEquals(key, value) => write!(f, "{}!={}", key, value),
+      LabelRequirement::Exists(key) => write!(f, "{}", key),
+      LabelRequirement::NotExists(key) => write!(f, "!{}", key),
+    }
+  }
+}
+
+/// parse selectors like `app=web,tier!=cache,canary,!legacy`
+fn parse_label_selector(selector: &str) -> Result<Vec<LabelRequirement>, String> {
+  selector
+    .split(',')
+    .map(|requirement| {
+      let requirement = if let Some((key, value)) = requirement.split_once("!=") {
+        LabelRequirement::NotEquals(key.into(), value.into())
+      } else if let Some((key, value)) = requirement
+        .split_once("==")
+        .or_else(|| requirement.split_once('='))
+      {
+        LabelRequirement::Equals(key.into(), value.into())
+      } else if let Some(key) = requirement.strip_prefix('!') {
+        LabelRequirement::NotExists(key.into())
+      } else {
+        LabelRequirement::Exists(requirement.into())
+      };
+      match &requirement {
+        LabelRequirement::Equals(key, _)
+        | LabelRequirement::NotEquals(key, _)
+        | LabelRequirement::Exists(key)
+        | LabelRequirement::NotExists(key)
+          if key.is_empty() =>
+        {
+          Err(format!("invalid label selector '{}'", selector))
+        }
+        _ => Ok(requirement),
+      }
+    })
+    .collect()
+}
+
+/// move a used filter to the front of the history
+pub fn add_to_history(history: &mut Vec<String>, filter: &str) {
+  if filter.is_empty() {
+    return;
+  }
+  history.retain(|f| f != filter);
+  history.insert(0, filter.to_owned());
+  history.truncate(MAX_FILTER_HISTORY);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::app::models::Scrollable;
+
+  #[test]
+  fn test_filter_input_debounce() {
+    let now = Instant::now();
+    let mut input = FilterInput::new(ActiveBlock::Pods, String::new(), &[], &[]);
+    assert!(!input.take_pending(now));
+    assert!(input.typed(now));
+    assert!(!input.typed(now + Duration::from_millis(30)));
+    assert!(!input.take_pending(now + Duration::from_millis(60)));
+    assert!(input.take_pending(now + FILTER_DEBOUNCE));
+    assert!(!input.take_pending(now + FILTER_DEBOUNCE * 3));
+  }
+
+  #[test]
+  fn test_name_filter() {
+    assert!(TableFilter::parse("").unwrap().is_empty());
+    let contains = TableFilter::parse("API").unwrap();
+    assert!(contains.matches("my-api-1", None));
+    assert!(!contains.matches("web", None));
+
+    let regex = TableFilter::parse("~^api-(foo|bar)-").unwrap();
+    assert!(regex.matches("api-foo-1", None));
+    assert!(regex.matches("API-bar-2", None));
+    assert!(!regex.matches("api-baz-1", None));
+    assert!(!regex.matches("my-api-foo-1", None));
+
+    assert!(TableFilter::parse("~api-(").is_err());
+  }
+
+  #[test]
+  fn test_exclude_filter() {
+    let exclude = TableFilter::parse("!kube-").unwrap();
+    assert!(!exclude.matches("kube-proxy-x7k2p", None));
+    assert!(exclude.matches("api-1", None));
+    let exclude_regex = TableFilter::parse("!~^(kube|calico)-").unwrap();
+    assert!(!exclude_regex.matches("calico-node-1", None));
+    assert!(exclude_regex.matches("my-kube-app", None));
+    // a lone `!` excludes nothing
+    assert!(TableFilter::parse("!").unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_label_filter() {
+    assert_eq!(
+      parse_label_selector("app=web,tier!=cache,canary,!legacy,env==prod").unwrap(),
+      vec![
+        LabelRequirement::Equals("app".into(), "web".into()),
+        LabelRequirement::NotEquals("tier".into(), "cache".into()),
+        LabelRequirement::Exists("canary".into()),
+        LabelRequirement::NotExists("legacy".into()),
+        LabelRequirement::Equals("env".into(), "prod".into()),
+      ]
+    );
+    assert!(parse_label_selector("app=web,=x").is_err());
+    assert!(TableFilter::parse("api -l").is_err());
+
+    let labels = BTreeMap::from([
+      ("app".to_owned(), "web".to_owned()),
+      ("tier".to_owned(), "frontend".to_owned()),
+    ]);
+    let by_labels = TableFilter::parse("-l app=web,tier!=cache").unwrap();
+    assert!(by_labels.matches("web-1", Some(&labels)));
+    assert!(!by_labels.matches("web-1", None));
+    let by_name_and_labels = TableFilter::parse("db -l app=web").unwrap();
+    assert!(!by_name_and_labels.matches("web-1", Some(&labels)));
+    assert!(by_name_and_labels.matches("db-1", Some(&labels)));
+    assert!(TableFilter::parse("-l !app").unwrap().matches("x", None));
+
+    let selector = TableFilter::parse("api -l app==web,!legacy -l canary").unwrap();
+    assert_eq!(
+      selector.label_selector(),
+      Some("app=web,!legacy,canary".into())
+    );
+    assert!(TableFilter::parse("api")
+      .unwrap()
+      .label_selector()
+      .is_none());
+  }
+
+  #[test]
+  fn test_field_filter() {
+    let filter = TableFilter::parse("api -f status.phase=Pending -f spec.nodeName=node-7").unwrap();
+    assert_eq!(
+      filter.field_selector(),
+      Some("status.phase=Pending,spec.nodeName=node-7".into())
+    );
+    assert!(!filter.is_empty());
+    // field selectors alone are applied by the API, not to the listed items
+    let fields_only = TableFilter::parse("-f status.phase=Pending").unwrap();
+    assert!(fields_only.is_empty());
+    assert!(TableFilter::parse("").unwrap().field_selector().is_none());
+    assert!(TableFilter::parse("-f status.phase").is_err());
+    assert!(TableFilter::parse("-f").is_err());
+  }
+
+  #[test]
+  fn test_add_to_history() {
+    let mut history = vec![];
+    add_to_history(&mut history, "api");
+    add_to_history(&mut history, "");
+    add_to_history(&mut history, "web");
+    add_to_history(&mut history, "api");
+    assert_eq!(history, vec!["api".to_owned(), "web".to_owned()]);
+
+    for i in 0..30 {
+      add_to_history(&mut history, &i.to_string());
+    }
+    assert_eq!(history.len(), MAX_FILTER_HISTORY);
+    assert_eq!(history[0], "29");
+  }
+
+  #[test]
+  fn test_filter_input_recall() {
+    let mut filter = FilterInput::new(
+      ActiveBlock::Pods,
+      "ng".into(),
+      &[("prod-api".into(), "api-prod".into())],
+      &["api".into(), "web".into()],
+    );
+    filter.recall();
+    assert_eq!(filter.input, "ng");
+    filter.choices.scroll_up(1);
+    filter.recall();
+    assert_eq!(filter.input, "api-prod");
+    filter.choices.scroll_down(1);
+    filter.choices.scroll_down(1);
+    filter.recall();
+    assert_eq!(filter.input, "web");
+    assert_eq!(filter.previous, "ng");
+  }
+}
@@ -1,9 +1,11 @@
+use std::{cmp::Ordering, str::FromStr};
+
 use k8s_openapi::{
   apimachinery::pkg::apis::meta::v1::Time,
   chrono::{DateTime, Duration, Utc},
 };
-
 use kube::{Resource, ResourceExt};
+use kubectl_view_allocations::qty::Qty;
 
 pub fn sanitize_obj<K: Resource>(mut obj: K) -> K {
   obj.managed_fields_mut().clear();
@@ -36,6 +38,16 @@ pub fn to_age_secs(timestamp: Option<&Time>, against: DateTime<Utc>) -> String {
   }
 }
 
+/// resources younger than this show their age with seconds
+const FRESH_AGE_MINUTES: i64 = 5;
+
+/// age of a resource created at `time`, computed when drawn so it keeps counting between polls.
+/// Clock skew can put `time` slightly in the future, which counts as `0s`
+pub fn live_age(time: DateTime<Utc>, against: DateTime<Utc>) -> String {
+  let duration = against.signed_duration_since(time).max(Duration::zero());
+  duration_to_age(duration, duration < Duration::minutes(FRESH_AGE_MINUTES))
+}
+
 pub fn duration_to_age(duration: Duration, with_secs: bool) -> String {
   let mut out = String::new();
   if duration.num_weeks() != 0 {
@@ -112,10 +124,111 @@ pub fn to_percent(used: f64, total: f64) -> f64 {
   ((used / total) * 100f64).floor()
 }
 
+/// human readable size in Mi or Gi
+pub fn bytes_to_human(bytes: u64) -> String {
+  let mi = bytes as f64 / (1024f64 * 1024f64);
+  if mi < 1024f64 {
+    format!("{:.0}Mi", mi)
+  } else {
+    format!("{:.1}Gi", mi / 1024f64)
+  }
+}
+
 pub fn convert_to_f64(s: &str) -> f64 {
   s.parse().unwrap_or(0f64)
 }
 
+/// columns holding ages or durations like `5h10m`
+const AGE_COLUMNS: [&str; 3] = ["Age", "Duration", "Last Scheduled"];
+
+/// compare cell values of a table column, ages, ratios and quantities are compared by their
+/// value and sorted before anything else, which is compared as case insensitive text. Only values
+/// of age columns are read as ages, elsewhere `250m` is a quantity
+pub fn compare_values(column: &str, a: &str, b: &str) -> Ordering {
+  let age = AGE_COLUMNS.contains(&column);
+  match (to_sort_number(a, age), to_sort_number(b, age)) {
+    (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    (Some(_), None) => Ordering::Less,
+    (None, Some(_)) => Ordering::Greater,
+    (None, None) => a.to_lowercase().cmp(&b.to_lowercase()),
+  }
+}
+
+fn to_sort_number(value: &str, age: bool) -> Option<f64> {
+  if age {
+    if let Some(secs) = age_to_secs(value) {
+      return Some(secs);
+    }
+  }
+  if let Some((done, total)) = value.split_once('/') {
+    let done = done.parse::<f64>().ok()?;
+    let total = total.parse::<f64>().ok()?;
+    return Some(if total > 0f64 { done / total } else { 0f64 });
+  }
+  Qty::from_str(value).ok().map(|q| f64::from(&q))
+}
+
+/// the reverse of `duration_to_age`, for ages like `1w2d` or `5h10m`
+fn age_to_secs(age: &str) -> Option<f64> {
+  let mut secs = 0i64;
+  let mut num = String::new();
+  for c in age.chars() {
+    if c.is_ascii_digit() {
+      num.push(c);
+      continue;
+    }
+    let unit = match c {
+      'w' => 604800,
+      'd' => 86400,
+      'h' => 3600,
+      'm' => 60,
+      's' => 1,
+      _ => return None,
+    };
+    secs += num.parse::<i64>().ok()? * unit;
+    num.clear();
+  }
+  if age.is_empty() || !num.is_empty() {
+    None
+  } else {
+    Some(secs as f64)
+  }
+}
+
+/// fuzzy match the pattern against the candidate, all pattern characters have to be found in
+/// order (ignoring case). Matches at the start and consecutive matches score higher, so do
+/// shorter candidates
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+  let candidate = candidate.to_lowercase();
+  let mut chars = candidate.chars().enumerate();
+  let mut last: Option<usize> = None;
+  let mut score = 0i64;
+  for p in pattern.to_lowercase().chars() {
+    let (i, _) = chars.by_ref().find(|(_, c)| *c == p)?;
+    score += match last {
+      Some(l) if l + 1 == i => 5,
+      None if i == 0 => 10,
+      _ => 1,
+    };
+    last = Some(i);
+  }
+  Some(score * 100 - candidate.chars().count() as i64)
+}
+
+/// vim style position of a scrolled view, `All` when everything fits, `Top`, `Bot` or how far
+/// down the view is scrolled in percent
+pub fn scroll_position(offset: usize, max_offset: usize) -> String {
+  if max_offset == 0 {
+    "All".into()
+  } else if offset == 0 {
+    "Top".into()
+  } else if offset >= max_offset {
+    "Bot".into()
+  } else {
+    format!("{}%", offset * 100 / max_offset)
+  }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
@@ -133,6 +246,61 @@ mod tests {
     assert_eq!(mem_to_mi(String::from("5")), String::from("5"));
   }
   #[test]
+  fn test_bytes_to_human() {
+    use super::bytes_to_human;
+    assert_eq!(bytes_to_human(0), String::from("0Mi"));
+    assert_eq!(bytes_to_human(512 * 1024 * 1024), String::from("512Mi"));
+    assert_eq!(
+      bytes_to_human(3 * 1024 * 1024 * 1024 / 2),
+      String::from("1.5Gi")
+    );
+  }
+  #[test]
+  fn test_compare_values() {
+    use std::cmp::Ordering;
+
+    use super::compare_values;
+
+    assert_eq!(compare_values("Age", "5h10m", "1d"), Ordering::Less);
+    assert_eq!(compare_values("Age", "1w2d", "6d"), Ordering::Greater);
+    assert_eq!(compare_values("Duration", "45s", "0m"), Ordering::Greater);
+    assert_eq!(compare_values("Mem", "590Mi", "2Gi"), Ordering::Less);
+    // millicores aren't minutes outside of the age columns
+    assert_eq!(compare_values("CPU", "250m", "1"), Ordering::Less);
+    assert_eq!(compare_values("Age", "250m", "1h"), Ordering::Greater);
+    assert_eq!(compare_values("Restarts", "9", "10"), Ordering::Less);
+    assert_eq!(compare_values("Ready", "0/2", "1/1"), Ordering::Less);
+    assert_eq!(compare_values("CPU", "10", "-"), Ordering::Less);
+    assert_eq!(
+      compare_values("Status", "Pending", "running"),
+      Ordering::Less
+    );
+    assert_eq!(compare_values("Name", "abc", "ABC"), Ordering::Equal);
+  }
+  #[test]
+  fn test_scroll_position() {
+    use super::scroll_position;
+
+    assert_eq!(scroll_position(0, 0), "All");
+    assert_eq!(scroll_position(0, 40), "Top");
+    assert_eq!(scroll_position(10, 40), "25%");
+    assert_eq!(scroll_position(40, 40), "Bot");
+    assert_eq!(scroll_position(45, 40), "Bot");
+  }
+
+  #[test]
+  fn test_fuzzy_score() {
+    use super::fuzzy_score;
+
+    assert_eq!(fuzzy_score("dpl", "deployments"), Some(1589));
+    assert_eq!(fuzzy_score("xyz", "deployments"), None);
+    assert_eq!(fuzzy_score("ld", "deployments"), None);
+    assert_eq!(fuzzy_score("", "pods"), Some(-4));
+    assert!(fuzzy_score("DEP", "deployments") > fuzzy_score("dep", "nodes-dep"));
+    assert!(fuzzy_score("po", "pods") > fuzzy_score("po", "replication-pods"));
+    assert!(fuzzy_score("ns", "namespace") > fuzzy_score("ns", "namespaces"));
+  }
+  #[test]
   fn test_to_cpu_percent() {
     use super::to_cpu_percent;
     assert_eq!(
@@ -292,6 +460,21 @@ mod tests {
       String::from("2676w14h")
     );
   }
+  #[test]
+  fn test_live_age() {
+    use k8s_openapi::chrono::Duration;
+
+    use super::live_age;
+
+    let now = Utc::now();
+    assert_eq!(live_age(now - Duration::seconds(42), now), "42s");
+    assert_eq!(live_age(now - Duration::seconds(190), now), "3m10s");
+    assert_eq!(live_age(now - Duration::minutes(7), now), "7m");
+    assert_eq!(live_age(now - Duration::hours(26), now), "1d2h");
+    // creation times in the future from clock skew
+    assert_eq!(live_age(now + Duration::seconds(3), now), "0s");
+  }
+
   #[test]
   fn test_to_age() {
     use std::time::SystemTime;
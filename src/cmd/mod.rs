@@ -1,4 +1,12 @@
-use std::sync::Arc;
+use std::{
+  ffi::OsStr,
+  fs,
+  io::{self, Write},
+  path::{Path, PathBuf},
+  process,
+  sync::Arc,
+  time::Duration,
+};
 
 use anyhow::anyhow;
 use duct::cmd;
@@ -6,7 +14,16 @@ use regex::Regex;
 use serde_json::Value as JValue;
 use tokio::sync::Mutex;
 
-use crate::app::{self, models::ScrollableTxt, App, Cli};
+use crate::{
+  app::{
+    self,
+    models::{ManifestFormat, ScrollableTxt},
+    notifications::NotificationLevel,
+    plugins::PluginRun,
+    App, Cli,
+  },
+  kubeconfig::tls_options,
+};
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum IoCmdEvent {
@@ -16,6 +33,87 @@ pub enum IoCmdEvent {
     value: String,
     ns: Option<String>,
   },
+  /// describe a resource in the split pane, leaving the describe view as it is
+  DescribeInSplitPane {
+    kind: String,
+    value: String,
+    ns: Option<String>,
+  },
+  /// write the manifest of a resource to a file
+  ExportManifest {
+    kind: String,
+    value: String,
+    ns: Option<String>,
+    format: ManifestFormat,
+    managed_fields: bool,
+    path: PathBuf,
+  },
+  /// run a shell command with the manifest of a resource as input, its output is shown in the
+  /// describe view
+  PipeManifest {
+    command: String,
+    manifest: String,
+  },
+  /// run a diff command on the last applied and the live manifest of a resource, its output is
+  /// shown in the describe view
+  DiffLastApplied {
+    command: String,
+    last_applied: String,
+    live: String,
+  },
+  /// run a diff command on the manifests two revisions of a Helm release rendered, its output is
+  /// shown in the describe view
+  DiffHelmRevisions {
+    command: String,
+    name: String,
+    old_revision: u32,
+    old: String,
+    new_revision: u32,
+    new: String,
+  },
+  /// run a plugin with its output captured, shown in the plugin popup
+  RunPlugin(PluginRun),
+  /// open a URL with the default browser
+  OpenUrl(String),
+  /// roll a Helm release back to a revision with the helm CLI
+  HelmRollback {
+    name: String,
+    namespace: String,
+    revision: u32,
+    context: Option<String>,
+  },
+  /// uninstall a Helm release with the helm CLI, `deleted` are the objects of its manifest
+  /// reported as deleted
+  HelmUninstall {
+    name: String,
+    namespace: String,
+    keep_history: bool,
+    context: Option<String>,
+    deleted: Vec<String>,
+  },
+  /// write a resource table to a CSV file
+  ExportTable {
+    title: String,
+    csv: String,
+    path: PathBuf,
+  },
+}
+
+impl IoCmdEvent {
+  /// whether the command changes the cluster, those are refused in read-only mode. Plugins and
+  /// the pipe and diff commands run arbitrary commands so they count as changing it, unless a
+  /// plugin is marked read-only safe
+  pub fn is_mutating(&self) -> bool {
+    match self {
+      IoCmdEvent::HelmRollback { .. }
+      | IoCmdEvent::HelmUninstall { .. }
+      | IoCmdEvent::PipeManifest { .. }
+      | IoCmdEvent::DiffLastApplied { .. }
+      | IoCmdEvent::DiffHelmRevisions { .. } => true,
+      IoCmdEvent::RunPlugin(run) => !run.read_only_safe,
+      _ => false,
+    }
+  }
 }
 
 #[derive(Clone)]
@@ -25,6 +123,13 @@ pub struct CmdRunner<'a> {
 
 static NOT_FOUND: &str = "Not found";
 
+/// how long a plugin with its output in a popup may run
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(60);
+/// how long the pipe and diff commands from the config may run, commands run one at a time
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+/// how long opening a URL may take, the opener hands it to the browser and exits
+const OPENER_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl<'a> CmdRunner<'a> {
   pub fn new(app: &'a Arc<Mutex<App>>) -> Self {
     CmdRunner { app }
@@ -36,12 +141,94 @@ impl<'a> CmdRunner<'a> {
         self.get_cli_info().await;
       }
       IoCmdEvent::GetDescribe { kind, value, ns } => {
-        self.get_describe(kind, value, ns).await;
+        self.get_describe(kind, value, ns, false).await;
+      }
+      IoCmdEvent::DescribeInSplitPane { kind, value, ns } => {
+        self.get_describe(kind, value, ns, true).await;
+      }
+      IoCmdEvent::ExportManifest {
+        kind,
+        value,
+        ns,
+        format,
+        managed_fields,
+        path,
+      } => {
+        self
+          .export_manifest(kind, value, ns, format, managed_fields, path)
+          .await;
+      }
+      IoCmdEvent::OpenUrl(url) => {
+        self.open_url(url).await;
+      }
+      IoCmdEvent::HelmRollback {
+        name,
+        namespace,
+        revision,
+        context,
+      } => {
+        self.helm_rollback(name, namespace, revision, context).await;
+      }
+      IoCmdEvent::HelmUninstall {
+        name,
+        namespace,
+        keep_history,
+        context,
+        deleted,
+      } => {
+        self
+          .helm_uninstall(name, namespace, keep_history, context, deleted)
+          .await;
+      }
+      IoCmdEvent::RunPlugin(run) => {
+        self.run_plugin(run).await;
+      }
+      IoCmdEvent::PipeManifest { command, manifest } => {
+        self.pipe_manifest(command, manifest).await;
+      }
+      IoCmdEvent::DiffLastApplied {
+        command,
+        last_applied,
+        live,
+      } => {
+        self.diff_last_applied(command, last_applied, live).await;
+      }
+      IoCmdEvent::DiffHelmRevisions {
+        command,
+        name,
+        old_revision,
+        old,
+        new_revision,
+        new,
+      } => {
+        self
+          .diff_manifests(
+            command,
+            (format!("{}-v{}.yaml", name, old_revision), old),
+            (format!("{}-v{}.yaml", name, new_revision), new),
+            "No differences between the manifests of the revisions",
+          )
+          .await;
+      }
+      IoCmdEvent::ExportTable { title, csv, path } => {
+        let result = write_file(&path, csv);
+        let mut app = self.app.lock().await;
+        match result {
+          Ok(()) => app.notify(
+            NotificationLevel::Success,
+            format!("Saved {} to {}", title, path.display()),
+          ),
+          Err(e) => app.notify(
+            NotificationLevel::Failure,
+            format!("Unable to write {}: {}", path.display(), e),
+          ),
+        }
       }
     };
 
     let mut app = self.app.lock().await;
     app.is_loading = false;
+    app.redraw = true;
   }
 
   async fn handle_error(&self, e: anyhow::Error) {
@@ -52,10 +239,9 @@ impl<'a> CmdRunner<'a> {
   async fn get_cli_info(&self) {
     let mut clis: Vec<Cli> = vec![];
 
-    let (version_c, version_s) = match cmd!("kubectl", "version", "-o", "json")
-      .stderr_null()
-      .read()
-    {
+    let mut args = vec!["version".to_owned(), "-o".into(), "json".into()];
+    args.extend(tls_options().kubectl_args());
+    let (version_c, version_s) = match duct::cmd("kubectl", &args).stderr_null().read() {
       Ok(out) => {
         let v: serde_json::Result<JValue> = serde_json::from_str(&out);
         match v {
@@ -123,20 +309,25 @@ impl<'a> CmdRunner<'a> {
   }
 
   // TODO temp solution, should build this from API response
-  async fn get_describe(&self, kind: String, value: String, ns: Option<String>) {
-    let mut args = vec!["describe", kind.as_str(), value.as_str()];
+  async fn get_describe(&self, kind: String, value: String, ns: Option<String>, split_pane: bool) {
+    let mut args = vec!["describe".to_owned(), kind.clone(), value];
 
-    if let Some(ns) = ns.as_ref() {
-      args.push("-n");
-      args.push(ns.as_str());
+    if let Some(ns) = ns {
+      args.push("-n".into());
+      args.push(ns);
     }
+    args.extend(tls_options().kubectl_args());
 
     let out = duct::cmd("kubectl", &args).stderr_null().read();
 
     match out {
       Ok(out) => {
         let mut app = self.app.lock().await;
-        app.data.describe_out = ScrollableTxt::with_string(out);
+        if split_pane {
+          app.data.split_pane_out = ScrollableTxt::with_string(out);
+        } else {
+          app.data.describe_out = ScrollableTxt::with_string(out);
+        }
       }
       Err(e) => {
         self
@@ -148,10 +339,353 @@ impl<'a> CmdRunner<'a> {
       }
     }
   }
+
+  async fn open_url(&self, url: String) {
+    #[cfg(target_os = "macos")]
+    let opener = duct::cmd("open", [&url]);
+    #[cfg(target_os = "windows")]
+    let opener = duct::cmd("cmd", ["/C", "start", "", &url]);
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let opener = duct::cmd("xdg-open", [&url]);
+
+    let result = match opener.stdout_null().stderr_null().unchecked().start() {
+      Ok(handle) => wait_with_timeout(Arc::new(handle), OPENER_TIMEOUT).await,
+      Err(e) => Err(e),
+    };
+    let mut app = self.app.lock().await;
+    match result {
+      Ok(out) if out.status.success() => {
+        app.notify(NotificationLevel::Success, format!("Opened {}", url))
+      }
+      _ => app.notify(
+        NotificationLevel::Failure,
+        format!("Unable to open {} in a browser", url),
+      ),
+    }
+  }
+
+  async fn helm_rollback(
+    &self,
+    name: String,
+    namespace: String,
+    revision: u32,
+    context: Option<String>,
+  ) {
+    let mut args = vec![
+      "rollback".to_owned(),
+      name.clone(),
+      revision.to_string(),
+      "-n".into(),
+      namespace.clone(),
+    ];
+    if let Some(context) = context {
+      args.push("--kube-context".into());
+      args.push(context);
+    }
+    let result = duct::cmd("helm", &args)
+      .stderr_to_stdout()
+      .stdout_capture()
+      .unchecked()
+      .run();
+
+    let mut app = self.app.lock().await;
+    match result {
+      Ok(out) if out.status.success() => app.notify(
+        NotificationLevel::Success,
+        format!(
+          "Rolled back {}/{} to revision {}",
+          namespace, name, revision
+        ),
+      ),
+      Ok(out) => app.notify(
+        NotificationLevel::Failure,
+        format!(
+          "Rollback of {}/{} failed: {}",
+          namespace,
+          name,
+          String::from_utf8_lossy(&out.stdout).trim()
+        ),
+      ),
+      Err(e) => app.notify(
+        NotificationLevel::Failure,
+        format!(
+          "Unable to run helm rollback. Make sure you have helm installed: {}",
+          e
+        ),
+      ),
+    }
+  }
+
+  async fn helm_uninstall(
+    &self,
+    name: String,
+    namespace: String,
+    keep_history: bool,
+    context: Option<String>,
+    deleted: Vec<String>,
+  ) {
+    let mut args = vec![
+      "uninstall".to_owned(),
+      name.clone(),
+      "-n".into(),
+      namespace.clone(),
+    ];
+    if keep_history {
+      args.push("--keep-history".into());
+    }
+    if let Some(context) = context {
+      args.push("--kube-context".into());
+      args.push(context);
+    }
+    let result = duct::cmd("helm", &args)
+      .stderr_to_stdout()
+      .stdout_capture()
+      .unchecked()
+      .run();
+
+    let mut app = self.app.lock().await;
+    match result {
+      Ok(out) if out.status.success() => app.notify(
+        NotificationLevel::Success,
+        uninstall_report(&namespace, &name, keep_history, &deleted),
+      ),
+      Ok(out) => app.notify(
+        NotificationLevel::Failure,
+        format!(
+          "Uninstall of {}/{} failed: {}",
+          namespace,
+          name,
+          String::from_utf8_lossy(&out.stdout).trim()
+        ),
+      ),
+      Err(e) => app.notify(
+        NotificationLevel::Failure,
+        format!(
+          "Unable to run helm uninstall. Make sure you have helm installed: {}",
+          e
+        ),
+      ),
+    }
+  }
+
+  /// the command is killed when it runs longer than `COMMAND_TIMEOUT`, like when it waits for
+  /// a terminal
+  async fn pipe_manifest(&self, command: String, manifest: String) {
+    let result = match duct::cmd("sh", ["-c", command.as_str()])
+      .stdin_bytes(manifest)
+      .stderr_to_stdout()
+      .stdout_capture()
+      .unchecked()
+      .start()
+    {
+      Ok(handle) => wait_with_timeout(Arc::new(handle), COMMAND_TIMEOUT).await,
+      Err(e) => Err(e),
+    };
+
+    let mut app = self.app.lock().await;
+    match result {
+      Ok(out) => {
+        if !out.status.success() {
+          app.notify(
+            NotificationLevel::Failure,
+            format!("`{}` failed with {}", command, out.status),
+          );
+        }
+        app.data.describe_out =
+          ScrollableTxt::with_string(String::from_utf8_lossy(&out.stdout).into_owned());
+      }
+      Err(e) => app.notify(
+        NotificationLevel::Failure,
+        format!("Unable to run `{}`: {}", command, e),
+      ),
+    }
+  }
+
+  /// run a plugin with its output captured, it is killed when it runs longer than
+  /// `PLUGIN_TIMEOUT`
+  async fn run_plugin(&self, run: PluginRun) {
+    let mut command = duct::cmd("sh", ["-c", run.command.as_str()]);
+    for (var, value) in &run.env {
+      command = command.env(var, value);
+    }
+    let result = match command
+      .stdin_null()
+      .stderr_to_stdout()
+      .stdout_capture()
+      .unchecked()
+      .start()
+    {
+      Ok(handle) => wait_with_timeout(Arc::new(handle), PLUGIN_TIMEOUT).await,
+      Err(e) => Err(e),
+    };
+
+    let mut app = self.app.lock().await;
+    match result {
+      Ok(out) => {
+        if !out.status.success() {
+          app.notify(
+            NotificationLevel::Failure,
+            format!("Plugin {} failed with {}", run.name, out.status),
+          );
+        }
+        let output = strip_ansi(&String::from_utf8_lossy(&out.stdout));
+        app.plugin_output = Some((run.name, ScrollableTxt::with_string(output)));
+      }
+      Err(e) => {
+        app.plugin_output = None;
+        app.notify(
+          NotificationLevel::Failure,
+          format!("Unable to run plugin {}: {}", run.name, e),
+        );
+      }
+    }
+  }
+
+  async fn diff_last_applied(&self, command: String, last_applied: String, live: String) {
+    self
+      .diff_manifests(
+        command,
+        ("last-applied.yaml".into(), last_applied),
+        ("live.yaml".into(), live),
+        "No differences to the last applied configuration",
+      )
+      .await;
+  }
+
+  /// the manifests are written to files, named as given, that are passed to the command as its
+  /// last two arguments. Like `diff` the command may exit with 1 when they differ
+  async fn diff_manifests(
+    &self,
+    command: String,
+    (old_file, old_manifest): (String, String),
+    (new_file, new_manifest): (String, String),
+    no_differences: &str,
+  ) {
+    // a new directory only the user can read, removed when dropped after the command is done
+    let started = tempfile::Builder::new()
+      .prefix("kdash-diff-")
+      .tempdir()
+      .and_then(|dir| {
+        let (old, new) = (dir.path().join(old_file), dir.path().join(new_file));
+        fs::write(&old, old_manifest)?;
+        fs::write(&new, new_manifest)?;
+        let script = format!("{} \"$1\" \"$2\"", command);
+        let args = [
+          OsStr::new("-c"),
+          OsStr::new(&script),
+          OsStr::new("sh"),
+          old.as_os_str(),
+          new.as_os_str(),
+        ];
+        let handle = duct::cmd("sh", args)
+          .stderr_to_stdout()
+          .stdout_capture()
+          .unchecked()
+          .start()?;
+        Ok((dir, handle))
+      });
+    let result = match started {
+      Ok((_dir, handle)) => wait_with_timeout(Arc::new(handle), COMMAND_TIMEOUT).await,
+      Err(e) => Err(e),
+    };
+
+    let mut app = self.app.lock().await;
+    match result {
+      Ok(out) => {
+        if !matches!(out.status.code(), Some(0 | 1)) {
+          app.notify(
+            NotificationLevel::Failure,
+            format!("`{}` failed with {}", command, out.status),
+          );
+        }
+        let diff = strip_ansi(&String::from_utf8_lossy(&out.stdout));
+        app.data.describe_out = ScrollableTxt::with_string(if diff.trim().is_empty() {
+          no_differences.into()
+        } else {
+          diff
+        });
+      }
+      Err(e) => app.notify(
+        NotificationLevel::Failure,
+        format!("Unable to run `{}`: {}", command, e),
+      ),
+    }
+  }
+
+  async fn export_manifest(
+    &self,
+    kind: String,
+    value: String,
+    ns: Option<String>,
+    format: ManifestFormat,
+    managed_fields: bool,
+    path: PathBuf,
+  ) {
+    let mut args = vec![
+      "get".to_owned(),
+      kind.clone(),
+      value.clone(),
+      "-o".into(),
+      format.extension().into(),
+    ];
+    if let Some(ns) = ns {
+      args.push("-n".into());
+      args.push(ns);
+    }
+    if managed_fields {
+      args.push("--show-managed-fields".into());
+    }
+    if let Some(context) = self.app.lock().await.data.selected.context.clone() {
+      args.push("--context".into());
+      args.push(context);
+    }
+    args.extend(tls_options().kubectl_args());
+
+    let result = duct::cmd("kubectl", &args)
+      .stdout_capture()
+      .stderr_capture()
+      .unchecked()
+      .run()
+      .map_err(|e| anyhow!("Unable to run kubectl: {}", e))
+      .and_then(|out| {
+        if !out.status.success() {
+          return Err(anyhow!(
+            "Unable to get {} {}: {}",
+            kind,
+            value,
+            String::from_utf8_lossy(&out.stderr).trim()
+          ));
+        }
+        write_file(&path, out.stdout)
+          .map_err(|e| anyhow!("Unable to write {}: {}", path.display(), e))
+      });
+
+    let mut app = self.app.lock().await;
+    match result {
+      Ok(()) => app.notify(
+        NotificationLevel::Success,
+        format!("Saved {} {} to {}", kind, value, path.display()),
+      ),
+      Err(e) => app.notify(NotificationLevel::Failure, e.to_string()),
+    }
+  }
 }
 
 // utils
 
+/// write a saved file, creating the directories of a templated path
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  let mut options = fs::OpenOptions::new();
+  options.create(true).write(true).truncate(true);
+  // manifests of Secrets are saved with their values
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+  options.open(path)?.write_all(contents.as_ref())
+}
+
 fn build_cli(name: &str, version: Option<String>) -> app::Cli {
   app::Cli {
     name: name.to_owned(),
@@ -160,6 +694,26 @@ fn build_cli(name: &str, version: Option<String>) -> app::Cli {
   }
 }
 
+/// the text without terminal escape sequences, like the colors of `delta` or `difft`
+fn strip_ansi(text: &str) -> String {
+  Regex::new(r"\x1b(\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(\x07|\x1b\\))")
+    .unwrap()
+    .replace_all(text, "")
+    .into_owned()
+}
+
+/// like `Uninstalled web/frontend, deleted Deployment/frontend, Service/frontend`
+fn uninstall_report(namespace: &str, name: &str, keep_history: bool, deleted: &[String]) -> String {
+  let mut report = format!("Uninstalled {}/{}", namespace, name);
+  if keep_history {
+    report.push_str(" keeping its history");
+  }
+  if !deleted.is_empty() {
+    report.push_str(&format!(", deleted {}", deleted.join(", ")));
+  }
+  report
+}
+
 /// execute a command and get info from it using regex
 fn get_info_by_regex(command: &str, args: &[&str], regex: &str) -> Option<String> {
   match cmd(command, args).stderr_null().read() {
@@ -174,6 +728,27 @@ fn get_info_by_regex(command: &str, args: &[&str], regex: &str) -> Option<String
   }
 }
 
+/// wait for a command off the async threads, killing it when it takes longer than the timeout
+async fn wait_with_timeout(
+  handle: Arc<duct::Handle>,
+  timeout: Duration,
+) -> io::Result<process::Output> {
+  let waiting = {
+    let handle = handle.clone();
+    tokio::task::spawn_blocking(move || handle.wait().cloned())
+  };
+  match tokio::time::timeout(timeout, waiting).await {
+    Ok(waited) => waited.map_err(io::Error::other)?,
+    Err(_) => {
+      handle.kill()?;
+      Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("timed out after {:?}", timeout),
+      ))
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   #[test]
@@ -197,4 +772,151 @@ mod tests {
       Some("1.8.2".into())
     );
   }
+
+  #[tokio::test]
+  async fn test_run_plugin() {
+    use super::*;
+
+    let app = Arc::new(Mutex::new(App::default()));
+    let runner = CmdRunner::new(&app);
+    runner
+      .run_plugin(PluginRun {
+        name: "echo".into(),
+        command: "echo \"$NAMESPACE/$NAME\"; exit 2".into(),
+        env: vec![
+          ("NAME".into(), "api 0".into()),
+          ("NAMESPACE".into(), "web".into()),
+        ],
+        read_only_safe: false,
+      })
+      .await;
+    let app = app.lock().await;
+    let (name, output) = app.plugin_output.as_ref().unwrap();
+    assert_eq!(name, "echo");
+    assert_eq!(output.get_txt(), "web/api 0\n");
+    let failure = app.notifications.iter().next().unwrap();
+    assert_eq!(failure.message, "Plugin echo failed with exit status: 2");
+
+    let handle = cmd!("sleep", "5").stdout_capture().start().unwrap();
+    let err = wait_with_timeout(Arc::new(handle), Duration::from_millis(50))
+      .await
+      .unwrap_err();
+    assert_eq!(err.to_string(), "timed out after 50ms");
+  }
+
+  #[test]
+  fn test_write_file() {
+    use super::*;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("web/secrets/db.yaml");
+    write_file(&path, "data:\n  password: aHVudGVyMg==\n").unwrap();
+    write_file(&path, "data: {}\n").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "data: {}\n");
+    #[cfg(unix)]
+    assert_eq!(
+      std::os::unix::fs::PermissionsExt::mode(&fs::metadata(&path).unwrap().permissions()) & 0o777,
+      0o600
+    );
+  }
+
+  #[test]
+  fn test_uninstall_report() {
+    use super::uninstall_report;
+
+    assert_eq!(
+      uninstall_report(
+        "web",
+        "frontend",
+        false,
+        &["Deployment/frontend".into(), "Service/frontend".into()]
+      ),
+      "Uninstalled web/frontend, deleted Deployment/frontend, Service/frontend"
+    );
+    assert_eq!(
+      uninstall_report("web", "frontend", true, &[]),
+      "Uninstalled web/frontend keeping its history"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_pipe_manifest() {
+    use super::*;
+
+    let app = Arc::new(Mutex::new(App::default()));
+    let runner = CmdRunner::new(&app);
+    runner
+      .pipe_manifest(
+        "grep name; echo oops >&2".into(),
+        "kind: Pod\nname: web\n".into(),
+      )
+      .await;
+    assert_eq!(
+      app.lock().await.data.describe_out.get_txt(),
+      "name: web\noops\n"
+    );
+
+    runner.pipe_manifest("exit 3".into(), String::new()).await;
+    let app = app.lock().await;
+    let failure = app.notifications.iter().next().unwrap();
+    assert_eq!(failure.level, NotificationLevel::Failure);
+    assert!(failure.message.starts_with("`exit 3` failed"));
+
+    // the command could apply the manifest
+    assert!(IoCmdEvent::PipeManifest {
+      command: "kubectl apply -f -".into(),
+      manifest: String::new(),
+    }
+    .is_mutating());
+  }
+
+  #[tokio::test]
+  async fn test_diff_last_applied() {
+    use super::*;
+
+    let app = Arc::new(Mutex::new(App::default()));
+    let runner = CmdRunner::new(&app);
+    runner
+      .diff_last_applied(
+        "printf '\\033[31m%s\\033[0m\\n' \"$(basename $1)\"; diff".into(),
+        "port: 80\n".into(),
+        "port: 8080\n".into(),
+      )
+      .await;
+    assert_eq!(
+      app.lock().await.data.describe_out.get_txt(),
+      "last-applied.yaml\n1c1\n< port: 80\n---\n> port: 8080\n"
+    );
+    assert!(app.lock().await.notifications.is_empty());
+
+    runner
+      .diff_last_applied("diff".into(), "port: 80\n".into(), "port: 80\n".into())
+      .await;
+    assert_eq!(
+      app.lock().await.data.describe_out.get_txt(),
+      "No differences to the last applied configuration"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_diff_helm_revisions() {
+    use super::*;
+
+    let app = Arc::new(Mutex::new(App::default()));
+    let mut runner = CmdRunner::new(&app);
+    runner
+      .handle_cmd_event(IoCmdEvent::DiffHelmRevisions {
+        command: "diff".into(),
+        name: "frontend".into(),
+        old_revision: 2,
+        old: "replicas: 1\n".into(),
+        new_revision: 3,
+        new: "replicas: 3\n".into(),
+      })
+      .await;
+    assert_eq!(
+      app.lock().await.data.describe_out.get_txt(),
+      "1c1\n< replicas: 1\n---\n> replicas: 3\n"
+    );
+  }
 }
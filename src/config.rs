@@ -0,0 +1,823 @@
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use tui::style::Color;
+
+use crate::{
+  app::{
+    command_palette::find_resource,
+    custom_views::{CustomColumn, CustomView},
+    hooks::Hooks,
+    key_binding::{HContext, KeyBindings},
+    models::TimestampFormat,
+    plugins::Plugin,
+    ActiveBlock,
+  },
+  event::Key,
+};
+
+/// user settings read from `$XDG_CONFIG_HOME/kdash/config.yaml` or `~/.config/kdash/config.yaml`
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  pub theme: ThemeConfig,
+  /// key overrides by binding name, like `quit: ctrl-q` or `up: [up, w]`
+  pub keybindings: BTreeMap<String, KeyList>,
+  /// namespaces listed first in the namespaces block, where number keys select them
+  pub pinned_namespaces: Vec<String>,
+  /// how Age columns show creation times on start: `relative`, `local` or `utc`
+  pub timestamp_format: TimestampFormat,
+  /// table filters by name, listed first when filtering a table
+  pub saved_filters: BTreeMap<String, String>,
+  /// tick rate in milliseconds, `--tick-rate` overrides it
+  pub tick_rate: Option<u64>,
+  /// network polling rate in milliseconds, `--poll-rate` overrides it
+  pub poll_rate: Option<u64>,
+  /// metrics polling rate in milliseconds, `--metrics-poll-rate` overrides it
+  pub metrics_poll_rate: Option<u64>,
+  /// polling rates in milliseconds by resource name or alias, like `nodes: 60000`, resources
+  /// left out use the poll rate
+  pub poll_rates: BTreeMap<String, u64>,
+  /// lines of a log kept while tailing it, 100000 by default
+  pub log_max_lines: Option<usize>,
+  /// bytes of a log kept while tailing it, 64 MiB by default
+  pub log_max_bytes: Option<usize>,
+  /// requests to the API server made at once when several resources are fetched, 8 by default
+  pub max_concurrent_requests: Option<usize>,
+  /// start with the light theme
+  pub light_theme: bool,
+  /// disable actions that change resources in the cluster, same as `--read-only`
+  pub read_only: bool,
+  /// show the info bar above the resource tabs on start, it is shown when left out
+  pub show_info_bar: Option<bool>,
+  /// proxy for connections to the cluster, overrides `HTTPS_PROXY`
+  pub https_proxy: Option<String>,
+  /// hosts connected to without the proxy, overrides `NO_PROXY`
+  pub no_proxy: Option<String>,
+  /// certificate authority for all clusters, `--certificate-authority` overrides it
+  pub certificate_authority: Option<PathBuf>,
+  /// skip checking the certificates of all clusters, same as `--insecure-skip-tls-verify`
+  pub insecure_skip_tls_verify: bool,
+  /// context to start in instead of the one used last
+  pub context: Option<String>,
+  /// namespace to start in instead of the one used last
+  pub namespace: Option<String>,
+  /// resource tab to start on instead of the one used last, a name or alias like `deploy`
+  pub tab: Option<String>,
+  /// resource tabs kept up to date while other tabs are shown, names or aliases like `deploy`
+  pub pinned_tabs: Vec<String>,
+  /// contexts whose health is compared in the fleet view
+  pub fleet_contexts: Vec<String>,
+  /// number of pods from which on large cluster mode is used, 5000 by default and 0 to never use it
+  pub large_cluster_threshold: Option<usize>,
+  /// which actions changing resources ask for confirmation first
+  pub confirm: ConfirmConfig,
+  /// shell command the manifest of the selected resource is piped to, like `kubectl neat`
+  pub pipe_command: Option<String>,
+  /// where manifests and tables are saved, like `~/kdash/{context}/{namespace}/{kind}-{name}`
+  pub export_path: Option<String>,
+  /// command the last applied and the live manifest files are given to, `diff -u` by default
+  pub diff_command: Option<String>,
+  /// send desktop notifications when pods or jobs start failing or nodes have problems
+  pub desktop_notifications: bool,
+  /// URL the same alerts are posted to as Slack compatible JSON
+  pub webhook_url: Option<String>,
+  /// copy with OSC 52 escape sequences through the terminal, for SSH sessions and tmux
+  pub osc52_clipboard: bool,
+  /// external commands run on the selected resource with a key, like the plugins of k9s
+  pub plugins: Vec<PluginConfig>,
+  /// tables of custom resources by group, version and kind, listed under More
+  pub custom_views: Vec<CustomViewConfig>,
+  /// columns added to the tables by resource name or alias, like `pods`, after their own
+  pub extra_columns: BTreeMap<String, Vec<CustomColumnConfig>>,
+  /// Lua script decorating table rows and turning events into alerts
+  pub hooks: Option<HooksConfig>,
+}
+
+/// prefix of the environment variables that replace settings, like `KDASH_POLL_RATE`
+pub const ENV_PREFIX: &str = "KDASH_";
+
+const DEFAULT_TICK_RATE: u64 = 250;
+const DEFAULT_POLL_RATE: u64 = 5000;
+/// requests made at once to the API server when several resources are fetched
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// how often the UI is redrawn and the cluster polled, in milliseconds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rates {
+  pub tick: u64,
+  pub poll: u64,
+  pub metrics_poll: u64,
+  /// resources polled at their own rate instead of `poll`
+  pub resource_polls: Vec<(ActiveBlock, u64)>,
+}
+
+/// an external command bound to a key in some resource tables
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PluginConfig {
+  /// shown on the help page
+  pub name: String,
+  pub key: KeyList,
+  /// resource names or aliases like `pods` or `deploy`, every table when left out
+  #[serde(default)]
+  pub kinds: Vec<String>,
+  /// run with `sh -c`, the selected resource is in `$NAME`, `$NAMESPACE` and `$CONTEXT`
+  pub command: String,
+  #[serde(default)]
+  pub output: PluginOutput,
+  /// the command only reads the cluster, plugins aren't run in read-only mode otherwise
+  #[serde(default)]
+  pub read_only_safe: bool,
+}
+
+/// a table of a custom resource with the columns picked from its objects
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomViewConfig {
+  /// title of the table, the kind when left out
+  pub name: Option<String>,
+  /// API group, empty for the core group
+  #[serde(default)]
+  pub group: String,
+  pub version: String,
+  pub kind: String,
+  /// resource name in URLs, guessed from the kind when left out
+  pub plural: Option<String>,
+  #[serde(default = "default_namespaced")]
+  pub namespaced: bool,
+  /// shown between the name and the age
+  #[serde(default)]
+  pub columns: Vec<CustomColumnConfig>,
+}
+
+fn default_namespaced() -> bool {
+  true
+}
+
+/// the columns with their paths parsed, the headers of the table they are added to can't be used
+fn custom_columns(
+  columns: &[CustomColumnConfig],
+  taken: &[&str],
+  table: &str,
+) -> Result<Vec<CustomColumn>> {
+  columns
+    .iter()
+    .map(|column| {
+      if taken.contains(&column.header.as_str()) {
+        return Err(anyhow!(
+          "Column {} of {} is always shown",
+          column.header,
+          table
+        ));
+      }
+      CustomColumn::new(&column.header, &column.path, column.width).map_err(|e| {
+        anyhow!(
+          "Invalid path '{}' of column {} in {}. {}",
+          column.path,
+          column.header,
+          table,
+          e
+        )
+      })
+    })
+    .collect()
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomColumnConfig {
+  pub header: String,
+  /// JSONPath like the custom columns of kubectl, `.spec.replicas`
+  pub path: String,
+  /// values are cut to this many characters
+  pub width: Option<usize>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+  /// defining `on_row(resource)` and `on_event(event)`
+  pub script: PathBuf,
+  /// header of the column `on_row` fills, `Notes` by default
+  pub column: Option<String>,
+}
+
+/// where the output of a plugin goes
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginOutput {
+  /// the terminal, with the UI suspended until the command exits. For interactive commands
+  #[default]
+  Terminal,
+  /// captured and shown in a popup
+  Popup,
+}
+
+/// color overrides for the dark (default) and light themes
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+  /// built in colors the overrides apply to
+  pub palette: Palette,
+  pub dark: ThemeColors,
+  pub light: ThemeColors,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+  #[default]
+  Default,
+  /// blue/orange instead of green/red for success and failure, safe for deuteranopia and
+  /// protanopia
+  ColorBlind,
+  /// no colors, only bold, underlined and reversed text. Custom colors are ignored
+  Monochrome,
+}
+
+/// the confirmation asked before each action that changes resources
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfirmConfig {
+  /// deleting resources, like uninstalling a Helm release
+  pub delete: ConfirmPolicy,
+  /// rolling a Helm release back to an earlier revision
+  pub rollback: ConfirmPolicy,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfirmPolicy {
+  /// run the action right away
+  Never,
+  /// ask yes or no
+  #[default]
+  Always,
+  /// ask to type the name of the resource
+  Paranoid,
+}
+
+/// colors that replace the built in ones, anything left out keeps the default
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeColors {
+  pub default: Option<ThemeColor>,
+  pub logo: Option<ThemeColor>,
+  pub failure: Option<ThemeColor>,
+  pub warning: Option<ThemeColor>,
+  pub success: Option<ThemeColor>,
+  pub primary: Option<ThemeColor>,
+  pub secondary: Option<ThemeColor>,
+  pub help: Option<ThemeColor>,
+  pub background: Option<ThemeColor>,
+  pub highlight_fg: Option<ThemeColor>,
+  pub highlight_bg: Option<ThemeColor>,
+  pub gauge: Option<ThemeColor>,
+}
+
+/// a color given by name (`red`, `light-blue`), hex (`#00e6e6`) or 256 color index (`42`)
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct ThemeColor(pub Color);
+
+impl TryFrom<String> for ThemeColor {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    parse_color(&value)
+      .map(ThemeColor)
+      .ok_or_else(|| format!("invalid color '{}'", value))
+  }
+}
+
+/// the key and optional alternative key of a binding, given as a single key or a list of one or two
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "RawKeyList")]
+pub struct KeyList(pub Vec<Key>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawKeyList {
+  One(String),
+  Many(Vec<String>),
+}
+
+impl TryFrom<RawKeyList> for KeyList {
+  type Error = String;
+
+  fn try_from(value: RawKeyList) -> Result<Self, Self::Error> {
+    let values = match value {
+      RawKeyList::One(key) => vec![key],
+      RawKeyList::Many(keys) => keys,
+    };
+    if values.is_empty() || values.len() > 2 {
+      return Err(format!("expected one or two keys, found {}", values.len()));
+    }
+    values
+      .iter()
+      .map(|key| key.parse::<Key>())
+      .collect::<Result<Vec<_>, _>>()
+      .map(KeyList)
+  }
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+  let value = value.trim();
+  if let Some(hex) = value.strip_prefix('#') {
+    if hex.len() != 6 {
+      return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+  }
+  if let Ok(index) = value.parse::<u8>() {
+    return Some(Color::Indexed(index));
+  }
+  let color = match value.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+    "reset" => Color::Reset,
+    "black" => Color::Black,
+    "red" => Color::Red,
+    "green" => Color::Green,
+    "yellow" => Color::Yellow,
+    "blue" => Color::Blue,
+    "magenta" => Color::Magenta,
+    "cyan" => Color::Cyan,
+    "gray" | "grey" => Color::Gray,
+    "darkgray" | "darkgrey" => Color::DarkGray,
+    "lightred" => Color::LightRed,
+    "lightgreen" => Color::LightGreen,
+    "lightyellow" => Color::LightYellow,
+    "lightblue" => Color::LightBlue,
+    "lightmagenta" => Color::LightMagenta,
+    "lightcyan" => Color::LightCyan,
+    "white" => Color::White,
+    _ => return None,
+  };
+  Some(color)
+}
+
+impl Config {
+  /// load the config from the given file or the default location, a missing default file is
+  /// the same as an empty one. `KDASH_<SETTING>` variables in `env_vars` replace the settings
+  /// of the file
+  pub fn load(path: Option<PathBuf>, env_vars: &[(String, String)]) -> Result<Config> {
+    let (path, required) = match path {
+      Some(path) => (Some(path), true),
+      None => (default_path(), false),
+    };
+    let contents = match path {
+      Some(path) if required || path.exists() => {
+        let contents = fs::read_to_string(&path)
+          .with_context(|| format!("Unable to read config file {}", path.display()))?;
+        Config::from_yaml(&contents)
+          .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?;
+        contents
+      }
+      _ => String::new(),
+    };
+    Config::from_yaml_with_env(&contents, env_vars)
+      .map_err(|e| anyhow!("Invalid {}* environment variable: {}", ENV_PREFIX, e))
+  }
+
+  /// the rates given on the command line, falling back to the config and then the defaults
+  pub fn rates(
+    &self,
+    tick: Option<u64>,
+    poll: Option<u64>,
+    metrics_poll: Option<u64>,
+  ) -> Result<Rates> {
+    let mut rates = Rates {
+      tick: tick.or(self.tick_rate).unwrap_or(DEFAULT_TICK_RATE),
+      poll: poll.or(self.poll_rate).unwrap_or(DEFAULT_POLL_RATE),
+      metrics_poll: metrics_poll
+        .or(self.metrics_poll_rate)
+        .unwrap_or(DEFAULT_POLL_RATE),
+      resource_polls: vec![],
+    };
+    if rates.tick == 0 || rates.tick >= 1000 {
+      return Err(anyhow!("Tick rate must be between 1 and 999"));
+    }
+    if rates.poll == 0 || !rates.poll.is_multiple_of(rates.tick) {
+      return Err(anyhow!("Poll rate must be a multiple of tick rate"));
+    }
+    if rates.metrics_poll == 0 || !rates.metrics_poll.is_multiple_of(rates.tick) {
+      return Err(anyhow!("Metrics poll rate must be a multiple of tick rate"));
+    }
+    for (name, rate) in &self.poll_rates {
+      let block =
+        find_resource(name).ok_or_else(|| anyhow!("Unknown resource '{}' in poll_rates", name))?;
+      if *rate == 0 || !rate.is_multiple_of(rates.tick) {
+        return Err(anyhow!(
+          "Poll rate of {} must be a multiple of tick rate",
+          name
+        ));
+      }
+      rates.resource_polls.push((block, *rate));
+    }
+    Ok(rates)
+  }
+
+  /// the plugins with their resources looked up, fails on unknown resources and on keys the
+  /// resource tables already use
+  pub fn plugins(&self, bindings: &KeyBindings) -> Result<Vec<Plugin>> {
+    self
+      .plugins
+      .iter()
+      .map(|plugin| {
+        let blocks = plugin
+          .kinds
+          .iter()
+          .map(|kind| {
+            find_resource(kind)
+              .ok_or_else(|| anyhow!("Unknown resource '{}' in plugin {}", kind, plugin.name))
+          })
+          .collect::<Result<Vec<_>>>()?;
+        for key in &plugin.key.0 {
+          if let Some(binding) = bindings.used_by(*key, HContext::Table) {
+            return Err(anyhow!(
+              "{} of plugin {} is used by {}",
+              key,
+              plugin.name,
+              binding
+            ));
+          }
+        }
+        Ok(Plugin {
+          name: plugin.name.clone(),
+          keys: plugin.key.0.clone(),
+          blocks,
+          command: plugin.command.clone(),
+          output: plugin.output,
+          read_only_safe: plugin.read_only_safe,
+        })
+      })
+      .collect()
+  }
+
+  /// the custom views with their column paths parsed, fails on invalid paths and on headers of
+  /// the columns every view has
+  pub fn custom_views(&self) -> Result<Vec<CustomView>> {
+    self
+      .custom_views
+      .iter()
+      .map(|view| {
+        let name = view.name.clone().unwrap_or_else(|| view.kind.clone());
+        let columns = custom_columns(
+          &view.columns,
+          &["Namespace", "Name", "Age"],
+          &format!("custom view {}", name),
+        )?;
+        Ok(CustomView::new(
+          &name,
+          &view.group,
+          &view.version,
+          &view.kind,
+          view.plural.as_deref(),
+          view.namespaced,
+          columns,
+        ))
+      })
+      .collect()
+  }
+
+  /// the extra columns of the tables by resource, fails on unknown resources, invalid paths and
+  /// on headers of columns the tables have
+  pub fn extra_columns(&self) -> Result<Vec<(ActiveBlock, Vec<CustomColumn>)>> {
+    self
+      .extra_columns
+      .iter()
+      .map(|(name, columns)| {
+        let block = find_resource(name)
+          .ok_or_else(|| anyhow!("Unknown resource '{}' in extra_columns", name))?;
+        let columns = custom_columns(
+          columns,
+          &crate::ui::table_headers(block),
+          &format!("table {}", name),
+        )?;
+        Ok((block, columns))
+      })
+      .collect()
+  }
+
+  /// the hooks with their script run, fails when it can't be read or has errors
+  pub fn hooks(&self) -> Result<Option<Hooks>> {
+    self
+      .hooks
+      .as_ref()
+      .map(|hooks| {
+        Hooks::from_file(&hooks.script, hooks.column.as_deref().unwrap_or("Notes"))
+          .with_context(|| format!("Failed to load hooks from {}", hooks.script.display()))
+      })
+      .transpose()
+  }
+
+  pub fn from_yaml(contents: &str) -> Result<Config, serde_yaml::Error> {
+    if contents.trim().is_empty() {
+      return Ok(Config::default());
+    }
+    serde_yaml::from_str(contents)
+  }
+
+  /// the config with the settings of `KDASH_<SETTING>` variables replacing the ones of the
+  /// YAML, values are read as YAML too so lists and maps can be given like `[web, db]`
+  fn from_yaml_with_env(
+    contents: &str,
+    env_vars: &[(String, String)],
+  ) -> Result<Config, serde_yaml::Error> {
+    let mut settings = if contents.trim().is_empty() {
+      Mapping::new()
+    } else {
+      serde_yaml::from_str(contents)?
+    };
+    for (name, value) in env_vars {
+      if let Some(setting) = name.strip_prefix(ENV_PREFIX) {
+        let value = serde_yaml::from_str(value).unwrap_or_else(|_| Value::from(value.as_str()));
+        settings.insert(Value::from(setting.to_lowercase()), value);
+      }
+    }
+    serde_yaml::from_value(Value::Mapping(settings))
+  }
+}
+
+fn default_path() -> Option<PathBuf> {
+  env::var_os("XDG_CONFIG_HOME")
+    .map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    .map(|dir| dir.join("kdash").join("config.yaml"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::app::key_binding::DEFAULT_KEYBINDING;
+
+  #[test]
+  fn test_config_rates() {
+    let config = Config::from_yaml("tick_rate: 100\npoll_rate: 2000\n").unwrap();
+    assert_eq!(
+      config.rates(None, None, None).unwrap(),
+      Rates {
+        tick: 100,
+        poll: 2000,
+        metrics_poll: 5000,
+        resource_polls: vec![],
+      }
+    );
+    // command line flags win over the config
+    assert_eq!(
+      config.rates(Some(200), None, Some(10000)).unwrap(),
+      Rates {
+        tick: 200,
+        poll: 2000,
+        metrics_poll: 10000,
+        resource_polls: vec![],
+      }
+    );
+    assert_eq!(Config::default().rates(None, None, None).unwrap().tick, 250);
+    let err = config.rates(Some(300), None, None).unwrap_err();
+    assert_eq!(err.to_string(), "Poll rate must be a multiple of tick rate");
+    assert!(config.rates(Some(1000), Some(1000), Some(1000)).is_err());
+    assert!(config.rates(Some(0), None, None).is_err());
+
+    let config = Config::from_yaml("poll_rates:\n  nodes: 60000\n  po: 1000\n").unwrap();
+    assert_eq!(
+      config.rates(None, None, None).unwrap().resource_polls,
+      vec![(ActiveBlock::Nodes, 60000), (ActiveBlock::Pods, 1000)]
+    );
+    let err = config.rates(Some(400), Some(2000), Some(2000)).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Poll rate of po must be a multiple of tick rate"
+    );
+    let config = Config::from_yaml("poll_rates:\n  widgets: 1000\n").unwrap();
+    let err = config.rates(None, None, None).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown resource 'widgets' in poll_rates");
+  }
+
+  #[test]
+  fn test_parse_color() {
+    assert_eq!(parse_color("#00e6e6"), Some(Color::Rgb(0, 230, 230)));
+    assert_eq!(parse_color("light-blue"), Some(Color::LightBlue));
+    assert_eq!(parse_color("DarkGray"), Some(Color::DarkGray));
+    assert_eq!(parse_color("42"), Some(Color::Indexed(42)));
+    assert_eq!(parse_color("#00e6"), None);
+    assert_eq!(parse_color("#gg0000"), None);
+    assert_eq!(parse_color("purple"), None);
+  }
+
+  #[test]
+  fn test_config_from_yaml_with_env() {
+    let env_vars = |vars: &[(&str, &str)]| {
+      vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect::<Vec<_>>()
+    };
+    let config = Config::from_yaml_with_env(
+      "poll_rate: 2000\nlight_theme: false\n",
+      &env_vars(&[
+        ("KDASH_LIGHT_THEME", "true"),
+        ("KDASH_PINNED_NAMESPACES", "[web, db]"),
+        ("KDASH_POLL_RATES", "{nodes: 60000}"),
+        ("KDASH_HTTPS_PROXY", "http://proxy:3128"),
+        ("HOME", "/home/kdash"),
+      ]),
+    )
+    .unwrap();
+    assert_eq!(config.poll_rate, Some(2000));
+    assert!(config.light_theme);
+    assert_eq!(config.pinned_namespaces, vec!["web", "db"]);
+    assert_eq!(config.poll_rates.get("nodes"), Some(&60000));
+    assert_eq!(config.https_proxy.as_deref(), Some("http://proxy:3128"));
+
+    assert_eq!(
+      Config::from_yaml_with_env("", &[]).unwrap(),
+      Config::default()
+    );
+    assert!(Config::from_yaml_with_env("", &env_vars(&[("KDASH_NO_SUCH", "1")])).is_err());
+    assert!(Config::from_yaml_with_env("", &env_vars(&[("KDASH_READ_ONLY", "maybe")])).is_err());
+  }
+
+  #[test]
+  fn test_config_from_yaml() {
+    assert_eq!(Config::from_yaml("").unwrap(), Config::default());
+
+    let config = Config::from_yaml(
+      "theme:\n  dark:\n    primary: \"#00e6e6\"\n    highlight_bg: blue\n  light:\n    failure: red\n",
+    )
+    .unwrap();
+    assert_eq!(
+      config.theme.dark.primary,
+      Some(ThemeColor(Color::Rgb(0, 230, 230)))
+    );
+    assert_eq!(
+      config.theme.dark.highlight_bg,
+      Some(ThemeColor(Color::Blue))
+    );
+    assert_eq!(config.theme.dark.failure, None);
+    assert_eq!(config.theme.light.failure, Some(ThemeColor(Color::Red)));
+
+    let err = Config::from_yaml("theme:\n  dark:\n    primary: purple\n").unwrap_err();
+    assert!(err.to_string().contains("invalid color 'purple'"));
+    assert!(Config::from_yaml("theme:\n  dark:\n    primery: red\n").is_err());
+    let config = Config::from_yaml("theme:\n  palette: color-blind\n").unwrap();
+    assert_eq!(config.theme.palette, Palette::ColorBlind);
+  }
+
+  #[test]
+  fn test_keybindings_from_yaml() {
+    let config =
+      Config::from_yaml("keybindings:\n  quit: ctrl-q\n  up: [up, w]\n  help: F1\n").unwrap();
+    assert_eq!(
+      config.keybindings.get("quit"),
+      Some(&KeyList(vec![Key::Ctrl('q')]))
+    );
+    assert_eq!(
+      config.keybindings.get("up"),
+      Some(&KeyList(vec![Key::Up, Key::Char('w')]))
+    );
+    assert_eq!(
+      config.keybindings.get("help"),
+      Some(&KeyList(vec![Key::F1]))
+    );
+
+    let config = Config::from_yaml("pinned_namespaces: [web, kube-system]\n").unwrap();
+    assert_eq!(config.pinned_namespaces, vec!["web", "kube-system"]);
+    let config = Config::from_yaml("timestamp_format: utc\n").unwrap();
+    assert_eq!(config.timestamp_format, TimestampFormat::Utc);
+    let config = Config::from_yaml("saved_filters:\n  prod-api: api-prod\n").unwrap();
+    assert_eq!(config.saved_filters["prod-api"], "api-prod");
+
+    let config =
+      Config::from_yaml("light_theme: true\nshow_info_bar: false\ncontext: prod\nnamespace: web\n")
+        .unwrap();
+    assert!(config.light_theme);
+    assert!(!config.read_only);
+    assert_eq!(config.show_info_bar, Some(false));
+    assert_eq!(config.context.as_deref(), Some("prod"));
+    assert_eq!(config.namespace.as_deref(), Some("web"));
+    assert_eq!(
+      Config::from_yaml("tab: deployments\n")
+        .unwrap()
+        .tab
+        .as_deref(),
+      Some("deployments")
+    );
+
+    let config = Config::from_yaml("confirm:\n  delete: paranoid\n  rollback: never\n").unwrap();
+    assert_eq!(
+      config.confirm,
+      ConfirmConfig {
+        delete: ConfirmPolicy::Paranoid,
+        rollback: ConfirmPolicy::Never,
+      }
+    );
+    assert_eq!(
+      Config::from_yaml("confirm: {}\n").unwrap().confirm.rollback,
+      ConfirmPolicy::Always
+    );
+    assert!(Config::from_yaml("confirm:\n  delete: sometimes\n").is_err());
+    // actions KDash does not have are refused
+    assert!(Config::from_yaml("confirm:\n  scale: never\n").is_err());
+    assert!(Config::from_yaml("confirm:\n  drain: paranoid\n").is_err());
+
+    let config = Config::from_yaml(
+      "plugins:\n  - name: dive\n    key: ctrl-i\n    kinds: [po, deploy]\n    command: dive $NAME\n  - name: events\n    key: alt-e\n    command: kubectl events\n    output: popup\n    read_only_safe: true\n",
+    )
+    .unwrap();
+    let plugins = config.plugins(&DEFAULT_KEYBINDING).unwrap();
+    assert_eq!(
+      plugins[0].blocks,
+      vec![ActiveBlock::Pods, ActiveBlock::Deployments]
+    );
+    assert_eq!(plugins[0].output, PluginOutput::Terminal);
+    assert_eq!(plugins[1].keys, vec![Key::Alt('e')]);
+    assert!(plugins[1].blocks.is_empty());
+    assert_eq!(plugins[1].output, PluginOutput::Popup);
+    assert!(!plugins[0].read_only_safe);
+    assert!(plugins[1].read_only_safe);
+    let config =
+      Config::from_yaml("plugins:\n  - name: dive\n    key: d\n    command: dive $NAME\n").unwrap();
+    let err = config.plugins(&DEFAULT_KEYBINDING).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "<d> of plugin dive is used by describe_resource"
+    );
+    let config = Config::from_yaml(
+      "plugins:\n  - name: dive\n    key: ctrl-i\n    kinds: [widgets]\n    command: dive\n",
+    )
+    .unwrap();
+    let err = config.plugins(&DEFAULT_KEYBINDING).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown resource 'widgets' in plugin dive");
+
+    let config = Config::from_yaml(
+      "custom_views:\n  - group: cert-manager.io\n    version: v1\n    kind: ClusterIssuer\n    namespaced: false\n    columns:\n      - header: Ready\n        path: '{.status.conditions[0].status}'\n        width: 5\n",
+    )
+    .unwrap();
+    let views = config.custom_views().unwrap();
+    assert_eq!(views[0].name, "ClusterIssuer");
+    assert_eq!(views[0].resource.plural, "clusterissuers");
+    assert!(!views[0].namespaced);
+    assert_eq!(views[0].columns[0].path, "$.status.conditions[0].status");
+    assert_eq!(views[0].columns[0].width, Some(5));
+    let config = Config::from_yaml(
+      "custom_views:\n  - version: v1\n    kind: Widget\n    columns:\n      - header: Size\n        path: .spec[\n",
+    )
+    .unwrap();
+    assert!(config
+      .custom_views()
+      .unwrap_err()
+      .to_string()
+      .starts_with("Invalid path '.spec[' of column Size in custom view Widget."));
+    assert!(Config::from_yaml("custom_views:\n  - kind: Widget\n").is_err());
+
+    let config = Config::from_yaml(
+      "extra_columns:\n  po:\n    - header: Node Name\n      path: spec.nodeName\n    - header: Team\n      path: \"{.metadata.annotations['example.com/team']}\"\n",
+    )
+    .unwrap();
+    let extra = config.extra_columns().unwrap();
+    assert_eq!(extra[0].0, ActiveBlock::Pods);
+    assert_eq!(
+      extra[0]
+        .1
+        .iter()
+        .map(|c| c.path.as_str())
+        .collect::<Vec<_>>(),
+      vec![
+        "$.spec.nodeName",
+        "$.metadata.annotations['example.com/team']"
+      ]
+    );
+    let config =
+      Config::from_yaml("extra_columns:\n  deploy:\n    - header: Ready\n      path: .status\n")
+        .unwrap();
+    assert_eq!(
+      config.extra_columns().unwrap_err().to_string(),
+      "Column Ready of table deploy is always shown"
+    );
+    let config =
+      Config::from_yaml("extra_columns:\n  widgets:\n    - header: Size\n      path: .size\n")
+        .unwrap();
+    assert_eq!(
+      config.extra_columns().unwrap_err().to_string(),
+      "Unknown resource 'widgets' in extra_columns"
+    );
+
+    let config = Config::from_yaml("hooks:\n  script: /nonexistent/hooks.lua\n").unwrap();
+    assert_eq!(
+      config.hooks().err().unwrap().to_string(),
+      "Failed to load hooks from /nonexistent/hooks.lua"
+    );
+    assert!(Config::default().hooks().unwrap().is_none());
+
+    let err = Config::from_yaml("keybindings:\n  quit: ctrl-foo\n").unwrap_err();
+    assert!(err.to_string().contains("invalid key 'ctrl-foo'"));
+    let err = Config::from_yaml("keybindings:\n  quit: [a, b, c]\n").unwrap_err();
+    assert!(err
+      .to_string()
+      .contains("expected one or two keys, found 3"));
+  }
+}
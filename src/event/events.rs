@@ -1,6 +1,9 @@
 //  adapted from tui-rs/examples/crossterm_demo.rs
 use std::{
-  sync::mpsc,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc,
+  },
   thread,
   time::{Duration, Instant},
 };
@@ -12,7 +15,6 @@ use super::Key;
 #[derive(Debug, Clone, Copy)]
 /// Configuration for event handling.
 pub struct EventConfig {
-  pub exit_key: Key,
   /// The tick rate at which the application will sent an tick event.
   pub tick_rate: Duration,
 }
@@ -20,7 +22,6 @@ pub struct EventConfig {
 impl Default for EventConfig {
   fn default() -> EventConfig {
     EventConfig {
-      exit_key: Key::Ctrl('c'),
       tick_rate: Duration::from_millis(250),
     }
   }
@@ -41,6 +42,8 @@ pub struct Events {
   rx: mpsc::Receiver<Event<Key, MouseEvent>>,
   // Need to be kept around to prevent disposing the sender side.
   _tx: mpsc::Sender<Event<Key, MouseEvent>>,
+  /// stop reading the terminal while another process uses it
+  paused: Arc<AtomicBool>,
 }
 
 impl Events {
@@ -48,7 +51,6 @@ impl Events {
   pub fn new(tick_rate: u64) -> Events {
     Events::with_config(EventConfig {
       tick_rate: Duration::from_millis(tick_rate),
-      ..EventConfig::default()
     })
   }
 
@@ -59,9 +61,15 @@ impl Events {
     let tick_rate = config.tick_rate;
 
     let event_tx = tx.clone();
+    let paused = Arc::new(AtomicBool::new(false));
+    let thread_paused = paused.clone();
     thread::spawn(move || {
       let mut last_tick = Instant::now();
       loop {
+        if thread_paused.load(Ordering::Relaxed) {
+          thread::sleep(tick_rate);
+          continue;
+        }
         let timeout = tick_rate
           .checked_sub(last_tick.elapsed())
           .unwrap_or_else(|| Duration::from_secs(0));
@@ -85,7 +93,16 @@ impl Events {
       }
     });
 
-    Events { rx, _tx: tx }
+    Events {
+      rx,
+      _tx: tx,
+      paused,
+    }
+  }
+
+  /// pause reading input until resumed, a poll already waiting ends within the tick rate
+  pub fn pause(&self, paused: bool) {
+    self.paused.store(paused, Ordering::Relaxed);
   }
 
   /// Attempts to read an event.
@@ -1,5 +1,5 @@
 // from https://github.com/Rigellute/spotify-tui
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use crossterm::event;
 
@@ -89,6 +89,46 @@ impl Key {
       _ => panic!("unknown function key: F{}", n),
     }
   }
+
+  /// the bytes a terminal sends for the key, to pass it on to a container attached to
+  pub fn to_bytes(self) -> Vec<u8> {
+    let escape = |sequence: &str| [b"\x1b", sequence.as_bytes()].concat();
+    match self {
+      Key::Enter => vec![b'\r'],
+      Key::Tab => vec![b'\t'],
+      Key::Backspace => vec![0x7f],
+      Key::Esc => vec![0x1b],
+      Key::Up => escape("[A"),
+      Key::Down => escape("[B"),
+      Key::Right => escape("[C"),
+      Key::Left => escape("[D"),
+      Key::Home => escape("[H"),
+      Key::End => escape("[F"),
+      Key::Ins => escape("[2~"),
+      Key::Delete => escape("[3~"),
+      Key::PageUp => escape("[5~"),
+      Key::PageDown => escape("[6~"),
+      Key::F1 => escape("OP"),
+      Key::F2 => escape("OQ"),
+      Key::F3 => escape("OR"),
+      Key::F4 => escape("OS"),
+      Key::F5 => escape("[15~"),
+      Key::F6 => escape("[17~"),
+      Key::F7 => escape("[18~"),
+      Key::F8 => escape("[19~"),
+      Key::F9 => escape("[20~"),
+      Key::F10 => escape("[21~"),
+      Key::F11 => escape("[23~"),
+      Key::F12 => escape("[24~"),
+      Key::Char(c) => c.to_string().into_bytes(),
+      Key::Ctrl(' ') => vec![0],
+      Key::Ctrl(c) if ('@'..='_').contains(&c.to_ascii_uppercase()) => {
+        vec![c.to_ascii_uppercase() as u8 & 0x1f]
+      }
+      Key::Alt(c) => escape(&c.to_string()),
+      Key::Ctrl(_) | Key::F0 | Key::Unknown => vec![],
+    }
+  }
 }
 
 impl fmt::Display for Key {
@@ -106,6 +146,77 @@ impl fmt::Display for Key {
   }
 }
 
+/// Parses keys as written in the config file, like `q`, `ctrl-r`, `alt+x`, `enter` or `<F5>`
+impl FromStr for Key {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let value = s.trim();
+    let value = value
+      .strip_prefix('<')
+      .and_then(|v| v.strip_suffix('>'))
+      .unwrap_or(value);
+    let mut chars = value.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+      return Ok(Key::Char(c));
+    }
+    let lower = value.to_lowercase();
+    let modified = |prefix: &str| {
+      [format!("{}-", prefix), format!("{}+", prefix)]
+        .iter()
+        .find_map(|p| lower.strip_prefix(p.as_str()).map(|_| &value[p.len()..]))
+    };
+    if let Some(rest) = modified("ctrl") {
+      return single_char(rest)
+        .map(Key::Ctrl)
+        .ok_or_else(|| invalid_key(s));
+    }
+    if let Some(rest) = modified("alt") {
+      return single_char(rest)
+        .map(Key::Alt)
+        .ok_or_else(|| invalid_key(s));
+    }
+    let key = match lower.replace(['-', '_', ' '], "").as_str() {
+      "enter" | "return" => Key::Enter,
+      "tab" => Key::Tab,
+      "backspace" => Key::Backspace,
+      "esc" | "escape" => Key::Esc,
+      "left" | "leftarrowkey" => Key::Left,
+      "right" | "rightarrowkey" => Key::Right,
+      "up" | "uparrowkey" => Key::Up,
+      "down" | "downarrowkey" => Key::Down,
+      "ins" | "insert" => Key::Ins,
+      "del" | "delete" => Key::Delete,
+      "home" => Key::Home,
+      "end" => Key::End,
+      "pgup" | "pageup" => Key::PageUp,
+      "pgdown" | "pagedown" => Key::PageDown,
+      "space" => Key::Char(' '),
+      f if f.starts_with('f') => match f[1..].parse::<u8>() {
+        Ok(n) if n <= 12 => Key::from_f(n),
+        _ => return Err(invalid_key(s)),
+      },
+      _ => return Err(invalid_key(s)),
+    };
+    Ok(key)
+  }
+}
+
+fn single_char(value: &str) -> Option<char> {
+  if value.eq_ignore_ascii_case("space") {
+    return Some(' ');
+  }
+  let mut chars = value.chars();
+  match (chars.next(), chars.next()) {
+    (Some(c), None) => Some(c),
+    _ => None,
+  }
+}
+
+fn invalid_key(value: &str) -> String {
+  format!("invalid key '{}'", value)
+}
+
 impl From<event::KeyEvent> for Key {
   fn from(key_event: event::KeyEvent) -> Self {
     match key_event {
@@ -205,6 +316,37 @@ mod tests {
     assert_eq!(format!("{}", Key::Enter), "<Enter>");
     assert_eq!(format!("{}", Key::F10), "<F10>");
   }
+  #[test]
+  fn test_key_from_str() {
+    assert_eq!("q".parse::<Key>(), Ok(Key::Char('q')));
+    assert_eq!("Q".parse::<Key>(), Ok(Key::Char('Q')));
+    assert_eq!("<?>".parse::<Key>(), Ok(Key::Char('?')));
+    assert_eq!("ctrl-r".parse::<Key>(), Ok(Key::Ctrl('r')));
+    assert_eq!("Ctrl+R".parse::<Key>(), Ok(Key::Ctrl('R')));
+    assert_eq!("alt-space".parse::<Key>(), Ok(Key::Alt(' ')));
+    assert_eq!("space".parse::<Key>(), Ok(Key::Char(' ')));
+    assert_eq!("Enter".parse::<Key>(), Ok(Key::Enter));
+    assert_eq!("page-down".parse::<Key>(), Ok(Key::PageDown));
+    assert_eq!("<Left Arrow Key>".parse::<Key>(), Ok(Key::Left));
+    assert_eq!("<F10>".parse::<Key>(), Ok(Key::F10));
+    assert_eq!("f13".parse::<Key>(), Err("invalid key 'f13'".to_string()));
+    assert!("ctrl-ab".parse::<Key>().is_err());
+    assert!("foo".parse::<Key>().is_err());
+    assert!("".parse::<Key>().is_err());
+  }
+
+  #[test]
+  fn test_key_to_bytes() {
+    assert_eq!(Key::Char('é').to_bytes(), "é".as_bytes());
+    assert_eq!(Key::Enter.to_bytes(), b"\r");
+    assert_eq!(Key::Ctrl('c').to_bytes(), [0x03]);
+    assert_eq!(Key::Ctrl('[').to_bytes(), [0x1b]);
+    assert_eq!(Key::Alt('b').to_bytes(), b"\x1bb");
+    assert_eq!(Key::Up.to_bytes(), b"\x1b[A");
+    assert_eq!(Key::F5.to_bytes(), b"\x1b[15~");
+    assert!(Key::Unknown.to_bytes().is_empty());
+  }
+
   #[test]
   fn test_key_from_event() {
     assert_eq!(
@@ -1,11 +1,13 @@
-use crossterm::event::{MouseEvent, MouseEventKind};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use kubectl_view_allocations::GroupBy;
 use serde::Serialize;
 
 use crate::{
   app::{
-    key_binding::DEFAULT_KEYBINDING,
-    models::{KubeResource, Scrollable, ScrollableTxt, StatefulTable},
+    export::ExportTarget,
+    key_binding::key_bindings,
+    models::{KubeResource, ScrollStep, Scrollable, ScrollableTxt, StatefulList, StatefulTable},
+    notifications::NotificationLevel,
     secrets::KubeSecret,
     ActiveBlock, App, Route, RouteId,
   },
@@ -13,48 +15,154 @@ use crate::{
   event::Key,
 };
 
+/// counts are capped to keep repeated scrolling short
+const MAX_COUNT: usize = 9999;
+
 pub async fn handle_key_events(key: Key, app: &mut App) {
+  // the command palette takes all keys while it is open
+  if app.command_palette.is_some() {
+    handle_command_palette(key, app).await;
+    return;
+  }
+  if app.resource_finder.is_some() {
+    handle_resource_finder(key, app);
+    return;
+  }
+  if app.context_switcher.is_some() {
+    handle_context_switcher(key, app);
+    return;
+  }
+  if app.cell_picker.is_some() {
+    handle_cell_picker(key, app);
+    return;
+  }
+  if app.filter_input.is_some() {
+    handle_filter_input(key, app);
+    return;
+  }
+  if app.export_prompt.is_some() {
+    handle_export_prompt(key, app).await;
+    return;
+  }
+  if app.confirmation.is_some() {
+    handle_confirmation(key, app).await;
+    return;
+  }
+  if app.plugin_output.is_some() {
+    handle_plugin_output(key, app);
+    return;
+  }
+  if app.is_help_searching {
+    handle_help_search(key, app);
+    return;
+  }
+  if app.is_detail_searching {
+    handle_detail_search(key, app);
+    return;
+  }
+  if let Some(digit) = count_digit(app, key) {
+    app.pending_count = (app.pending_count * 10 + digit).min(MAX_COUNT);
+    return;
+  }
+  let count = std::mem::take(&mut app.pending_count);
+  let pending_top = std::mem::take(&mut app.pending_top);
   // First handle any global event and then move to route event
   match key {
-    _ if key == DEFAULT_KEYBINDING.esc.key => {
+    _ if key_bindings().esc.matches(key) => {
       handle_escape(app);
     }
-    _ if key == DEFAULT_KEYBINDING.quit.key || key == DEFAULT_KEYBINDING.quit.alt.unwrap() => {
+    _ if key_bindings().quit.matches(key) => {
       app.should_quit = true;
     }
-    _ if key == DEFAULT_KEYBINDING.up.key || key == DEFAULT_KEYBINDING.up.alt.unwrap() => {
-      handle_block_scroll(app, true, false, false).await;
+    _ if key_bindings().up.matches(key) || key_bindings().down.matches(key) => {
+      let up = key_bindings().up.matches(key);
+      for _ in 0..count.max(1) {
+        handle_block_scroll(app, up, false, ScrollStep::Line).await;
+      }
     }
-    _ if key == DEFAULT_KEYBINDING.down.key || key == DEFAULT_KEYBINDING.down.alt.unwrap() => {
-      handle_block_scroll(app, false, false, false).await;
+    _ if key_bindings().pg_up.matches(key) || key_bindings().pg_down.matches(key) => {
+      let up = key_bindings().pg_up.matches(key);
+      for _ in 0..count.max(1) {
+        handle_block_scroll(app, up, false, ScrollStep::Page).await;
+      }
     }
-    _ if key == DEFAULT_KEYBINDING.pg_up.key => {
-      handle_block_scroll(app, true, false, true).await;
+    _ if key_bindings().half_page_up.matches(key) || key_bindings().half_page_down.matches(key) => {
+      let up = key_bindings().half_page_up.matches(key);
+      for _ in 0..count.max(1) {
+        handle_block_scroll(app, up, false, ScrollStep::HalfPage).await;
+      }
     }
-    _ if key == DEFAULT_KEYBINDING.pg_down.key => {
-      handle_block_scroll(app, false, false, true).await;
+    // g cycles the grouping of the utilization view
+    _ if key_bindings().scroll_to_top.matches(key)
+      && app.get_current_route().id != RouteId::Utilization =>
+    {
+      if matches!(key, Key::Char(_)) && !pending_top {
+        app.pending_top = true;
+        app.pending_count = count;
+      } else {
+        handle_scroll_to(app, count.saturating_sub(1), false);
+      }
     }
-    _ if key == DEFAULT_KEYBINDING.toggle_theme.key => {
+    _ if key_bindings().scroll_to_bottom.matches(key) => match count {
+      0 => handle_scroll_to(app, usize::MAX, true),
+      row => handle_scroll_to(app, row - 1, false),
+    },
+    _ if key_bindings().toggle_theme.matches(key) => {
       app.light_theme = !app.light_theme;
     }
-    _ if key == DEFAULT_KEYBINDING.refresh.key => {
+    _ if key_bindings().cycle_layout.matches(key) => {
+      app.cycle_layout_mode();
+      app.notify(
+        NotificationLevel::Info,
+        format!("{:?} layout", app.layout_mode),
+      );
+    }
+    _ if key_bindings().refresh.matches(key) => {
       app.refresh();
+      app.notify(NotificationLevel::Info, "Refreshing all resources");
     }
-    _ if key == DEFAULT_KEYBINDING.help.key => {
+    _ if key_bindings().refresh_tab.matches(key) => {
+      app.refresh_current_view();
+    }
+    _ if key_bindings().replay_back.matches(key) && app.replay.is_some() => app.step_replay(-1),
+    _ if key_bindings().replay_forward.matches(key) && app.replay.is_some() => app.step_replay(1),
+    _ if key_bindings().copy_kubectl.matches(key) => match app.kubectl_command() {
+      Some(command) => copy_to_clipboard(app, command),
+      None => app.notify(
+        NotificationLevel::Info,
+        "Select a resource to copy its kubectl command",
+      ),
+    },
+    _ if key_bindings().help.matches(key) => {
       if app.get_current_route().active_block != ActiveBlock::Help {
         app.push_navigation_stack(RouteId::HelpMenu, ActiveBlock::Help);
       }
     }
-    _ if key == DEFAULT_KEYBINDING.jump_to_all_context.key => {
+    _ if key_bindings().command_palette.matches(key) => {
+      app.open_command_palette();
+    }
+    _ if key_bindings().find_resource.matches(key) => {
+      app.open_resource_finder();
+    }
+    _ if key_bindings().jump_to_all_context.matches(key) => {
       app.route_contexts();
     }
-    _ if key == DEFAULT_KEYBINDING.jump_to_current_context.key => {
+    _ if key_bindings().jump_to_current_context.matches(key) => {
       app.route_home();
     }
-    _ if key == DEFAULT_KEYBINDING.jump_to_utilization.key => {
+    _ if key_bindings().switch_context.matches(key) => {
+      app.open_context_switcher();
+    }
+    _ if key_bindings().jump_to_utilization.matches(key) => {
       app.route_utilization();
     }
-    _ if key == DEFAULT_KEYBINDING.cycle_main_views.key => {
+    _ if key_bindings().jump_to_metrics_history.matches(key) => {
+      app.route_metrics_history();
+    }
+    _ if key_bindings().jump_to_fleet.matches(key) => {
+      app.route_fleet();
+    }
+    _ if key_bindings().cycle_main_views.matches(key) => {
       app.cycle_main_routes();
     }
     _ => handle_route_events(key, app).await,
@@ -64,25 +172,293 @@ pub async fn handle_key_events(key: Key, app: &mut App) {
 pub async fn handle_mouse_events(mouse: MouseEvent, app: &mut App) {
   match mouse.kind {
     // mouse scrolling is inverted
-    MouseEventKind::ScrollDown => handle_block_scroll(app, true, true, false).await,
-    MouseEventKind::ScrollUp => handle_block_scroll(app, false, true, false).await,
+    MouseEventKind::ScrollDown => handle_block_scroll(app, true, true, ScrollStep::Line).await,
+    MouseEventKind::ScrollUp => handle_block_scroll(app, false, true, ScrollStep::Line).await,
+    MouseEventKind::Down(MouseButton::Left) => {
+      handle_mouse_click(app, mouse.column, mouse.row).await
+    }
     _ => {}
   }
 }
 
+async fn handle_mouse_click(app: &mut App, column: u16, row: u16) {
+  if let Some(index) = app.main_tabs.index_at(column, row) {
+    let route = app.main_tabs.set_index(index).route.clone();
+    app.push_navigation_route(route);
+    return;
+  }
+
+  let route = app.get_current_route().clone();
+  if route.id == RouteId::Home {
+    if let Some(index) = app.context_tabs.index_at(column, row) {
+      let route = app.context_tabs.set_index(index).route.clone();
+      app.push_navigation_route(route);
+      return;
+    }
+    if app.is_info_bar_shown() {
+      if let Some(clicked_again) = handle_table_click(&mut app.data.namespaces, column, row) {
+        if route.active_block != ActiveBlock::Namespaces {
+          app.push_navigation_stack(RouteId::Home, ActiveBlock::Namespaces);
+        } else if clicked_again {
+          handle_route_events(key_bindings().submit.key, app).await;
+        }
+        return;
+      }
+    }
+    // clicking the resource table moves the focus back from the namespaces block
+    if route.active_block == ActiveBlock::Namespaces {
+      let prev_block = app.get_prev_route().active_block;
+      if handle_block_click(app, prev_block, column, row).is_some() {
+        app.pop_navigation_stack();
+      }
+      return;
+    }
+  }
+
+  // clicking the selected row again works like pressing enter on it
+  if let Some(true) = handle_block_click(app, route.active_block, column, row) {
+    handle_route_events(key_bindings().submit.key, app).await;
+  }
+}
+
+async fn handle_command_palette(key: Key, app: &mut App) {
+  if let Some(palette) = app.command_palette.as_mut() {
+    match key {
+      Key::Esc => app.command_palette = None,
+      Key::Enter => app.run_command().await,
+      Key::Up => palette.suggestions.scroll_up(1),
+      Key::Down => palette.suggestions.scroll_down(1),
+      Key::Tab => {
+        palette.complete();
+        app.update_command_suggestions();
+      }
+      Key::Backspace => {
+        if palette.input.pop().is_none() {
+          app.command_palette = None;
+        } else {
+          app.update_command_suggestions();
+        }
+      }
+      Key::Char(c) => {
+        palette.input.push(c);
+        app.update_command_suggestions();
+      }
+      _ => {}
+    }
+  }
+}
+
+fn handle_resource_finder(key: Key, app: &mut App) {
+  if let Some(finder) = app.resource_finder.as_mut() {
+    match key {
+      Key::Esc => app.resource_finder = None,
+      Key::Enter => app.jump_to_finder_match(),
+      Key::Up => finder.matches.scroll_up(1),
+      Key::Down => finder.matches.scroll_down(1),
+      Key::Backspace => {
+        finder.input.pop();
+        app.update_finder_matches();
+      }
+      Key::Char(c) => {
+        finder.input.push(c);
+        app.update_finder_matches();
+      }
+      _ => {}
+    }
+  }
+}
+
+fn handle_context_switcher(key: Key, app: &mut App) {
+  if let Some(switcher) = app.context_switcher.as_mut() {
+    match key {
+      Key::Esc => app.context_switcher = None,
+      Key::Enter => app.switch_to_picked_context(),
+      Key::Up => switcher.matches.scroll_up(1),
+      Key::Down => switcher.matches.scroll_down(1),
+      Key::Backspace => {
+        switcher.input.pop();
+        app.update_context_matches();
+      }
+      Key::Char(c) => {
+        switcher.input.push(c);
+        app.update_context_matches();
+      }
+      _ => {}
+    }
+  }
+}
+
+fn handle_filter_input(key: Key, app: &mut App) {
+  if let Some(filter) = app.filter_input.as_mut() {
+    match key {
+      Key::Esc => app.close_filter(false),
+      Key::Enter => app.close_filter(true),
+      Key::Up | Key::Down => {
+        if key == Key::Up {
+          filter.choices.scroll_up(1);
+        } else {
+          filter.choices.scroll_down(1);
+        }
+        filter.recall();
+        app.apply_filter_input();
+      }
+      Key::Backspace => {
+        filter.input.pop();
+        app.type_filter_input();
+      }
+      Key::Char(c) => {
+        filter.input.push(c);
+        filter.choices.state.select(None);
+        app.type_filter_input();
+      }
+      _ => {}
+    }
+  }
+}
+
+async fn handle_export_prompt(key: Key, app: &mut App) {
+  if let Some(prompt) = app.export_prompt.as_mut() {
+    match key {
+      Key::Esc => app.export_prompt = None,
+      Key::Enter => app.save_export().await,
+      Key::Tab => {
+        if let ExportTarget::Manifest {
+          strip_managed_fields,
+          ..
+        } = &mut prompt.target
+        {
+          *strip_managed_fields = !*strip_managed_fields;
+        }
+      }
+      Key::Backspace => {
+        prompt.path.pop();
+      }
+      Key::Char(c) => prompt.path.push(c),
+      _ => {}
+    }
+  }
+}
+
+async fn handle_confirmation(key: Key, app: &mut App) {
+  if let Some(confirmation) = app.confirmation.as_mut() {
+    match key {
+      Key::Esc => app.confirmation = None,
+      Key::Enter => app.confirm_action().await,
+      // a yes or no question is answered with y or n, the name to type may contain them
+      Key::Char('y') if confirmation.typed_name.is_none() => app.confirm_action().await,
+      Key::Char('n') if confirmation.typed_name.is_none() => app.confirmation = None,
+      Key::Backspace => {
+        confirmation.input.pop();
+      }
+      Key::Char(c) if confirmation.typed_name.is_some() => confirmation.input.push(c),
+      _ => {}
+    }
+  }
+}
+
+fn handle_plugin_output(key: Key, app: &mut App) {
+  if let Some((_, output)) = app.plugin_output.as_mut() {
+    match key {
+      _ if key_bindings().esc.matches(key) || key_bindings().quit.matches(key) => {
+        app.plugin_output = None
+      }
+      _ if key_bindings().up.matches(key) => output.scroll_up(1),
+      _ if key_bindings().down.matches(key) => output.scroll_down(1),
+      _ if key_bindings().pg_up.matches(key) => output.scroll_up(10),
+      _ if key_bindings().pg_down.matches(key) => output.scroll_down(10),
+      _ => {}
+    }
+  }
+}
+
+fn handle_cell_picker(key: Key, app: &mut App) {
+  if let Some(picker) = app.cell_picker.as_mut() {
+    match key {
+      Key::Esc => app.cell_picker = None,
+      Key::Enter => {
+        let value = picker
+          .state
+          .selected()
+          .and_then(|i| picker.items.get(i))
+          .map(|(_, value)| value.clone());
+        app.cell_picker = None;
+        if let Some(value) = value {
+          copy_to_clipboard(app, value);
+        }
+      }
+      Key::Up => picker.scroll_up(1),
+      Key::Down => picker.scroll_down(1),
+      _ => {}
+    }
+  }
+}
+
+fn handle_help_search(key: Key, app: &mut App) {
+  match key {
+    Key::Esc => {
+      app.is_help_searching = false;
+      app.help_query.clear();
+    }
+    Key::Enter => app.is_help_searching = false,
+    Key::Up => app.help_docs.scroll_up(1),
+    Key::Down => app.help_docs.scroll_down(1),
+    Key::Backspace => {
+      app.help_query.pop();
+    }
+    Key::Char(c) => app.help_query.push(c),
+    _ => return,
+  }
+  app.update_help_docs();
+}
+
+fn handle_detail_search(key: Key, app: &mut App) {
+  let mut search = app.data.describe_out.search().to_owned();
+  match key {
+    Key::Esc => {
+      app.is_detail_searching = false;
+      search.clear();
+    }
+    Key::Enter => {
+      app.is_detail_searching = false;
+      return;
+    }
+    Key::Backspace => {
+      search.pop();
+    }
+    Key::Char(c) => search.push(c),
+    _ => return,
+  }
+  app.data.describe_out.set_search(search);
+}
+
 fn handle_escape(app: &mut App) {
   // dismiss error
   if !app.api_error.is_empty() {
     app.api_error = String::default();
   }
   match app.get_current_route().id {
+    // a help search is cleared before leaving the help page
+    RouteId::HelpMenu if !app.help_query.is_empty() => {
+      app.help_query.clear();
+      app.update_help_docs();
+    }
     RouteId::HelpMenu => {
       app.pop_navigation_stack();
     }
+    // a describe or YAML search is cleared before leaving the view
+    _ if matches!(
+      app.get_current_route().active_block,
+      ActiveBlock::Describe | ActiveBlock::Yaml
+    ) && !app.data.describe_out.search().is_empty() =>
+    {
+      app.data.describe_out.set_search(String::new());
+    }
     _ => match app.get_current_route().active_block {
       ActiveBlock::Namespaces
       | ActiveBlock::Logs
       | ActiveBlock::Containers
+      | ActiveBlock::HelmHistory
+      | ActiveBlock::Columns
       | ActiveBlock::Yaml
       | ActiveBlock::Describe => {
         app.pop_navigation_stack();
@@ -106,17 +482,58 @@ where
   T: KubeResource<S> + 'static,
   S: Serialize,
 {
-  if key == DEFAULT_KEYBINDING.describe_resource.key {
+  if key_bindings().describe_resource.matches(key) {
     app.data.describe_out = ScrollableTxt::new();
     app.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
     app.dispatch_cmd(action).await;
     true
-  } else if key == DEFAULT_KEYBINDING.resource_yaml.key {
-    let yaml = res.resource_to_yaml();
-    app.data.describe_out = ScrollableTxt::with_string(yaml);
+  } else if key_bindings().resource_yaml.matches(key) {
+    let manifest = app.manifest_format.render(res.resource_to_yaml());
+    app.data.describe_out = ScrollableTxt::with_string(manifest);
     app.push_navigation_stack(RouteId::Home, ActiveBlock::Yaml);
     true
-  } else if key == DEFAULT_KEYBINDING.decode_secret.key {
+  } else if key_bindings().pipe_manifest.matches(key) {
+    match app.pipe_command.clone() {
+      Some(command) => {
+        if app.ensure_writable() {
+          app.data.describe_out = ScrollableTxt::new();
+          app.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
+          app
+            .dispatch_cmd(IoCmdEvent::PipeManifest {
+              command,
+              manifest: res.resource_to_yaml(),
+            })
+            .await;
+        }
+      }
+      None => app.notify(
+        NotificationLevel::Info,
+        "Set pipe_command in the config to pipe manifests to a command",
+      ),
+    }
+    true
+  } else if key_bindings().diff_last_applied.matches(key) {
+    match res.last_applied_manifests() {
+      Some((last_applied, live)) => {
+        if app.ensure_writable() {
+          app.data.describe_out = ScrollableTxt::new();
+          app.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
+          app
+            .dispatch_cmd(IoCmdEvent::DiffLastApplied {
+              command: app.diff_command.clone(),
+              last_applied,
+              live,
+            })
+            .await;
+        }
+      }
+      None => app.notify(
+        NotificationLevel::Info,
+        "The resource has no last applied configuration",
+      ),
+    }
+    true
+  } else if key_bindings().decode_secret.matches(key) {
     // make sure the resources is of type 'KubeSecret'
     let of_any = res as &dyn std::any::Any;
     if let Some(secret) = of_any.downcast_ref::<KubeSecret>() {
@@ -156,63 +573,143 @@ async fn handle_route_events(key: Key, app: &mut App) {
     // handle resource tabs on overview
     RouteId::Home => {
       match key {
-        _ if key == DEFAULT_KEYBINDING.right.key
-          || key == DEFAULT_KEYBINDING.right.alt.unwrap() =>
-        {
+        _ if app.has_plugin(key) => app.run_plugin(key).await,
+        _ if key_bindings().right.matches(key) => {
           app.context_tabs.next();
           app.push_navigation_route(app.context_tabs.get_active_route().clone());
         }
-        _ if key == DEFAULT_KEYBINDING.left.key || key == DEFAULT_KEYBINDING.left.alt.unwrap() => {
+        _ if key_bindings().left.matches(key) => {
           app.context_tabs.previous();
           app.push_navigation_route(app.context_tabs.get_active_route().clone());
         }
-        _ if key == DEFAULT_KEYBINDING.toggle_info.key => {
+        _ if key_bindings().toggle_info.matches(key) => {
           app.show_info_bar = !app.show_info_bar;
         }
-        _ if key == DEFAULT_KEYBINDING.select_all_namespace.key => app.data.selected.ns = None,
-        _ if key == DEFAULT_KEYBINDING.jump_to_namespace.key => {
-          if app.get_current_route().active_block != ActiveBlock::Namespaces {
-            app.push_navigation_stack(RouteId::Home, ActiveBlock::Namespaces);
+        _ if key_bindings().select_all_namespace.matches(key) => app.data.selected.ns = None,
+        _ if key_bindings().search.matches(key) => match app.get_current_route().active_block {
+          ActiveBlock::Describe | ActiveBlock::Yaml => app.is_detail_searching = true,
+          _ => app.open_filter(),
+        },
+        _ if key_bindings().cycle_timestamp_format.matches(key) => {
+          app.timestamp_format = app.timestamp_format.next();
+          app.notify(
+            NotificationLevel::Info,
+            format!("{:?} timestamps", app.timestamp_format),
+          );
+        }
+        _ if key_bindings().cycle_sort.matches(key) => {
+          let block = app.get_current_route().active_block;
+          if let Some(table) = app.data.resource_table(block) {
+            table.sort_by_next_column();
+          }
+        }
+        _ if key_bindings().scroll_left.matches(key)
+          || key_bindings().scroll_right.matches(key) =>
+        {
+          handle_horizontal_scroll(app, key_bindings().scroll_right.matches(key));
+        }
+        _ if key_bindings().cycle_split_pane.matches(key) => app.cycle_split_pane(),
+        _ if key_bindings().mark_row.matches(key) || key_bindings().mark_all_rows.matches(key) => {
+          let block = app.get_current_route().active_block;
+          if let Some(table) = app.data.resource_table(block) {
+            if key_bindings().mark_row.matches(key) {
+              table.toggle_mark();
+            } else {
+              table.toggle_mark_all();
+            }
+          }
+        }
+        _ if key_bindings().copy_name.matches(key)
+          || key_bindings().copy_namespaced_name.matches(key) =>
+        {
+          let block = app.get_current_route().active_block;
+          if let Some((namespace, name)) = app
+            .data
+            .resource_table(block)
+            .and_then(|t| t.selected_key())
+          {
+            let content =
+              if key_bindings().copy_namespaced_name.matches(key) && !namespace.is_empty() {
+                format!("{}/{}", namespace, name)
+              } else {
+                name
+              };
+            copy_to_clipboard(app, content);
+          }
+        }
+        _ if key_bindings().export_yaml.matches(key) => app.open_export(),
+        _ if key_bindings().export_csv.matches(key) => app.open_table_export(),
+        _ if key_bindings().toggle_json.matches(key) => app.toggle_manifest_format(),
+        _ if key_bindings().copy_cell.matches(key) => {
+          let block = app.get_current_route().active_block;
+          if let Some(table) = app.data.resource_table(block) {
+            let cells = table.selected_cells();
+            if !cells.is_empty() {
+              app.cell_picker = Some(StatefulList::with_items(cells));
+            }
+          }
+        }
+        _ if key_bindings().pick_columns.matches(key)
+          && app.get_current_route().active_block != ActiveBlock::Columns =>
+        {
+          handle_column_picker(app);
+        }
+        _ if key_bindings().jump_to_namespace.matches(key)
+          && app.get_current_route().active_block != ActiveBlock::Namespaces =>
+        {
+          app.push_navigation_stack(RouteId::Home, ActiveBlock::Namespaces);
+        }
+        // number keys select pinned namespaces while the namespaces block is active
+        Key::Char(c @ '1'..='9')
+          if app.get_current_route().active_block == ActiveBlock::Namespaces =>
+        {
+          let index = c as usize - '1' as usize;
+          if let Some(ns) = app.pinned_namespaces.get(index).cloned() {
+            if app.data.namespaces.items.iter().any(|n| n.name == ns) {
+              app.data.selected.ns = Some(ns);
+              app.cache_all_resource_data().await;
+              app.pop_navigation_stack();
+            }
           }
         }
         // as these are tabs with index the order here matters, atleast for readability
-        _ if key == DEFAULT_KEYBINDING.jump_to_pods.key => {
+        _ if key_bindings().jump_to_pods.matches(key) => {
           let route = app.context_tabs.set_index(0).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_services.key => {
+        _ if key_bindings().jump_to_services.matches(key) => {
           let route = app.context_tabs.set_index(1).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_nodes.key => {
+        _ if key_bindings().jump_to_nodes.matches(key) => {
           let route = app.context_tabs.set_index(2).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_configmaps.key => {
+        _ if key_bindings().jump_to_configmaps.matches(key) => {
           let route = app.context_tabs.set_index(3).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_statefulsets.key => {
+        _ if key_bindings().jump_to_statefulsets.matches(key) => {
           let route = app.context_tabs.set_index(4).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_replicasets.key => {
+        _ if key_bindings().jump_to_replicasets.matches(key) => {
           let route = app.context_tabs.set_index(5).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_deployments.key => {
+        _ if key_bindings().jump_to_deployments.matches(key) => {
           let route = app.context_tabs.set_index(6).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_jobs.key => {
+        _ if key_bindings().jump_to_jobs.matches(key) => {
           let route = app.context_tabs.set_index(7).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_daemonsets.key => {
+        _ if key_bindings().jump_to_daemonsets.matches(key) => {
           let route = app.context_tabs.set_index(8).route.clone();
           app.push_navigation_route(route);
         }
-        _ if key == DEFAULT_KEYBINDING.jump_to_more_resources.key => {
+        _ if key_bindings().jump_to_more_resources.matches(key) => {
           let route = app.context_tabs.set_index(9).route.clone();
           app.push_navigation_route(route);
         }
@@ -222,7 +719,9 @@ async fn handle_route_events(key: Key, app: &mut App) {
       // handle block specific stuff
       match app.get_current_route().active_block {
         ActiveBlock::Namespaces => {
-          if let Some(ns) = handle_block_action(key, &mut app.data.namespaces) {
+          if key_bindings().pin_namespace.matches(key) {
+            app.toggle_pinned_namespace();
+          } else if let Some(ns) = handle_block_action(key, &mut app.data.namespaces) {
             app.data.selected.ns = Some(ns.name);
             app.cache_all_resource_data().await;
             app.pop_navigation_stack();
@@ -259,10 +758,13 @@ async fn handle_route_events(key: Key, app: &mut App) {
             if !ok {
               app.push_navigation_stack(RouteId::Home, ActiveBlock::Containers);
               app.data.selected.pod = Some(pod.name);
-              app.data.containers.set_items(pod.containers);
+              app.data.containers.set_sorted_items(pod.containers);
             }
           }
         }
+        ActiveBlock::Containers if key_bindings().attach.matches(key) => {
+          app.attach_to_container();
+        }
         ActiveBlock::Containers => {
           if let Some(c) = handle_block_action(key, &mut app.data.containers) {
             app.data.selected.container = Some(c.name.clone());
@@ -270,15 +772,34 @@ async fn handle_route_events(key: Key, app: &mut App) {
           }
         }
         ActiveBlock::Logs => {
-          if key == DEFAULT_KEYBINDING.log_auto_scroll.key {
+          if key_bindings().log_auto_scroll.matches(key) {
             app.log_auto_scroll = !app.log_auto_scroll;
-          } else if key == DEFAULT_KEYBINDING.copy_to_clipboard.key {
-            copy_to_clipboard(app.data.logs.get_plain_text());
+          } else if key_bindings().copy_to_clipboard.matches(key) {
+            copy_to_clipboard(app, app.data.logs.get_plain_text());
           }
         }
         ActiveBlock::Describe | ActiveBlock::Yaml => {
-          if key == DEFAULT_KEYBINDING.copy_to_clipboard.key {
-            copy_to_clipboard(app.data.describe_out.get_txt());
+          if key_bindings().copy_to_clipboard.matches(key) {
+            copy_to_clipboard(app, app.data.describe_out.get_txt());
+          } else if key_bindings().next_match.matches(key) {
+            app.data.describe_out.next_match(true);
+          } else if key_bindings().previous_match.matches(key) {
+            app.data.describe_out.next_match(false);
+          }
+        }
+        ActiveBlock::Services if key_bindings().open_url.matches(key) => {
+          let url = app
+            .data
+            .services
+            .get_selected_item_copy()
+            .map(|svc| svc.url());
+          match url {
+            Some(Some(url)) => app.dispatch_cmd(IoCmdEvent::OpenUrl(url)).await,
+            Some(None) => app.notify(
+              NotificationLevel::Info,
+              "Only LoadBalancer services with an external address can be opened",
+            ),
+            None => {}
           }
         }
         ActiveBlock::Services => {
@@ -386,8 +907,20 @@ async fn handle_route_events(key: Key, app: &mut App) {
             .await;
           }
         }
+        ActiveBlock::Columns => {
+          if key_bindings().submit.matches(key) {
+            if let Some(i) = app.column_picker.state.selected() {
+              let column = app.column_picker.items[i].0.clone();
+              let block = app.get_prev_route().active_block;
+              if let Some(table) = app.data.resource_table(block) {
+                table.toggle_column(&column);
+                app.column_picker.items = table.column_choices();
+              }
+            }
+          }
+        }
         ActiveBlock::More => {
-          if key == DEFAULT_KEYBINDING.submit.key {
+          if key_bindings().submit.matches(key) {
             if let Some((_title, active_block)) = app
               .more_resources_menu
               .state
@@ -521,17 +1054,85 @@ async fn handle_route_events(key: Key, app: &mut App) {
             .await;
           }
         }
-        ActiveBlock::Contexts | ActiveBlock::Utilization | ActiveBlock::Help => { /* Do nothing */ }
+        ActiveBlock::HelmReleases
+          if key_bindings().helm_uninstall.matches(key)
+            || key_bindings().helm_uninstall_keep_history.matches(key) =>
+        {
+          app
+            .uninstall_helm_release(key_bindings().helm_uninstall_keep_history.matches(key))
+            .await;
+        }
+        ActiveBlock::HelmReleases if key_bindings().helm_values.matches(key) => {
+          if let Some(release) = app.data.helm_releases.get_selected_item_copy() {
+            app.show_helm_values(&release);
+          }
+        }
+        ActiveBlock::HelmReleases => {
+          if let Some(res) = handle_block_action(key, &mut app.data.helm_releases) {
+            // the secret keeping the latest revision of the release
+            let ok = handle_describe_decode_or_yaml_action(
+              key,
+              app,
+              &res,
+              IoCmdEvent::GetDescribe {
+                kind: "secret".to_owned(),
+                value: res.get_k8s_obj().metadata.name.clone().unwrap_or_default(),
+                ns: Some(res.namespace.to_owned()),
+              },
+            )
+            .await;
+            if !ok {
+              app.open_helm_history(&res);
+            }
+          }
+        }
+        ActiveBlock::HelmHistory if key_bindings().helm_values.matches(key) => {
+          if let Some(revision) = app.data.helm_history.get_selected_item_copy() {
+            app.show_helm_values(&revision);
+          }
+        }
+        ActiveBlock::HelmHistory if key_bindings().diff_last_applied.matches(key) => {
+          app.diff_helm_revisions().await;
+        }
+        ActiveBlock::HelmHistory if key_bindings().helm_rollback.matches(key) => {
+          app.rollback_helm_release().await;
+        }
+        ActiveBlock::CustomView(i) => {
+          let selected = app
+            .data
+            .custom_objects
+            .get_mut(&i)
+            .and_then(|table| handle_block_action(key, table));
+          if let Some(res) = selected {
+            let _ok = handle_describe_decode_or_yaml_action(
+              key,
+              app,
+              &res,
+              IoCmdEvent::GetDescribe {
+                kind: res.resource.to_owned(),
+                value: res.name.to_owned(),
+                ns: (!res.namespace.is_empty()).then(|| res.namespace.to_owned()),
+              },
+            )
+            .await;
+          }
+        }
+        ActiveBlock::Contexts
+        | ActiveBlock::Utilization
+        | ActiveBlock::MetricsHistory
+        | ActiveBlock::Fleet
+        | ActiveBlock::FleetEvents
+        | ActiveBlock::HelmHistory
+        | ActiveBlock::Help => { /* Do nothing */ }
       }
     }
     RouteId::Contexts => {
       if let Some(ctx) = handle_block_action(key, &mut app.data.contexts) {
-        app.data.selected.context = Some(ctx.name);
-        app.refresh();
+        app.switch_context(ctx.name);
       }
     }
     RouteId::Utilization => {
-      if key == DEFAULT_KEYBINDING.cycle_group_by.key {
+      if key_bindings().cycle_group_by.matches(key) {
         if app.utilization_group_by.len() == 1 {
           app.utilization_group_by = vec![
             GroupBy::resource,
@@ -546,20 +1147,43 @@ async fn handle_route_events(key: Key, app: &mut App) {
         app.tick_count = 0; // to force network request
       }
     }
-    RouteId::HelpMenu => { /* Do nothing */ }
+    RouteId::HelpMenu => {
+      if key_bindings().search.matches(key) {
+        app.is_help_searching = true;
+      }
+    }
+    RouteId::MetricsHistory => { /* Do nothing */ }
+    RouteId::Fleet => {
+      let context = match app.get_current_route().active_block {
+        ActiveBlock::FleetEvents => {
+          handle_block_action(key, &mut app.fleet_events).map(|event| event.context)
+        }
+        _ if key_bindings().fleet_events.matches(key) => {
+          app.push_navigation_stack(RouteId::Fleet, ActiveBlock::FleetEvents);
+          None
+        }
+        _ => handle_block_action(key, &mut app.fleet).map(|cluster| cluster.context),
+      };
+      if let Some(context) = context {
+        app.switch_context(context);
+        app.route_home();
+      }
+    }
   }
   // reset tick_count so that network requests are made faster
-  if key == DEFAULT_KEYBINDING.submit.key {
+  if key_bindings().submit.matches(key) {
     app.tick_count = 0;
   }
 }
 
 fn handle_block_action<T: Clone>(key: Key, item: &mut StatefulTable<T>) -> Option<T> {
   match key {
-    _ if key == DEFAULT_KEYBINDING.submit.key
-      || key == DEFAULT_KEYBINDING.describe_resource.key
-      || key == DEFAULT_KEYBINDING.resource_yaml.key
-      || key == DEFAULT_KEYBINDING.decode_secret.key =>
+    _ if key_bindings().submit.matches(key)
+      || key_bindings().describe_resource.matches(key)
+      || key_bindings().resource_yaml.matches(key)
+      || key_bindings().pipe_manifest.matches(key)
+      || key_bindings().diff_last_applied.matches(key)
+      || key_bindings().decode_secret.matches(key) =>
     {
       item.get_selected_item_copy()
     }
@@ -567,55 +1191,244 @@ fn handle_block_action<T: Clone>(key: Key, item: &mut StatefulTable<T>) -> Optio
   }
 }
 
-async fn handle_block_scroll(app: &mut App, up: bool, is_mouse: bool, page: bool) {
+/// select the table row at the clicked position, returns whether the row was already selected
+fn handle_table_click<T>(table: &mut StatefulTable<T>, column: u16, row: u16) -> Option<bool> {
+  table.row_at(column, row).map(|index| {
+    let clicked_again = table.state.selected() == Some(index);
+    table.state.select(Some(index));
+    clicked_again
+  })
+}
+
+fn handle_block_click(app: &mut App, block: ActiveBlock, column: u16, row: u16) -> Option<bool> {
+  match block {
+    ActiveBlock::Namespaces => handle_table_click(&mut app.data.namespaces, column, row),
+    ActiveBlock::Pods => handle_table_click(&mut app.data.pods, column, row),
+    ActiveBlock::Containers => handle_table_click(&mut app.data.containers, column, row),
+    ActiveBlock::Services => handle_table_click(&mut app.data.services, column, row),
+    ActiveBlock::Nodes => handle_table_click(&mut app.data.nodes, column, row),
+    ActiveBlock::ConfigMaps => handle_table_click(&mut app.data.config_maps, column, row),
+    ActiveBlock::StatefulSets => handle_table_click(&mut app.data.stateful_sets, column, row),
+    ActiveBlock::ReplicaSets => handle_table_click(&mut app.data.replica_sets, column, row),
+    ActiveBlock::Deployments => handle_table_click(&mut app.data.deployments, column, row),
+    ActiveBlock::Jobs => handle_table_click(&mut app.data.jobs, column, row),
+    ActiveBlock::DaemonSets => handle_table_click(&mut app.data.daemon_sets, column, row),
+    ActiveBlock::CronJobs => handle_table_click(&mut app.data.cronjobs, column, row),
+    ActiveBlock::Secrets => handle_table_click(&mut app.data.secrets, column, row),
+    ActiveBlock::RplCtrl => handle_table_click(&mut app.data.rpl_ctrls, column, row),
+    ActiveBlock::StorageClasses => handle_table_click(&mut app.data.storage_classes, column, row),
+    ActiveBlock::Roles => handle_table_click(&mut app.data.roles, column, row),
+    ActiveBlock::RoleBindings => handle_table_click(&mut app.data.role_bindings, column, row),
+    ActiveBlock::ClusterRoles => handle_table_click(&mut app.data.cluster_roles, column, row),
+    ActiveBlock::ClusterRoleBinding => {
+      handle_table_click(&mut app.data.cluster_role_binding, column, row)
+    }
+    ActiveBlock::HelmReleases => handle_table_click(&mut app.data.helm_releases, column, row),
+    ActiveBlock::HelmHistory => handle_table_click(&mut app.data.helm_history, column, row),
+    ActiveBlock::CustomView(i) => app
+      .data
+      .custom_objects
+      .get_mut(&i)
+      .and_then(|table| handle_table_click(table, column, row)),
+    ActiveBlock::Contexts => handle_table_click(&mut app.data.contexts, column, row),
+    ActiveBlock::Fleet => handle_table_click(&mut app.fleet, column, row),
+    ActiveBlock::FleetEvents => handle_table_click(&mut app.fleet_events, column, row),
+    ActiveBlock::Help => handle_table_click(&mut app.help_docs, column, row),
+    // utilization rows do not map one to one to items and the rest are not tables
+    ActiveBlock::Utilization
+    | ActiveBlock::More
+    | ActiveBlock::Columns
+    | ActiveBlock::MetricsHistory
+    | ActiveBlock::Logs
+    | ActiveBlock::Describe
+    | ActiveBlock::Yaml => None,
+  }
+}
+
+fn handle_horizontal_scroll(app: &mut App, right: bool) {
+  match app.get_current_route().active_block {
+    ActiveBlock::Describe | ActiveBlock::Yaml => {
+      app.data.describe_out.scroll_horizontal(right, 10);
+    }
+    block => {
+      if let Some(table) = app.data.resource_table(block) {
+        table.scroll_columns(right);
+      }
+    }
+  }
+}
+
+fn handle_column_picker(app: &mut App) {
+  let block = app.get_current_route().active_block;
+  if let Some(table) = app.data.resource_table(block) {
+    let choices = table.column_choices();
+    if !choices.is_empty() {
+      app.column_picker = StatefulList::with_items(choices);
+      app.push_navigation_stack(RouteId::Home, ActiveBlock::Columns);
+    }
+  }
+}
+
+async fn handle_block_scroll(app: &mut App, up: bool, is_mouse: bool, step: ScrollStep) {
   match app.get_current_route().active_block {
-    ActiveBlock::Namespaces => app.data.namespaces.handle_scroll(up, page),
-    ActiveBlock::Pods => app.data.pods.handle_scroll(up, page),
-    ActiveBlock::Containers => app.data.containers.handle_scroll(up, page),
-    ActiveBlock::Services => app.data.services.handle_scroll(up, page),
-    ActiveBlock::Nodes => app.data.nodes.handle_scroll(up, page),
-    ActiveBlock::ConfigMaps => app.data.config_maps.handle_scroll(up, page),
-    ActiveBlock::StatefulSets => app.data.stateful_sets.handle_scroll(up, page),
-    ActiveBlock::ReplicaSets => app.data.replica_sets.handle_scroll(up, page),
-    ActiveBlock::Deployments => app.data.deployments.handle_scroll(up, page),
-    ActiveBlock::Jobs => app.data.jobs.handle_scroll(up, page),
-    ActiveBlock::DaemonSets => app.data.daemon_sets.handle_scroll(up, page),
-    ActiveBlock::CronJobs => app.data.cronjobs.handle_scroll(up, page),
-    ActiveBlock::Secrets => app.data.secrets.handle_scroll(up, page),
-    ActiveBlock::RplCtrl => app.data.rpl_ctrls.handle_scroll(up, page),
-    ActiveBlock::StorageClasses => app.data.storage_classes.handle_scroll(up, page),
-    ActiveBlock::Roles => app.data.roles.handle_scroll(up, page),
-    ActiveBlock::RoleBindings => app.data.role_bindings.handle_scroll(up, page),
-    ActiveBlock::ClusterRoles => app.data.cluster_roles.handle_scroll(up, page),
-    ActiveBlock::ClusterRoleBinding => app.data.cluster_role_binding.handle_scroll(up, page),
-    ActiveBlock::Contexts => app.data.contexts.handle_scroll(up, page),
-    ActiveBlock::Utilization => app.data.metrics.handle_scroll(up, page),
-    ActiveBlock::Help => app.help_docs.handle_scroll(up, page),
-    ActiveBlock::More => app.more_resources_menu.handle_scroll(up, page),
+    ActiveBlock::Namespaces => app.data.namespaces.handle_scroll(up, step),
+    ActiveBlock::Pods => app.data.pods.handle_scroll(up, step),
+    ActiveBlock::Containers => app.data.containers.handle_scroll(up, step),
+    ActiveBlock::Services => app.data.services.handle_scroll(up, step),
+    ActiveBlock::Nodes => app.data.nodes.handle_scroll(up, step),
+    ActiveBlock::ConfigMaps => app.data.config_maps.handle_scroll(up, step),
+    ActiveBlock::StatefulSets => app.data.stateful_sets.handle_scroll(up, step),
+    ActiveBlock::ReplicaSets => app.data.replica_sets.handle_scroll(up, step),
+    ActiveBlock::Deployments => app.data.deployments.handle_scroll(up, step),
+    ActiveBlock::Jobs => app.data.jobs.handle_scroll(up, step),
+    ActiveBlock::DaemonSets => app.data.daemon_sets.handle_scroll(up, step),
+    ActiveBlock::CronJobs => app.data.cronjobs.handle_scroll(up, step),
+    ActiveBlock::Secrets => app.data.secrets.handle_scroll(up, step),
+    ActiveBlock::RplCtrl => app.data.rpl_ctrls.handle_scroll(up, step),
+    ActiveBlock::StorageClasses => app.data.storage_classes.handle_scroll(up, step),
+    ActiveBlock::Roles => app.data.roles.handle_scroll(up, step),
+    ActiveBlock::RoleBindings => app.data.role_bindings.handle_scroll(up, step),
+    ActiveBlock::ClusterRoles => app.data.cluster_roles.handle_scroll(up, step),
+    ActiveBlock::ClusterRoleBinding => app.data.cluster_role_binding.handle_scroll(up, step),
+    ActiveBlock::HelmReleases => app.data.helm_releases.handle_scroll(up, step),
+    ActiveBlock::HelmHistory => app.data.helm_history.handle_scroll(up, step),
+    ActiveBlock::CustomView(i) => {
+      if let Some(table) = app.data.custom_objects.get_mut(&i) {
+        table.handle_scroll(up, step);
+      }
+    }
+    ActiveBlock::Contexts => app.data.contexts.handle_scroll(up, step),
+    ActiveBlock::Fleet => app.fleet.handle_scroll(up, step),
+    ActiveBlock::FleetEvents => app.fleet_events.handle_scroll(up, step),
+    ActiveBlock::Utilization => app.data.metrics.handle_scroll(up, step),
+    ActiveBlock::Help => app.help_docs.handle_scroll(up, step),
+    ActiveBlock::More => app.more_resources_menu.handle_scroll(up, step),
+    ActiveBlock::Columns => app.column_picker.handle_scroll(up, step),
+    ActiveBlock::MetricsHistory => { /* Nothing to scroll */ }
     ActiveBlock::Logs => {
       app.log_auto_scroll = false;
-      app.data.logs.handle_scroll(inverse_dir(up, is_mouse), page);
+      app.data.logs.handle_scroll(inverse_dir(up, is_mouse), step);
     }
     ActiveBlock::Describe | ActiveBlock::Yaml => app
       .data
       .describe_out
-      .handle_scroll(inverse_dir(up, is_mouse), page),
+      .handle_scroll(inverse_dir(up, is_mouse), step),
+  }
+}
+
+/// select a row or scroll to a line of the active block, past the end goes to the last one.
+/// Following the logs is the way to the end of them
+fn handle_scroll_to(app: &mut App, line: usize, follow_logs: bool) {
+  match app.get_current_route().active_block {
+    ActiveBlock::Logs => {
+      app.log_auto_scroll = follow_logs;
+      if !follow_logs {
+        app.data.logs.select_line(line);
+      }
+    }
+    ActiveBlock::Describe | ActiveBlock::Yaml => app.data.describe_out.scroll_to_line(line),
+    ActiveBlock::Help => {
+      let last = app.help_docs.items.len().saturating_sub(1);
+      app.help_docs.state.select(Some(line.min(last)));
+    }
+    block => {
+      if let Some(table) = app.data.resource_table(block) {
+        // rows past the end are not selected, leaving the last one selected
+        table.select_edge(true);
+        table.select_row(line);
+      }
+    }
+  }
+}
+
+/// digits typed before a scroll key are a count, except where they are bound like the resource
+/// tab jumps on the home route. A count can't start with 0
+fn count_digit(app: &App, key: Key) -> Option<usize> {
+  let digit = match key {
+    Key::Char(c) => c.to_digit(10)? as usize,
+    _ => return None,
+  };
+  if app.pending_count > 0 {
+    return Some(digit);
+  }
+  if digit == 0 {
+    return None;
+  }
+  let route = app.get_current_route();
+  let free = match (&route.id, route.active_block) {
+    (RouteId::Home, ActiveBlock::Describe | ActiveBlock::Yaml | ActiveBlock::Logs) => true,
+    (RouteId::Home, ActiveBlock::Namespaces) => false,
+    (RouteId::Home, _) => {
+      let kb = key_bindings();
+      ![
+        &kb.jump_to_pods,
+        &kb.jump_to_services,
+        &kb.jump_to_nodes,
+        &kb.jump_to_configmaps,
+        &kb.jump_to_statefulsets,
+        &kb.jump_to_replicasets,
+        &kb.jump_to_deployments,
+        &kb.jump_to_jobs,
+        &kb.jump_to_daemonsets,
+        &kb.jump_to_more_resources,
+      ]
+      .iter()
+      .any(|binding| binding.matches(key))
+    }
+    _ => true,
+  };
+  free.then_some(digit)
+}
+
+fn copy_to_clipboard(app: &mut App, content: String) {
+  let result = if app.osc52_clipboard {
+    set_clipboard_osc52(&content)
+  } else {
+    set_clipboard(content)
+  };
+  match result {
+    Ok(()) => app.notify(NotificationLevel::Success, "Copied to clipboard"),
+    Err(e) => app.notify(
+      NotificationLevel::Failure,
+      format!("Unable to copy to clipboard. {}", e),
+    ),
+  }
+}
+
+/// copy through the terminal, which works over SSH where the system clipboard is out of reach
+fn set_clipboard_osc52(content: &str) -> Result<(), Box<dyn std::error::Error>> {
+  use std::io::Write;
+
+  let sequence = osc52_sequence(content, std::env::var_os("TMUX").is_some());
+  let mut stdout = std::io::stdout();
+  stdout.write_all(sequence.as_bytes())?;
+  stdout.flush()?;
+  Ok(())
+}
+
+/// the OSC 52 escape sequence setting the clipboard, tmux passes it through to the outer
+/// terminal when it is wrapped in a DCS sequence
+fn osc52_sequence(content: &str, tmux: bool) -> String {
+  let sequence = format!("\x1b]52;c;{}\x07", base64::encode(content));
+  if tmux {
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+  } else {
+    sequence
   }
 }
 
 #[cfg(target_arch = "x86_64")]
-fn copy_to_clipboard(content: String) {
+fn set_clipboard(content: String) -> Result<(), Box<dyn std::error::Error>> {
   use clipboard::{ClipboardContext, ClipboardProvider};
 
-  let mut ctx: ClipboardContext = ClipboardProvider::new().expect("Unable to obtain clipboard");
-  ctx
-    .set_contents(content)
-    .expect("Unable to set content to clipboard");
+  let mut ctx: ClipboardContext = ClipboardProvider::new()?;
+  ctx.set_contents(content)
 }
 
 #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
-fn copy_to_clipboard(_content: String) {
-  // do nothing as its a PITA to compile for ARM with XCB and this feature is not that important
+fn set_clipboard(_content: String) -> Result<(), Box<dyn std::error::Error>> {
+  // its a PITA to compile for ARM with XCB and this feature is not that important
+  Err("not supported on this platform".into())
 }
 
 /// inverse direction for natural scrolling on mouse and keyboard
@@ -629,10 +1442,408 @@ fn inverse_dir(up: bool, is_mouse: bool) -> bool {
 
 #[cfg(test)]
 mod tests {
+  use std::time::{Duration, Instant};
+
   use k8s_openapi::ByteString;
+  use tui::layout::Rect;
 
   use super::*;
-  use crate::app::{contexts::KubeContext, pods::KubePod};
+  use crate::app::{contexts::KubeContext, models::ResourceTable, ns::KubeNs, pods::KubePod};
+
+  #[test]
+  fn test_osc52_sequence() {
+    assert_eq!(osc52_sequence("web-0", false), "\x1b]52;c;d2ViLTA=\x07");
+    assert_eq!(
+      osc52_sequence("web-0", true),
+      "\x1bPtmux;\x1b\x1b]52;c;d2ViLTA=\x07\x1b\\"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_handle_help_search() {
+    let mut app = App::default();
+    handle_key_events(key_bindings().help.key, &mut app).await;
+    let all = app.help_docs.items.len();
+
+    handle_key_events(Key::Char('/'), &mut app).await;
+    assert!(app.is_help_searching);
+    // keys bound to actions are typed into the search
+    for c in "quit".chars() {
+      handle_key_events(Key::Char(c), &mut app).await;
+    }
+    assert!(!app.should_quit);
+    assert_eq!(app.help_query, "quit");
+    assert_eq!(app.help_docs.items.len(), 2);
+
+    handle_key_events(Key::Enter, &mut app).await;
+    assert!(!app.is_help_searching);
+    assert_eq!(app.help_docs.items.len(), 2);
+
+    // esc clears the search first and then leaves the help page
+    handle_key_events(Key::Esc, &mut app).await;
+    assert_eq!(app.help_docs.items.len(), all);
+    assert_eq!(app.get_current_route().id, RouteId::HelpMenu);
+    handle_key_events(Key::Esc, &mut app).await;
+    assert_ne!(app.get_current_route().id, RouteId::HelpMenu);
+  }
+
+  #[tokio::test]
+  async fn test_handle_detail_search() {
+    let mut app = App::default();
+    app.route_home();
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Yaml);
+    app.data.describe_out = ScrollableTxt::with_string(
+      "kind: Pod\nmetadata:\n  name: web\nspec:\n  hostname: web".into(),
+    );
+
+    handle_key_events(key_bindings().search.key, &mut app).await;
+    assert!(app.is_detail_searching);
+    assert!(app.filter_input.is_none());
+    for c in "web".chars() {
+      handle_key_events(Key::Char(c), &mut app).await;
+    }
+    assert_eq!(app.data.describe_out.offset, 2);
+    handle_key_events(Key::Enter, &mut app).await;
+    assert!(!app.is_detail_searching);
+
+    handle_key_events(key_bindings().next_match.key, &mut app).await;
+    assert_eq!(app.data.describe_out.offset, 4);
+    handle_key_events(key_bindings().previous_match.key, &mut app).await;
+    assert_eq!(app.data.describe_out.offset, 2);
+
+    // esc clears the search first and then leaves the view
+    handle_key_events(Key::Esc, &mut app).await;
+    assert_eq!(app.data.describe_out.search_position(), None);
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Yaml);
+    handle_key_events(Key::Esc, &mut app).await;
+    assert_ne!(app.get_current_route().active_block, ActiveBlock::Yaml);
+  }
+
+  #[tokio::test]
+  async fn test_handle_vim_navigation() {
+    let mut app = App::default();
+    app.route_home();
+    app
+      .data
+      .pods
+      .set_items((0..30).map(|_| KubePod::default()).collect());
+    app.data.pods.set_view(Rect::new(0, 0, 10, 10));
+
+    // digits jump to resource tabs on tables, so counts are used in the detail views
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
+    app.data.describe_out = ScrollableTxt::with_string(
+      (0..40)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join("\n"),
+    );
+    for key in [Key::Char('1'), Key::Char('5'), Key::Char('j')] {
+      handle_key_events(key, &mut app).await;
+    }
+    assert_eq!(app.data.describe_out.offset, 15);
+    assert_eq!(app.pending_count, 0);
+    handle_key_events(Key::Char('G'), &mut app).await;
+    assert_eq!(app.data.describe_out.offset, 37);
+    handle_key_events(Key::Char('g'), &mut app).await;
+    assert_eq!(app.data.describe_out.offset, 37);
+    handle_key_events(Key::Char('g'), &mut app).await;
+    assert_eq!(app.data.describe_out.offset, 0);
+    for key in [Key::Char('3'), Key::Char('0'), Key::Char('G')] {
+      handle_key_events(key, &mut app).await;
+    }
+    assert_eq!(app.data.describe_out.offset, 29);
+
+    handle_key_events(Key::Esc, &mut app).await;
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Pods);
+    handle_key_events(key_bindings().half_page_down.key, &mut app).await;
+    assert_eq!(app.data.pods.state.selected(), Some(5));
+    handle_key_events(Key::Char('G'), &mut app).await;
+    assert_eq!(app.data.pods.state.selected(), Some(29));
+    handle_key_events(key_bindings().half_page_up.key, &mut app).await;
+    assert_eq!(app.data.pods.state.selected(), Some(24));
+    handle_key_events(Key::Char('g'), &mut app).await;
+    handle_key_events(Key::Char('g'), &mut app).await;
+    assert_eq!(app.data.pods.state.selected(), Some(0));
+    handle_key_events(Key::Char('2'), &mut app).await;
+    assert_eq!(app.pending_count, 0);
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Services);
+  }
+
+  #[tokio::test]
+  async fn test_handle_mark_rows() {
+    let mut app = App::default();
+    app.route_home();
+    let mut pods = vec![KubePod::default(), KubePod::default()];
+    pods[0].name = "pod-1".into();
+    pods[1].name = "pod-2".into();
+    app.data.pods.set_items(pods);
+
+    handle_key_events(key_bindings().mark_row.key, &mut app).await;
+    assert_eq!(app.data.pods.marked_items()[0].name, "pod-1");
+    handle_key_events(key_bindings().mark_all_rows.key, &mut app).await;
+    assert_eq!(app.data.pods.marked.len(), 2);
+    handle_key_events(key_bindings().mark_all_rows.key, &mut app).await;
+    assert!(app.data.pods.marked.is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_handle_pinned_namespace_keys() {
+    let mut app = App::default();
+    app.route_home();
+    let mut namespaces = vec![KubeNs::default(), KubeNs::default()];
+    namespaces[0].name = "default".into();
+    namespaces[1].name = "web".into();
+    app.set_namespaces(namespaces);
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Namespaces);
+
+    handle_key_events(Key::Down, &mut app).await;
+    handle_key_events(key_bindings().pin_namespace.key, &mut app).await;
+    assert_eq!(app.pinned_namespaces, vec!["web"]);
+    assert_eq!(app.data.namespaces.items[0].name, "web");
+
+    // unpinned numbers do nothing
+    handle_key_events(Key::Char('2'), &mut app).await;
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::Namespaces
+    );
+    handle_key_events(Key::Char('1'), &mut app).await;
+    assert_eq!(app.data.selected.ns, Some("web".into()));
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Pods);
+  }
+
+  #[tokio::test]
+  async fn test_handle_scroll_to_edges() {
+    let mut app = App::default();
+    app.route_home();
+    app.data.pods.set_items(vec![KubePod::default(); 30]);
+
+    handle_key_events(key_bindings().scroll_to_bottom.key, &mut app).await;
+    assert_eq!(app.data.pods.state.selected(), Some(29));
+    handle_key_events(key_bindings().pg_up.key, &mut app).await;
+    assert_eq!(app.data.pods.state.selected(), Some(19));
+    handle_key_events(key_bindings().scroll_to_top.key, &mut app).await;
+    assert_eq!(app.data.pods.state.selected(), Some(0));
+  }
+
+  #[tokio::test]
+  async fn test_handle_cell_picker() {
+    let mut app = App::default();
+    app.route_home();
+    handle_key_events(key_bindings().copy_cell.key, &mut app).await;
+    assert!(app.cell_picker.is_none());
+
+    let mut pod = KubePod::default();
+    pod.name = "pod-1".into();
+    app.data.pods.set_items(vec![pod]);
+    app.data.pods.set_columns(&["Namespace", "Name"]);
+    handle_key_events(key_bindings().copy_cell.key, &mut app).await;
+    let cells = &app.cell_picker.as_ref().unwrap().items;
+    assert_eq!(cells[0], ("Namespace".into(), String::new()));
+    assert_eq!(cells[1], ("Name".into(), "pod-1".into()));
+    // keys bound to actions are ignored while picking
+    handle_key_events(key_bindings().quit.key, &mut app).await;
+    assert!(!app.should_quit);
+    handle_key_events(Key::Down, &mut app).await;
+    assert_eq!(app.cell_picker.as_ref().unwrap().state.selected(), Some(1));
+    handle_key_events(Key::Esc, &mut app).await;
+    assert!(app.cell_picker.is_none());
+  }
+
+  #[tokio::test]
+  async fn test_handle_export_prompt() {
+    let mut app = App::default();
+    app.route_home();
+    let mut pod = KubePod::default();
+    pod.name = "web-1".into();
+    pod.namespace = "web".into();
+    app.data.pods.set_sorted_items(vec![pod]);
+
+    handle_key_events(key_bindings().export_yaml.key, &mut app).await;
+    let prompt = app.export_prompt.as_ref().unwrap();
+    assert_eq!(prompt.path, "./pod-web-1.yaml");
+    assert!(matches!(
+      prompt.target,
+      ExportTarget::Manifest {
+        strip_managed_fields: true,
+        ..
+      }
+    ));
+    // keys go to the prompt while it is open
+    handle_key_events(Key::Tab, &mut app).await;
+    for key in [Key::Backspace, Key::Backspace, Key::Backspace] {
+      handle_key_events(key, &mut app).await;
+    }
+    handle_key_events(Key::Char('m'), &mut app).await;
+    handle_key_events(Key::Char('l'), &mut app).await;
+    let prompt = app.export_prompt.as_ref().unwrap();
+    assert_eq!(prompt.path, "./pod-web-1.yml");
+    assert!(matches!(
+      prompt.target,
+      ExportTarget::Manifest {
+        strip_managed_fields: false,
+        ..
+      }
+    ));
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Pods);
+    handle_key_events(Key::Esc, &mut app).await;
+    assert!(app.export_prompt.is_none());
+
+    app.data.pods.set_columns(&["Namespace", "Name"]);
+    handle_key_events(key_bindings().export_csv.key, &mut app).await;
+    let prompt = app.export_prompt.as_ref().unwrap();
+    assert_eq!(prompt.path, "./pods.csv");
+    assert_eq!(
+      prompt.target,
+      ExportTarget::Table {
+        title: "Pods".into(),
+        headers: vec!["Namespace".into(), "Name".into()],
+        rows: vec![vec!["web".into(), "web-1".into()]],
+      }
+    );
+
+    handle_key_events(Key::Esc, &mut app).await;
+
+    app.export_path = Some("/tmp/kdash/{context}/{namespace}/{kind}-{name}".into());
+    handle_key_events(key_bindings().export_yaml.key, &mut app).await;
+    assert_eq!(
+      app.export_prompt.as_ref().unwrap().path,
+      "/tmp/kdash/default/web/pod-web-1.yaml"
+    );
+    handle_key_events(Key::Esc, &mut app).await;
+    handle_key_events(key_bindings().export_csv.key, &mut app).await;
+    assert_eq!(
+      app.export_prompt.as_ref().unwrap().path,
+      "/tmp/kdash/default/all/pods-table.csv"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_toggle_manifest_format() {
+    let mut app = App::default();
+    app.route_home();
+    let mut pod = KubePod::default();
+    pod.name = "web-1".into();
+    app.data.pods.set_sorted_items(vec![pod]);
+
+    handle_key_events(key_bindings().resource_yaml.key, &mut app).await;
+    assert!(!app.data.describe_out.get_txt().starts_with('{'));
+    handle_key_events(key_bindings().toggle_json.key, &mut app).await;
+    assert!(app.data.describe_out.get_txt().starts_with('{'));
+    assert_eq!(app.breadcrumbs().last().unwrap(), "JSON");
+
+    handle_key_events(key_bindings().export_yaml.key, &mut app).await;
+    assert_eq!(app.export_prompt.as_ref().unwrap().path, "./pod-web-1.json");
+    handle_key_events(Key::Esc, &mut app).await;
+    handle_key_events(key_bindings().toggle_json.key, &mut app).await;
+    assert!(!app.data.describe_out.get_txt().starts_with('{'));
+  }
+
+  #[tokio::test]
+  async fn test_handle_filter_input() {
+    let mut app = App::default();
+    app.route_home();
+    let pods = ["api-1", "web-1", "api-2"]
+      .into_iter()
+      .map(|name| {
+        let mut pod = KubePod::default();
+        pod.name = name.into();
+        pod
+      })
+      .collect();
+    app.data.pods.set_sorted_items(pods);
+    app.filter_history = vec!["web".into()];
+    app.saved_filters = vec![("first".into(), "api-1".into())];
+
+    handle_key_events(key_bindings().search.key, &mut app).await;
+    for c in "api".chars() {
+      handle_key_events(Key::Char(c), &mut app).await;
+    }
+    // the table is filtered while typing, the keys after the first are applied once typing paused
+    assert_eq!(app.data.pods.filter(), "a");
+    app.apply_pending_filter_input(Instant::now() + Duration::from_secs(1));
+    assert_eq!(app.data.pods.filter(), "api");
+    assert_eq!(app.data.pods.items.len(), 2);
+    handle_key_events(Key::Enter, &mut app).await;
+    assert!(app.filter_input.is_none());
+    assert_eq!(app.filter_history, vec!["api".to_owned(), "web".to_owned()]);
+
+    handle_key_events(key_bindings().search.key, &mut app).await;
+    assert_eq!(app.filter_input.as_ref().unwrap().input, "api");
+    handle_key_events(Key::Down, &mut app).await;
+    assert_eq!(app.filter_input.as_ref().unwrap().input, "api-1");
+    assert_eq!(app.data.pods.items.len(), 1);
+    handle_key_events(Key::Down, &mut app).await;
+    handle_key_events(Key::Down, &mut app).await;
+    assert_eq!(app.filter_input.as_ref().unwrap().input, "web");
+    assert_eq!(app.data.pods.items[0].name, "web-1");
+    // Esc restores the filter from before
+    handle_key_events(Key::Esc, &mut app).await;
+    assert_eq!(app.data.pods.filter(), "api");
+    assert_eq!(app.data.pods.items.len(), 2);
+
+    // a new field selector lists the pods again on the next tick
+    app.tick_count = 3;
+    handle_key_events(key_bindings().search.key, &mut app).await;
+    for c in " -f status.phase=Pending".chars() {
+      handle_key_events(Key::Char(c), &mut app).await;
+    }
+    handle_key_events(Key::Enter, &mut app).await;
+    assert_eq!(app.tick_count, 0);
+    assert_eq!(
+      app.data.pods.field_selector(),
+      Some("status.phase=Pending".into())
+    );
+
+    // and so does a new label selector
+    app.tick_count = 3;
+    handle_key_events(key_bindings().search.key, &mut app).await;
+    for c in " -l app=web".chars() {
+      handle_key_events(Key::Char(c), &mut app).await;
+    }
+    handle_key_events(Key::Enter, &mut app).await;
+    assert_eq!(app.tick_count, 0);
+    assert_eq!(app.data.pods.label_selector(), Some("app=web".into()));
+  }
+
+  #[tokio::test]
+  async fn test_handle_command_palette() {
+    let mut app = App::default();
+    app.route_home();
+
+    handle_key_events(Key::Char(':'), &mut app).await;
+    assert!(app.command_palette.is_some());
+    // keys bound to actions are typed into the palette
+    for c in "qdpl".chars() {
+      handle_key_events(Key::Char(c), &mut app).await;
+    }
+    assert!(!app.should_quit);
+    handle_key_events(Key::Backspace, &mut app).await;
+    handle_key_events(Key::Backspace, &mut app).await;
+    handle_key_events(Key::Backspace, &mut app).await;
+    handle_key_events(Key::Backspace, &mut app).await;
+    handle_key_events(Key::Char('d'), &mut app).await;
+    handle_key_events(Key::Char('p'), &mut app).await;
+    handle_key_events(Key::Tab, &mut app).await;
+    assert_eq!(app.command_palette.as_ref().unwrap().input, "deployments");
+    handle_key_events(Key::Enter, &mut app).await;
+    assert!(app.command_palette.is_none());
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::Deployments
+    );
+
+    handle_key_events(Key::Char(':'), &mut app).await;
+    handle_key_events(Key::Backspace, &mut app).await;
+    assert!(app.command_palette.is_none());
+    handle_key_events(Key::Char(':'), &mut app).await;
+    handle_key_events(Key::Esc, &mut app).await;
+    assert!(app.command_palette.is_none());
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::Deployments
+    );
+  }
 
   #[test]
   fn test_inverse_dir() {
@@ -760,9 +1971,9 @@ mod tests {
 
     // mouse scroll
     assert_eq!(app.data.pods.state.selected(), Some(0));
-    handle_block_scroll(&mut app, false, true, false).await;
+    handle_block_scroll(&mut app, false, true, ScrollStep::Line).await;
     assert_eq!(app.data.pods.state.selected(), Some(1));
-    handle_block_scroll(&mut app, true, true, false).await;
+    handle_block_scroll(&mut app, true, true, ScrollStep::Line).await;
     assert_eq!(app.data.pods.state.selected(), Some(0));
 
     // check logs keyboard scroll
@@ -773,24 +1984,111 @@ mod tests {
     app.data.logs.add_record("record 2".to_string());
     app.data.logs.add_record("record 3".to_string());
 
-    handle_block_scroll(&mut app, true, false, false).await;
+    handle_block_scroll(&mut app, true, false, ScrollStep::Line).await;
     assert_eq!(app.data.logs.state.selected(), Some(0));
   }
 
+  #[tokio::test]
+  async fn test_handle_sort() {
+    let mut app = App::default();
+    app.route_home();
+    let mut pod_b = KubePod::default();
+    pod_b.namespace = "b".into();
+    let mut pod_a = KubePod::default();
+    pod_a.namespace = "a".into();
+    app.data.pods.set_columns(&["Namespace", "Name"]);
+    app.data.pods.set_sorted_items(vec![pod_b, pod_a]);
+
+    handle_route_events(key_bindings().cycle_sort.key, &mut app).await;
+    assert_eq!(app.data.pods.items[0].namespace, "a");
+    handle_route_events(key_bindings().cycle_sort.key, &mut app).await;
+    assert_eq!(app.data.pods.items[0].namespace, "b");
+  }
+
+  #[tokio::test]
+  async fn test_handle_horizontal_scroll() {
+    let mut app = App::default();
+    app.route_home();
+    app.data.pods.set_columns(&["Namespace", "Name"]);
+
+    handle_route_events(key_bindings().scroll_right.key, &mut app).await;
+    assert_eq!(app.data.pods.shown_columns(), vec!["Name"]);
+    handle_route_events(key_bindings().scroll_left.key, &mut app).await;
+    assert_eq!(app.data.pods.shown_columns(), vec!["Namespace", "Name"]);
+
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Describe);
+    app.data.describe_out = ScrollableTxt::with_string("a".repeat(50));
+    handle_route_events(key_bindings().scroll_right.key, &mut app).await;
+    assert_eq!(app.data.describe_out.h_offset, 10);
+  }
+
+  #[tokio::test]
+  async fn test_handle_column_picker() {
+    let mut app = App::default();
+    app.route_home();
+    app.data.pods.set_columns(&["Name", "Node"]);
+
+    handle_route_events(key_bindings().pick_columns.key, &mut app).await;
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Columns);
+    assert_eq!(
+      app.column_picker.items,
+      vec![("Name".into(), true), ("Node".into(), false)]
+    );
+
+    app.column_picker.scroll_down(1);
+    handle_route_events(key_bindings().submit.key, &mut app).await;
+    assert_eq!(app.data.pods.visible_columns(), vec!["Name", "Node"]);
+    assert_eq!(app.column_picker.items[1], ("Node".into(), true));
+  }
+
+  #[tokio::test]
+  async fn test_handle_mouse_click() {
+    let mut app = App::default();
+    app.route_home();
+    app
+      .data
+      .pods
+      .set_items(vec![KubePod::default(), KubePod::default()]);
+    app.data.pods.set_view(Rect::new(0, 10, 50, 5));
+    app
+      .context_tabs
+      .set_title_areas(vec![Rect::new(1, 2, 8, 1), Rect::new(12, 2, 12, 1)]);
+
+    // select a row
+    handle_mouse_click(&mut app, 5, 11).await;
+    assert_eq!(app.data.pods.state.selected(), Some(1));
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Pods);
+    // clicking it again opens it
+    handle_mouse_click(&mut app, 5, 11).await;
+    assert_eq!(
+      app.get_current_route().active_block,
+      ActiveBlock::Containers
+    );
+
+    // switch tabs
+    handle_mouse_click(&mut app, 14, 2).await;
+    assert_eq!(app.context_tabs.index, 1);
+    assert_eq!(app.get_current_route().active_block, ActiveBlock::Services);
+  }
+
   #[tokio::test]
   async fn test_context_switch() {
     let mut app = App::default();
     let ctx = KubeContext {
       name: "test".into(),
+      namespace: Some("web".into()),
       ..KubeContext::default()
     };
     app.data.contexts.set_items(vec![ctx]);
+    app.data.selected.ns = Some("kube-system".into());
 
     assert_eq!(app.data.selected.context, None);
     app.route_contexts();
     handle_route_events(Key::Enter, &mut app).await;
 
     assert_eq!(app.data.selected.context, Some("test".into()));
+    // the namespace of the old context might not exist in the new one
+    assert_eq!(app.data.selected.ns, Some("web".into()));
     assert!(app.refresh);
   }
 }
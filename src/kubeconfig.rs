@@ -0,0 +1,272 @@
+use std::{collections::HashSet, env, ffi::OsString, path::PathBuf, sync::OnceLock};
+
+use anyhow::{anyhow, Context, Result};
+use kube::config::Kubeconfig;
+
+/// TLS settings from the command line or config file, they override the ones of every cluster
+/// in the kubeconfig like the kubectl flags of the same names
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsOptions {
+  pub certificate_authority: Option<PathBuf>,
+  pub insecure_skip_tls_verify: bool,
+}
+
+static TLS_OPTIONS: OnceLock<TlsOptions> = OnceLock::new();
+
+pub fn set_tls_options(options: TlsOptions) {
+  let _ = TLS_OPTIONS.set(options);
+}
+
+/// the active TLS options, nothing is overridden unless the flags or config file set them
+pub fn tls_options() -> TlsOptions {
+  TLS_OPTIONS.get().cloned().unwrap_or_default()
+}
+
+impl TlsOptions {
+  pub fn new(
+    certificate_authority: Option<PathBuf>,
+    insecure_skip_tls_verify: bool,
+  ) -> Result<Self> {
+    if let Some(path) = &certificate_authority {
+      if insecure_skip_tls_verify {
+        return Err(anyhow!(
+          "A certificate authority can't be used with insecure-skip-tls-verify"
+        ));
+      }
+      if !path.is_file() {
+        return Err(anyhow!(
+          "Unable to read certificate authority {}",
+          path.display()
+        ));
+      }
+    }
+    Ok(TlsOptions {
+      certificate_authority,
+      insecure_skip_tls_verify,
+    })
+  }
+
+  fn apply(&self, kubeconfig: &mut Kubeconfig) {
+    for named in kubeconfig.clusters.iter_mut() {
+      let cluster = &mut named.cluster;
+      if let Some(path) = &self.certificate_authority {
+        cluster.certificate_authority = Some(path.to_string_lossy().into_owned());
+        cluster.certificate_authority_data = None;
+        cluster.insecure_skip_tls_verify = None;
+      } else if self.insecure_skip_tls_verify {
+        // kube checks the certificates when a certificate authority is left
+        cluster.certificate_authority = None;
+        cluster.certificate_authority_data = None;
+        cluster.insecure_skip_tls_verify = Some(true);
+      }
+    }
+  }
+
+  /// the kubectl flags for the options, for the kubectl commands kdash runs
+  pub fn kubectl_args(&self) -> Vec<String> {
+    let mut args = vec![];
+    if let Some(path) = &self.certificate_authority {
+      args.push(format!("--certificate-authority={}", path.display()));
+    }
+    if self.insecure_skip_tls_verify {
+      args.push("--insecure-skip-tls-verify=true".into());
+    }
+    args
+  }
+}
+
+/// the kubeconfig files to read, the colon separated paths in `KUBECONFIG` or `~/.kube/config`
+pub fn kubeconfig_paths(kubeconfig: Option<OsString>, home: Option<PathBuf>) -> Vec<PathBuf> {
+  let paths = kubeconfig
+    .map(|value| {
+      env::split_paths(&value)
+        .filter(|p| !p.as_os_str().is_empty())
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  if paths.is_empty() {
+    home
+      .map(|home| vec![home.join(".kube").join("config")])
+      .unwrap_or_default()
+  } else {
+    paths
+  }
+}
+
+/// read and merge the kubeconfig files like kubectl does, listed files that don't exist are
+/// skipped
+pub fn read_kubeconfig() -> Result<Kubeconfig> {
+  let paths = kubeconfig_paths(
+    env::var_os("KUBECONFIG"),
+    env::var_os("HOME").map(PathBuf::from),
+  );
+  let configs = paths
+    .iter()
+    .filter(|path| path.exists())
+    .map(|path| {
+      Kubeconfig::read_from(path)
+        .with_context(|| format!("Unable to read kubeconfig {}", path.display()))
+    })
+    .collect::<Result<Vec<_>>>()?;
+  if configs.is_empty() {
+    return Err(anyhow!(
+      "No kubeconfig found at {}",
+      env::join_paths(&paths)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default()
+    ));
+  }
+  let mut kubeconfig = merge_kubeconfigs(configs);
+  tls_options().apply(&mut kubeconfig);
+  Ok(kubeconfig)
+}
+
+/// the namespace kubectl uses in a context, or in the current context when none is given
+pub fn kubectl_namespace(kubeconfig: &Kubeconfig, context: Option<&str>) -> String {
+  let context = context.or(kubeconfig.current_context.as_deref());
+  kubeconfig
+    .contexts
+    .iter()
+    .find(|c| Some(c.name.as_str()) == context)
+    .and_then(|c| c.context.namespace.clone())
+    .unwrap_or_else(|| "default".into())
+}
+
+/// merge kubeconfigs, the first one to set a value or a named cluster, user or context wins
+fn merge_kubeconfigs(configs: Vec<Kubeconfig>) -> Kubeconfig {
+  configs
+    .into_iter()
+    .reduce(|mut merged, next| {
+      merged.current_context = merged.current_context.or(next.current_context);
+      merged.preferences = merged.preferences.or(next.preferences);
+      merged.extensions = merged.extensions.or(next.extensions);
+      merged.kind = merged.kind.or(next.kind);
+      merged.api_version = merged.api_version.or(next.api_version);
+      append_new_named(&mut merged.clusters, next.clusters, |c| &c.name);
+      append_new_named(&mut merged.auth_infos, next.auth_infos, |a| &a.name);
+      append_new_named(&mut merged.contexts, next.contexts, |c| &c.name);
+      merged
+    })
+    .unwrap_or_default()
+}
+
+fn append_new_named<T>(items: &mut Vec<T>, next: Vec<T>, name: fn(&T) -> &String) {
+  let existing: HashSet<String> = items.iter().map(|i| name(i).clone()).collect();
+  items.extend(next.into_iter().filter(|i| !existing.contains(name(i))));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_kubeconfig_paths() {
+    let home = Some(PathBuf::from("/home/kdash"));
+    assert_eq!(
+      kubeconfig_paths(None, home.clone()),
+      vec![PathBuf::from("/home/kdash/.kube/config")]
+    );
+    assert_eq!(
+      kubeconfig_paths(Some("".into()), home.clone()),
+      vec![PathBuf::from("/home/kdash/.kube/config")]
+    );
+    assert_eq!(
+      kubeconfig_paths(Some("/a/config::/b/config".into()), home),
+      vec![PathBuf::from("/a/config"), PathBuf::from("/b/config")]
+    );
+    assert!(kubeconfig_paths(None, None).is_empty());
+  }
+
+  #[test]
+  fn test_tls_options() {
+    let mut kubeconfig = Kubeconfig::from_yaml(
+      r#"
+clusters:
+  - name: lab
+    cluster:
+      server: https://lab:6443
+      certificate-authority-data: Zm9v
+contexts: []
+users: []
+"#,
+    )
+    .unwrap();
+    let insecure = TlsOptions::new(None, true).unwrap();
+    assert_eq!(
+      insecure.kubectl_args(),
+      vec!["--insecure-skip-tls-verify=true"]
+    );
+    insecure.apply(&mut kubeconfig);
+    let cluster = &kubeconfig.clusters[0].cluster;
+    assert_eq!(cluster.insecure_skip_tls_verify, Some(true));
+    assert_eq!(cluster.certificate_authority_data, None);
+
+    let ca = env::current_dir().unwrap().join("Cargo.toml");
+    let options = TlsOptions::new(Some(ca.clone()), false).unwrap();
+    options.apply(&mut kubeconfig);
+    let cluster = &kubeconfig.clusters[0].cluster;
+    assert_eq!(cluster.insecure_skip_tls_verify, None);
+    assert_eq!(
+      cluster.certificate_authority,
+      Some(ca.to_string_lossy().into_owned())
+    );
+
+    assert!(TlsOptions::new(Some(ca), true).is_err());
+    assert!(TlsOptions::new(Some("/no/such/ca.crt".into()), false).is_err());
+    assert!(TlsOptions::default().kubectl_args().is_empty());
+  }
+
+  #[test]
+  fn test_merge_kubeconfigs() {
+    let first = Kubeconfig::from_yaml(
+      r#"
+current-context: dev
+clusters:
+  - name: dev
+    cluster:
+      server: https://dev:6443
+contexts:
+  - name: dev
+    context:
+      cluster: dev
+      user: dev
+users: []
+"#,
+    )
+    .unwrap();
+    let second = Kubeconfig::from_yaml(
+      r#"
+current-context: prod
+clusters:
+  - name: dev
+    cluster:
+      server: https://other:6443
+  - name: prod
+    cluster:
+      server: https://prod:6443
+contexts:
+  - name: prod
+    context:
+      cluster: prod
+      user: prod
+      namespace: payments
+users: []
+"#,
+    )
+    .unwrap();
+    let merged = merge_kubeconfigs(vec![first, second]);
+    assert_eq!(merged.current_context.as_deref(), Some("dev"));
+    assert_eq!(kubectl_namespace(&merged, None), "default");
+    assert_eq!(kubectl_namespace(&merged, Some("prod")), "payments");
+    assert_eq!(
+      merged
+        .contexts
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect::<Vec<_>>(),
+      vec!["dev", "prod"]
+    );
+    assert_eq!(merged.clusters.len(), 2);
+    assert_eq!(merged.clusters[0].cluster.server, "https://dev:6443");
+  }
+}
@@ -3,34 +3,64 @@
 mod app;
 mod banner;
 mod cmd;
+mod config;
 mod event;
 mod handlers;
+mod kubeconfig;
 mod network;
+mod snapshot;
+mod state;
 mod ui;
 
 use std::{
+  env,
   io::{self, stdout, Stdout},
-  panic::{self, PanicInfo},
-  sync::Arc,
+  panic::{self, PanicHookInfo},
+  path::PathBuf,
+  process::ExitStatus,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  thread,
+  time::Duration,
 };
 
 use anyhow::{anyhow, Result};
 
-use app::App;
+use app::{
+  command_palette::find_resource,
+  key_binding::{self, KeyBindings},
+  notifications::NotificationLevel,
+  pods::AttachRequest,
+  recording::Replay,
+  App,
+};
 use banner::BANNER;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use cmd::{CmdRunner, IoCmdEvent};
+use config::{Config, Palette, DEFAULT_MAX_CONCURRENT_REQUESTS, ENV_PREFIX};
 use crossterm::{
+  event::{DisableMouseCapture, EnableMouseCapture},
   execute,
   terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use event::Key;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, AttachParams, AttachedProcess};
+use kubeconfig::{kubectl_namespace, read_kubeconfig, TlsOptions};
 use network::{
   get_client,
   stream::{IoStreamEvent, NetworkStream},
   IoEvent, Network,
 };
-use tokio::sync::{mpsc, Mutex};
+use snapshot::OutputFormat;
+use state::UiState;
+use tokio::{
+  io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+  sync::{mpsc, Mutex},
+};
 use tui::{
   backend::{Backend, CrosstermBackend},
   Terminal,
@@ -40,16 +70,110 @@ use tui::{
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None, override_usage = "Press `?` while running the app to see keybindings", before_help = BANNER)]
 pub struct Cli {
-  /// Set the tick rate (milliseconds): the lower the number the higher the FPS.
-  #[arg(short, long, value_parser, default_value_t = 250)]
-  pub tick_rate: u64,
+  /// Set the tick rate (milliseconds): the lower the number the higher the FPS. [default: 250]
+  #[arg(short, long, value_parser, env = "KDASH_TICK_RATE")]
+  pub tick_rate: Option<u64>,
   /// Set the network call polling rate (milliseconds, should be multiples of tick-rate):
-  /// the lower the number the higher the network calls.
-  #[arg(short, long, value_parser, default_value_t = 5000)]
-  pub poll_rate: u64,
+  /// the lower the number the higher the network calls. [default: 5000]
+  #[arg(short, long, value_parser, env = "KDASH_POLL_RATE")]
+  pub poll_rate: Option<u64>,
+  /// Set the metrics polling rate (milliseconds, should be multiples of tick-rate).
+  /// Use a higher value than poll-rate to reduce the load on metrics-server in big clusters.
+  /// [default: 5000]
+  #[arg(short, long, value_parser, env = "KDASH_METRICS_POLL_RATE")]
+  pub metrics_poll_rate: Option<u64>,
   /// whether unicode symbols are used to improve the overall look of the app
-  #[arg(short, long, value_parser, default_value_t = true)]
+  #[arg(
+    short,
+    long,
+    value_parser,
+    default_value_t = true,
+    env = "KDASH_ENHANCED_GRAPHICS"
+  )]
   pub enhanced_graphics: bool,
+  /// Draw borders, gauges and markers with ASCII only, for terminals and fonts that mangle
+  /// unicode box drawing
+  #[arg(long, value_parser, default_value_t = false, env = "KDASH_ASCII")]
+  pub ascii: bool,
+  /// Disable mouse support, this lets the terminal handle mouse text selection instead
+  #[arg(
+    long,
+    value_parser,
+    default_value_t = false,
+    env = "KDASH_DISABLE_MOUSE"
+  )]
+  pub disable_mouse: bool,
+  /// Replace colors with bold, underlined and reversed text, also enabled by the `NO_COLOR`
+  /// environment variable
+  #[arg(long, value_parser, default_value_t = false, env = "KDASH_NO_COLOR")]
+  pub no_color: bool,
+  /// Context to start in, defaults to the one used last
+  #[arg(long, value_parser, env = "KDASH_CONTEXT", global = true)]
+  pub context: Option<String>,
+  /// Namespace to start in, defaults to the one used last
+  #[arg(short, long, value_parser, env = "KDASH_NAMESPACE", global = true)]
+  pub namespace: Option<String>,
+  /// Start in all namespaces, like the kubectl flag
+  #[arg(
+    short = 'A',
+    long,
+    value_parser,
+    default_value_t = false,
+    env = "KDASH_ALL_NAMESPACES",
+    global = true
+  )]
+  pub all_namespaces: bool,
+  /// Resource tab to start on, takes a resource name or alias like `deployments` or `deploy`
+  #[arg(long, value_parser, env = "KDASH_TAB")]
+  pub tab: Option<String>,
+  /// Disable actions that change resources in the cluster, for handing kdash to people who should
+  /// only look
+  #[arg(long, value_parser, default_value_t = false, env = "KDASH_READ_ONLY")]
+  pub read_only: bool,
+  /// Path of the kubeconfig file to use instead of `KUBECONFIG` or `~/.kube/config`
+  #[arg(long, value_parser, env = "KDASH_KUBECONFIG")]
+  pub kubeconfig: Option<PathBuf>,
+  /// Path of a certificate authority file checking the certificates of all clusters
+  #[arg(long, value_parser, env = "KDASH_CERTIFICATE_AUTHORITY")]
+  pub certificate_authority: Option<PathBuf>,
+  /// Skip checking the certificates of all clusters. This makes connections insecure, use it
+  /// only for lab clusters with self signed certificates
+  #[arg(
+    long,
+    value_parser,
+    default_value_t = false,
+    env = "KDASH_INSECURE_SKIP_TLS_VERIFY"
+  )]
+  pub insecure_skip_tls_verify: bool,
+  /// Append the resource lists received from the cluster to a file, to replay them later
+  #[arg(long, value_parser, env = "KDASH_RECORD")]
+  pub record: Option<PathBuf>,
+  /// Show a file written with `--record` instead of a cluster, `<` and `>` step through it
+  #[arg(long, value_parser, env = "KDASH_REPLAY", conflicts_with = "record")]
+  pub replay: Option<PathBuf>,
+  /// Path of the config file, defaults to `$XDG_CONFIG_HOME/kdash/config.yaml` or `~/.config/kdash/config.yaml`
+  #[arg(long, value_parser, env = "KDASH_CONFIG")]
+  pub config: Option<PathBuf>,
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Print completions of the flags for a shell, like `kdash completions zsh > ~/.zfunc/_kdash`
+  Completions {
+    #[arg(value_enum)]
+    shell: Shell,
+  },
+  /// Print the table of a resource once and exit, like `kdash get pods -n web`. Lists all
+  /// namespaces unless one is given
+  Get {
+    /// Resource name or alias like `deployments` or `deploy`
+    resource: String,
+    /// Print a table, or the items as JSON or YAML for scripts
+    #[arg(short, long, value_enum, default_value_t)]
+    output: OutputFormat,
+  },
 }
 
 #[tokio::main]
@@ -60,13 +184,70 @@ async fn main() -> Result<()> {
 
   // parse CLI arguments
   let cli = Cli::parse();
+  if let Some(Command::Completions { shell }) = cli.command {
+    clap_complete::generate(shell, &mut Cli::command(), "kdash", &mut stdout());
+    return Ok(());
+  }
+  // kubectl, which kdash runs for some views, reads the same kubeconfig this way
+  if let Some(path) = &cli.kubeconfig {
+    if !path.is_file() {
+      return Err(anyhow!("Unable to read kubeconfig {}", path.display()));
+    }
+    env::set_var("KUBECONFIG", path);
+  }
 
-  if cli.tick_rate >= 1000 {
-    panic!("Tick rate must be below 1000");
+  let mut config = Config::load(cli.config.clone(), &config_env_vars())?;
+  let rates = config.rates(cli.tick_rate, cli.poll_rate, cli.metrics_poll_rate)?;
+  let max_concurrent_requests = config
+    .max_concurrent_requests
+    .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS);
+  // through the environment kubectl uses the same proxy
+  if let Some(proxy) = &config.https_proxy {
+    env::set_var("HTTPS_PROXY", proxy);
+    env::remove_var("https_proxy");
   }
-  if (cli.poll_rate % cli.tick_rate) > 0u64 {
-    panic!("Poll rate must be multiple of tick-rate");
+  if let Some(no_proxy) = &config.no_proxy {
+    env::set_var("NO_PROXY", no_proxy);
+    env::remove_var("no_proxy");
   }
+  // https://no-color.org: any non empty value disables colors
+  if cli.no_color || env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+    config.theme.palette = Palette::Monochrome;
+  }
+  // flags replace the TLS options of the config file as a whole so they can't conflict
+  let tls_options = if cli.certificate_authority.is_some() || cli.insecure_skip_tls_verify {
+    TlsOptions::new(
+      cli.certificate_authority.clone(),
+      cli.insecure_skip_tls_verify,
+    )?
+  } else {
+    TlsOptions::new(
+      config.certificate_authority.take(),
+      config.insecure_skip_tls_verify,
+    )?
+  };
+  kubeconfig::set_tls_options(tls_options);
+  if let Some(Command::Get { resource, output }) = &cli.command {
+    let block =
+      find_resource(resource).ok_or_else(|| anyhow!("Unknown resource '{}'", resource))?;
+    return snapshot::print_snapshot(
+      block,
+      cli.context.clone().or(config.context),
+      if cli.all_namespaces {
+        None
+      } else {
+        cli.namespace.clone().or(config.namespace)
+      },
+      *output,
+    )
+    .await;
+  }
+  key_binding::set_key_bindings(KeyBindings::with_overrides(&config.keybindings)?);
+  let plugins = config.plugins(key_binding::key_bindings())?;
+  let custom_views = config.custom_views()?;
+  let extra_columns = config.extra_columns()?;
+  let hooks = config.hooks()?;
+  ui::set_custom_theme(config.theme);
 
   // channels for communication between network/cmd threads & UI thread
   let (sync_io_tx, sync_io_rx) = mpsc::channel::<IoEvent>(500);
@@ -78,43 +259,183 @@ async fn main() -> Result<()> {
     sync_io_tx,
     sync_io_stream_tx,
     sync_io_cmd_tx,
-    cli.enhanced_graphics,
-    cli.poll_rate / cli.tick_rate,
+    // braille chart markers have no ASCII stand in
+    cli.enhanced_graphics && !cli.ascii,
+    rates.poll / rates.tick,
+    rates.metrics_poll / rates.tick,
   )));
+  {
+    let mut app = app.lock().await;
+    app.resource_poll_ticks = rates
+      .resource_polls
+      .iter()
+      .map(|(block, rate)| (*block, rate / rates.tick))
+      .collect();
+    app.pinned_namespaces = config.pinned_namespaces;
+    app.fleet_contexts = config.fleet_contexts;
+    app.timestamp_format = config.timestamp_format;
+    app.saved_filters = config.saved_filters.into_iter().collect();
+    app.ascii_graphics = cli.ascii;
+    app.light_theme = config.light_theme;
+    app.read_only = cli.read_only || config.read_only;
+    app.confirm_policies = config.confirm;
+    app.pipe_command = config.pipe_command;
+    app.export_path = config.export_path;
+    app.recording = cli.record.clone();
+    if let Some(command) = config.diff_command {
+      app.diff_command = command;
+    }
+    app.osc52_clipboard = config.osc52_clipboard;
+    app.plugins = plugins;
+    app.set_custom_views(custom_views);
+    app.extra_columns = extra_columns;
+    app.hooks = hooks.map(Arc::new);
+    app.update_help_docs();
+    if let Some(lines) = config.log_max_lines {
+      app.log_limits.lines = lines;
+    }
+    if let Some(bytes) = config.log_max_bytes {
+      app.log_limits.bytes = bytes;
+    }
+    app.alerts.desktop = config.desktop_notifications;
+    app.alerts.webhook = config.webhook_url;
+    app.show_info_bar = config.show_info_bar.unwrap_or(true);
+    if let Some(threshold) = config.large_cluster_threshold {
+      app.large_cluster_threshold = threshold;
+    }
+  }
+
+  let tab = match cli.tab.as_ref().or(config.tab.as_ref()) {
+    Some(tab) => Some(find_resource(tab).ok_or_else(|| anyhow!("Unknown resource tab '{}'", tab))?),
+    None => None,
+  };
+  let pinned_tabs = config
+    .pinned_tabs
+    .iter()
+    .map(|tab| {
+      find_resource(tab).ok_or_else(|| anyhow!("Unknown resource tab '{}' in pinned_tabs", tab))
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+  let mut state = UiState::load();
+  // as `kubectl dash` kdash starts where kubectl is instead of where it was left
+  let plugin = is_kubectl_plugin();
+  if plugin {
+    state.context = None;
+  }
+  // flags win over the config file, which wins over the state of the last run
+  if let Some(context) = cli.context.clone().or(config.context) {
+    let exists = read_kubeconfig().is_ok_and(|k| k.contexts.iter().any(|c| c.name == context));
+    if !exists {
+      return Err(anyhow!("Context '{}' is not in the kubeconfig", context));
+    }
+    // another context starts in the namespace it was left in
+    if state.context.as_ref() != Some(&context) {
+      if let Some(ns) = state.context_namespaces.get(&context) {
+        state.namespace = ns.clone();
+      }
+    }
+    state.context = Some(context);
+  }
+  if plugin {
+    state.namespace = read_kubeconfig()
+      .ok()
+      .map(|k| kubectl_namespace(&k, state.context.as_deref()));
+  }
+  if let Some(namespace) = cli.namespace.clone().or(config.namespace) {
+    state.namespace = Some(namespace);
+  }
+  if cli.all_namespaces {
+    state.namespace = None;
+  }
+  // a context removed from the kubeconfig since the last run falls back to the default one
+  if let Some(context) = &state.context {
+    let exists = read_kubeconfig().is_ok_and(|k| k.contexts.iter().any(|c| &c.name == context));
+    if !exists {
+      state.context = None;
+    }
+  }
+  let context = state.context.clone();
+  {
+    let mut app = app.lock().await;
+    app.restore_ui_state(state);
+    if let Some(block) = tab {
+      app.route_resource(block);
+    }
+    app.pinned_tabs = pinned_tabs;
+    if let Some(path) = &cli.replay {
+      app.start_replay(Replay::load(path)?)?;
+    }
+  }
 
   // make copies for the network/cli threads
   let app_nw = Arc::clone(&app);
   let app_stream = Arc::clone(&app);
   let app_cli = Arc::clone(&app);
 
-  // Launch network thread
-  std::thread::spawn(move || {
-    start_network(sync_io_rx, &app_nw);
-  });
-  // Launch network thread for streams
-  std::thread::spawn(move || {
-    start_stream_network(sync_io_stream_rx, &app_stream);
-  });
-  // Launch thread for cmd runner
-  std::thread::spawn(move || {
-    start_cmd_runner(sync_io_cmd_rx, &app_cli);
-  });
+  // a replay doesn't connect to the cluster
+  if cli.replay.is_none() {
+    // Launch network thread
+    let context_nw = context.clone();
+    thread::spawn(move || {
+      start_network(sync_io_rx, &app_nw, context_nw, max_concurrent_requests);
+    });
+    // Launch network thread for streams
+    thread::spawn(move || {
+      start_stream_network(sync_io_stream_rx, &app_stream, context);
+    });
+    // Launch thread for cmd runner
+    thread::spawn(move || {
+      start_cmd_runner(sync_io_cmd_rx, &app_cli);
+    });
+  }
   // Launch the UI asynchronously
   // The UI must run in the "main" thread
-  start_ui(cli, &app).await?;
+  start_ui(cli, rates.tick, &app).await?;
+
+  if let Err(e) = app.lock().await.ui_state().save() {
+    eprintln!("Unable to save UI state. {:?}", e);
+  }
 
   Ok(())
 }
 
+/// kubectl runs executables named `kubectl-<name>` on the path for `kubectl <name>`
+fn is_kubectl_plugin() -> bool {
+  env::args_os()
+    .next()
+    .map(PathBuf::from)
+    .is_some_and(|path| path.file_stem().is_some_and(|name| name == "kubectl-dash"))
+}
+
+/// the `KDASH_*` variables of config file settings, clap reads the ones of the flags
+fn config_env_vars() -> Vec<(String, String)> {
+  let cli = Cli::command();
+  let flag_vars = cli
+    .get_arguments()
+    .filter_map(|arg| arg.get_env())
+    .collect::<Vec<_>>();
+  env::vars_os()
+    .filter(|(name, _)| !flag_vars.contains(&name.as_os_str()))
+    .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+    .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+    .collect()
+}
+
 #[tokio::main]
-async fn start_network(mut io_rx: mpsc::Receiver<IoEvent>, app: &Arc<Mutex<App>>) {
-  match get_client(None).await {
+async fn start_network(
+  io_rx: mpsc::Receiver<IoEvent>,
+  app: &Arc<Mutex<App>>,
+  context: Option<String>,
+  max_concurrent_requests: usize,
+) {
+  let unauthorized = Arc::default();
+  match get_client(context, &unauthorized).await {
     Ok(client) => {
-      let mut network = Network::new(client, app);
-
-      while let Some(io_event) = io_rx.recv().await {
-        network.handle_network_event(io_event).await;
-      }
+      let mut network = Network::new(client, unauthorized, app);
+      network
+        .handle_network_events(io_rx, max_concurrent_requests)
+        .await;
     }
     Err(e) => {
       let mut app = app.lock().await;
@@ -124,10 +445,15 @@ async fn start_network(mut io_rx: mpsc::Receiver<IoEvent>, app: &Arc<Mutex<App>>
 }
 
 #[tokio::main]
-async fn start_stream_network(mut io_rx: mpsc::Receiver<IoStreamEvent>, app: &Arc<Mutex<App>>) {
-  match get_client(None).await {
+async fn start_stream_network(
+  mut io_rx: mpsc::Receiver<IoStreamEvent>,
+  app: &Arc<Mutex<App>>,
+  context: Option<String>,
+) {
+  let unauthorized = Arc::default();
+  match get_client(context, &unauthorized).await {
     Ok(client) => {
-      let mut network = NetworkStream::new(client, app);
+      let mut network = NetworkStream::new(client, unauthorized, app);
 
       while let Some(io_event) = io_rx.recv().await {
         network.handle_network_stream_event(io_event).await;
@@ -149,11 +475,14 @@ async fn start_cmd_runner(mut io_rx: mpsc::Receiver<IoCmdEvent>, app: &Arc<Mutex
   }
 }
 
-async fn start_ui(cli: Cli, app: &Arc<Mutex<App>>) -> Result<()> {
+async fn start_ui(cli: Cli, tick_rate: u64, app: &Arc<Mutex<App>>) -> Result<()> {
   // Terminal initialization
   let mut stdout = stdout();
-  // not capturing mouse to make text select/copy possible
   execute!(stdout, EnterAlternateScreen)?;
+  // mouse capture can be disabled to make text select/copy possible
+  if !cli.disable_mouse {
+    execute!(stdout, EnableMouseCapture)?;
+  }
   // see https://docs.rs/crossterm/0.17.7/crossterm/terminal/#raw-mode
   enable_raw_mode()?;
   // terminal backend for cross platform support
@@ -162,52 +491,118 @@ async fn start_ui(cli: Cli, app: &Arc<Mutex<App>>) -> Result<()> {
   terminal.clear()?;
   terminal.hide_cursor()?;
   // custom events
-  let events = event::Events::new(cli.tick_rate);
+  let events = event::Events::new(tick_rate);
   let mut is_first_render = true;
+  // ages and other times are kept current by drawing at least once a second
+  let ticks_per_draw = (1000 / tick_rate).max(1);
+  let mut idle_ticks = 0;
   // main UI loop
   loop {
-    let mut app = app.lock().await;
-    // Get the size of the screen on each loop to account for resize event
-    if let Ok(size) = terminal.backend().size() {
-      // Reset the help menu if the terminal was resized
-      if app.refresh || app.size != size {
-        app.size = size;
-
-        // Based on the size of the terminal, adjust how many cols are
-        // displayed in the tables
-        if app.size.width > 8 {
-          app.table_cols = app.size.width - 1;
-        } else {
-          app.table_cols = 2;
+    // the lock is let go before handing the terminal to an attached container or a plugin, so
+    // the network threads keep updating the app meanwhile
+    let (attach, plugin) = {
+      let mut app = app.lock().await;
+      // Get the size of the screen on each loop to account for resize event
+      if let Ok(size) = terminal.backend().size() {
+        // Reset the help menu if the terminal was resized
+        if app.refresh || app.size != size {
+          app.size = size;
+          app.redraw = true;
+
+          // Based on the size of the terminal, adjust how many cols are
+          // displayed in the tables
+          if app.size.width > 8 {
+            app.table_cols = app.size.width - 1;
+          } else {
+            app.table_cols = 2;
+          }
         }
-      }
-    };
+      };
 
-    // draw the UI layout
-    terminal.draw(|f| ui::draw(f, &mut app))?;
+      // draw the UI layout only when something changed to save CPU while idle
+      if app.redraw {
+        terminal.draw(|f| ui::draw(f, &mut app))?;
+        app.redraw = false;
+        idle_ticks = 0;
+      }
 
-    // handle key events
-    match events.next()? {
-      event::Event::Input(key) => {
-        // quit on CTRL + C
-        if key == Key::Ctrl('c') {
-          break;
+      // handle key events
+      match events.next()? {
+        event::Event::Input(key) => {
+          // quit on CTRL + C
+          if key == Key::Ctrl('c') {
+            break;
+          }
+          // handle all other keys
+          handlers::handle_key_events(key, &mut app).await;
+          app.redraw = true;
+        }
+        // handle mouse events
+        event::Event::MouseInput(mouse) => {
+          handlers::handle_mouse_events(mouse, &mut app).await;
+          app.redraw = true;
+        }
+        // handle tick events
+        event::Event::Tick => {
+          app.on_tick(is_first_render).await;
+          idle_ticks += 1;
+          if idle_ticks >= ticks_per_draw {
+            app.redraw = true;
+          }
         }
-        // handle all other keys
-        handlers::handle_key_events(key, &mut app).await
       }
-      // handle mouse events
-      event::Event::MouseInput(mouse) => handlers::handle_mouse_events(mouse, &mut app).await,
-      // handle tick events
-      event::Event::Tick => {
-        app.on_tick(is_first_render).await;
+
+      is_first_render = false;
+
+      if app.should_quit {
+        break;
       }
-    }
+      (app.attach_request.take(), app.plugin_request.take())
+    };
 
-    is_first_render = false;
+    if let Some(request) = attach {
+      let attached = attach_container(
+        &mut terminal,
+        &events,
+        request,
+        tick_rate,
+        cli.disable_mouse,
+      )
+      .await;
+      let mut app = app.lock().await;
+      app.redraw = true;
+      if let Err(e) = attached {
+        app.notify(
+          NotificationLevel::Failure,
+          format!("Unable to attach: {}", e),
+        );
+      }
+    }
 
-    if app.should_quit {
-      break;
+    if let Some(run) = plugin {
+      let mut command = std::process::Command::new("sh");
+      command.args(["-c", run.command.as_str()]).envs(run.env);
+      let status = run_suspended(
+        &mut terminal,
+        &events,
+        command,
+        tick_rate,
+        cli.disable_mouse,
+      )
+      .await;
+      let mut app = app.lock().await;
+      app.redraw = true;
+      match status {
+        Ok(status) if !status.success() => app.notify(
+          NotificationLevel::Failure,
+          format!("Plugin {} exited with {}", run.name, status),
+        ),
+        Ok(_) => {}
+        Err(e) => app.notify(
+          NotificationLevel::Failure,
+          format!("Unable to run plugin {}: {}", run.name, e),
+        ),
+      }
     }
   }
 
@@ -217,16 +612,254 @@ async fn start_ui(cli: Cli, app: &Arc<Mutex<App>>) -> Result<()> {
   Ok(())
 }
 
+/// hand the terminal to something else, the UI stops reading input until `resume`. Raw mode is
+/// kept to pass keys on as they are typed. The banner is printed before
+async fn suspend(
+  terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+  events: &event::Events,
+  tick_rate: u64,
+  banner: Option<&str>,
+  raw: bool,
+) -> Result<()> {
+  events.pause(true);
+  // let a poll for input that is already waiting end
+  tokio::time::sleep(Duration::from_millis(tick_rate)).await;
+  if !raw {
+    disable_raw_mode()?;
+  }
+  execute!(
+    terminal.backend_mut(),
+    LeaveAlternateScreen,
+    DisableMouseCapture
+  )?;
+  terminal.show_cursor()?;
+  if let Some(banner) = banner {
+    print!("{}\r\n", banner);
+    io::Write::flush(&mut stdout())?;
+  }
+  Ok(())
+}
+
+/// take the terminal back for the UI after `suspend`
+fn resume(
+  terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+  events: &event::Events,
+  disable_mouse: bool,
+) -> Result<()> {
+  enable_raw_mode()?;
+  execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+  if !disable_mouse {
+    execute!(terminal.backend_mut(), EnableMouseCapture)?;
+  }
+  terminal.clear()?;
+  terminal.hide_cursor()?;
+  events.pause(false);
+  Ok(())
+}
+
+/// run a command like a terminal plugin with the terminal until it exits. Ctrl+C is passed on to
+/// the command instead of quitting kdash
+async fn run_suspended(
+  terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+  events: &event::Events,
+  mut command: std::process::Command,
+  tick_rate: u64,
+  disable_mouse: bool,
+) -> Result<ExitStatus> {
+  suspend(terminal, events, tick_rate, None, false).await?;
+  let status = tokio::task::spawn_blocking(move || {
+    #[cfg(unix)]
+    let _sigint = SigintGuard::new()?;
+    command.status()
+  })
+  .await;
+  resume(terminal, events, disable_mouse)?;
+  Ok(status??)
+}
+
+/// ignores SIGINT while a command has the terminal, the handler kdash had is put back when it is
+/// dropped. Commands get the default handler again when they start
+#[cfg(unix)]
+struct SigintGuard(libc::sigaction);
+
+#[cfg(unix)]
+impl SigintGuard {
+  fn new() -> io::Result<Self> {
+    extern "C" fn ignore(_: libc::c_int) {}
+    // SAFETY: the actions are zeroed structs with a handler that does nothing, as sigaction takes
+    unsafe {
+      let mut action: libc::sigaction = std::mem::zeroed();
+      action.sa_sigaction = ignore as extern "C" fn(libc::c_int) as libc::sighandler_t;
+      let mut previous: libc::sigaction = std::mem::zeroed();
+      if libc::sigaction(libc::SIGINT, &action, &mut previous) != 0 {
+        return Err(io::Error::last_os_error());
+      }
+      Ok(SigintGuard(previous))
+    }
+  }
+}
+
+#[cfg(unix)]
+impl Drop for SigintGuard {
+  fn drop(&mut self) {
+    // SAFETY: puts back the action sigaction returned before
+    unsafe {
+      libc::sigaction(libc::SIGINT, &self.0, std::ptr::null_mut());
+    }
+  }
+}
+
+/// attach to the stdin and stdout of a container through the API with the terminal, until the
+/// container ends the session or it is detached with Ctrl+P Ctrl+Q
+async fn attach_container(
+  terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+  events: &event::Events,
+  request: AttachRequest,
+  tick_rate: u64,
+  disable_mouse: bool,
+) -> Result<()> {
+  let client = get_client(request.context.clone(), &Arc::default()).await?;
+  let params = AttachParams::default()
+    .container(request.container.as_str())
+    .stdin(true)
+    .stdout(true)
+    // the TTY has stderr in stdout
+    .stderr(!request.tty)
+    .tty(request.tty);
+  let mut attached = Api::<Pod>::namespaced(client, &request.namespace)
+    .attach(&request.pod, &params)
+    .await?;
+  let banner = format!(
+    "Attached to {}/{}, detach with Ctrl+P Ctrl+Q",
+    request.pod, request.container
+  );
+  suspend(terminal, events, tick_rate, Some(&banner), true).await?;
+
+  let (keys_tx, keys_rx) = mpsc::channel(64);
+  let ended = Arc::new(AtomicBool::new(false));
+  let reader = {
+    let ended = ended.clone();
+    tokio::task::spawn_blocking(move || read_keys(&keys_tx, &ended))
+  };
+  let piped = pipe_attached(&mut attached, keys_rx, request.tty).await;
+  // the reader stops polling the terminal before the UI reads it again
+  ended.store(true, Ordering::Relaxed);
+  let read = reader.await;
+  attached.abort();
+  resume(terminal, events, disable_mouse)?;
+  piped.and(read?)
+}
+
+/// pass the keys typed on as the bytes a terminal sends, until Ctrl+P Ctrl+Q is typed or the
+/// session ended
+fn read_keys(keys: &mpsc::Sender<Vec<u8>>, ended: &AtomicBool) -> Result<()> {
+  let mut detaching = false;
+  while !ended.load(Ordering::Relaxed) {
+    if !crossterm::event::poll(Duration::from_millis(100))? {
+      continue;
+    }
+    let key = match crossterm::event::read()? {
+      crossterm::event::Event::Key(key) => Key::from(key),
+      _ => continue,
+    };
+    let mut bytes = vec![];
+    // Ctrl+P is held back until it is clear it doesn't start detaching
+    if detaching {
+      if key == Key::Ctrl('q') {
+        return Ok(());
+      }
+      bytes = Key::Ctrl('p').to_bytes();
+    }
+    detaching = key == Key::Ctrl('p');
+    if !detaching {
+      bytes.extend(key.to_bytes());
+    }
+    if !bytes.is_empty() && keys.blocking_send(bytes).is_err() {
+      return Ok(());
+    }
+  }
+  Ok(())
+}
+
+/// copy the keys to the stdin of the container and its output to the terminal, until either
+/// side is done
+async fn pipe_attached(
+  attached: &mut AttachedProcess,
+  mut keys: mpsc::Receiver<Vec<u8>>,
+  tty: bool,
+) -> Result<()> {
+  let mut stdin = attached
+    .stdin()
+    .ok_or_else(|| anyhow!("The container has no stdin"))?;
+  let mut output = attached
+    .stdout()
+    .ok_or_else(|| anyhow!("The container has no stdout"))?;
+  let mut errors = attached.stderr();
+  let mut terminal_out = tokio::io::stdout();
+  let (mut out_buf, mut err_buf) = ([0; 4096], [0; 4096]);
+  loop {
+    let read = tokio::select! {
+      typed = keys.recv() => match typed {
+        Some(bytes) => {
+          stdin.write_all(&bytes).await?;
+          continue;
+        }
+        None => return Ok(()),
+      },
+      read = output.read(&mut out_buf) => match read? {
+        0 => return Ok(()),
+        n => &out_buf[..n],
+      },
+      read = read_optional(&mut errors, &mut err_buf) => match read? {
+        0 => {
+          errors = None;
+          continue;
+        }
+        n => &err_buf[..n],
+      },
+    };
+    // without a TTY nothing turns the line feeds into new lines of the raw terminal
+    let text = if tty {
+      read.to_vec()
+    } else {
+      read
+        .iter()
+        .flat_map(|b| match b {
+          b'\n' => b"\r\n".to_vec(),
+          b => vec![*b],
+        })
+        .collect()
+    };
+    terminal_out.write_all(&text).await?;
+    terminal_out.flush().await?;
+  }
+}
+
+/// read from a stream that might not be there, never ending without it
+async fn read_optional<R: AsyncRead + Unpin>(
+  reader: &mut Option<R>,
+  buf: &mut [u8],
+) -> io::Result<usize> {
+  match reader {
+    Some(reader) => reader.read(buf).await,
+    None => std::future::pending().await,
+  }
+}
+
 // shutdown the CLI and show terminal
 fn shutdown(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
   disable_raw_mode()?;
-  execute!(terminal.backend_mut(), LeaveAlternateScreen,)?;
+  execute!(
+    terminal.backend_mut(),
+    LeaveAlternateScreen,
+    DisableMouseCapture
+  )?;
   terminal.show_cursor()?;
   Ok(())
 }
 
 #[cfg(debug_assertions)]
-fn panic_hook(info: &PanicInfo<'_>) {
+fn panic_hook(info: &PanicHookInfo<'_>) {
   use backtrace::Backtrace;
   use crossterm::style::Print;
 
@@ -246,6 +879,7 @@ fn panic_hook(info: &PanicInfo<'_>) {
   execute!(
     io::stdout(),
     LeaveAlternateScreen,
+    DisableMouseCapture,
     Print(format!(
       "thread '<unnamed>' panicked at '{}', {}\n\r{}",
       msg, location, stacktrace
@@ -255,7 +889,7 @@ fn panic_hook(info: &PanicInfo<'_>) {
 }
 
 #[cfg(not(debug_assertions))]
-fn panic_hook(info: &PanicInfo<'_>) {
+fn panic_hook(info: &PanicHookInfo<'_>) {
   use human_panic::{handle_dump, print_msg, Metadata};
 
   let meta = Metadata {
@@ -266,6 +900,6 @@ fn panic_hook(info: &PanicInfo<'_>) {
   };
   let file_path = handle_dump(&meta, info);
   disable_raw_mode().unwrap();
-  execute!(io::stdout(), LeaveAlternateScreen).unwrap();
+  execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture).unwrap();
   print_msg(file_path, &meta).expect("human-panic: printing error message to console failed");
 }
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::future::join_all;
+use k8s_openapi::api::core::v1::{Event, Node, Pod};
+use kube::{api::ListParams, Api, Client};
+
+use super::{get_client, Network};
+use crate::app::fleet::{merge_fleet_events, ClusterHealth, FleetEvent};
+
+impl<'a> Network<'a> {
+  /// poll the health and warning events of all contexts in the fleet view at once
+  pub async fn get_fleet_health(&self) {
+    let contexts = self.app.lock().await.fleet_contexts.clone();
+    let (clusters, events): (Vec<_>, Vec<_>) = join_all(
+      contexts
+        .iter()
+        .map(|context| self.get_cluster_health(context)),
+    )
+    .await
+    .into_iter()
+    .unzip();
+    let mut app = self.app.lock().await;
+    app.fleet.set_items(clusters);
+    app
+      .fleet_events
+      .set_items(merge_fleet_events(events.concat()));
+  }
+
+  async fn get_cluster_health(&self, context: &str) -> (ClusterHealth, Vec<FleetEvent>) {
+    let client = match self.fleet_client(context).await {
+      Ok(client) => client,
+      Err(e) => {
+        return (
+          ClusterHealth::unreachable(context, format!("{:#}", e)),
+          vec![],
+        )
+      }
+    };
+    let lp = ListParams::default();
+    let nodes = Api::<Node>::all(client.clone());
+    let pods = Api::<Pod>::all(client.clone());
+    let events = Api::<Event>::all(client);
+    let warnings = ListParams::default().fields("type=Warning");
+    let (health, events) = tokio::join!(
+      async { tokio::try_join!(nodes.list(&lp), pods.list(&lp)) },
+      events.list(&warnings)
+    );
+    match health {
+      Ok((nodes, pods)) => (
+        ClusterHealth::from_api(context, &nodes, &pods),
+        // users allowed to list pods might not be allowed to list events
+        events
+          .map(|events| {
+            events
+              .iter()
+              .map(|event| FleetEvent::from_api(context, event))
+              .collect()
+          })
+          .unwrap_or_default(),
+      ),
+      Err(e) => {
+        // build the client again on the next poll, which renews expired credentials
+        self.fleet_clients.lock().await.remove(context);
+        (ClusterHealth::unreachable(context, e.to_string()), vec![])
+      }
+    }
+  }
+
+  /// the client of a context in the fleet view, kept between polls
+  async fn fleet_client(&self, context: &str) -> Result<Client> {
+    if let Some(client) = self.fleet_clients.lock().await.get(context) {
+      return Ok(client.clone());
+    }
+    let client = get_client(Some(context.to_owned()), &Arc::default()).await?;
+    self
+      .fleet_clients
+      .lock()
+      .await
+      .insert(context.to_owned(), client.clone());
+    Ok(client)
+  }
+}
@@ -1,48 +1,85 @@
 use std::fmt;
 
 use anyhow::anyhow;
+use futures::stream::{self, StreamExt};
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
 use k8s_openapi::api::batch::v1::{CronJob, Job};
 use k8s_openapi::api::core::v1::{
-  ConfigMap, Namespace, Node, Pod, ReplicationController, Secret, Service,
+  ConfigMap, Event, Namespace, Node, Pod, ReplicationController, Secret, Service,
 };
 use k8s_openapi::api::rbac::v1::{ClusterRole, ClusterRoleBinding, Role, RoleBinding};
 use k8s_openapi::api::storage::v1::StorageClass;
 use kube::{
   api::{ListMeta, ListParams, ObjectList},
-  config::Kubeconfig,
+  core::DynamicObject,
   Api, Resource as ApiResource,
 };
 use kubectl_view_allocations::{
   extract_allocatable_from_nodes, extract_allocatable_from_pods,
   extract_utilizations_from_pod_metrics, make_qualifiers, metrics::PodMetrics, Resource,
 };
-use serde::de::DeserializeOwned;
-
-use super::Network;
-use crate::app::{
-  configmaps::KubeConfigMap,
-  contexts,
-  cronjobs::KubeCronJob,
-  daemonsets::KubeDaemonSet,
-  deployments::KubeDeployment,
-  jobs::KubeJob,
-  metrics::{self, KubeNodeMetrics},
-  nodes::KubeNode,
-  ns::KubeNs,
-  pods::KubePod,
-  replicasets::KubeReplicaSet,
-  replication_controllers::KubeReplicationController,
-  roles::{KubeClusterRole, KubeClusterRoleBinding, KubeRole, KubeRoleBinding},
-  secrets::KubeSecret,
-  statefulsets::KubeStatefulSet,
-  storageclass::KubeStorageClass,
-  svcs::KubeSvc,
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{
+  watch::{first_page, next_page, WatchKey},
+  Network,
+};
+use crate::{
+  app::{
+    configmaps::KubeConfigMap,
+    contexts,
+    cronjobs::KubeCronJob,
+    custom_views::KubeCustomObject,
+    daemonsets::KubeDaemonSet,
+    deployments::KubeDeployment,
+    helm::{KubeHelmRelease, HELM_RELEASE_TYPE},
+    hooks::Decorations,
+    jobs::KubeJob,
+    metrics::{
+      self, utilization_rows, ClusterCapacity, EphemeralStorage, KubeNodeMetrics, NodeStatsSummary,
+    },
+    models::StatefulTable,
+    nodes::KubeNode,
+    notifications::NotificationLevel,
+    ns::KubeNs,
+    pods::KubePod,
+    replicasets::KubeReplicaSet,
+    replication_controllers::KubeReplicationController,
+    roles::{KubeClusterRole, KubeClusterRoleBinding, KubeRole, KubeRoleBinding},
+    secrets::KubeSecret,
+    statefulsets::KubeStatefulSet,
+    storageclass::KubeStorageClass,
+    svcs::KubeSvc,
+    ActiveBlock,
+  },
+  kubeconfig::read_kubeconfig,
 };
 
+/// kubelet summaries requested at the same time
+const SUMMARY_CONCURRENCY: usize = 8;
+
+/// the items of a first page with the ones of the pages after it
+async fn with_next_pages<K>(
+  api: &Api<K>,
+  lp: &ListParams,
+  list: ObjectList<K>,
+) -> kube::Result<Vec<K>>
+where
+  K: Clone + DeserializeOwned + fmt::Debug,
+{
+  let mut items = list.items;
+  let mut metadata = list.metadata;
+  while let Some(page) = next_page(api, lp, &metadata).await {
+    let page = page?;
+    items.extend(page.items);
+    metadata = page.metadata;
+  }
+  Ok(items)
+}
+
 impl<'a> Network<'a> {
   pub async fn get_kube_config(&self) {
-    match Kubeconfig::read() {
+    match read_kubeconfig() {
       Ok(config) => {
         let mut app = self.app.lock().await;
         let selected_ctx = app.data.selected.context.to_owned();
@@ -69,23 +106,111 @@ impl<'a> Network<'a> {
           .map(|metric| KubeNodeMetrics::from_api(metric, &app))
           .collect();
 
-        app.data.node_metrics = items;
+        app.data.set_node_metrics(items);
+        app.data.metrics_server_unreachable = false;
+        app.data.cluster_capacity.set_usage(&node_metrics.items);
+        let at = app.data.metrics_history.elapsed();
+        let capacity = app.data.cluster_capacity.clone();
+        app.data.metrics_history.add_cluster_point(at, &capacity);
       }
       Err(_) => {
         let mut app = self.app.lock().await;
-        app.data.node_metrics = vec![];
-        // lets not show error as it will always be showing up and be annoying
-        // TODO may be show once and then disable polling
+        app.data.set_node_metrics(vec![]);
+        app.data.cluster_capacity.set_usage(&[]);
+        // an error would always be showing up and be annoying, so only notify once
+        if !app.data.metrics_server_unreachable {
+          app.data.metrics_server_unreachable = true;
+          app.notify(
+            NotificationLevel::Failure,
+            "Metrics server unreachable, usage metrics are not available",
+          );
+        }
       }
     };
+    self.get_selected_pod_metrics().await;
+  }
+
+  /// ephemeral storage usage is only available from the kubelet summary API of each node
+  pub async fn get_ephemeral_storage(&self) {
+    let node_names = {
+      let app = self.app.lock().await;
+      // a summary request for every node is too slow in large cluster mode
+      if app.large_cluster {
+        return;
+      }
+      app
+        .data
+        .nodes
+        .items
+        .iter()
+        .map(|n| n.name.clone())
+        .collect::<Vec<_>>()
+    };
+
+    let summaries = stream::iter(node_names)
+      .map(|name| {
+        let client = self.client.clone();
+        async move {
+          let request =
+            http::Request::get(format!("/api/v1/nodes/{}/proxy/stats/summary", name)).body(vec![]);
+          // the summary API might not be accessible to everyone, just skip the node in that case
+          client.request::<NodeStatsSummary>(request.ok()?).await.ok()
+        }
+      })
+      .buffer_unordered(SUMMARY_CONCURRENCY)
+      .collect::<Vec<_>>()
+      .await;
+
+    let mut storage = EphemeralStorage::default();
+    for summary in summaries.into_iter().flatten() {
+      storage.add_summary(summary);
+    }
+
+    let mut app = self.app.lock().await;
+    let data = &mut app.data;
+    data.nodes.items_mut().iter_mut().for_each(|node| {
+      node.set_disk_usage(storage.nodes.get(&node.name).copied());
+    });
+    data.pods.items_mut().iter_mut().for_each(|pod| {
+      pod.set_disk_usage(storage.get_pod_usage(&pod.namespace, &pod.name));
+    });
+    data.ephemeral_storage = storage;
+  }
+
+  /// record usage of the pod selected in the pods table for the metrics history view
+  async fn get_selected_pod_metrics(&self) {
+    let pod = {
+      let app = self.app.lock().await;
+      app.data.pods.get_selected_item_copy()
+    };
+    if let Some(pod) = pod {
+      let api: Api<PodMetrics> = Api::namespaced(self.client.clone(), &pod.namespace);
+      if let Ok(pod_metrics) = api.get(&pod.name).await {
+        let mut app = self.app.lock().await;
+        let at = app.data.metrics_history.elapsed();
+        app.data.metrics_history.add_pod_point(
+          at,
+          format!("{}/{}", pod.namespace, pod.name),
+          &pod_metrics,
+        );
+      }
+    }
   }
 
   pub async fn get_utilizations(&self) {
     let mut resources: Vec<Resource> = vec![];
 
     let api: Api<Node> = Api::all(self.client.clone());
-    match api.list(&ListParams::default()).await {
-      Ok(node_list) => {
+    let lp = ListParams::default();
+    match self
+      .list_watched(api, &lp, None, ActiveBlock::Utilization)
+      .await
+    {
+      Ok(items) => {
+        let node_list = ObjectList {
+          metadata: ListMeta::default(),
+          items,
+        };
         if let Err(e) = extract_allocatable_from_nodes(node_list, &mut resources).await {
           self
             .handle_error(anyhow!(
@@ -106,8 +231,16 @@ impl<'a> Network<'a> {
     }
 
     let api: Api<Pod> = self.get_namespaced_api().await;
-    match api.list(&ListParams::default()).await {
-      Ok(pod_list) => {
+    let ns = self.app.lock().await.data.selected.ns.clone();
+    match self
+      .list_watched(api, &lp, ns, ActiveBlock::Utilization)
+      .await
+    {
+      Ok(items) => {
+        let pod_list = ObjectList {
+          metadata: ListMeta::default(),
+          items,
+        };
         if let Err(e) = extract_allocatable_from_pods(pod_list, &mut resources).await {
           self
             .handle_error(anyhow!("Failed to extract pod allocation metrics. {:?}", e))
@@ -139,7 +272,7 @@ impl<'a> Network<'a> {
 
     let data = make_qualifiers(&resources, &app.utilization_group_by, &[]);
 
-    app.data.metrics.set_items(data);
+    app.data.metrics.set_items(utilization_rows(&data));
   }
 
   pub async fn get_nodes(&self) {
@@ -147,26 +280,54 @@ impl<'a> Network<'a> {
     let api_pods: Api<Pod> = Api::all(self.client.clone());
     let api_nodes: Api<Node> = Api::all(self.client.clone());
 
-    match api_nodes.list(&lp).await {
-      Ok(node_list) => {
-        self.get_node_metrics().await;
-
-        let pods_list = match api_pods.list(&lp).await {
-          Ok(list) => list,
-          Err(_) => ObjectList {
-            metadata: ListMeta::default(),
-            items: vec![],
+    let nodes_lp = self.list_params(ActiveBlock::Nodes).await;
+    match self
+      .list_watched(api_nodes, &nodes_lp, None, ActiveBlock::Nodes)
+      .await
+    {
+      Ok(items) => {
+        let node_list = ObjectList {
+          metadata: ListMeta::default(),
+          items,
+        };
+        // the pods of all namespaces are too many to keep in large cluster mode
+        let large_cluster = self.app.lock().await.large_cluster;
+        let pods_list = ObjectList {
+          metadata: ListMeta::default(),
+          items: if large_cluster {
+            vec![]
+          } else {
+            self
+              .list_watched(api_pods, &lp, None, ActiveBlock::Nodes)
+              .await
+              .unwrap_or_default()
           },
         };
+        self.record(ActiveBlock::Nodes, &node_list.items).await;
+        self.decorate(ActiveBlock::Nodes, &node_list.items).await;
 
         let mut app = self.app.lock().await;
+        app.count_pods(pods_list.items.len());
 
         let items = node_list
           .iter()
-          .map(|node| KubeNode::from_api_with_pods(node, &pods_list, &mut app))
+          .map(|node| {
+            KubeNode::from_api_with_pods(node, (!large_cluster).then_some(&pods_list), &mut app)
+          })
           .collect::<Vec<_>>();
-
-        app.data.nodes.set_items(items);
+        // the usage percentages of the node metrics are set from the nodes
+        app.data.update_node_usage();
+
+        let mut capacity =
+          ClusterCapacity::from_api(&node_list, (!large_cluster).then_some(&pods_list));
+        // usage is kept up to date separately by the node metrics calls
+        capacity.cpu.used = app.data.cluster_capacity.cpu.used;
+        capacity.mem.used = app.data.cluster_capacity.mem.used;
+        app.data.cluster_capacity = capacity;
+
+        let alerts = app.alerts.update_nodes(&items);
+        self.send_alerts(&app.alerts, alerts);
+        app.data.nodes.set_sorted_items(items);
       }
       Err(e) => {
         self
@@ -174,17 +335,87 @@ impl<'a> Network<'a> {
           .await;
       }
     }
+    self.run_event_hooks().await;
+  }
+
+  /// list the events and alert the ones the `on_event` hook returns alerts for, only while alerts
+  /// are sent
+  async fn run_event_hooks(&self) {
+    let hooks = {
+      let app = self.app.lock().await;
+      match &app.hooks {
+        Some(hooks) if app.alerts.is_enabled() && hooks.has_on_event() => hooks.clone(),
+        _ => return,
+      }
+    };
+    let api: Api<Event> = self.get_namespaced_api().await;
+    let events = match first_page(&api, &ListParams::default()).await {
+      Ok(list) => list.items,
+      Err(e) => {
+        self
+          .handle_error(anyhow!("Failed to get events. {:?}", e))
+          .await;
+        return;
+      }
+    };
+    let alerts = hooks.on_event(&events);
+    let mut app = self.app.lock().await;
+    match alerts {
+      Ok(alerts) => {
+        let alerts = app.alerts.update("events", alerts);
+        self.send_alerts(&app.alerts, alerts);
+      }
+      Err(e) => app.notify(
+        NotificationLevel::Failure,
+        format!("The on_event hook failed. {:#}", e),
+      ),
+    }
+  }
+
+  /// fill the extra columns of a table from the listed objects, and color its rows and fill its
+  /// hook column with the `on_row` hook
+  async fn decorate<K>(&self, block: ActiveBlock, objects: &[K])
+  where
+    K: ApiResource + Serialize,
+  {
+    let (hooks, columns) = {
+      let app = self.app.lock().await;
+      (app.hooks.clone(), app.extra_columns(block))
+    };
+    if hooks.is_none() && columns.is_empty() {
+      return;
+    }
+    let (decorations, error) = match hooks.map(|hooks| hooks.on_row(objects)) {
+      Some(Ok(decorations)) => (decorations, None),
+      Some(Err(e)) => (Decorations::default(), Some(e)),
+      None => (Decorations::default(), None),
+    };
+    let decorations = decorations.with_extra_columns(&columns, objects);
+    let mut app = self.app.lock().await;
+    if let Some(e) = error {
+      app.notify(
+        NotificationLevel::Failure,
+        format!("The on_row hook failed. {:#}", e),
+      );
+    }
+    if let Some(table) = app.data.resource_table(block) {
+      table.set_decorations(decorations);
+    }
   }
 
   pub async fn get_namespaces(&self) {
     let api: Api<Namespace> = Api::all(self.client.clone());
 
     let lp = ListParams::default();
-    match api.list(&lp).await {
+    match self
+      .list_watched(api, &lp, None, ActiveBlock::Namespaces)
+      .await
+    {
       Ok(ns_list) => {
+        self.record(ActiveBlock::Namespaces, &ns_list).await;
         let items = ns_list.into_iter().map(KubeNs::from).collect::<Vec<_>>();
         let mut app = self.app.lock().await;
-        app.data.namespaces.set_items(items);
+        app.set_namespaces(items);
       }
       Err(e) => {
         self
@@ -195,9 +426,21 @@ impl<'a> Network<'a> {
   }
 
   pub async fn get_pods(&self) {
-    let items: Vec<KubePod> = self.get_namespaced_resources(Pod::into).await;
+    let mut items: Vec<KubePod> = self
+      .get_namespaced_resources(ActiveBlock::Pods, Pod::into)
+      .await;
 
     let mut app = self.app.lock().await;
+    app.count_pods(items.len());
+    if !app.large_cluster {
+      items.iter_mut().for_each(|pod| {
+        let used = app
+          .data
+          .ephemeral_storage
+          .get_pod_usage(&pod.namespace, &pod.name);
+        pod.set_disk_usage(used);
+      });
+    }
     if app.data.selected.pod.is_some() {
       let containers = &items.iter().find_map(|pod| {
         if pod.name == app.data.selected.pod.clone().unwrap() {
@@ -207,132 +450,227 @@ impl<'a> Network<'a> {
         }
       });
       if containers.is_some() {
-        app.data.containers.set_items(containers.unwrap().clone());
+        app
+          .data
+          .containers
+          .set_sorted_items(containers.unwrap().clone());
       }
     }
-    app.data.pods.set_items(items);
+    let alerts = app.alerts.update_pods(&items);
+    self.send_alerts(&app.alerts, alerts);
+    app.data.pods.set_sorted_items(items);
   }
 
   pub async fn get_services(&self) {
-    let items: Vec<KubeSvc> = self.get_namespaced_resources(Service::into).await;
+    let items: Vec<KubeSvc> = self
+      .get_namespaced_resources(ActiveBlock::Services, Service::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.services.set_items(items);
+    app.data.services.set_sorted_items(items);
   }
 
   pub async fn get_config_maps(&self) {
-    let items: Vec<KubeConfigMap> = self.get_namespaced_resources(ConfigMap::into).await;
+    let items: Vec<KubeConfigMap> = self
+      .get_namespaced_resources(ActiveBlock::ConfigMaps, ConfigMap::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.config_maps.set_items(items);
+    app.data.config_maps.set_sorted_items(items);
   }
 
   pub async fn get_stateful_sets(&self) {
-    let items: Vec<KubeStatefulSet> = self.get_namespaced_resources(StatefulSet::into).await;
+    let items: Vec<KubeStatefulSet> = self
+      .get_namespaced_resources(ActiveBlock::StatefulSets, StatefulSet::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.stateful_sets.set_items(items);
+    app.data.stateful_sets.set_sorted_items(items);
   }
 
   pub async fn get_replica_sets(&self) {
-    let items: Vec<KubeReplicaSet> = self.get_namespaced_resources(ReplicaSet::into).await;
+    let items: Vec<KubeReplicaSet> = self
+      .get_namespaced_resources(ActiveBlock::ReplicaSets, ReplicaSet::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.replica_sets.set_items(items);
+    app.data.replica_sets.set_sorted_items(items);
   }
 
   pub async fn get_jobs(&self) {
-    let items: Vec<KubeJob> = self.get_namespaced_resources(Job::into).await;
+    let items: Vec<KubeJob> = self
+      .get_namespaced_resources(ActiveBlock::Jobs, Job::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.jobs.set_items(items);
+    let alerts = app.alerts.update_jobs(&items);
+    self.send_alerts(&app.alerts, alerts);
+    app.data.jobs.set_sorted_items(items);
   }
 
   pub async fn get_cron_jobs(&self) {
-    let items: Vec<KubeCronJob> = self.get_namespaced_resources(CronJob::into).await;
+    let items: Vec<KubeCronJob> = self
+      .get_namespaced_resources(ActiveBlock::CronJobs, CronJob::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.cronjobs.set_items(items);
+    app.data.cronjobs.set_sorted_items(items);
   }
 
   pub async fn get_secrets(&self) {
-    let items: Vec<KubeSecret> = self.get_namespaced_resources(Secret::into).await;
+    let items: Vec<KubeSecret> = self
+      .get_namespaced_resources(ActiveBlock::Secrets, Secret::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.secrets.set_items(items);
+    app.data.secrets.set_sorted_items(items);
   }
 
   pub async fn get_replication_controllers(&self) {
     let items: Vec<KubeReplicationController> = self
-      .get_namespaced_resources(ReplicationController::into)
+      .get_namespaced_resources(ActiveBlock::RplCtrl, ReplicationController::into)
       .await;
 
     let mut app = self.app.lock().await;
-    app.data.rpl_ctrls.set_items(items);
+    app.data.rpl_ctrls.set_sorted_items(items);
   }
 
   pub async fn get_deployments(&self) {
-    let items: Vec<KubeDeployment> = self.get_namespaced_resources(Deployment::into).await;
+    let items: Vec<KubeDeployment> = self
+      .get_namespaced_resources(ActiveBlock::Deployments, Deployment::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.deployments.set_items(items);
+    app.data.deployments.set_sorted_items(items);
   }
 
   pub async fn get_daemon_sets_jobs(&self) {
-    let items: Vec<KubeDaemonSet> = self.get_namespaced_resources(DaemonSet::into).await;
+    let items: Vec<KubeDaemonSet> = self
+      .get_namespaced_resources(ActiveBlock::DaemonSets, DaemonSet::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.daemon_sets.set_items(items);
+    app.data.daemon_sets.set_sorted_items(items);
   }
 
   pub async fn get_storage_classes(&self) {
-    let items: Vec<KubeStorageClass> = self.get_namespaced_resources(StorageClass::into).await;
+    let items: Vec<KubeStorageClass> = self
+      .get_namespaced_resources(ActiveBlock::StorageClasses, StorageClass::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.storage_classes.set_items(items);
+    app.data.storage_classes.set_sorted_items(items);
   }
 
   pub async fn get_roles(&self) {
-    let items: Vec<KubeRole> = self.get_namespaced_resources(Role::into).await;
+    let items: Vec<KubeRole> = self
+      .get_namespaced_resources(ActiveBlock::Roles, Role::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.roles.set_items(items);
+    app.data.roles.set_sorted_items(items);
   }
 
   pub async fn get_role_bindings(&self) {
-    let items: Vec<KubeRoleBinding> = self.get_namespaced_resources(RoleBinding::into).await;
+    let items: Vec<KubeRoleBinding> = self
+      .get_namespaced_resources(ActiveBlock::RoleBindings, RoleBinding::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.role_bindings.set_items(items);
+    app.data.role_bindings.set_sorted_items(items);
   }
 
   pub async fn get_cluster_roles(&self) {
-    let items: Vec<KubeClusterRole> = self.get_namespaced_resources(ClusterRole::into).await;
+    let items: Vec<KubeClusterRole> = self
+      .get_namespaced_resources(ActiveBlock::ClusterRoles, ClusterRole::into)
+      .await;
 
     let mut app = self.app.lock().await;
-    app.data.cluster_roles.set_items(items);
+    app.data.cluster_roles.set_sorted_items(items);
   }
 
   pub async fn get_cluster_role_binding(&self) {
     let items: Vec<KubeClusterRoleBinding> = self
-      .get_namespaced_resources(ClusterRoleBinding::into)
+      .get_namespaced_resources(ActiveBlock::ClusterRoleBinding, ClusterRoleBinding::into)
       .await;
 
     let mut app = self.app.lock().await;
-    app.data.cluster_role_binding.set_items(items);
+    app.data.cluster_role_binding.set_sorted_items(items);
   }
 
-  /// calls the kubernetes API to list the given resource for either selected namespace or all namespaces
-  async fn get_namespaced_resources<K: ApiResource, T, F>(&self, map_fn: F) -> Vec<T>
+  pub async fn get_helm_releases(&self) {
+    let items: Vec<KubeHelmRelease> = self
+      .get_namespaced_resources(ActiveBlock::HelmReleases, Secret::into)
+      .await;
+
+    let mut app = self.app.lock().await;
+    app.data.set_helm_revisions(items);
+  }
+
+  /// list the objects of a custom view from the config, in pages without a watch as the
+  /// resource is only known at runtime
+  pub async fn get_custom_resources(&self, index: usize) {
+    let block = ActiveBlock::CustomView(index);
+    let (view, ns) = {
+      let app = self.app.lock().await;
+      match app.custom_views.get(index) {
+        Some(view) => (view.clone(), app.data.selected.ns.clone()),
+        None => return,
+      }
+    };
+    let api: Api<DynamicObject> = match ns.filter(|_| view.namespaced) {
+      Some(ns) => Api::namespaced_with(self.client.clone(), &ns, &view.resource),
+      None => Api::all_with(self.client.clone(), &view.resource),
+    };
+    let lp = self.list_params(block).await;
+    let result = match first_page(&api, &lp).await {
+      Ok(list) => with_next_pages(&api, &lp, list).await,
+      Err(e) => Err(e),
+    };
+    match result {
+      Ok(items) => {
+        self.decorate(block, &items).await;
+        let items = items
+          .into_iter()
+          .map(|object| KubeCustomObject::from_api(&view, object))
+          .collect();
+        let mut app = self.app.lock().await;
+        app
+          .data
+          .custom_objects
+          .entry(index)
+          .or_insert_with(StatefulTable::new)
+          .set_sorted_items(items);
+      }
+      Err(e) => {
+        self
+          .handle_error(anyhow!("Failed to get {}. {:?}", view.name, e))
+          .await;
+      }
+    }
+  }
+
+  /// calls the kubernetes API to list the given resource for either selected namespace or all namespaces,
+  /// the list is then kept up to date with a watch
+  async fn get_namespaced_resources<K, T, F>(&self, block: ActiveBlock, map_fn: F) -> Vec<T>
   where
+    K: ApiResource,
     <K as ApiResource>::DynamicType: Default,
-    K: Clone + DeserializeOwned + fmt::Debug,
+    K: Clone + DeserializeOwned + Serialize + fmt::Debug + Send + Sync + 'static,
     F: Fn(K) -> T,
   {
     let api: Api<K> = self.get_namespaced_api().await;
-    let lp = ListParams::default();
-    match api.list(&lp).await {
-      Ok(list) => list.into_iter().map(map_fn).collect::<Vec<_>>(),
+    let lp = self.list_params(block).await;
+    let ns = self.app.lock().await.data.selected.ns.clone();
+    match self.list_watched(api, &lp, ns, block).await {
+      Ok(items) => {
+        self.record(block, &items).await;
+        // the rows of releases are not the secrets they are kept in
+        if block != ActiveBlock::HelmReleases {
+          self.decorate(block, &items).await;
+        }
+        items.into_iter().map(map_fn).collect::<Vec<_>>()
+      }
       Err(e) => {
         self
           .handle_error(anyhow!(
@@ -346,6 +684,62 @@ impl<'a> Network<'a> {
     }
   }
 
+  /// the items of a resource for a view from the shared watches, the resource is listed in pages
+  /// and watched when no watch has them
+  async fn list_watched<K>(
+    &self,
+    api: Api<K>,
+    lp: &ListParams,
+    ns: Option<String>,
+    block: ActiveBlock,
+  ) -> kube::Result<Vec<K>>
+  where
+    K: ApiResource + Clone + DeserializeOwned + fmt::Debug + Send + Sync + 'static,
+  {
+    let mut live = self.app.lock().await.live_tabs();
+    live.extend([
+      ActiveBlock::Namespaces,
+      ActiveBlock::Nodes,
+      ActiveBlock::Utilization,
+    ]);
+    self.watches.keep(&live);
+    let key = WatchKey::new::<K>(ns.clone(), lp);
+    if let Some(items) = self.watches.items(&key, block) {
+      return Ok(items);
+    }
+    let list = first_page(&api, lp).await?;
+    if self.watch {
+      // the other pages are added to the watched items as they come
+      self
+        .watches
+        .start(api, lp, ns, &list, block, self.app.clone());
+      return Ok(list.items);
+    }
+    with_next_pages(&api, lp, list).await
+  }
+
+  /// list params with the field and label selectors of the table filter, so that the API
+  /// narrows the results
+  async fn list_params(&self, block: ActiveBlock) -> ListParams {
+    let mut app = self.app.lock().await;
+    let mut lp = ListParams::default();
+    let mut fields = vec![];
+    if block == ActiveBlock::HelmReleases {
+      // only the secrets Helm keeps the releases in
+      fields.push(format!("type={}", HELM_RELEASE_TYPE));
+    }
+    if let Some(table) = app.data.resource_table(block) {
+      fields.extend(table.field_selector());
+      if let Some(selector) = table.label_selector() {
+        lp = lp.labels(&selector);
+      }
+    }
+    if !fields.is_empty() {
+      lp = lp.fields(&fields.join(","));
+    }
+    lp
+  }
+
   async fn get_namespaced_api<K: ApiResource>(&self) -> Api<K>
   where
     <K as ApiResource>::DynamicType: Default,
@@ -1,20 +1,46 @@
 // adapted from https://github.com/Rigellute/spotify-tui
+mod fleet;
 mod kube_api;
+mod proxy;
 pub(crate) mod stream;
+mod watch;
+mod webhook;
 
 use core::convert::TryFrom;
-use std::sync::Arc;
+use std::{
+  collections::BTreeMap,
+  env,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::Instant,
+};
 
 use anyhow::{anyhow, Result};
-use kube::Client;
-use tokio::sync::Mutex;
+use futures::stream::{FuturesUnordered, StreamExt};
+use kube::{client::ClientBuilder, Client};
+use serde::Serialize;
+use tokio::sync::{mpsc, Mutex};
+use tower::util::MapResultLayer;
+use watch::Watches;
 
-use crate::app::App;
+use crate::{
+  app::{
+    alerts::{notify_desktop, Alerts},
+    notifications::NotificationLevel,
+    recording, ActiveBlock, App,
+  },
+  kubeconfig::{read_kubeconfig, tls_options},
+};
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum IoEvent {
   GetKubeConfig,
   GetNodes,
+  GetNodeMetrics,
+  /// disk usage of the nodes and pods from the kubelets, polled less often than the node metrics
+  GetEphemeralStorage,
   GetNamespaces,
   GetPods,
   GetServices,
@@ -32,18 +58,67 @@ pub enum IoEvent {
   GetRoleBindings,
   GetClusterRoles,
   GetClusterRoleBinding,
+  GetHelmReleases,
+  /// the objects of the custom view with the index
+  GetCustomResources(usize),
   GetMetrics,
+  GetFleetHealth,
   RefreshClient,
 }
 
-async fn refresh_kube_config(context: &Option<String>) -> Result<kube::Client> {
+impl IoEvent {
+  /// the event listing the items of a resource table
+  pub fn list(block: ActiveBlock) -> Option<IoEvent> {
+    let event = match block {
+      ActiveBlock::Pods | ActiveBlock::Containers => IoEvent::GetPods,
+      ActiveBlock::Services => IoEvent::GetServices,
+      ActiveBlock::Nodes => IoEvent::GetNodes,
+      ActiveBlock::ConfigMaps => IoEvent::GetConfigMaps,
+      ActiveBlock::StatefulSets => IoEvent::GetStatefulSets,
+      ActiveBlock::ReplicaSets => IoEvent::GetReplicaSets,
+      ActiveBlock::Deployments => IoEvent::GetDeployments,
+      ActiveBlock::Jobs => IoEvent::GetJobs,
+      ActiveBlock::DaemonSets => IoEvent::GetDaemonSets,
+      ActiveBlock::CronJobs => IoEvent::GetCronJobs,
+      ActiveBlock::Secrets => IoEvent::GetSecrets,
+      ActiveBlock::RplCtrl => IoEvent::GetReplicationControllers,
+      ActiveBlock::StorageClasses => IoEvent::GetStorageClasses,
+      ActiveBlock::Roles => IoEvent::GetRoles,
+      ActiveBlock::RoleBindings => IoEvent::GetRoleBindings,
+      ActiveBlock::ClusterRoles => IoEvent::GetClusterRoles,
+      ActiveBlock::ClusterRoleBinding => IoEvent::GetClusterRoleBinding,
+      ActiveBlock::HelmReleases | ActiveBlock::HelmHistory => IoEvent::GetHelmReleases,
+      ActiveBlock::CustomView(i) => IoEvent::GetCustomResources(i),
+      _ => return None,
+    };
+    Some(event)
+  }
+}
+
+/// what the requests of a client found out about the API server, read and reset by the threads
+/// using the client
+#[derive(Debug, Default)]
+pub struct ClientStatus {
+  /// the API server rejected the credentials
+  pub unauthorized: AtomicBool,
+  /// a request got a response
+  pub reached: AtomicBool,
+  /// a request got no response, the API server is down or the network dropped
+  pub unreachable: AtomicBool,
+}
+
+async fn refresh_kube_config(
+  context: &Option<String>,
+  status: &Arc<ClientStatus>,
+) -> Result<kube::Client> {
   // HACK force refresh token by calling "kubectl cluster-info before loading configuration"
-  let mut args = vec!["cluster-info"];
+  let mut args = vec!["cluster-info".to_owned()];
 
   if let Some(context) = context {
-    args.push("--context");
-    args.push(context.as_str());
+    args.push("--context".into());
+    args.push(context.clone());
   }
+  args.extend(tls_options().kubectl_args());
   let out = duct::cmd("kubectl", &args)
     .stderr_null()
     // we don't care about the output
@@ -53,49 +128,126 @@ async fn refresh_kube_config(context: &Option<String>) -> Result<kube::Client> {
   if out.is_err() {
     return Err(anyhow!("Running `kubectl cluster-info` failed",));
   }
-  get_client(context.to_owned()).await
+  get_client(context.to_owned(), status).await
 }
 
-pub async fn get_client(context: Option<String>) -> Result<kube::Client> {
-  let client_config = match context.as_ref() {
-    Some(context) => {
-      kube::Config::from_kubeconfig(&kube::config::KubeConfigOptions {
-        context: Some(context.to_owned()),
+/// a client for the context, its requests are noted in `status`
+pub async fn get_client(
+  context: Option<String>,
+  status: &Arc<ClientStatus>,
+) -> Result<kube::Client> {
+  let client_config = match read_kubeconfig() {
+    Ok(kubeconfig) => {
+      let options = kube::config::KubeConfigOptions {
+        context,
         ..Default::default()
-      })
-      .await?
+      };
+      kube::Config::from_custom_kubeconfig(kubeconfig, &options).await?
     }
-    None => kube::Config::infer().await?,
+    // without a kubeconfig kdash might be running in a cluster
+    Err(_) if context.is_none() => kube::Config::infer().await?,
+    Err(e) => return Err(e),
   };
-  Ok(kube::Client::try_from(client_config)?)
+  let proxy = proxy::cluster_proxy(
+    &client_config.cluster_url,
+    client_config.proxy_url.as_ref(),
+    |name| env::var(name).ok(),
+  )?;
+  let status = status.clone();
+  match proxy {
+    Some(proxy) => proxy::proxied_client(client_config, proxy, note_status(status)),
+    None => Ok(
+      ClientBuilder::try_from(client_config)?
+        .with_layer(&MapResultLayer::new(note_status(status)))
+        .build(),
+    ),
+  }
+}
+
+/// note whether requests got a response and responses rejecting the credentials. kube only runs
+/// exec credential plugins again when the token they returned has an expiry, so like kubectl the
+/// client is built again on a 401
+fn note_status<B, E>(
+  status: Arc<ClientStatus>,
+) -> impl Fn(Result<http::Response<B>, E>) -> Result<http::Response<B>, E> + Clone {
+  move |result| {
+    match &result {
+      Ok(response) => {
+        status.reached.store(true, Ordering::Relaxed);
+        if response.status() == http::StatusCode::UNAUTHORIZED {
+          status.unauthorized.store(true, Ordering::Relaxed);
+        }
+      }
+      Err(_) => status.unreachable.store(true, Ordering::Relaxed),
+    }
+    result
+  }
 }
 
 #[derive(Clone)]
 pub struct Network<'a> {
   pub client: Client,
   pub app: &'a Arc<Mutex<App>>,
+  status: Arc<ClientStatus>,
+  watches: Watches,
+  /// keep listed resources up to date with watches, off to list everything once
+  pub watch: bool,
+  /// clients of the contexts in the fleet view, shared by the clones of the network
+  fleet_clients: Arc<Mutex<BTreeMap<String, Client>>>,
 }
 
 impl<'a> Network<'a> {
-  pub fn new(client: Client, app: &'a Arc<Mutex<App>>) -> Self {
-    Network { client, app }
+  pub fn new(client: Client, status: Arc<ClientStatus>, app: &'a Arc<Mutex<App>>) -> Self {
+    Network {
+      client,
+      app,
+      status,
+      watches: Watches::default(),
+      watch: true,
+      fleet_clients: Arc::default(),
+    }
+  }
+
+  /// build the client again after the API server rejected its credentials, which runs exec
+  /// credential plugins like `aws eks get-token` again for a new token
+  async fn renew_credentials(&mut self) {
+    let context = self.app.lock().await.data.selected.context.clone();
+    match get_client(context, &self.status).await {
+      Ok(client) => {
+        self.client = client;
+        self.watches.clear();
+      }
+      Err(e) => {
+        self
+          .handle_error(anyhow!("Failed to renew credentials. {:?}", e))
+          .await
+      }
+    }
   }
 
   pub async fn refresh_client(&mut self) {
-    let context = {
+    let (context, ns) = {
       let mut app = self.app.lock().await;
       let context = app.data.selected.context.clone();
       // so that if refresh fails we dont see mixed results
       app.data.selected.context = None;
-      context
+      (context, app.data.selected.ns.clone())
     };
 
-    match refresh_kube_config(&context).await {
+    match refresh_kube_config(&context, &self.status).await {
       Ok(client) => {
         self.client = client;
+        self.watches.clear();
         let mut app = self.app.lock().await;
         app.reset();
+        if let Some(context) = &context {
+          app.notify(
+            NotificationLevel::Success,
+            format!("Connected to context {}", context),
+          );
+        }
         app.data.selected.context = context;
+        app.data.selected.ns = ns;
       }
       Err(e) => {
         self
@@ -108,8 +260,54 @@ impl<'a> Network<'a> {
     }
   }
 
+  /// handle the events with up to `limit` requests in flight at once, so the kinds listed on a
+  /// refresh load side by side. Refreshing the client and renewing credentials wait for the
+  /// requests in flight, no response made with the old client lands after them
+  pub async fn handle_network_events(&mut self, mut io_rx: mpsc::Receiver<IoEvent>, limit: usize) {
+    let mut in_flight = FuturesUnordered::new();
+    loop {
+      tokio::select! {
+        Some(()) = in_flight.next(), if !in_flight.is_empty() => {}
+        io_event = io_rx.recv(), if in_flight.len() < limit.max(1) => {
+          let io_event = match io_event {
+            Some(io_event) => io_event,
+            None => break,
+          };
+          let refresh = io_event == IoEvent::RefreshClient;
+          if refresh || self.status.unauthorized.load(Ordering::Relaxed) {
+            while in_flight.next().await.is_some() {}
+          }
+          // the requests are made with clones, so the client is renewed here
+          if self.status.unauthorized.swap(false, Ordering::Relaxed) {
+            self.renew_credentials().await;
+          }
+          if refresh {
+            self.handle_network_event(io_event).await;
+          } else {
+            let mut network = self.clone();
+            in_flight.push(async move { network.handle_network_event(io_event).await });
+          }
+        }
+      }
+    }
+    while in_flight.next().await.is_some() {}
+  }
+
   #[allow(clippy::cognitive_complexity)]
   pub async fn handle_network_event(&mut self, io_event: IoEvent) {
+    if self.status.unauthorized.swap(false, Ordering::Relaxed) {
+      self.renew_credentials().await;
+    }
+    // while the API server is unreachable requests wait for the next retry, the fleet view polls
+    // other clusters
+    {
+      let mut app = self.app.lock().await;
+      let waits = !matches!(io_event, IoEvent::RefreshClient | IoEvent::GetFleetHealth);
+      if waits && app.connection.is_waiting(Instant::now()) {
+        app.is_loading = false;
+        return;
+      }
+    }
     match io_event {
       IoEvent::RefreshClient => {
         self.refresh_client().await;
@@ -120,6 +318,12 @@ impl<'a> Network<'a> {
       IoEvent::GetNodes => {
         self.get_nodes().await;
       }
+      IoEvent::GetNodeMetrics => {
+        self.get_node_metrics().await;
+      }
+      IoEvent::GetEphemeralStorage => {
+        self.get_ephemeral_storage().await;
+      }
       IoEvent::GetNamespaces => {
         self.get_namespaces().await;
       }
@@ -159,6 +363,15 @@ impl<'a> Network<'a> {
       IoEvent::GetMetrics => {
         self.get_utilizations().await;
       }
+      IoEvent::GetHelmReleases => {
+        self.get_helm_releases().await;
+      }
+      IoEvent::GetCustomResources(i) => {
+        self.get_custom_resources(i).await;
+      }
+      IoEvent::GetFleetHealth => {
+        self.get_fleet_health().await;
+      }
       IoEvent::GetStorageClasses => {
         self.get_storage_classes().await;
       }
@@ -178,10 +391,71 @@ impl<'a> Network<'a> {
 
     let mut app = self.app.lock().await;
     app.is_loading = false;
+    app.redraw = true;
+    if self.status.reached.swap(false, Ordering::Relaxed) {
+      app.connection.reached();
+    } else if self.status.unreachable.swap(false, Ordering::Relaxed) {
+      app.connection.failed(Instant::now());
+    }
   }
 
   async fn handle_error(&self, e: anyhow::Error) {
     let mut app = self.app.lock().await;
     app.handle_error(e);
   }
+
+  /// append a received list to the recording, the recording stops when it can't be written
+  async fn record<K: Serialize>(&self, block: ActiveBlock, items: &[K]) {
+    let mut app = self.app.lock().await;
+    if let Some(path) = &app.recording {
+      if let Err(e) = recording::record(path, block, items) {
+        app.recording = None;
+        app.notify(
+          NotificationLevel::Failure,
+          format!("Stopped recording. {:#}", e),
+        );
+      }
+    }
+  }
+
+  /// send new alerts as desktop notifications and to the webhook, without waiting for them
+  fn send_alerts(&self, settings: &Alerts, alerts: Vec<String>) {
+    for alert in alerts {
+      if settings.desktop {
+        notify_desktop(alert.clone());
+      }
+      if let Some(url) = settings.webhook.clone() {
+        let app = self.app.clone();
+        tokio::spawn(async move {
+          if let Err(e) = webhook::post_alert(&url, &alert).await {
+            app.lock().await.notify(
+              NotificationLevel::Failure,
+              format!("Unable to post alert to webhook. {}", e),
+            );
+          }
+        });
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_note_status() {
+    let status = Arc::new(ClientStatus::default());
+    let note = note_status(status.clone());
+    let response = |code| Ok::<_, ()>(http::Response::builder().status(code).body(()).unwrap());
+
+    let _ = note(response(http::StatusCode::FORBIDDEN));
+    assert!(!status.unauthorized.load(Ordering::Relaxed));
+    assert!(status.reached.load(Ordering::Relaxed));
+    let _ = note(response(http::StatusCode::UNAUTHORIZED));
+    assert!(status.unauthorized.load(Ordering::Relaxed));
+    assert!(!status.unreachable.load(Ordering::Relaxed));
+    let _ = note(Err(()));
+    assert!(status.unreachable.load(Ordering::Relaxed));
+  }
 }
@@ -1,4 +1,7 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+  sync::{atomic::Ordering, Arc},
+  time::Duration,
+};
 
 use anyhow::anyhow;
 use k8s_openapi::api::core::v1::Pod;
@@ -6,24 +9,36 @@ use kube::{api::LogParams, Api, Client};
 use tokio::sync::Mutex;
 use tokio_stream::StreamExt;
 
-use super::refresh_kube_config;
-use crate::app::{ActiveBlock, App};
+use super::{get_client, refresh_kube_config, ClientStatus};
+use crate::app::{ActiveBlock, App, SplitPane};
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum IoStreamEvent {
   RefreshClient,
   GetPodLogs(bool),
+  /// follow the logs of a container in the split pane, tailing the last lines first
+  GetSplitPaneLogs {
+    namespace: String,
+    pod: String,
+    container: String,
+    tail: bool,
+  },
 }
 
 #[derive(Clone)]
 pub struct NetworkStream<'a> {
   pub client: Client,
   pub app: &'a Arc<Mutex<App>>,
+  status: Arc<ClientStatus>,
 }
 
 impl<'a> NetworkStream<'a> {
-  pub fn new(client: Client, app: &'a Arc<Mutex<App>>) -> Self {
-    NetworkStream { client, app }
+  pub fn new(client: Client, status: Arc<ClientStatus>, app: &'a Arc<Mutex<App>>) -> Self {
+    NetworkStream {
+      client,
+      app,
+      status,
+    }
   }
 
   pub async fn refresh_client(&mut self) {
@@ -31,7 +46,7 @@ impl<'a> NetworkStream<'a> {
       let app = self.app.lock().await;
       app.data.selected.context.clone()
     };
-    match refresh_kube_config(&context).await {
+    match refresh_kube_config(&context, &self.status).await {
       Ok(client) => {
         self.client = client;
       }
@@ -44,6 +59,18 @@ impl<'a> NetworkStream<'a> {
   }
 
   pub async fn handle_network_stream_event(&mut self, io_event: IoStreamEvent) {
+    // a log stream refused with an expired token gets a new one the next time
+    if self.status.unauthorized.swap(false, Ordering::Relaxed) {
+      let context = self.app.lock().await.data.selected.context.clone();
+      match get_client(context, &self.status).await {
+        Ok(client) => self.client = client,
+        Err(e) => {
+          self
+            .handle_error(anyhow!("Failed to renew credentials. {:?}", e))
+            .await
+        }
+      }
+    }
     match io_event {
       IoStreamEvent::RefreshClient => {
         self.refresh_client().await;
@@ -51,10 +78,21 @@ impl<'a> NetworkStream<'a> {
       IoStreamEvent::GetPodLogs(tail) => {
         self.stream_container_logs(tail).await;
       }
+      IoStreamEvent::GetSplitPaneLogs {
+        namespace,
+        pod,
+        container,
+        tail,
+      } => {
+        self
+          .stream_logs(namespace, pod, container, tail, true)
+          .await;
+      }
     };
 
     let mut app = self.app.lock().await;
     app.is_loading = false;
+    app.redraw = true;
   }
 
   async fn handle_error(&self, e: anyhow::Error) {
@@ -86,6 +124,22 @@ impl<'a> NetworkStream<'a> {
     if pod_name.is_empty() || cont_name.is_empty() {
       return;
     }
+    self
+      .stream_logs(namespace, pod_name, cont_name, tail, false)
+      .await;
+  }
+
+  /// follow the logs of a container into the logs view, or into the split pane, for as long as
+  /// it is shown there
+  async fn stream_logs(
+    &self,
+    namespace: String,
+    pod_name: String,
+    cont_name: String,
+    tail: bool,
+    split_pane: bool,
+  ) {
+    let id = format!("{}/{}/{}", namespace, pod_name, cont_name);
     let api: Api<Pod> = Api::namespaced(self.client.clone(), &namespace);
     let lp = LogParams {
       container: Some(cont_name.clone()),
@@ -99,7 +153,11 @@ impl<'a> NetworkStream<'a> {
 
     {
       let mut app = self.app.lock().await;
-      app.is_streaming = true;
+      if split_pane {
+        app.split_pane_streaming = true;
+      } else {
+        app.is_streaming = true;
+      }
     }
 
     // TODO investigate why this gives wrong log some times
@@ -113,15 +171,24 @@ impl<'a> NetworkStream<'a> {
         while let (true, Ok(Some(Ok(line)))) = (
           {
             let app = self.app.lock().await;
-            app.get_current_route().active_block == ActiveBlock::Logs
-              && app.data.logs.id == cont_name
+            if split_pane {
+              app.split_pane == Some(SplitPane::Logs) && app.data.split_pane_logs.id == id
+            } else {
+              app.get_current_route().active_block == ActiveBlock::Logs
+                && app.data.logs.id == cont_name
+            }
           },
           logs.try_next().await,
         ) {
           let line = String::from_utf8_lossy(&line).trim().to_string();
           if !line.is_empty() {
             let mut app = self.app.lock().await;
-            app.data.logs.add_record(line);
+            if split_pane {
+              app.data.split_pane_logs.add_record(line);
+            } else {
+              app.data.logs.add_record(line);
+            }
+            app.redraw = true;
           }
         }
       }
@@ -133,6 +200,11 @@ impl<'a> NetworkStream<'a> {
     };
 
     let mut app = self.app.lock().await;
-    app.is_streaming = false;
+    if split_pane {
+      app.split_pane_streaming = false;
+    } else {
+      app.is_streaming = false;
+    }
+    app.redraw = true;
   }
 }
@@ -0,0 +1,435 @@
+use std::{
+  any::{type_name, Any},
+  collections::{BTreeMap, HashMap},
+  fmt,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex as SyncMutex,
+  },
+};
+
+use kube::{
+  api::{ListMeta, ListParams, ObjectList, WatchEvent},
+  Api, Resource as ApiResource,
+};
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+use tokio_stream::StreamExt;
+
+use super::IoEvent;
+use crate::app::{ActiveBlock, App};
+
+/// seconds a watch request is kept open by the API server before it is made again, shorter than
+/// the timeouts of most load balancers in front of API servers
+const WATCH_TIMEOUT: u32 = 290;
+
+/// items asked for with each list request, like the default chunk size of kubectl
+const PAGE_SIZE: u32 = 500;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// what a watch lists, a resource in a namespace or all of them with field and label selectors
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct WatchKey {
+  kind: &'static str,
+  namespace: Option<String>,
+  fields: Option<String>,
+  labels: Option<String>,
+}
+
+impl WatchKey {
+  pub fn new<K>(namespace: Option<String>, lp: &ListParams) -> Self {
+    WatchKey {
+      kind: type_name::<K>(),
+      namespace,
+      fields: lp.field_selector.clone(),
+      labels: lp.label_selector.clone(),
+    }
+  }
+}
+
+/// the items of a resource listed once and kept up to date with a watch
+struct Watch {
+  id: u64,
+  /// the objects by namespace and name, a `BTreeMap<String, K>`
+  items: Box<dyn Any + Send>,
+  /// the views reading the items, with whether an update was requested that they haven't read
+  users: Vec<(ActiveBlock, bool)>,
+}
+
+/// a cache of watched resources shared by all views of a network thread. A view reads the watch
+/// of its resource, namespace and field selector, or the one of all namespaces. A watch is
+/// stopped when no view reads it anymore, and a resource is listed again when its watch ended
+#[derive(Clone, Default)]
+pub struct Watches(Arc<SyncMutex<HashMap<WatchKey, Watch>>>);
+
+impl Watches {
+  /// the current items of a watched resource for a view, None when it isn't watched
+  pub fn items<K: ApiResource + Clone + 'static>(
+    &self,
+    key: &WatchKey,
+    block: ActiveBlock,
+  ) -> Option<Vec<K>> {
+    let mut watches = self.0.lock().unwrap();
+    let all_namespaces = WatchKey {
+      namespace: None,
+      ..key.clone()
+    };
+    let found = [key, &all_namespaces]
+      .into_iter()
+      .find(|k| watches.contains_key(k))?
+      .clone();
+    use_watch(&mut watches, &found, block);
+    let items = watches[&found]
+      .items
+      .downcast_ref::<BTreeMap<String, K>>()?;
+    Some(
+      items
+        .values()
+        .filter(|item| key.namespace.is_none() || item.meta().namespace == key.namespace)
+        .cloned()
+        .collect(),
+    )
+  }
+
+  /// stop reading the watches for views that aren't kept up to date, watches no view reads
+  /// anymore are stopped
+  pub fn keep(&self, blocks: &[ActiveBlock]) {
+    let mut watches = self.0.lock().unwrap();
+    for watch in watches.values_mut() {
+      watch.users.retain(|(block, _)| blocks.contains(block));
+    }
+    watches.retain(|_, watch| !watch.users.is_empty());
+  }
+
+  /// stop all watches, for a new client or context
+  pub fn clear(&self) {
+    self.0.lock().unwrap().clear();
+  }
+
+  /// keep the listed items up to date from the resource version of the list for a view
+  pub fn start<K>(
+    &self,
+    api: Api<K>,
+    lp: &ListParams,
+    namespace: Option<String>,
+    list: &ObjectList<K>,
+    block: ActiveBlock,
+    app: Arc<Mutex<App>>,
+  ) where
+    K: ApiResource + Clone + DeserializeOwned + fmt::Debug + Send + Sync + 'static,
+  {
+    if list.metadata.resource_version.is_none() {
+      return;
+    }
+    let key = WatchKey::new::<K>(namespace, lp);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let items: BTreeMap<String, K> = list.iter().map(|i| (object_key(i), i.clone())).collect();
+    {
+      let mut watches = self.0.lock().unwrap();
+      watches.insert(
+        key.clone(),
+        Watch {
+          id,
+          items: Box::new(items),
+          users: vec![],
+        },
+      );
+      use_watch(&mut watches, &key, block);
+    }
+    let lp = lp.clone().timeout(WATCH_TIMEOUT);
+    let watches = self.clone();
+    let metadata = list.metadata.clone();
+    tokio::spawn(async move {
+      watches.run(api, lp, key, id, metadata, app).await;
+    });
+  }
+
+  async fn run<K>(
+    self,
+    api: Api<K>,
+    lp: ListParams,
+    key: WatchKey,
+    id: u64,
+    metadata: ListMeta,
+    app: Arc<Mutex<App>>,
+  ) where
+    K: ApiResource + Clone + DeserializeOwned + fmt::Debug + Send + Sync + 'static,
+  {
+    // the views show the pages of the list as they come, all pages are of the version of the
+    // first one
+    let mut version = metadata.resource_version.clone().unwrap_or_default();
+    let mut metadata = metadata;
+    while let Some(page) = next_page(&api, &lp, &metadata).await {
+      let page = match page {
+        Ok(page) => page,
+        // the continue token expired, list again
+        Err(_) => {
+          self.remove(&key, id);
+          return;
+        }
+      };
+      let changed = self.update(&key, id, |items: &mut BTreeMap<String, K>| {
+        items.extend(page.items.into_iter().map(|item| (object_key(&item), item)));
+      });
+      match changed {
+        Some(blocks) => {
+          for block in blocks {
+            request_update(&app, block).await;
+          }
+        }
+        None => return,
+      }
+      metadata = page.metadata;
+    }
+    // a watch request ends after its timeout and is made again from the last version seen
+    while let Ok(stream) = api.watch(&lp, &version).await {
+      tokio::pin!(stream);
+      while let Some(event) = stream.next().await {
+        let changed = match event {
+          Ok(WatchEvent::Added(object)) | Ok(WatchEvent::Modified(object)) => {
+            version = object.meta().resource_version.clone().unwrap_or(version);
+            self.update(&key, id, |items: &mut BTreeMap<String, K>| {
+              items.insert(object_key(&object), object);
+            })
+          }
+          Ok(WatchEvent::Deleted(object)) => {
+            version = object.meta().resource_version.clone().unwrap_or(version);
+            self.update(&key, id, |items: &mut BTreeMap<String, K>| {
+              items.remove(&object_key(&object));
+            })
+          }
+          Ok(WatchEvent::Bookmark(bookmark)) => {
+            version = bookmark.metadata.resource_version;
+            Some(vec![])
+          }
+          // the version is too old to watch from or the stream broke, list again
+          Ok(WatchEvent::Error(_)) | Err(_) => None,
+        };
+        match changed {
+          Some(blocks) => {
+            for block in blocks {
+              request_update(&app, block).await;
+            }
+          }
+          None => {
+            self.remove(&key, id);
+            return;
+          }
+        }
+      }
+      if !self.is_current(&key, id) {
+        return;
+      }
+    }
+    self.remove(&key, id);
+  }
+
+  /// change the items of a watch, returns the views to update or None when the watch was
+  /// stopped or replaced
+  fn update<K: 'static>(
+    &self,
+    key: &WatchKey,
+    id: u64,
+    change: impl FnOnce(&mut BTreeMap<String, K>),
+  ) -> Option<Vec<ActiveBlock>> {
+    let mut watches = self.0.lock().unwrap();
+    let watch = watches.get_mut(key).filter(|watch| watch.id == id)?;
+    change(watch.items.downcast_mut()?);
+    Some(
+      watch
+        .users
+        .iter_mut()
+        .filter(|(_, pending)| !*pending)
+        .map(|(block, pending)| {
+          *pending = true;
+          *block
+        })
+        .collect(),
+    )
+  }
+
+  fn is_current(&self, key: &WatchKey, id: u64) -> bool {
+    self.0.lock().unwrap().get(key).map(|watch| watch.id) == Some(id)
+  }
+
+  fn remove(&self, key: &WatchKey, id: u64) {
+    let mut watches = self.0.lock().unwrap();
+    if watches.get(key).map(|watch| watch.id) == Some(id) {
+      watches.remove(key);
+    }
+  }
+}
+
+/// make a view read a watch of the same resource instead of the one it read before, watches no
+/// view reads anymore are stopped
+fn use_watch(watches: &mut HashMap<WatchKey, Watch>, key: &WatchKey, block: ActiveBlock) {
+  for (k, watch) in watches.iter_mut() {
+    if k.kind == key.kind && k != key {
+      watch.users.retain(|(b, _)| *b != block);
+    }
+  }
+  watches.retain(|k, watch| k == key || !watch.users.is_empty());
+  if let Some(watch) = watches.get_mut(key) {
+    match watch.users.iter_mut().find(|(b, _)| *b == block) {
+      Some(user) => user.1 = false,
+      None => watch.users.push((block, false)),
+    }
+  }
+}
+
+/// list the first page of a resource, the others are listed with `next_page`
+pub async fn first_page<K>(api: &Api<K>, lp: &ListParams) -> kube::Result<ObjectList<K>>
+where
+  K: Clone + DeserializeOwned + fmt::Debug,
+{
+  api.list(&lp.clone().limit(PAGE_SIZE)).await
+}
+
+/// the page of a list after the one of the metadata, None after the last page
+pub async fn next_page<K>(
+  api: &Api<K>,
+  lp: &ListParams,
+  metadata: &ListMeta,
+) -> Option<kube::Result<ObjectList<K>>>
+where
+  K: Clone + DeserializeOwned + fmt::Debug,
+{
+  let token = metadata
+    .continue_
+    .as_deref()
+    .filter(|token| !token.is_empty())?;
+  Some(
+    api
+      .list(&lp.clone().limit(PAGE_SIZE).continue_token(token))
+      .await,
+  )
+}
+
+/// ask the network thread to update the table from the watched items
+async fn request_update(app: &Arc<Mutex<App>>, block: ActiveBlock) {
+  let event = match block {
+    ActiveBlock::Namespaces => Some(IoEvent::GetNamespaces),
+    block => IoEvent::list(block),
+  };
+  if let Some(event) = event {
+    app.lock().await.dispatch(event).await;
+  }
+}
+
+fn object_key<K: ApiResource>(object: &K) -> String {
+  let meta = object.meta();
+  format!(
+    "{}/{}",
+    meta.namespace.as_deref().unwrap_or_default(),
+    meta.name.as_deref().unwrap_or_default()
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::ObjectMeta};
+
+  use super::*;
+
+  fn pod(name: &str) -> Pod {
+    Pod {
+      metadata: ObjectMeta {
+        name: Some(name.into()),
+        namespace: Some("web".into()),
+        ..ObjectMeta::default()
+      },
+      ..Pod::default()
+    }
+  }
+
+  #[test]
+  fn test_watches() {
+    let watches = Watches::default();
+    let all = WatchKey::new::<Pod>(None, &ListParams::default());
+    let web = WatchKey::new::<Pod>(Some("web".into()), &ListParams::default());
+    assert!(watches.items::<Pod>(&web, ActiveBlock::Pods).is_none());
+
+    let mut other = pod("db-0");
+    other.metadata.namespace = Some("data".into());
+    watches.0.lock().unwrap().insert(
+      all.clone(),
+      Watch {
+        id: 7,
+        items: Box::new(BTreeMap::from([
+          (object_key(&pod("api-0")), pod("api-0")),
+          (object_key(&other), other),
+        ])),
+        users: vec![],
+      },
+    );
+    // the nodes read all pods and the pods of a namespace are read from the same watch
+    assert_eq!(
+      watches
+        .items::<Pod>(&all, ActiveBlock::Nodes)
+        .unwrap()
+        .len(),
+      2
+    );
+    let names = |pods: Vec<Pod>| {
+      pods
+        .into_iter()
+        .map(|pod| pod.metadata.name.unwrap())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      names(watches.items::<Pod>(&web, ActiveBlock::Pods).unwrap()),
+      vec!["api-0"]
+    );
+
+    let add = |items: &mut BTreeMap<String, Pod>| {
+      items.insert(object_key(&pod("api-1")), pod("api-1"));
+    };
+    // only the first change until a view read the items requests its update
+    assert_eq!(
+      watches.update(&all, 7, add),
+      Some(vec![ActiveBlock::Nodes, ActiveBlock::Pods])
+    );
+    assert_eq!(
+      watches.update(&all, 7, |_: &mut BTreeMap<String, Pod>| {}),
+      Some(vec![])
+    );
+    assert_eq!(
+      names(watches.items::<Pod>(&web, ActiveBlock::Pods).unwrap()),
+      vec!["api-0", "api-1"]
+    );
+    assert_eq!(
+      watches.update(&all, 7, |_: &mut BTreeMap<String, Pod>| {}),
+      Some(vec![ActiveBlock::Pods])
+    );
+
+    // a replaced watch stops changing the items
+    assert_eq!(
+      watches.update(&all, 6, |_: &mut BTreeMap<String, Pod>| {}),
+      None
+    );
+    watches.remove(&all, 6);
+    assert!(watches.is_current(&all, 7));
+
+    // a watch is stopped when no view reads it anymore
+    let mut watches_map = watches.0.lock().unwrap();
+    watches_map.insert(
+      web.clone(),
+      Watch {
+        id: 8,
+        items: Box::new(BTreeMap::<String, Pod>::new()),
+        users: vec![],
+      },
+    );
+    use_watch(&mut watches_map, &web, ActiveBlock::Pods);
+    assert!(watches_map.contains_key(&all));
+    use_watch(&mut watches_map, &web, ActiveBlock::Nodes);
+    assert!(!watches_map.contains_key(&all));
+    drop(watches_map);
+    // or when the views reading it aren't kept up to date
+    watches.keep(&[ActiveBlock::Nodes]);
+    assert!(watches.is_current(&web, 8));
+    watches.keep(&[ActiveBlock::Services]);
+    assert!(watches.items::<Pod>(&web, ActiveBlock::Pods).is_none());
+  }
+}
@@ -0,0 +1,34 @@
+use anyhow::{anyhow, Result};
+use hyper::{header, Body, Client, Request};
+
+/// the Slack compatible body of an alert
+fn alert_body(message: &str) -> String {
+  serde_json::json!({ "text": format!("kdash: {}", message) }).to_string()
+}
+
+/// post an alert to a webhook, like a Slack incoming webhook
+pub async fn post_alert(url: &str, message: &str) -> Result<()> {
+  let https = hyper_openssl::HttpsConnector::new()?;
+  let client = Client::builder().build::<_, Body>(https);
+  let request = Request::post(url)
+    .header(header::CONTENT_TYPE, "application/json")
+    .body(Body::from(alert_body(message)))?;
+  let response = client.request(request).await?;
+  if !response.status().is_success() {
+    return Err(anyhow!("{} answered {}", url, response.status()));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_alert_body() {
+    assert_eq!(
+      alert_body("Pod web/\"api\" failed"),
+      r#"{"text":"kdash: Pod web/\"api\" failed"}"#
+    );
+  }
+}
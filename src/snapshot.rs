@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use serde_yaml::{Mapping, Value};
+use tokio::sync::Mutex;
+
+use crate::{
+  app::{ActiveBlock, App},
+  network::{get_client, IoEvent, Network},
+  ui::table_headers,
+};
+
+/// gap between the columns of a printed table
+const COLUMN_GAP: usize = 3;
+
+/// how `kdash get` prints the items
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+  /// the table like it is drawn
+  #[default]
+  Table,
+  /// a list with an object of every column per item, including the wide columns
+  Json,
+  /// the same list as YAML
+  Yaml,
+}
+
+/// fetch the items of a resource table once and print them, for `kdash get`. Without a namespace
+/// the items of all namespaces are listed
+pub async fn print_snapshot(
+  block: ActiveBlock,
+  context: Option<String>,
+  namespace: Option<String>,
+  output: OutputFormat,
+) -> Result<()> {
+  let event = IoEvent::list(block).ok_or_else(|| anyhow!("{:?} can't be listed", block))?;
+  let app = Arc::new(Mutex::new(App::default()));
+  app.lock().await.data.selected.ns = namespace;
+
+  let unauthorized = Arc::default();
+  let client = get_client(context, &unauthorized).await?;
+  let mut network = Network::new(client, unauthorized, &app);
+  // all pages are listed before printing
+  network.watch = false;
+  if block == ActiveBlock::Nodes {
+    // the usage columns of nodes come from metrics-server
+    network.handle_network_event(IoEvent::GetNodeMetrics).await;
+  }
+  network.handle_network_event(event).await;
+
+  let mut app = app.lock().await;
+  if !app.api_error.is_empty() {
+    return Err(anyhow!(app.api_error.clone()));
+  }
+  let (headers, rows) = app
+    .data
+    .resource_table(block)
+    .map(|table| {
+      table.set_columns(&table_headers(block));
+      table.set_wide(output != OutputFormat::Table);
+      table.rows()
+    })
+    .unwrap_or_default();
+  let out = match output {
+    OutputFormat::Table => format_table(&headers, &rows),
+    OutputFormat::Json => serde_json::to_string_pretty(&to_value(&headers, rows))? + "\n",
+    OutputFormat::Yaml => serde_yaml::to_string(&to_value(&headers, rows))?,
+  };
+  print!("{}", out);
+  Ok(())
+}
+
+/// a list of items with the cells by header, in column order
+fn to_value(headers: &[String], rows: Vec<Vec<String>>) -> Value {
+  Value::Sequence(
+    rows
+      .into_iter()
+      .map(|row| {
+        Value::Mapping(
+          headers
+            .iter()
+            .map(|header| Value::String(header.clone()))
+            .zip(row.into_iter().map(Value::String))
+            .collect::<Mapping>(),
+        )
+      })
+      .collect(),
+  )
+}
+
+/// the rows under the headers with the columns padded to their widest cell
+fn format_table(headers: &[String], rows: &[Vec<String>]) -> String {
+  let widths: Vec<usize> = headers
+    .iter()
+    .enumerate()
+    .map(|(i, header)| {
+      rows
+        .iter()
+        .filter_map(|row| row.get(i))
+        .chain([header])
+        .map(|cell| cell.chars().count())
+        .max()
+        .unwrap_or(0)
+    })
+    .collect();
+  [headers.to_vec()]
+    .iter()
+    .chain(rows)
+    .map(|row| {
+      let line = row
+        .iter()
+        .zip(&widths)
+        .map(|(cell, width)| format!("{:width$}", cell, width = width + COLUMN_GAP))
+        .collect::<String>();
+      format!("{}\n", line.trim_end())
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_format_table() {
+    let headers = vec!["Name".to_owned(), "Ready".into(), "Age".into()];
+    let rows = vec![
+      vec!["web-7d9c".to_owned(), "1/1".into(), "5m".into()],
+      vec!["db".to_owned(), "0/1".into(), "".into()],
+    ];
+    assert_eq!(
+      format_table(&headers, &rows),
+      "Name       Ready   Age\nweb-7d9c   1/1     5m\ndb         0/1\n"
+    );
+    assert_eq!(format_table(&headers, &[]), "Name   Ready   Age\n");
+
+    let value = to_value(&headers, rows);
+    assert_eq!(
+      serde_json::to_string(&value).unwrap(),
+      r#"[{"Name":"web-7d9c","Ready":"1/1","Age":"5m"},{"Name":"db","Ready":"0/1","Age":""}]"#
+    );
+    assert_eq!(
+      serde_yaml::to_string(&value).unwrap(),
+      "- Name: web-7d9c\n  Ready: 1/1\n  Age: 5m\n- Name: db\n  Ready: 0/1\n  Age: ''\n"
+    );
+  }
+}
@@ -0,0 +1,94 @@
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::models::TableSort;
+
+/// UI state saved on quit to `$XDG_STATE_HOME/kdash/state.yaml` or
+/// `~/.local/state/kdash/state.yaml` and restored on the next start
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct UiState {
+  pub context: Option<String>,
+  pub namespace: Option<String>,
+  /// index of the active resource tab
+  pub resource_tab: usize,
+  /// sort of the resource tables by table title
+  pub sorts: BTreeMap<String, TableSort>,
+  /// table filters used before, newest first
+  pub filter_history: Vec<String>,
+  /// contexts switched to, most recent first
+  pub recent_contexts: Vec<String>,
+  /// namespace each context was left in, None for all namespaces
+  pub context_namespaces: BTreeMap<String, Option<String>>,
+}
+
+impl UiState {
+  /// the saved state, a missing or unreadable state file starts from scratch
+  pub fn load() -> UiState {
+    state_path()
+      .and_then(|path| fs::read_to_string(path).ok())
+      .and_then(|contents| serde_yaml::from_str(&contents).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save(&self) -> Result<()> {
+    let path = match state_path() {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)
+        .with_context(|| format!("Unable to create state directory {}", dir.display()))?;
+    }
+    fs::write(&path, serde_yaml::to_string(self)?)
+      .with_context(|| format!("Unable to write state file {}", path.display()))
+  }
+}
+
+fn state_path() -> Option<PathBuf> {
+  env::var_os("XDG_STATE_HOME")
+    .map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state")))
+    .map(|dir| dir.join("kdash").join("state.yaml"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_ui_state_yaml() {
+    let mut sorts = BTreeMap::new();
+    sorts.insert(
+      "Pods".to_owned(),
+      TableSort {
+        column: "Age".into(),
+        descending: true,
+      },
+    );
+    let mut context_namespaces = BTreeMap::new();
+    context_namespaces.insert("prod".to_owned(), Some("payments".to_owned()));
+    context_namespaces.insert("dev".to_owned(), None);
+    let state = UiState {
+      context: Some("prod".into()),
+      namespace: None,
+      resource_tab: 6,
+      sorts,
+      filter_history: vec!["api".into()],
+      recent_contexts: vec!["prod".into(), "dev".into()],
+      context_namespaces,
+    };
+    let yaml = serde_yaml::to_string(&state).unwrap();
+    assert_eq!(serde_yaml::from_str::<UiState>(&yaml).unwrap(), state);
+    // fields missing from older state files keep their defaults
+    assert_eq!(
+      serde_yaml::from_str::<UiState>("context: dev\n").unwrap(),
+      UiState {
+        context: Some("dev".into()),
+        ..UiState::default()
+      }
+    );
+  }
+}
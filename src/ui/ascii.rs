@@ -0,0 +1,81 @@
+use tui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+/// rewrites the unicode box drawing, gauge and marker symbols of a drawn frame with ASCII, for
+/// terminals and fonts that mangle them. Drawn last, over the whole frame
+pub struct AsciiGraphics;
+
+impl Widget for AsciiGraphics {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    for y in area.top()..area.bottom() {
+      for x in area.left()..area.right() {
+        let cell = buf.get_mut(x, y);
+        if let Some(symbol) = to_ascii(&cell.symbol) {
+          cell.set_symbol(symbol);
+        }
+      }
+    }
+  }
+}
+
+/// the ASCII stand in for a symbol, `None` for ASCII and text that isn't drawing
+fn to_ascii(symbol: &str) -> Option<&'static str> {
+  let mut chars = symbol.chars();
+  let c = chars.next()?;
+  if c.is_ascii() || chars.next().is_some() {
+    return None;
+  }
+  let ascii = match c {
+    '─' | '━' | '═' | '╌' | '┄' => "-",
+    '│' | '┃' | '║' | '╎' | '┆' => "|",
+    // corners and joints of the box drawing block
+    '\u{2500}'..='\u{257f}' => "+",
+    '█' | '▉' | '▊' | '▋' | '▌' | '▍' | '▎' | '▏' => "#",
+    '▀' | '▄' | '▁' | '▂' | '▃' | '▅' | '▆' | '▇' => "=",
+    '\u{2800}'..='\u{28ff}' => ".",
+    '●' | '•' | '♥' => "*",
+    '▸' | '►' | '→' => ">",
+    '←' => "<",
+    '↑' => "^",
+    '↓' => "v",
+    _ => return None,
+  };
+  Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+  use tui::{
+    backend::TestBackend,
+    widgets::{Block, Borders},
+    Terminal,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_to_ascii() {
+    assert_eq!(to_ascii("┌"), Some("+"));
+    assert_eq!(to_ascii("━"), Some("-"));
+    assert_eq!(to_ascii("⣿"), Some("."));
+    assert_eq!(to_ascii("↓"), Some("v"));
+    assert_eq!(to_ascii("a"), None);
+    // text in other scripts is left alone
+    assert_eq!(to_ascii("é"), None);
+  }
+
+  #[test]
+  fn test_ascii_graphics() {
+    let backend = TestBackend::new(6, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal
+      .draw(|f| {
+        let block = Block::default().borders(Borders::ALL).title("é");
+        f.render_widget(block, f.size());
+        f.render_widget(AsciiGraphics, f.size());
+      })
+      .unwrap();
+    terminal
+      .backend()
+      .assert_buffer(&Buffer::with_lines(vec!["+é---+", "|    |", "+----+"]));
+  }
+}
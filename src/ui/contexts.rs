@@ -8,7 +8,7 @@ use tui::{
 use super::{
   utils::{
     layout_block_active, loading, style_highlight, style_primary, style_secondary,
-    table_header_style,
+    table_header_style, table_rows_area,
   },
   HIGHLIGHT,
 };
@@ -19,6 +19,10 @@ pub fn draw_contexts<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect
   let block = layout_block_active(title.as_str(), app.light_theme);
 
   if !app.data.contexts.items.is_empty() {
+    app
+      .data
+      .contexts
+      .set_view(table_rows_area(block.inner(area)));
     let rows = app.data.contexts.items.iter().map(|c| {
       let style = if c.is_active {
         style_secondary(app.light_theme)
@@ -29,22 +33,24 @@ pub fn draw_contexts<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect
         Cell::from(c.name.as_ref()),
         Cell::from(c.cluster.as_ref()),
         Cell::from(c.user.as_ref()),
+        Cell::from(c.namespace.as_deref().unwrap_or("")),
       ])
       .style(style)
     });
 
     let table = Table::new(rows)
       .header(table_header_style(
-        vec!["Context", "Cluster", "User"],
+        vec!["Context", "Cluster", "User", "Namespace"],
         app.light_theme,
       ))
       .block(block)
       .widths(&[
-        Constraint::Percentage(34),
-        Constraint::Percentage(33),
-        Constraint::Percentage(33),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
       ])
-      .highlight_style(style_highlight())
+      .highlight_style(style_highlight(app.light_theme))
       .highlight_symbol(HIGHLIGHT);
 
     f.render_stateful_widget(table, area, &mut app.data.contexts.state);
@@ -0,0 +1,148 @@
+use tui::{
+  backend::Backend,
+  layout::{Constraint, Rect},
+  widgets::{Block, Cell, Paragraph, Row, Table, Wrap},
+  Frame,
+};
+
+use super::{
+  resource_tabs::health_style,
+  utils::{
+    layout_block_active, layout_block_default, loading, style_failure, style_highlight,
+    style_secondary, table_header_style, table_rows_area, vertical_chunks,
+  },
+  HIGHLIGHT,
+};
+use crate::app::{models::TableRow, ActiveBlock, App};
+
+const COLUMNS: [&str; 8] = [
+  "Context",
+  "Status",
+  "Nodes",
+  "Pods",
+  "Failing",
+  "Pending",
+  "CPU Headroom",
+  "Mem Headroom",
+];
+
+const EVENT_COLUMNS: [&str; 7] = [
+  "Last Seen",
+  "Context",
+  "Namespace",
+  "Object",
+  "Reason",
+  "Count",
+  "Message",
+];
+
+pub fn draw_fleet<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  if app.fleet_contexts.is_empty() {
+    let text = Paragraph::new(
+      "\n List the contexts to compare in `fleet_contexts` in the config file to see their health here",
+    )
+    .style(style_secondary(app.light_theme))
+    .block(layout_block_active(" Fleet [0] ", app.light_theme))
+    .wrap(Wrap { trim: false });
+    f.render_widget(text, area);
+    return;
+  }
+  // a row per context, the warning events get the rest
+  let chunks = vertical_chunks(
+    vec![
+      Constraint::Length(app.fleet_contexts.len() as u16 + 3),
+      Constraint::Min(3),
+    ],
+    area,
+  );
+  let events_active = app.get_current_route().active_block == ActiveBlock::FleetEvents;
+  draw_clusters(f, app, chunks[0], !events_active);
+  draw_events(f, app, chunks[1], events_active);
+}
+
+fn fleet_block(title: &str, active: bool, light: bool) -> Block<'_> {
+  if active {
+    layout_block_active(title, light)
+  } else {
+    layout_block_default(title)
+  }
+}
+
+fn draw_clusters<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect, active: bool) {
+  let title = format!(" Fleet [{}] ", app.fleet_contexts.len());
+  let block = fleet_block(&title, active, app.light_theme);
+
+  if !app.fleet.items.is_empty() {
+    app.fleet.set_view(table_rows_area(block.inner(area)));
+    let light_theme = app.light_theme;
+    let rows = app.fleet.items.iter().map(|cluster| {
+      Row::new(
+        COLUMNS
+          .iter()
+          .map(|column| Cell::from(cluster.column_value(column)))
+          .collect::<Vec<_>>(),
+      )
+      .style(health_style(cluster.health(), light_theme))
+    });
+
+    let table = Table::new(rows)
+      .header(table_header_style(COLUMNS.to_vec(), app.light_theme))
+      .block(block)
+      .widths(&[
+        Constraint::Percentage(20),
+        Constraint::Percentage(30),
+        Constraint::Percentage(8),
+        Constraint::Percentage(8),
+        Constraint::Percentage(8),
+        Constraint::Percentage(8),
+        Constraint::Percentage(9),
+        Constraint::Percentage(9),
+      ])
+      .highlight_style(style_highlight(app.light_theme))
+      .highlight_symbol(HIGHLIGHT);
+
+    f.render_stateful_widget(table, area, &mut app.fleet.state);
+  } else {
+    loading(f, block, area, app.is_loading, app.light_theme);
+  }
+}
+
+fn draw_events<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect, active: bool) {
+  let title = format!(" Warning Events [{}] ", app.fleet_events.items.len());
+  let block = fleet_block(&title, active, app.light_theme);
+
+  if !app.fleet_events.items.is_empty() {
+    app
+      .fleet_events
+      .set_view(table_rows_area(block.inner(area)));
+    let light_theme = app.light_theme;
+    let rows = app.fleet_events.items.iter().map(|event| {
+      Row::new(
+        EVENT_COLUMNS
+          .iter()
+          .map(|column| Cell::from(event.column_value(column)))
+          .collect::<Vec<_>>(),
+      )
+      .style(style_failure(light_theme))
+    });
+
+    let table = Table::new(rows)
+      .header(table_header_style(EVENT_COLUMNS.to_vec(), app.light_theme))
+      .block(block)
+      .widths(&[
+        Constraint::Length(9),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+        Constraint::Percentage(18),
+        Constraint::Percentage(12),
+        Constraint::Length(6),
+        Constraint::Percentage(46),
+      ])
+      .highlight_style(style_highlight(app.light_theme))
+      .highlight_symbol(HIGHLIGHT);
+
+    f.render_stateful_widget(table, area, &mut app.fleet_events.state);
+  } else {
+    f.render_widget(block, area);
+  }
+}
@@ -1,13 +1,14 @@
 use tui::{
   backend::Backend,
   layout::{Constraint, Rect},
+  style::Modifier,
   widgets::{Row, Table},
   Frame,
 };
 
 use super::{
   utils::{
-    layout_block_active_span, style_highlight, style_primary, style_secondary,
+    layout_block_active_span, style_highlight, style_primary, style_secondary, table_rows_area,
     title_with_dual_style, vertical_chunks,
   },
   HIGHLIGHT,
@@ -19,25 +20,41 @@ pub fn draw_help<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
 
   // Create a one-column table to avoid flickering due to non-determinism when
   // resolving constraints on widths of table columns.
-  let format_row =
-    |r: &Vec<String>| -> Vec<String> { vec![format!("{:50}{:40}{:20}", r[0], r[1], r[2])] };
+  let format_row = |r: &Vec<String>| -> Vec<String> {
+    match r.as_slice() {
+      [key, action] => vec![format!("{:50}{:50}", key, action)],
+      // group titles
+      _ => vec![r.concat()],
+    }
+  };
 
-  let header = ["Key", "Action", "Context"];
+  let header = ["Key", "Action"];
   let header = format_row(&header.iter().map(|s| s.to_string()).collect());
 
-  let help_docs = app
+  let rows = app.help_docs.items.iter().map(|item| {
+    let style = if item.len() == 1 {
+      style_secondary(app.light_theme).add_modifier(Modifier::BOLD)
+    } else {
+      style_primary(app.light_theme)
+    };
+    Row::new(format_row(item)).style(style)
+  });
+  let rows: Vec<Row<'_>> = rows.collect();
+
+  let title = if app.is_help_searching || !app.help_query.is_empty() {
+    format!(
+      " Help [/{}{}] ",
+      app.help_query,
+      if app.is_help_searching { "_" } else { "" }
+    )
+  } else {
+    " Help ".into()
+  };
+  let title = title_with_dual_style(title, "| search </> | close <esc> ".into(), app.light_theme);
+  let block = layout_block_active_span(title, app.light_theme);
+  app
     .help_docs
-    .items
-    .iter()
-    .map(format_row)
-    .collect::<Vec<Vec<String>>>();
-  let help_docs = &help_docs[0_usize..];
-
-  let rows = help_docs
-    .iter()
-    .map(|item| Row::new(item.clone()).style(style_primary(app.light_theme)));
-
-  let title = title_with_dual_style(" Help ".into(), "| close <esc> ".into(), app.light_theme);
+    .set_view(table_rows_area(block.inner(chunks[0])));
 
   let help_menu = Table::new(rows)
     .header(
@@ -45,8 +62,8 @@ pub fn draw_help<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
         .style(style_secondary(app.light_theme))
         .bottom_margin(0),
     )
-    .block(layout_block_active_span(title, app.light_theme))
-    .highlight_style(style_highlight())
+    .block(block)
+    .highlight_style(style_highlight(app.light_theme))
     .highlight_symbol(HIGHLIGHT)
     .widths(&[Constraint::Percentage(100)]);
   f.render_stateful_widget(help_menu, chunks[0], &mut app.help_docs.state);
@@ -78,19 +95,19 @@ mod tests {
       .unwrap();
 
     let mut expected = Buffer::with_lines(vec![
-        "┌ Help | close <esc> ──────────────────────────────────────────────────────────────────────────────┐",
-        "│   Key                                               Action                                  Conte│",
-        "│=> <Ctrl+c> | <q>                                    Quit                                    Gener│",
-        "│   <Esc>                                             Close child page/Go back                Gener│",
-        "│   <?>                                               Help page                               Gener│",
-        "│   <Enter>                                           Select table row                        Gener│",
+        "┌ Help | search </> | close <esc> ─────────────────────────────────────────────────────────────────┐",
+        "│   Key                                               Action                                       │",
+        "│=> Global                                                                                         │",
+        "│   <Ctrl+c> | <q>                                    Quit                                         │",
+        "│   <Esc> | <Backspace>                               Close child page/Go back                     │",
+        "│   <?>                                               Help page                                    │",
         "└──────────────────────────────────────────────────────────────────────────────────────────────────┘",
       ]);
     // set row styles
     // First row heading style
     for col in 0..=99 {
       match col {
-        0 | 21..=99 => {
+        0 | 34..=99 => {
           expected
             .get_mut(col, 0)
             .set_style(Style::default().fg(COLOR_YELLOW));
@@ -119,14 +136,14 @@ mod tests {
         .set_style(Style::default().fg(COLOR_YELLOW));
     }
 
-    // first table data row style
+    // first table row is the selected group title
     for col in 0..=99 {
       match col {
         1..=98 => {
           expected.get_mut(col, 2).set_style(
             Style::default()
-              .fg(COLOR_CYAN)
-              .add_modifier(Modifier::REVERSED),
+              .fg(COLOR_YELLOW)
+              .add_modifier(Modifier::BOLD | Modifier::REVERSED),
           );
         }
         _ => {
@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+
+use tui::{
+  backend::Backend,
+  layout::{Constraint, Rect},
+  symbols,
+  text::Span,
+  widgets::{Axis, Chart, Dataset, GraphType},
+  Frame,
+};
+
+use super::utils::{
+  horizontal_chunks, layout_block_active, layout_block_default, loading, style_default,
+  style_primary, style_secondary, vertical_chunks,
+};
+use crate::app::App;
+
+pub fn draw_metrics_history<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let chunks = vertical_chunks(
+    vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+    area,
+  );
+  draw_cluster_chart(f, app, chunks[0]);
+
+  let chunks = horizontal_chunks(
+    vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+    chunks[1],
+  );
+  draw_pod_charts(f, app, chunks[0], chunks[1]);
+}
+
+fn draw_cluster_chart<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let block = layout_block_active(" Cluster Usage (% of allocatable) ", app.light_theme);
+  let history = &app.data.metrics_history;
+
+  if history.cluster_cpu.is_empty() && history.cluster_mem.is_empty() {
+    loading(f, block, area, app.is_loading, app.light_theme);
+    return;
+  }
+
+  let cpu = to_points(&history.cluster_cpu);
+  let mem = to_points(&history.cluster_mem);
+  let max = max_value(&[&cpu, &mem]).max(100f64);
+  let marker = get_marker(app.enhanced_graphics);
+
+  let datasets = vec![
+    Dataset::default()
+      .name("CPU")
+      .marker(marker)
+      .graph_type(GraphType::Line)
+      .style(style_primary(app.light_theme))
+      .data(&cpu),
+    Dataset::default()
+      .name("Memory")
+      .marker(marker)
+      .graph_type(GraphType::Line)
+      .style(style_secondary(app.light_theme))
+      .data(&mem),
+  ];
+
+  let chart = Chart::new(datasets)
+    .block(block)
+    .x_axis(time_axis(&[&cpu, &mem], app.light_theme))
+    .y_axis(value_axis(max, "%", app.light_theme));
+  f.render_widget(chart, area);
+}
+
+fn draw_pod_charts<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, left: Rect, right: Rect) {
+  let history = &app.data.metrics_history;
+  let pod = history.pod.clone().unwrap_or_default();
+
+  let cpu_title = format!(" Pod CPU (m) [{}] ", pod);
+  let mem_title = format!(" Pod Memory (Mi) [{}] ", pod);
+
+  if history.pod_cpu.is_empty() {
+    let message = " Pod usage (select a pod in the pods tab) ";
+    loading(
+      f,
+      layout_block_default(message),
+      left,
+      app.is_loading,
+      app.light_theme,
+    );
+    loading(
+      f,
+      layout_block_default(message),
+      right,
+      app.is_loading,
+      app.light_theme,
+    );
+    return;
+  }
+
+  let marker = get_marker(app.enhanced_graphics);
+  let cpu = to_points(&history.pod_cpu);
+  let mem = to_points(&history.pod_mem);
+
+  let charts = [(cpu_title, &cpu, "m", left), (mem_title, &mem, "Mi", right)];
+  for (title, points, unit, area) in charts {
+    let datasets = vec![Dataset::default()
+      .marker(marker)
+      .graph_type(GraphType::Line)
+      .style(style_primary(app.light_theme))
+      .data(points)];
+
+    let chart = Chart::new(datasets)
+      .block(layout_block_default(&title))
+      .x_axis(time_axis(&[points], app.light_theme))
+      .y_axis(value_axis(max_value(&[points]), unit, app.light_theme));
+    f.render_widget(chart, area);
+  }
+}
+
+fn get_marker(enhanced_graphics: bool) -> symbols::Marker {
+  if enhanced_graphics {
+    symbols::Marker::Braille
+  } else {
+    symbols::Marker::Dot
+  }
+}
+
+fn to_points(series: &VecDeque<(f64, f64)>) -> Vec<(f64, f64)> {
+  series.iter().copied().collect()
+}
+
+fn max_value(series: &[&Vec<(f64, f64)>]) -> f64 {
+  series
+    .iter()
+    .flat_map(|s| s.iter().map(|(_, v)| *v))
+    .fold(0f64, f64::max)
+}
+
+/// bounds of the x axis covering all the given series
+fn time_bounds(series: &[&Vec<(f64, f64)>]) -> [f64; 2] {
+  let mut points = series.iter().flat_map(|s| s.iter().map(|(t, _)| *t));
+  match points.next() {
+    Some(first) => {
+      let (min, max) = points.fold((first, first), |(min, max), t| (min.min(t), max.max(t)));
+      // avoid a zero width axis when there is only one point
+      [min, max.max(min + 1f64)]
+    }
+    None => [0f64, 1f64],
+  }
+}
+
+fn time_axis<'a>(series: &[&Vec<(f64, f64)>], light: bool) -> Axis<'a> {
+  let bounds = time_bounds(series);
+  Axis::default()
+    .title(Span::styled("session time", style_default(light)))
+    .style(style_default(light))
+    .bounds(bounds)
+    .labels(vec![
+      Span::raw(format_duration(bounds[0])),
+      Span::raw(format_duration(bounds[1])),
+    ])
+}
+
+fn value_axis<'a>(max: f64, unit: &str, light: bool) -> Axis<'a> {
+  // leave some head room above the highest point
+  let max = if max > 0f64 { max * 1.1 } else { 1f64 };
+  Axis::default()
+    .style(style_default(light))
+    .bounds([0f64, max])
+    .labels(vec![
+      Span::raw(format!("0{}", unit)),
+      Span::raw(format!("{:.0}{}", max / 2f64, unit)),
+      Span::raw(format!("{:.0}{}", max, unit)),
+    ])
+}
+
+fn format_duration(secs: f64) -> String {
+  let secs = secs as u64;
+  format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  #[allow(clippy::float_cmp)]
+  fn test_time_bounds() {
+    assert_eq!(time_bounds(&[]), [0f64, 1f64]);
+    assert_eq!(time_bounds(&[&vec![(5f64, 1f64)]]), [5f64, 6f64]);
+    assert_eq!(
+      time_bounds(&[&vec![(5f64, 1f64), (9f64, 2f64)], &vec![(3f64, 1f64)]]),
+      [3f64, 9f64]
+    );
+  }
+
+  #[test]
+  #[allow(clippy::float_cmp)]
+  fn test_max_value() {
+    assert_eq!(max_value(&[]), 0f64);
+    assert_eq!(
+      max_value(&[&vec![(5f64, 1f64), (9f64, 20f64)], &vec![(3f64, 7f64)]]),
+      20f64
+    );
+  }
+
+  #[test]
+  fn test_format_duration() {
+    assert_eq!(format_duration(0f64), "00:00");
+    assert_eq!(format_duration(61.5), "01:01");
+    assert_eq!(format_duration(3600f64), "60:00");
+  }
+}
@@ -1,29 +1,47 @@
+mod ascii;
 mod contexts;
+mod fleet;
 mod help;
+mod metrics_history;
 mod overview;
 mod resource_tabs;
+mod status_bar;
 mod utilization;
 mod utils;
+mod yaml;
+
+use std::time::Instant;
 
 use tui::{
   backend::Backend,
   layout::{Alignment, Constraint, Rect},
   text::{Span, Spans, Text},
-  widgets::{Block, Borders, Paragraph, Tabs, Wrap},
+  widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
   Frame,
 };
 
 use self::{
+  ascii::AsciiGraphics,
   contexts::draw_contexts,
+  fleet::draw_fleet,
   help::draw_help,
+  metrics_history::draw_metrics_history,
   overview::draw_overview,
+  status_bar::draw_status_bar,
   utilization::draw_utilization,
   utils::{
-    horizontal_chunks_with_margin, layout_block, style_default, style_failure, style_help,
-    style_main_background, style_primary, style_secondary, title_style_logo, vertical_chunks,
+    centered_rect, horizontal_chunks_with_margin, layout_block, layout_block_active,
+    layout_block_default, style_default, style_failure, style_help, style_highlight,
+    style_main_background, style_primary, style_secondary, style_success, style_warning,
+    tab_title_areas, title_style_logo, vertical_chunks,
   },
 };
-use crate::app::{App, RouteId};
+use crate::app::{
+  export::ExportTarget, key_binding::key_bindings, notifications::NotificationLevel,
+  table_filter::TableFilter, App, LayoutMode, RouteId,
+};
+
+pub use self::{resource_tabs::table_headers, utils::set_custom_theme};
 
 static HIGHLIGHT: &str = "=> ";
 
@@ -31,25 +49,48 @@ pub fn draw<B: Backend>(f: &mut Frame<'_, B>, app: &mut App) {
   let block = Block::default().style(style_main_background(app.light_theme));
   f.render_widget(block, f.size());
 
+  // the compact layout has a single line header without the logo
+  let header_height = if app.layout_mode == LayoutMode::Compact {
+    1
+  } else {
+    3
+  };
   let chunks = if !app.api_error.is_empty() {
     let chunks = vertical_chunks(
       vec![
-        Constraint::Length(3),
+        Constraint::Length(header_height),
+        Constraint::Length(1),
         Constraint::Length(3),
         Constraint::Min(0),
+        Constraint::Length(1),
       ],
       f.size(),
     );
-    draw_app_error(f, app, chunks[1]);
+    draw_app_error(f, app, chunks[2]);
     chunks
   } else {
-    vertical_chunks(vec![Constraint::Length(3), Constraint::Min(0)], f.size())
+    vertical_chunks(
+      vec![
+        Constraint::Length(header_height),
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+      ],
+      f.size(),
+    )
   };
 
   // draw header and logo
-  draw_app_header(f, app, chunks[0]);
+  if app.layout_mode == LayoutMode::Compact {
+    draw_compact_header(f, app, chunks[0]);
+  } else {
+    draw_app_header(f, app, chunks[0]);
+  }
+  draw_breadcrumbs(f, app, chunks[1]);
 
-  let last_chunk = chunks[chunks.len() - 1];
+  draw_status_bar(f, app, chunks[chunks.len() - 1]);
+
+  let last_chunk = chunks[chunks.len() - 2];
   match app.get_current_route().id {
     RouteId::HelpMenu => {
       draw_help(f, app, last_chunk);
@@ -60,24 +101,402 @@ pub fn draw<B: Backend>(f: &mut Frame<'_, B>, app: &mut App) {
     RouteId::Utilization => {
       draw_utilization(f, app, last_chunk);
     }
+    RouteId::MetricsHistory => {
+      draw_metrics_history(f, app, last_chunk);
+    }
+    RouteId::Fleet => {
+      draw_fleet(f, app, last_chunk);
+    }
     _ => {
       draw_overview(f, app, last_chunk);
     }
   }
+
+  if app.command_palette.is_some() {
+    draw_command_palette(f, app, last_chunk);
+  }
+  if app.resource_finder.is_some() {
+    draw_resource_finder(f, app, last_chunk);
+  }
+  if app.context_switcher.is_some() {
+    draw_context_switcher(f, app, last_chunk);
+  }
+  if app.cell_picker.is_some() {
+    draw_cell_picker(f, app, last_chunk);
+  }
+  if app.filter_input.is_some() {
+    draw_filter_input(f, app, last_chunk);
+  }
+  if app.export_prompt.is_some() {
+    draw_export_prompt(f, app, last_chunk);
+  }
+  if app.confirmation.is_some() {
+    draw_confirmation(f, app, last_chunk);
+  }
+  if app.plugin_output.is_some() {
+    draw_plugin_output(f, app, last_chunk);
+  }
+  if !app.notifications.is_empty() {
+    draw_notifications(f, app, last_chunk);
+  }
+  if app.ascii_graphics {
+    f.render_widget(AsciiGraphics, f.size());
+  }
+}
+
+/// stack the notifications in the bottom right corner, newest at the bottom
+fn draw_notifications<B: Backend>(f: &mut Frame<'_, B>, app: &App, area: Rect) {
+  let width = 50.min(area.width);
+  let mut bottom = area.y + area.height;
+  for notification in app.notifications.iter().rev() {
+    if bottom < area.y + 3 {
+      break;
+    }
+    bottom -= 3;
+    let rect = Rect::new(area.x + area.width - width, bottom, width, 3);
+    let (title, style) = match notification.level {
+      NotificationLevel::Info => (" Info ", style_primary(app.light_theme)),
+      NotificationLevel::Success => (" Success ", style_success(app.light_theme)),
+      NotificationLevel::Failure => (" Failed ", style_failure(app.light_theme)),
+    };
+    let paragraph = Paragraph::new(notification.message.clone())
+      .style(style)
+      .block(
+        Block::default()
+          .title(title)
+          .borders(Borders::ALL)
+          .style(style_main_background(app.light_theme).patch(style)),
+      );
+    f.render_widget(Clear, rect);
+    f.render_widget(paragraph, rect);
+  }
+}
+
+fn draw_breadcrumbs<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let crumbs = app.breadcrumbs();
+  let last = crumbs.len() - 1;
+  let mut spans = vec![Span::raw(" ")];
+  for (i, crumb) in crumbs.into_iter().enumerate() {
+    if i == last {
+      spans.push(Span::styled(crumb, style_secondary(app.light_theme)));
+    } else {
+      spans.push(Span::styled(crumb, style_default(app.light_theme)));
+      spans.push(Span::styled(" ▸ ", style_help(app.light_theme)));
+    }
+  }
+  if app.read_only {
+    spans.push(Span::styled(" [read-only]", style_warning(app.light_theme)));
+  }
+  f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+fn draw_command_palette<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let light_theme = app.light_theme;
+  if let Some(palette) = app.command_palette.as_mut() {
+    let items = palette
+      .suggestions
+      .items
+      .iter()
+      .map(|s| ListItem::new(s.clone()))
+      .collect();
+    draw_input_popup(
+      f,
+      area,
+      " Command | complete <tab> | run <enter> | close <esc> ",
+      Some((format!(":{}", palette.input), " Matches ")),
+      items,
+      &mut palette.suggestions.state,
+      light_theme,
+    );
+  }
+}
+
+fn draw_resource_finder<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let light_theme = app.light_theme;
+  if let Some(finder) = app.resource_finder.as_mut() {
+    let items = finder
+      .matches
+      .items
+      .iter()
+      .map(|m| {
+        let name = if m.namespace.is_empty() {
+          m.name.clone()
+        } else {
+          format!("{}/{}", m.namespace, m.name)
+        };
+        ListItem::new(Spans::from(vec![
+          Span::styled(format!("{:22}", m.kind), style_secondary(light_theme)),
+          Span::raw(name),
+        ]))
+      })
+      .collect();
+    draw_input_popup(
+      f,
+      area,
+      " Find resource | jump <enter> | close <esc> ",
+      Some((finder.input.clone(), " Matches ")),
+      items,
+      &mut finder.matches.state,
+      light_theme,
+    );
+  }
+}
+
+fn draw_context_switcher<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let light_theme = app.light_theme;
+  if let Some(switcher) = app.context_switcher.as_mut() {
+    let items = switcher
+      .matches
+      .items
+      .iter()
+      .map(|(name, active)| {
+        let marker = if *active { "* " } else { "  " };
+        ListItem::new(Spans::from(vec![
+          Span::styled(marker, style_secondary(light_theme)),
+          Span::raw(name.clone()),
+        ]))
+      })
+      .collect();
+    draw_input_popup(
+      f,
+      area,
+      " Switch context | switch <enter> | close <esc> ",
+      Some((switcher.input.clone(), " Contexts ")),
+      items,
+      &mut switcher.matches.state,
+      light_theme,
+    );
+  }
+}
+
+fn draw_cell_picker<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let light_theme = app.light_theme;
+  if let Some(picker) = app.cell_picker.as_mut() {
+    let width = picker.items.iter().map(|(c, _)| c.len()).max().unwrap_or(0) + 2;
+    let items = picker
+      .items
+      .iter()
+      .map(|(column, value)| {
+        ListItem::new(Spans::from(vec![
+          Span::styled(format!("{:width$}", column), style_secondary(light_theme)),
+          Span::raw(value.clone()),
+        ]))
+      })
+      .collect();
+    draw_input_popup(
+      f,
+      area,
+      " Copy cell | copy <enter> | close <esc> ",
+      None,
+      items,
+      &mut picker.state,
+      light_theme,
+    );
+  }
+}
+
+fn draw_filter_input<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let light_theme = app.light_theme;
+  if let Some(filter) = app.filter_input.as_mut() {
+    let width = filter
+      .choices
+      .items
+      .iter()
+      .map(|(name, _)| name.len())
+      .max()
+      .unwrap_or(0);
+    let items = filter
+      .choices
+      .items
+      .iter()
+      .map(|(name, filter)| {
+        let name = if width > 0 {
+          format!("{:width$}  ", name)
+        } else {
+          String::new()
+        };
+        ListItem::new(Spans::from(vec![
+          Span::styled(name, style_secondary(light_theme)),
+          Span::raw(filter.clone()),
+        ]))
+      })
+      .collect();
+    let title = match TableFilter::parse(&filter.input) {
+      Err(e) => format!(" Filter | {} ", e),
+      Ok(_) => {
+        " Filter by name (! exclude, ~ regex, -l labels, -f fields) | recall <up/down> | apply <enter> | cancel <esc> "
+          .to_owned()
+      }
+    };
+    draw_input_popup(
+      f,
+      area,
+      &title,
+      Some((format!("/{}", filter.input), " Saved and recent ")),
+      items,
+      &mut filter.choices.state,
+      light_theme,
+    );
+  }
+}
+
+fn draw_export_prompt<B: Backend>(f: &mut Frame<'_, B>, app: &App, area: Rect) {
+  if let Some(prompt) = &app.export_prompt {
+    let (title, option) = match &prompt.target {
+      ExportTarget::Manifest {
+        kind,
+        name,
+        format,
+        strip_managed_fields,
+        ..
+      } => (
+        format!(
+          " Save {} {} as {} | toggle managedFields <tab> | save <enter> | cancel <esc> ",
+          kind,
+          name,
+          format.title()
+        ),
+        if *strip_managed_fields {
+          "managedFields left out".to_owned()
+        } else {
+          "managedFields kept".to_owned()
+        },
+      ),
+      ExportTarget::Table {
+        title,
+        headers,
+        rows,
+        ..
+      } => (
+        format!(" Save {} as CSV | save <enter> | cancel <esc> ", title),
+        format!("{} rows, {} columns as shown", rows.len(), headers.len()),
+      ),
+    };
+    draw_input_popup(
+      f,
+      area,
+      &title,
+      Some((prompt.path.clone(), " Options ")),
+      vec![ListItem::new(option)],
+      &mut ListState::default(),
+      app.light_theme,
+    );
+  }
+}
+
+/// an optional text input at the top of the area with a titled list of choices below it
+fn draw_confirmation<B: Backend>(f: &mut Frame<'_, B>, app: &App, area: Rect) {
+  if let Some(confirmation) = &app.confirmation {
+    match &confirmation.typed_name {
+      Some(name) => draw_input_popup(
+        f,
+        area,
+        &format!(
+          " {} | confirm <enter> | cancel <esc> ",
+          confirmation.question
+        ),
+        Some((confirmation.input.clone(), " Confirm ")),
+        vec![ListItem::new(format!("Type {} to confirm", name))],
+        &mut ListState::default(),
+        app.light_theme,
+      ),
+      None => draw_input_popup(
+        f,
+        area,
+        " Confirm | yes <y> | no <n> ",
+        None,
+        vec![ListItem::new(confirmation.question.clone())],
+        &mut ListState::default(),
+        app.light_theme,
+      ),
+    }
+  }
+}
+
+/// the output of a plugin over most of the screen
+fn draw_plugin_output<B: Backend>(f: &mut Frame<'_, B>, app: &App, area: Rect) {
+  if let Some((name, output)) = &app.plugin_output {
+    let area = centered_rect(area.width * 9 / 10, area.height * 9 / 10, area);
+    f.render_widget(Clear, area);
+    let title = format!(
+      " {} | scroll <{}/{}> | close <{}> ",
+      name,
+      key_bindings().up.key,
+      key_bindings().down.key,
+      key_bindings().esc.key
+    );
+    let paragraph = Paragraph::new(output.get_txt())
+      .style(style_primary(app.light_theme))
+      .block(layout_block_active(&title, app.light_theme))
+      .scroll((output.offset, 0));
+    f.render_widget(paragraph, area);
+  }
+}
+
+fn draw_input_popup<B: Backend>(
+  f: &mut Frame<'_, B>,
+  area: Rect,
+  title: &str,
+  input: Option<(String, &str)>,
+  items: Vec<ListItem<'_>>,
+  state: &mut ListState,
+  light_theme: bool,
+) {
+  let input_height = if input.is_some() { 3 } else { 0 };
+  let width = (area.width * 3 / 5).max(30).min(area.width);
+  let height = (items.len() as u16 + input_height + 2).min(area.height);
+  let area = Rect::new(area.x + (area.width - width) / 2, area.y, width, height);
+  let chunks = vertical_chunks(
+    vec![Constraint::Length(input_height), Constraint::Min(0)],
+    area,
+  );
+  f.render_widget(Clear, area);
+
+  let list_block = match input {
+    Some((input, list_title)) => {
+      let cursor_x = chunks[0].x + 1 + input.chars().count() as u16;
+      let paragraph = Paragraph::new(input)
+        .style(style_primary(light_theme))
+        .block(layout_block_active(title, light_theme));
+      f.render_widget(paragraph, chunks[0]);
+      f.set_cursor(cursor_x, chunks[0].y + 1);
+      layout_block_default(list_title)
+    }
+    None => layout_block_active(title, light_theme),
+  };
+
+  f.render_stateful_widget(
+    List::new(items)
+      .block(list_block)
+      .style(style_primary(light_theme))
+      .highlight_style(style_highlight(light_theme))
+      .highlight_symbol(HIGHLIGHT),
+    chunks[1],
+    state,
+  );
 }
 
 fn draw_app_header<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
   let chunks =
-    horizontal_chunks_with_margin(vec![Constraint::Length(75), Constraint::Min(0)], area, 1);
+    horizontal_chunks_with_margin(vec![Constraint::Length(85), Constraint::Min(0)], area, 1);
 
-  let titles = app
+  let titles: Vec<Spans<'_>> = app
     .main_tabs
     .items
     .iter()
-    .map(|t| Spans::from(Span::styled(&t.title, style_default(app.light_theme))))
+    .map(|t| {
+      Spans::from(Span::styled(
+        t.title.clone(),
+        style_default(app.light_theme),
+      ))
+    })
     .collect();
+  let block = layout_block(title_style_logo(app.title, app.light_theme));
+  app
+    .main_tabs
+    .set_title_areas(tab_title_areas(&titles, block.inner(area)));
   let tabs = Tabs::new(titles)
-    .block(layout_block(title_style_logo(app.title, app.light_theme)))
+    .block(block)
     .highlight_style(style_secondary(app.light_theme))
     .select(app.main_tabs.index);
 
@@ -85,18 +504,59 @@ fn draw_app_header<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect)
   draw_header_text(f, app, chunks[1]);
 }
 
+fn draw_compact_header<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let titles: Vec<Spans<'_>> = app
+    .main_tabs
+    .items
+    .iter()
+    .map(|t| {
+      Spans::from(Span::styled(
+        t.title.clone(),
+        style_default(app.light_theme),
+      ))
+    })
+    .collect();
+  app
+    .main_tabs
+    .set_title_areas(tab_title_areas(&titles, area));
+  let tabs = Tabs::new(titles)
+    .highlight_style(style_secondary(app.light_theme))
+    .select(app.main_tabs.index);
+  f.render_widget(tabs, area);
+}
+
 fn draw_header_text<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
-  let text = match app.get_current_route().id {
-    RouteId::Contexts => vec![Spans::from("<↑↓> scroll | <enter> select | <?> help ")],
-    RouteId::Home => vec![Spans::from(
-      "<←→> switch tabs | <char> select block | <↑↓> scroll | <enter> select | <?> help ",
-    )],
-    RouteId::Utilization => vec![Spans::from(
-      "<↑↓> scroll | <g> cycle through grouping | <?> help ",
-    )],
-    RouteId::HelpMenu => vec![],
+  let help = match app.get_current_route().id {
+    RouteId::Contexts => "<↑↓> scroll | <enter> select | <?> help ",
+    RouteId::Home => {
+      "<←→> switch tabs | <char> select block | <↑↓> scroll | <enter> select | <?> help "
+    }
+    RouteId::Utilization => "<↑↓> scroll | <g> cycle through grouping | <?> help ",
+    RouteId::MetricsHistory => "<?> help ",
+    RouteId::Fleet => "<↑↓> scroll | <enter> switch context | <W> warnings | <?> help ",
+    RouteId::HelpMenu => "",
   };
-  let paragraph = Paragraph::new(text)
+  let mut spans = vec![];
+  if let Some(status) = app.connection.status(Instant::now()) {
+    spans.push(Span::styled(
+      format!("{} | ", status),
+      style_failure(app.light_theme),
+    ));
+  }
+  if let Some(replay) = &app.replay {
+    let kb = key_bindings();
+    spans.push(Span::styled(
+      format!(
+        "replay {} | {} {} step | ",
+        replay.progress(),
+        kb.replay_back.key,
+        kb.replay_forward.key
+      ),
+      style_secondary(app.light_theme),
+    ));
+  }
+  spans.push(Span::raw(help));
+  let paragraph = Paragraph::new(Spans::from(spans))
     .style(style_help(app.light_theme))
     .block(Block::default())
     .alignment(Alignment::Right);
@@ -10,18 +10,19 @@ use super::{
   resource_tabs::draw_resource_tabs_block,
   utils::{
     get_gauge_style, horizontal_chunks, layout_block_default, loading, style_default,
-    style_failure, style_highlight, style_logo, style_primary, style_secondary, table_header_style,
-    vertical_chunks, vertical_chunks_with_margin,
+    style_failure, style_gauge, style_highlight, style_logo, style_primary, style_secondary,
+    style_warning, table_header_style, table_rows_area, vertical_chunks,
+    vertical_chunks_with_margin,
   },
   HIGHLIGHT,
 };
 use crate::{
-  app::{key_binding::DEFAULT_KEYBINDING, metrics::KubeNodeMetrics, ActiveBlock, App},
+  app::{key_binding::key_bindings, metrics::CapacityUsage, ActiveBlock, App},
   banner::BANNER,
 };
 
 pub fn draw_overview<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
-  if app.show_info_bar {
+  if app.is_info_bar_shown() {
     let chunks = vertical_chunks(vec![Constraint::Length(9), Constraint::Min(10)], area);
     draw_status_block(f, app, chunks[0]);
     draw_resource_tabs_block(f, app, chunks[1]);
@@ -30,21 +31,75 @@ pub fn draw_overview<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect
   }
 }
 
+/// blocks of the info bar, in the order they are drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StatusBlock {
+  Namespaces,
+  Context,
+  Capacity,
+  CliVersion,
+  Logo,
+}
+
+impl StatusBlock {
+  /// width the block needs, the context block takes up whatever is left
+  fn width(self) -> u16 {
+    match self {
+      StatusBlock::Namespaces => 35,
+      StatusBlock::Context => 30,
+      StatusBlock::Capacity => 51,
+      StatusBlock::CliVersion => 30,
+      StatusBlock::Logo => 32,
+    }
+  }
+}
+
+/// the info bar blocks fitting the width, the banner is hidden first and the context last
+fn status_blocks(width: u16) -> Vec<StatusBlock> {
+  let by_priority = [
+    StatusBlock::Context,
+    StatusBlock::Namespaces,
+    StatusBlock::Capacity,
+    StatusBlock::CliVersion,
+    StatusBlock::Logo,
+  ];
+  let mut used = 0;
+  let mut blocks: Vec<StatusBlock> = by_priority
+    .into_iter()
+    .take_while(|block| {
+      used += block.width();
+      used <= width
+    })
+    .collect();
+  if blocks.is_empty() {
+    blocks.push(StatusBlock::Context);
+  }
+  blocks.sort_by_key(|block| *block as u8);
+  blocks
+}
+
 fn draw_status_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let blocks = status_blocks(area.width);
   let chunks = horizontal_chunks(
-    vec![
-      Constraint::Length(35),
-      Constraint::Min(10),
-      Constraint::Length(30),
-      Constraint::Length(32),
-    ],
+    blocks
+      .iter()
+      .map(|block| match block {
+        StatusBlock::Context => Constraint::Min(10),
+        block => Constraint::Length(block.width()),
+      })
+      .collect(),
     area,
   );
 
-  draw_namespaces_block(f, app, chunks[0]);
-  draw_context_info_block(f, app, chunks[1]);
-  draw_cli_version_block(f, app, chunks[2]);
-  draw_logo_block(f, app, chunks[3])
+  for (block, chunk) in blocks.into_iter().zip(chunks) {
+    match block {
+      StatusBlock::Namespaces => draw_namespaces_block(f, app, chunk),
+      StatusBlock::Context => draw_context_info_block(f, app, chunk),
+      StatusBlock::Capacity => draw_capacity_block(f, app, chunk),
+      StatusBlock::CliVersion => draw_cli_version_block(f, app, chunk),
+      StatusBlock::Logo => draw_logo_block(f, app, chunk),
+    }
+  }
 }
 
 fn draw_logo_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
@@ -88,6 +143,99 @@ fn draw_cli_version_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area:
   }
 }
 
+fn draw_capacity_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let capacity = &app.data.cluster_capacity;
+  let title = if capacity.requests_unknown {
+    " Cluster Capacity (requests unknown) "
+  } else if capacity.cpu.is_overcommitted() || capacity.mem.is_overcommitted() {
+    " Cluster Capacity (over-committed) "
+  } else {
+    " Cluster Capacity "
+  };
+  let block = layout_block_default(title);
+
+  if capacity.is_empty() {
+    loading(f, block, area, app.is_loading, app.light_theme);
+    return;
+  }
+
+  let rows = vec![
+    capacity_row(
+      "CPU",
+      &capacity.cpu,
+      capacity.requests_unknown,
+      format_cpu,
+      app.light_theme,
+    ),
+    capacity_row(
+      "Mem",
+      &capacity.mem,
+      capacity.requests_unknown,
+      format_mem,
+      app.light_theme,
+    ),
+  ];
+
+  let table = Table::new(rows)
+    .header(table_header_style(
+      vec!["", "Capacity", "Alloc", "Requested", "Used"],
+      app.light_theme,
+    ))
+    .block(block)
+    .widths(&[
+      Constraint::Length(3),
+      Constraint::Length(8),
+      Constraint::Length(8),
+      Constraint::Length(13),
+      Constraint::Length(13),
+    ]);
+  f.render_widget(table, area);
+}
+
+fn capacity_row<'a>(
+  name: &'a str,
+  usage: &CapacityUsage,
+  requests_unknown: bool,
+  format: fn(f64) -> String,
+  light: bool,
+) -> Row<'a> {
+  let ratio_style = |ratio: f64| {
+    if ratio > 1f64 {
+      style_failure(light)
+    } else if ratio > 0.8 {
+      style_warning(light)
+    } else {
+      style_primary(light)
+    }
+  };
+  let requested_ratio = usage.requested_ratio();
+  let used_ratio = usage.used_ratio();
+  let requested = if requests_unknown {
+    Cell::from("n/a")
+  } else {
+    Cell::from(format!(
+      "{} ({:.0}%)",
+      format(usage.requested),
+      requested_ratio * 100f64
+    ))
+    .style(ratio_style(requested_ratio))
+  };
+
+  Row::new(vec![
+    Cell::from(name),
+    Cell::from(format(usage.capacity)),
+    Cell::from(format(usage.allocatable)),
+    requested,
+    Cell::from(format!(
+      "{} ({:.0}%)",
+      format(usage.used),
+      used_ratio * 100f64
+    ))
+    .style(ratio_style(used_ratio)),
+  ])
+  .style(style_primary(light))
+}
+
 fn draw_context_info_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
   let chunks = vertical_chunks_with_margin(
     vec![
@@ -131,23 +279,23 @@ fn draw_context_info_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area
   let paragraph = Paragraph::new(text).block(Block::default());
   f.render_widget(paragraph, chunks[0]);
 
-  let ratio = get_nm_ratio(app.data.node_metrics.as_ref(), |nm| nm.cpu_percent);
+  let ratio = app.data.node_usage.cpu;
   let limited_ratio = if ratio > 1f64 { 1f64 } else { ratio };
 
   let cpu_gauge = LineGauge::default()
     .block(Block::default().title("CPU:"))
-    .gauge_style(style_primary(app.light_theme))
+    .gauge_style(style_gauge(app.light_theme))
     .line_set(get_gauge_style(app.enhanced_graphics))
     .ratio(limited_ratio)
     .label(Spans::from(format!("{:.0}%", ratio * 100.0)));
   f.render_widget(cpu_gauge, chunks[1]);
 
-  let ratio = get_nm_ratio(app.data.node_metrics.as_ref(), |nm| nm.mem_percent);
+  let ratio = app.data.node_usage.mem;
   let limited_ratio = if ratio > 1f64 { 1f64 } else { ratio };
 
   let mem_gauge = LineGauge::default()
     .block(Block::default().title("Memory:"))
-    .gauge_style(style_primary(app.light_theme))
+    .gauge_style(style_gauge(app.light_theme))
     .line_set(get_gauge_style(app.enhanced_graphics))
     .ratio(limited_ratio)
     .label(Spans::from(format!("{:.0}%", ratio * 100.0)));
@@ -156,8 +304,10 @@ fn draw_context_info_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area
 
 fn draw_namespaces_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
   let title = format!(
-    " Namespaces {} (all: {}) ",
-    DEFAULT_KEYBINDING.jump_to_namespace.key, DEFAULT_KEYBINDING.select_all_namespace.key
+    " Namespaces {} (all: {}, pin: {}) ",
+    key_bindings().jump_to_namespace.key,
+    key_bindings().select_all_namespace.key,
+    key_bindings().pin_namespace.key
   );
   let mut block = layout_block_default(title.as_str());
 
@@ -166,23 +316,29 @@ fn draw_namespaces_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area:
   }
 
   if !app.data.namespaces.items.is_empty() {
+    app
+      .data
+      .namespaces
+      .set_view(table_rows_area(block.inner(area)));
     let rows = app.data.namespaces.items.iter().map(|s| {
       let style = if Some(s.name.clone()) == app.data.selected.ns {
         style_secondary(app.light_theme)
       } else {
         style_primary(app.light_theme)
       };
-      Row::new(vec![
-        Cell::from(s.name.as_ref()),
-        Cell::from(s.status.as_ref()),
-      ])
-      .style(style)
+      // pinned namespaces show the number key that selects them
+      let name = match app.pinned_namespaces.iter().position(|p| *p == s.name) {
+        Some(i) if i < 9 => format!("{} {}", i + 1, s.name),
+        Some(_) => format!("* {}", s.name),
+        None => s.name.clone(),
+      };
+      Row::new(vec![Cell::from(name), Cell::from(s.status.as_ref())]).style(style)
     });
 
     let table = Table::new(rows)
       .header(table_header_style(vec!["Name", "Status"], app.light_theme))
       .block(block)
-      .highlight_style(style_highlight())
+      .highlight_style(style_highlight(app.light_theme))
       .highlight_symbol(HIGHLIGHT)
       .widths(&[Constraint::Length(22), Constraint::Length(6)]);
 
@@ -194,14 +350,14 @@ fn draw_namespaces_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area:
 
 // Utility methods
 
-/// covert percent value from metrics to ratio that gauge can understand
-fn get_nm_ratio(node_metrics: &[KubeNodeMetrics], f: fn(b: &KubeNodeMetrics) -> f64) -> f64 {
-  if !node_metrics.is_empty() {
-    let sum = node_metrics.iter().map(f).sum::<f64>();
-    (sum / node_metrics.len() as f64) / 100f64
-  } else {
-    0f64
-  }
+/// CPU is shown in cores
+fn format_cpu(cores: f64) -> String {
+  format!("{:.1}", cores)
+}
+
+/// memory is shown in GiB as that is what most nodes are sized in
+fn format_mem(bytes: f64) -> String {
+  format!("{:.1}Gi", bytes / (1024f64 * 1024f64 * 1024f64))
 }
 
 fn nw_loading_indicator<'a>(loading: bool) -> &'a str {
@@ -217,26 +373,28 @@ mod tests {
   use super::*;
 
   #[test]
-  #[allow(clippy::float_cmp)]
-  fn test_get_nm_ratio() {
-    let mut app = App::default();
+  fn test_status_blocks() {
+    use StatusBlock::*;
+
     assert_eq!(
-      get_nm_ratio(app.data.node_metrics.as_ref(), |nm| nm.cpu_percent),
-      0.0f64
+      status_blocks(200),
+      vec![Namespaces, Context, Capacity, CliVersion, Logo]
     );
-    app.data.node_metrics = vec![
-      KubeNodeMetrics {
-        cpu_percent: 80f64,
-        ..KubeNodeMetrics::default()
-      },
-      KubeNodeMetrics {
-        cpu_percent: 60f64,
-        ..KubeNodeMetrics::default()
-      },
-    ];
+    assert_eq!(status_blocks(178), status_blocks(200));
     assert_eq!(
-      get_nm_ratio(app.data.node_metrics.as_ref(), |nm| nm.cpu_percent),
-      0.7f64
+      status_blocks(150),
+      vec![Namespaces, Context, Capacity, CliVersion]
     );
+    assert_eq!(status_blocks(110), vec![Namespaces, Context]);
+    assert_eq!(status_blocks(40), vec![Context]);
+    assert_eq!(status_blocks(20), vec![Context]);
+  }
+
+  #[test]
+  fn test_format_capacity() {
+    assert_eq!(format_cpu(0.25), "0.2");
+    assert_eq!(format_cpu(8f64), "8.0");
+    assert_eq!(format_mem(32734444f64 * 1024f64), "31.2Gi");
+    assert_eq!(format_mem(0f64), "0.0Gi");
   }
 }
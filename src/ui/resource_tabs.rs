@@ -1,27 +1,36 @@
 use tui::{
   backend::Backend,
   layout::{Constraint, Rect},
-  style::Style,
-  text::{Span, Spans, Text},
-  widgets::{Cell, List, ListItem, Paragraph, Row, Table, Tabs, Wrap},
+  style::{Modifier, Style},
+  text::{Span, Spans},
+  widgets::{Cell, Clear, List, ListItem, Paragraph, Row, Table, TableState, Tabs, Wrap},
   Frame,
 };
 
 use super::{
   utils::{
-    centered_rect, layout_block_default, layout_block_top_border, loading, style_default,
-    style_failure, style_highlight, style_primary, style_secondary, style_success,
-    table_header_style, title_with_dual_style, vertical_chunks_with_margin,
+    centered_rect, horizontal_chunks, layout_block_default, layout_block_top_border, loading,
+    sorted_header_cells, style_default, style_failure, style_highlight, style_primary,
+    style_secondary, style_warning, tab_title_areas, table_header_style, table_rows_area,
+    title_with_dual_style, vertical_chunks_with_margin,
   },
+  yaml::yaml_segments,
   HIGHLIGHT,
 };
-use crate::app::{models::StatefulTable, ActiveBlock, App};
+use crate::app::{
+  key_binding::key_bindings,
+  models::{ManifestFormat, ResourceTable, RowHealth, ScrollableTxt, StatefulTable, TableRow},
+  ActiveBlock, App, SplitPane,
+};
 
 static DESCRIBE_AND_YAML_HINT: &str = "| describe <d> | yaml <y> ";
 static DESCRIBE_YAML_AND_ESC_HINT: &str = "| describe <d> | yaml <y> | back to menu <esc> ";
 static DESCRIBE_YAML_DECODE_AND_ESC_HINT: &str =
   "| describe <d> | yaml <y> | decode <x> | back to menu <esc> ";
 static COPY_HINT: &str = "| copy <c>";
+static MARKER: &str = "●";
+/// columns are not narrowed below this to fit the table
+const MIN_COLUMN_WIDTH: usize = 8;
 static NODES_TITLE: &str = "Nodes";
 static PODS_TITLE: &str = "Pods";
 static SERVICES_TITLE: &str = "Services";
@@ -39,10 +48,13 @@ static ROLES_TITLE: &str = "Roles";
 static ROLE_BINDINGS_TITLE: &str = "RoleBindings";
 static CLUSTER_ROLES_TITLE: &str = "ClusterRoles";
 static CLUSTER_ROLES_BINDING_TITLE: &str = "ClusterRoleBinding";
+static HELM_RELEASES_TITLE: &str = "HelmReleases";
 static DESCRIBE_ACTIVE: &str = "-> Describe ";
 static YAML_ACTIVE: &str = "-> YAML ";
+static JSON_ACTIVE: &str = "-> JSON ";
 
 pub fn draw_resource_tabs_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  app.update_table_display(f.size().width);
   let chunks =
     vertical_chunks_with_margin(vec![Constraint::Length(2), Constraint::Min(0)], area, 1);
 
@@ -51,12 +63,26 @@ pub fn draw_resource_tabs_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App,
     block = block.style(style_secondary(app.light_theme))
   }
 
-  let titles = app
+  let filtered_tabs = app.filtered_tabs();
+  let titles: Vec<Spans<'_>> = app
     .context_tabs
     .items
     .iter()
-    .map(|t| Spans::from(Span::styled(&t.title, style_default(app.light_theme))))
+    .zip(filtered_tabs)
+    .map(|(t, filtered)| {
+      let mut spans = vec![Span::styled(
+        t.title.clone(),
+        style_default(app.light_theme),
+      )];
+      if filtered {
+        spans.push(Span::styled(" [/]", style_primary(app.light_theme)));
+      }
+      Spans::from(spans)
+    })
     .collect();
+  app
+    .context_tabs
+    .set_title_areas(tab_title_areas(&titles, block.inner(area)));
   let tabs = Tabs::new(titles)
     .block(block)
     .highlight_style(style_secondary(app.light_theme))
@@ -64,20 +90,61 @@ pub fn draw_resource_tabs_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App,
 
   f.render_widget(tabs, area);
 
+  let area = if app.is_split_pane_shown() {
+    let panes = horizontal_chunks(
+      vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+      chunks[1],
+    );
+    draw_split_pane(f, app, panes[1]);
+    panes[0]
+  } else {
+    chunks[1]
+  };
+
   // render tab content
   match app.context_tabs.index {
-    0 => draw_pods_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    1 => draw_services_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    2 => draw_nodes_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    3 => draw_config_maps_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    4 => draw_stateful_sets_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    5 => draw_replica_sets_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    6 => draw_deployments_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    7 => draw_jobs_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    8 => draw_daemon_sets_tab(app.get_current_route().active_block, f, app, chunks[1]),
-    9 => draw_more(app.get_current_route().active_block, f, app, chunks[1]),
+    0 => draw_pods_tab(app.get_current_route().active_block, f, app, area),
+    1 => draw_services_tab(app.get_current_route().active_block, f, app, area),
+    2 => draw_nodes_tab(app.get_current_route().active_block, f, app, area),
+    3 => draw_config_maps_tab(app.get_current_route().active_block, f, app, area),
+    4 => draw_stateful_sets_tab(app.get_current_route().active_block, f, app, area),
+    5 => draw_replica_sets_tab(app.get_current_route().active_block, f, app, area),
+    6 => draw_deployments_tab(app.get_current_route().active_block, f, app, area),
+    7 => draw_jobs_tab(app.get_current_route().active_block, f, app, area),
+    8 => draw_daemon_sets_tab(app.get_current_route().active_block, f, app, area),
+    9 => draw_more(app.get_current_route().active_block, f, app, area),
     _ => {}
   };
+
+  if app.get_current_route().active_block == ActiveBlock::Columns {
+    draw_column_picker(f, app, area);
+  }
+}
+
+/// describe, YAML or logs of the selected item next to the resource table
+fn draw_split_pane<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let title = match app.split_pane {
+    Some(SplitPane::Yaml) if app.manifest_format == ManifestFormat::Json => " JSON ",
+    Some(SplitPane::Yaml) => " YAML ",
+    Some(SplitPane::Logs) => " Logs ",
+    _ => " Describe ",
+  };
+  let title = title_with_dual_style(
+    title.into(),
+    format!("| cycle {} ", key_bindings().cycle_split_pane.key),
+    app.light_theme,
+  );
+  if app.split_pane == Some(SplitPane::Logs) {
+    let block = layout_block_top_border(title);
+    let style = style_primary(app.light_theme);
+    app
+      .data
+      .split_pane_logs
+      .render_list(f, area, block, style, true);
+  } else {
+    let is_yaml = app.split_pane == Some(SplitPane::Yaml);
+    draw_txt_block(f, app, &app.data.split_pane_out, is_yaml, area, title);
+  }
 }
 
 /// more resources tab
@@ -92,6 +159,10 @@ fn draw_more<B: Backend>(block: ActiveBlock, f: &mut Frame<'_, B>, app: &mut App
     ActiveBlock::RoleBindings => draw_role_bindings_tab(block, f, app, area),
     ActiveBlock::ClusterRoles => draw_cluster_roles_tab(block, f, app, area),
     ActiveBlock::ClusterRoleBinding => draw_cluster_role_binding_tab(block, f, app, area),
+    ActiveBlock::HelmReleases | ActiveBlock::HelmHistory => {
+      draw_helm_releases_tab(block, f, app, area)
+    }
+    ActiveBlock::CustomView(i) => draw_custom_view_tab(i, block, f, app, area),
     ActiveBlock::Describe | ActiveBlock::Yaml => {
       let mut prev_route = app.get_prev_route();
       if prev_route.active_block == block {
@@ -106,10 +177,16 @@ fn draw_more<B: Backend>(block: ActiveBlock, f: &mut Frame<'_, B>, app: &mut App
         ActiveBlock::RoleBindings => draw_role_bindings_tab(block, f, app, area),
         ActiveBlock::ClusterRoles => draw_cluster_roles_tab(block, f, app, area),
         ActiveBlock::ClusterRoleBinding => draw_cluster_role_binding_tab(block, f, app, area),
+        ActiveBlock::HelmReleases | ActiveBlock::HelmHistory => {
+          draw_helm_releases_tab(block, f, app, area)
+        }
+        ActiveBlock::CustomView(i) => draw_custom_view_tab(i, block, f, app, area),
         _ => { /* do nothing */ }
       }
     }
-    ActiveBlock::Namespaces => draw_more(app.get_prev_route().active_block, f, app, area),
+    ActiveBlock::Namespaces | ActiveBlock::Columns => {
+      draw_more(app.get_prev_route().active_block, f, app, area)
+    }
     _ => draw_menu(f, app, area),
   }
 }
@@ -127,13 +204,38 @@ fn draw_menu<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
   f.render_stateful_widget(
     List::new(items)
       .block(layout_block_default(" Select Resource "))
-      .highlight_style(style_highlight())
+      .highlight_style(style_highlight(app.light_theme))
       .highlight_symbol(HIGHLIGHT),
     area,
     &mut app.more_resources_menu.state,
   );
 }
 
+/// pick the columns shown in the resource table underneath
+fn draw_column_picker<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let area = centered_rect(30, 50, area);
+
+  let items: Vec<ListItem<'_>> = app
+    .column_picker
+    .items
+    .iter()
+    .map(|(column, visible)| {
+      ListItem::new(format!("[{}] {}", if *visible { "x" } else { " " }, column))
+    })
+    .collect();
+  f.render_widget(Clear, area);
+  f.render_stateful_widget(
+    List::new(items)
+      .block(layout_block_default(
+        " Columns | toggle <enter> | close <esc> ",
+      ))
+      .highlight_style(style_highlight(app.light_theme))
+      .highlight_symbol(HIGHLIGHT),
+    area,
+    &mut app.column_picker.state,
+  );
+}
+
 // using a macro to reuse code as generics will make handling lifetimes a PITA
 macro_rules! draw_resource_tab {
   ($title:expr, $block:expr, $f:expr, $app:expr, $area:expr, $fn1:expr, $fn2:expr, $res:expr) => {
@@ -143,12 +245,19 @@ macro_rules! draw_resource_tab {
         $app,
         $area,
         title_with_dual_style(
-          get_resource_title($app, $title, get_describe_active($block), $res.items.len()),
+          get_resource_title(
+            $app,
+            $title,
+            get_describe_active($app, $block),
+            $res.items.len(),
+          ),
           format!("{} | {} <esc> ", COPY_HINT, $title),
           $app.light_theme,
         ),
       ),
-      ActiveBlock::Namespaces => $fn1($app.get_prev_route().active_block, $f, $app, $area),
+      ActiveBlock::Namespaces | ActiveBlock::Columns => {
+        $fn1($app.get_prev_route().active_block, $f, $app, $area)
+      }
       _ => $fn2($f, $app, $area),
     };
   };
@@ -165,7 +274,7 @@ fn draw_pods_tab<B: Backend>(block: ActiveBlock, f: &mut Frame<'_, B>, app: &mut
         get_resource_title(
           app,
           PODS_TITLE,
-          get_describe_active(block),
+          get_describe_active(app, block),
           app.data.pods.items.len(),
         ),
         format!("{} | {} <esc> ", COPY_HINT, PODS_TITLE),
@@ -173,7 +282,9 @@ fn draw_pods_tab<B: Backend>(block: ActiveBlock, f: &mut Frame<'_, B>, app: &mut
       ),
     ),
     ActiveBlock::Logs => draw_logs_block(f, app, area),
-    ActiveBlock::Namespaces => draw_pods_tab(app.get_prev_route().active_block, f, app, area),
+    ActiveBlock::Namespaces | ActiveBlock::Columns => {
+      draw_pods_tab(app.get_prev_route().active_block, f, app, area)
+    }
     _ => draw_pods_block(f, app, area),
   };
 }
@@ -188,27 +299,7 @@ fn draw_pods_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect)
       title,
       inline_help: format!("| Containers <enter> {}", DESCRIBE_AND_YAML_HINT),
       resource: &mut app.data.pods,
-      table_headers: vec!["Namespace", "Name", "Ready", "Status", "Restarts", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(25),
-        Constraint::Percentage(35),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      let style = get_resource_row_style(c.status.as_str(), c.ready, app.light_theme);
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(format!("{}/{}", c.ready.0, c.ready.1)),
-        Cell::from(c.status.to_owned()),
-        Cell::from(c.restarts.to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style)
+      table_headers: table_headers(ActiveBlock::Pods),
     },
     app.light_theme,
     app.is_loading,
@@ -225,43 +316,7 @@ fn draw_containers_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area:
       title,
       inline_help: format!("| Logs <enter> | {} <esc> ", PODS_TITLE),
       resource: &mut app.data.containers,
-      table_headers: vec![
-        "Name",
-        "Image",
-        "Init",
-        "Ready",
-        "State",
-        "Restarts",
-        "Probes(L/R)",
-        "Ports",
-        "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(20),
-        Constraint::Percentage(25),
-        Constraint::Percentage(5),
-        Constraint::Percentage(5),
-        Constraint::Percentage(10),
-        Constraint::Percentage(5),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      let style = get_resource_row_style(c.status.as_str(), (0, 0), app.light_theme);
-      Row::new(vec![
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.image.to_owned()),
-        Cell::from(c.init.to_string()),
-        Cell::from(c.ready.to_owned()),
-        Cell::from(c.status.to_owned()),
-        Cell::from(c.restarts.to_string()),
-        Cell::from(format!("{}/{}", c.liveliness_probe, c.readiness_probe,)),
-        Cell::from(c.ports.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style)
+      table_headers: table_headers(ActiveBlock::Containers),
     },
     app.light_theme,
     app.is_loading,
@@ -272,7 +327,7 @@ fn draw_logs_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect)
   let selected_container = app.data.selected.container.clone();
   let container_name = selected_container.unwrap_or_default();
 
-  let title = title_with_dual_style(
+  let mut title = title_with_dual_style(
     get_container_title(
       app,
       app.data.containers.items.len(),
@@ -281,6 +336,16 @@ fn draw_logs_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect)
     "| copy <c> | Containers <esc> ".into(),
     app.light_theme,
   );
+  if container_name == app.data.logs.id {
+    let position = app.data.logs.scroll_position(
+      app.log_auto_scroll,
+      usize::from(area.height.saturating_sub(1)),
+    );
+    title.0.push(Span::styled(
+      format!("[{}] ", position),
+      style_primary(app.light_theme),
+    ));
+  }
 
   let block = layout_block_top_border(title);
 
@@ -307,19 +372,35 @@ fn draw_nodes_tab<B: Backend>(block: ActiveBlock, f: &mut Frame<'_, B>, app: &mu
         get_cluster_wide_resource_title(
           NODES_TITLE,
           app.data.nodes.items.len(),
-          get_describe_active(block),
+          get_describe_active(app, block),
         ),
         format!("{} | {} <esc> ", COPY_HINT, NODES_TITLE),
         app.light_theme,
       ),
     ),
-    ActiveBlock::Namespaces => draw_nodes_tab(app.get_prev_route().active_block, f, app, area),
+    ActiveBlock::Namespaces | ActiveBlock::Columns => {
+      draw_nodes_tab(app.get_prev_route().active_block, f, app, area)
+    }
     _ => draw_nodes_block(f, app, area),
   };
 }
 
 fn draw_nodes_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
-  let title = get_cluster_wide_resource_title(NODES_TITLE, app.data.nodes.items.len(), "");
+  let disk_pressure = app
+    .data
+    .nodes
+    .items
+    .iter()
+    .any(|n| n.disk_fs.is_some_and(|fs| fs.is_near_eviction()));
+  let title = get_cluster_wide_resource_title(
+    NODES_TITLE,
+    app.data.nodes.items.len(),
+    if disk_pressure {
+      "-> low disk space"
+    } else {
+      ""
+    },
+  );
 
   draw_resource_block(
     f,
@@ -328,46 +409,7 @@ fn draw_nodes_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect)
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.nodes,
-      table_headers: vec![
-        "Name", "Status", "Roles", "Version", PODS_TITLE, "CPU", "Mem", "CPU %", "Mem %", "CPU/A",
-        "Mem/A", "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(25),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(5),
-        Constraint::Percentage(5),
-        Constraint::Percentage(5),
-        Constraint::Percentage(5),
-        Constraint::Percentage(5),
-        Constraint::Percentage(5),
-        Constraint::Percentage(5),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      let style = if c.status != "Ready" {
-        style_failure(app.light_theme)
-      } else {
-        style_primary(app.light_theme)
-      };
-      Row::new(vec![
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.status.to_owned()),
-        Cell::from(c.role.to_owned()),
-        Cell::from(c.version.to_owned()),
-        Cell::from(c.pods.to_string()),
-        Cell::from(c.cpu.to_owned()),
-        Cell::from(c.mem.to_owned()),
-        Cell::from(c.cpu_percent.to_owned()),
-        Cell::from(c.mem_percent.to_owned()),
-        Cell::from(c.cpu_a.to_owned()),
-        Cell::from(c.mem_a.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style)
+      table_headers: table_headers(ActiveBlock::Nodes),
     },
     app.light_theme,
     app.is_loading,
@@ -402,36 +444,7 @@ fn draw_services_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Re
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.services,
-      table_headers: vec![
-        "Namespace",
-        "Name",
-        "Type",
-        "Cluster IP",
-        "External IP",
-        "Ports",
-        "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(10),
-        Constraint::Percentage(25),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(15),
-        Constraint::Percentage(20),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.type_.to_owned()),
-        Cell::from(c.cluster_ip.to_owned()),
-        Cell::from(c.external_ip.to_owned()),
-        Cell::from(c.ports.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::Services),
     },
     app.light_theme,
     app.is_loading,
@@ -466,22 +479,7 @@ fn draw_config_maps_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area:
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.config_maps,
-      table_headers: vec!["Namespace", "Name", "Data", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(30),
-        Constraint::Percentage(40),
-        Constraint::Percentage(15),
-        Constraint::Percentage(15),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.data.len().to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::ConfigMaps),
     },
     app.light_theme,
     app.is_loading,
@@ -516,24 +514,7 @@ fn draw_stateful_sets_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, are
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.stateful_sets,
-      table_headers: vec!["Namespace", "Name", "Ready", "Service", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(25),
-        Constraint::Percentage(30),
-        Constraint::Percentage(10),
-        Constraint::Percentage(25),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.ready.to_owned()),
-        Cell::from(c.service.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::StatefulSets),
     },
     app.light_theme,
     app.is_loading,
@@ -573,26 +554,7 @@ fn draw_replica_sets_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.replica_sets,
-      table_headers: vec!["Namespace", "Name", "Desired", "Current", "Ready", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(25),
-        Constraint::Percentage(35),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.desired.to_string()),
-        Cell::from(c.current.to_string()),
-        Cell::from(c.ready.to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::ReplicaSets),
     },
     app.light_theme,
     app.is_loading,
@@ -627,33 +589,7 @@ fn draw_deployments_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area:
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.deployments,
-      table_headers: vec![
-        "Namespace",
-        "Name",
-        "Ready",
-        "Up-to-date",
-        "Available",
-        "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(25),
-        Constraint::Percentage(35),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.ready.to_owned()),
-        Cell::from(c.updated.to_string()),
-        Cell::from(c.available.to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::Deployments),
     },
     app.light_theme,
     app.is_loading,
@@ -683,24 +619,7 @@ fn draw_jobs_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect)
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.jobs,
-      table_headers: vec!["Namespace", "Name", "Completions", "Duration", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(25),
-        Constraint::Percentage(40),
-        Constraint::Percentage(15),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.completions.to_owned()),
-        Cell::from(c.duration.to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::Jobs),
     },
     app.light_theme,
     app.is_loading,
@@ -735,39 +654,7 @@ fn draw_daemon_sets_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area:
       title,
       inline_help: DESCRIBE_AND_YAML_HINT.into(),
       resource: &mut app.data.daemon_sets,
-      table_headers: vec![
-        "Namespace",
-        "Name",
-        "Desired",
-        "Current",
-        "Ready",
-        "Up-to-date",
-        "Available",
-        "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(20),
-        Constraint::Percentage(20),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.desired.to_string()),
-        Cell::from(c.current.to_string()),
-        Cell::from(c.ready.to_string()),
-        Cell::from(c.up_to_date.to_string()),
-        Cell::from(c.available.to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::DaemonSets),
     },
     app.light_theme,
     app.is_loading,
@@ -802,36 +689,7 @@ fn draw_cronjobs_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Re
       title,
       inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
       resource: &mut app.data.cronjobs,
-      table_headers: vec![
-        "Namespace",
-        "Name",
-        "Schedule",
-        "Last Scheduled",
-        "Suspend",
-        "Active",
-        "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(20),
-        Constraint::Percentage(25),
-        Constraint::Percentage(15),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.schedule.to_owned()),
-        Cell::from(c.last_schedule.to_string()),
-        Cell::from(c.suspend.to_string()),
-        Cell::from(c.active.to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::CronJobs),
     },
     app.light_theme,
     app.is_loading,
@@ -866,24 +724,7 @@ fn draw_secrets_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rec
       title,
       inline_help: DESCRIBE_YAML_DECODE_AND_ESC_HINT.into(),
       resource: &mut app.data.secrets,
-      table_headers: vec!["Namespace", "Name", "Type", "Data", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(25),
-        Constraint::Percentage(30),
-        Constraint::Percentage(25),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.type_.to_owned()),
-        Cell::from(c.data.len().to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::Secrets),
     },
     app.light_theme,
     app.is_loading,
@@ -918,42 +759,7 @@ fn draw_replication_controllers_block<B: Backend>(f: &mut Frame<'_, B>, app: &mu
       title,
       inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
       resource: &mut app.data.rpl_ctrls,
-      table_headers: vec![
-        "Namespace",
-        "Name",
-        "Desired",
-        "Current",
-        "Ready",
-        "Containers",
-        "Images",
-        "Selector",
-        "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(15),
-        Constraint::Percentage(15),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.desired.to_string()),
-        Cell::from(c.current.to_string()),
-        Cell::from(c.ready.to_string()),
-        Cell::from(c.containers.to_owned()),
-        Cell::from(c.images.to_owned()),
-        Cell::from(c.selector.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::RplCtrl),
     },
     app.light_theme,
     app.is_loading,
@@ -992,39 +798,68 @@ fn draw_storage_classes_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, a
       title,
       inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
       resource: &mut app.data.storage_classes,
-      table_headers: vec![
-        "Name",
-        "Provisioner",
-        "Reclaim Policy",
-        "Volume Binding Mode",
-        "Allow Volume Expansion",
-        "Age",
-      ],
-      column_widths: vec![
-        Constraint::Percentage(10),
-        Constraint::Percentage(20),
-        Constraint::Percentage(10),
-        Constraint::Percentage(20),
-        Constraint::Percentage(20),
-        Constraint::Percentage(10),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.provisioner.to_owned()),
-        Cell::from(c.reclaim_policy.to_owned()),
-        Cell::from(c.volume_binding_mode.to_owned()),
-        Cell::from(c.allow_volume_expansion.to_string()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::StorageClasses),
     },
     app.light_theme,
     app.is_loading,
   );
 }
 
+/// a table of custom resources from `custom_views` in the config
+fn draw_custom_view_tab<B: Backend>(
+  index: usize,
+  block: ActiveBlock,
+  f: &mut Frame<'_, B>,
+  app: &mut App,
+  area: Rect,
+) {
+  let name = match app.custom_views.get(index) {
+    Some(view) => view.name.clone(),
+    None => return,
+  };
+  let items = app
+    .data
+    .custom_objects
+    .get(&index)
+    .map_or(0, |table| table.items.len());
+  match block {
+    ActiveBlock::Describe | ActiveBlock::Yaml => draw_describe_block(
+      f,
+      app,
+      area,
+      title_with_dual_style(
+        get_resource_title(app, name.as_str(), get_describe_active(app, block), items),
+        format!("{} | {} <esc> ", COPY_HINT, name),
+        app.light_theme,
+      ),
+    ),
+    _ => {
+      let view = &app.custom_views[index];
+      let title = if view.namespaced {
+        get_resource_title(app, name.as_str(), "", items)
+      } else {
+        get_cluster_wide_resource_title(name.as_str(), items, "")
+      };
+      draw_resource_block(
+        f,
+        area,
+        ResourceTableProps {
+          title,
+          inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
+          resource: app
+            .data
+            .custom_objects
+            .entry(index)
+            .or_insert_with(StatefulTable::new),
+          table_headers: view.headers(),
+        },
+        app.light_theme,
+        app.is_loading,
+      );
+    }
+  }
+}
+
 fn draw_roles_tab<B: Backend>(block: ActiveBlock, f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
   draw_resource_tab!(
     ROLES_TITLE,
@@ -1048,20 +883,7 @@ fn draw_roles_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect)
       title,
       inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
       resource: &mut app.data.roles,
-      table_headers: vec!["Namespace", "Name", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(40),
-        Constraint::Percentage(40),
-        Constraint::Percentage(20),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::Roles),
     },
     app.light_theme,
     app.is_loading,
@@ -1101,22 +923,7 @@ fn draw_role_bindings_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, are
       title,
       inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
       resource: &mut app.data.role_bindings,
-      table_headers: vec!["Namespace", "Name", "Role", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(20),
-        Constraint::Percentage(30),
-        Constraint::Percentage(30),
-        Constraint::Percentage(20),
-      ],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.namespace.to_owned()),
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.role.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::RoleBindings),
     },
     app.light_theme,
     app.is_loading,
@@ -1156,15 +963,7 @@ fn draw_cluster_roles_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, are
       title,
       inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
       resource: &mut app.data.cluster_roles,
-      table_headers: vec!["Name", "Age"],
-      column_widths: vec![Constraint::Percentage(50), Constraint::Percentage(50)],
-    },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+      table_headers: table_headers(ActiveBlock::ClusterRoles),
     },
     app.light_theme,
     app.is_loading,
@@ -1204,20 +1003,113 @@ fn draw_cluster_role_binding_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut A
       title,
       inline_help: DESCRIBE_YAML_AND_ESC_HINT.into(),
       resource: &mut app.data.cluster_role_binding,
-      table_headers: vec!["Name", "Role", "Age"],
-      column_widths: vec![
-        Constraint::Percentage(40),
-        Constraint::Percentage(40),
-        Constraint::Percentage(20),
-      ],
+      table_headers: table_headers(ActiveBlock::ClusterRoleBinding),
     },
-    |c| {
-      Row::new(vec![
-        Cell::from(c.name.to_owned()),
-        Cell::from(c.role.to_owned()),
-        Cell::from(c.age.to_owned()),
-      ])
-      .style(style_primary(app.light_theme))
+    app.light_theme,
+    app.is_loading,
+  );
+}
+
+fn draw_helm_releases_tab<B: Backend>(
+  block: ActiveBlock,
+  f: &mut Frame<'_, B>,
+  app: &mut App,
+  area: Rect,
+) {
+  if block == ActiveBlock::HelmHistory {
+    draw_helm_history_block(f, app, area);
+    return;
+  }
+  draw_resource_tab!(
+    HELM_RELEASES_TITLE,
+    block,
+    f,
+    app,
+    area,
+    draw_helm_releases_tab,
+    draw_helm_releases_block,
+    app.data.helm_releases
+  );
+}
+
+fn draw_helm_releases_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let title = get_resource_title(
+    app,
+    HELM_RELEASES_TITLE,
+    "",
+    app.data.helm_releases.items.len(),
+  );
+
+  draw_resource_block(
+    f,
+    area,
+    ResourceTableProps {
+      title,
+      inline_help: if app.read_only {
+        format!(
+          "| History <enter> | values <{}> {}",
+          key_bindings().helm_values.key,
+          DESCRIBE_YAML_AND_ESC_HINT
+        )
+      } else {
+        format!(
+          "| History <enter> | values <{}> | uninstall <{}> {}",
+          key_bindings().helm_values.key,
+          key_bindings().helm_uninstall.key,
+          DESCRIBE_YAML_AND_ESC_HINT
+        )
+      },
+      resource: &mut app.data.helm_releases,
+      table_headers: table_headers(ActiveBlock::HelmReleases),
+    },
+    app.light_theme,
+    app.is_loading,
+  );
+}
+
+fn draw_helm_history_block<B: Backend>(f: &mut Frame<'_, B>, app: &mut App, area: Rect) {
+  let release = app
+    .data
+    .selected
+    .helm_release
+    .as_ref()
+    .map(|(_, name)| name.clone())
+    .unwrap_or_default();
+  let suffix = format!(
+    "-> History [{}] {} ",
+    app.data.helm_history.items.len(),
+    release
+  );
+  let title = get_resource_title(
+    app,
+    HELM_RELEASES_TITLE,
+    suffix.as_str(),
+    app.data.helm_releases.items.len(),
+  );
+
+  draw_resource_block(
+    f,
+    area,
+    ResourceTableProps {
+      title,
+      inline_help: if app.read_only {
+        format!(
+          "| values <{}> | diff <{}> | {} <esc> ",
+          key_bindings().helm_values.key,
+          key_bindings().diff_last_applied.key,
+          HELM_RELEASES_TITLE
+        )
+      } else {
+        format!(
+          "| values <{}> | diff <{}> | rollback <{}> | {} <esc> ",
+          key_bindings().helm_values.key,
+          key_bindings().diff_last_applied.key,
+          key_bindings().helm_rollback.key,
+          HELM_RELEASES_TITLE
+        )
+      },
+      resource: &mut app.data.helm_history,
+      table_headers: table_headers(ActiveBlock::HelmHistory),
     },
     app.light_theme,
     app.is_loading,
@@ -1231,85 +1123,372 @@ fn draw_describe_block<B: Backend>(
   area: Rect,
   title: Spans<'_>,
 ) {
+  let is_yaml = app.get_current_route().active_block == ActiveBlock::Yaml;
+  draw_txt_block(f, app, &app.data.describe_out, is_yaml, area, title);
+}
+
+/// scrollable and searchable text, highlighted as YAML when it is a manifest
+fn draw_txt_block<B: Backend>(
+  f: &mut Frame<'_, B>,
+  app: &App,
+  describe_out: &ScrollableTxt,
+  is_yaml: bool,
+  area: Rect,
+  mut title: Spans<'_>,
+) {
+  if let Some(position) = describe_out.search_position() {
+    title.0.push(Span::styled(
+      format!(
+        "[/{}{} {}] ",
+        describe_out.search(),
+        if app.is_detail_searching { "_" } else { "" },
+        position
+      ),
+      style_primary(app.light_theme),
+    ));
+  } else if app.is_detail_searching {
+    title
+      .0
+      .push(Span::styled("[/_] ", style_primary(app.light_theme)));
+  }
+  if !describe_out.get_txt().is_empty() {
+    title.0.push(Span::styled(
+      format!(
+        "[{}] ",
+        describe_out.scroll_position(area.width, area.height.saturating_sub(1))
+      ),
+      style_primary(app.light_theme),
+    ));
+  }
   let block = layout_block_top_border(title);
 
-  let txt = &app.data.describe_out.get_txt();
-  if !txt.is_empty() {
-    let mut txt = Text::from(txt.clone());
-    txt.patch_style(style_primary(app.light_theme));
+  if !describe_out.get_txt().is_empty() {
+    let query = describe_out.search().to_ascii_lowercase();
+    let txt: Vec<Spans<'_>> = describe_out
+      .lines()
+      .iter()
+      .map(|line| {
+        let segments = if is_yaml {
+          yaml_segments(line, app.light_theme)
+        } else {
+          vec![(0, style_primary(app.light_theme))]
+        };
+        highlight_matches(line, &segments, &query, style_highlight(app.light_theme))
+      })
+      .collect();
 
-    let paragraph = Paragraph::new(txt)
+    let mut paragraph = Paragraph::new(txt)
       .block(block)
-      .wrap(Wrap { trim: false })
-      .scroll((app.data.describe_out.offset, 0));
+      .scroll((describe_out.offset, describe_out.h_offset));
+    // lines can only be scrolled sideways when they are not wrapped
+    if describe_out.h_offset == 0 {
+      paragraph = paragraph.wrap(Wrap { trim: false });
+    }
     f.render_widget(paragraph, area);
   } else {
     loading(f, block, area, app.is_loading, app.light_theme);
   }
 }
 
+/// split a line styled from the given byte offsets into spans, with the case insensitive
+/// occurrences of a lowercase query highlighted
+fn highlight_matches<'a>(
+  line: &'a str,
+  segments: &[(usize, Style)],
+  query: &str,
+  highlight: Style,
+) -> Spans<'a> {
+  // ascii lowercasing keeps the byte offsets of the line
+  let lower = line.to_ascii_lowercase();
+  let matches: Vec<(usize, usize)> = if query.is_empty() {
+    vec![]
+  } else {
+    lower
+      .match_indices(query)
+      .map(|(i, _)| (i, i + query.len()))
+      .collect()
+  };
+  let mut cuts: Vec<usize> = segments
+    .iter()
+    .map(|(start, _)| *start)
+    .chain(matches.iter().flat_map(|(start, end)| [*start, *end]))
+    .chain([0, line.len()])
+    .collect();
+  cuts.sort_unstable();
+  cuts.dedup();
+
+  let spans = cuts
+    .windows(2)
+    .map(|cut| {
+      let style = if matches
+        .iter()
+        .any(|(start, end)| *start <= cut[0] && cut[0] < *end)
+      {
+        highlight
+      } else {
+        segments
+          .iter()
+          .rev()
+          .find(|(start, _)| *start <= cut[0])
+          .map_or_else(Style::default, |(_, style)| *style)
+      };
+      Span::styled(&line[cut[0]..cut[1]], style)
+    })
+    .collect::<Vec<_>>();
+  Spans::from(spans)
+}
+
 // Utility methods
 
+/// headers of the columns of a resource table, before hiding any
+pub fn table_headers(block: ActiveBlock) -> Vec<&'static str> {
+  match block {
+    ActiveBlock::Pods => vec![
+      "Namespace",
+      "Name",
+      "Ready",
+      "Status",
+      "Restarts",
+      "Disk",
+      "Node",
+      "IP",
+      "Age",
+    ],
+    ActiveBlock::Containers => vec![
+      "Name",
+      "Image",
+      "Init",
+      "Ready",
+      "State",
+      "Restarts",
+      "Probes(L/R)",
+      "Ports",
+      "Age",
+    ],
+    ActiveBlock::Nodes => vec![
+      "Name", "Status", "Roles", "Version", PODS_TITLE, "CPU", "Mem", "Disk", "CPU %", "Mem %",
+      "Disk %", "CPU/A", "Mem/A", "Disk/A", "Age",
+    ],
+    ActiveBlock::Services => vec![
+      "Namespace",
+      "Name",
+      "Type",
+      "Cluster IP",
+      "External IP",
+      "Ports",
+      "Age",
+    ],
+    ActiveBlock::ConfigMaps => vec!["Namespace", "Name", "Data", "Age"],
+    ActiveBlock::StatefulSets => vec!["Namespace", "Name", "Ready", "Service", "Age"],
+    ActiveBlock::ReplicaSets => vec!["Namespace", "Name", "Desired", "Current", "Ready", "Age"],
+    ActiveBlock::Deployments => vec![
+      "Namespace",
+      "Name",
+      "Ready",
+      "Up-to-date",
+      "Available",
+      "Age",
+    ],
+    ActiveBlock::Jobs => vec!["Namespace", "Name", "Completions", "Duration", "Age"],
+    ActiveBlock::DaemonSets => vec![
+      "Namespace",
+      "Name",
+      "Desired",
+      "Current",
+      "Ready",
+      "Up-to-date",
+      "Available",
+      "Age",
+    ],
+    ActiveBlock::CronJobs => vec![
+      "Namespace",
+      "Name",
+      "Schedule",
+      "Last Scheduled",
+      "Suspend",
+      "Active",
+      "Age",
+    ],
+    ActiveBlock::Secrets => vec!["Namespace", "Name", "Type", "Data", "Age"],
+    ActiveBlock::RplCtrl => vec![
+      "Namespace",
+      "Name",
+      "Desired",
+      "Current",
+      "Ready",
+      "Containers",
+      "Images",
+      "Selector",
+      "Age",
+    ],
+    ActiveBlock::StorageClasses => vec![
+      "Name",
+      "Provisioner",
+      "Reclaim Policy",
+      "Volume Binding Mode",
+      "Allow Volume Expansion",
+      "Age",
+    ],
+    ActiveBlock::Roles => vec!["Namespace", "Name", "Age"],
+    ActiveBlock::RoleBindings => vec!["Namespace", "Name", "Role", "Age"],
+    ActiveBlock::ClusterRoles => vec!["Name", "Age"],
+    ActiveBlock::ClusterRoleBinding => vec!["Name", "Role", "Age"],
+    ActiveBlock::HelmReleases => vec![
+      "Namespace",
+      "Name",
+      "Chart",
+      "App Version",
+      "Revision",
+      "Status",
+      "Age",
+    ],
+    ActiveBlock::HelmHistory => vec![
+      "Revision",
+      "Status",
+      "Chart",
+      "App Version",
+      "Description",
+      "Age",
+    ],
+    _ => vec![],
+  }
+}
+
 struct ResourceTableProps<'a, T> {
   title: String,
   inline_help: String,
   resource: &'a mut StatefulTable<T>,
   table_headers: Vec<&'a str>,
-  column_widths: Vec<Constraint>,
 }
 
 /// Draw a kubernetes resource overview tab
-fn draw_resource_block<'a, B, T, F>(
+fn draw_resource_block<'a, B, T>(
   f: &mut Frame<'_, B>,
   area: Rect,
   table_props: ResourceTableProps<'a, T>,
-  row_cell_mapper: F,
   light_theme: bool,
   is_loading: bool,
 ) where
   B: Backend,
-  F: Fn(&T) -> Row<'a>,
+  T: TableRow,
 {
-  let title = title_with_dual_style(table_props.title, table_props.inline_help, light_theme);
+  let marked = table_props.resource.marked.len();
+  let mut title = table_props.title;
+  if marked > 0 {
+    title = format!("{}[{} marked] ", title, marked);
+  }
+  let filter = table_props.resource.filter();
+  if !filter.is_empty() {
+    title = format!("{}[/{}] ", title, filter);
+  }
+  if let Some(position) = table_props.resource.position() {
+    title = format!("{}({}) ", title, position);
+  }
+  let title = title_with_dual_style(title, table_props.inline_help, light_theme);
   let block = layout_block_top_border(title);
 
+  table_props.resource.set_columns(&table_props.table_headers);
   if !table_props.resource.items.is_empty() {
-    let rows = table_props
+    table_props
       .resource
-      .items
+      .set_view(table_rows_area(block.inner(area)));
+    let columns = table_props.resource.shown_columns();
+    let mut headers = sorted_header_cells(&columns, table_props.resource.sort.as_ref());
+    if table_props.resource.is_column_scrolled() {
+      headers[0] = format!("← {}", headers[0]);
+    }
+    let columns = columns.into_iter().map(str::to_owned).collect::<Vec<_>>();
+    table_props.resource.update_row_cache(&columns);
+    let resource = &table_props.resource;
+    let lengths: Vec<usize> = headers
+      .iter()
+      .zip(resource.cached_widths())
+      .map(|(h, width)| h.chars().count().max(*width))
+      .collect();
+    // the highlight symbol, the marker column and the spacing between columns take room too
+    let reserved = HIGHLIGHT.chars().count() + if marked > 0 { 2 } else { 0 } + lengths.len();
+    let mut widths = content_column_widths(
+      &lengths,
+      usize::from(block.inner(area).width).saturating_sub(reserved),
+    );
+    // marked rows get a marker in an extra first column, shown only while something is marked
+    if marked > 0 {
+      headers.insert(0, String::new());
+      widths.insert(0, Constraint::Length(1));
+    }
+    // only the rows in view are drawn, long tables take as long to draw as short ones
+    let resource = &table_props.resource;
+    let in_view = resource.rows_in_view();
+    let rows = resource.items[in_view.clone()]
       .iter()
-      //   .map(|c| { Row::new(row_cell_mapper(c)) }.style(style_primary()));
-      .map(row_cell_mapper);
+      .zip(&resource.cached_rows()[in_view.clone()])
+      .map(|(c, row)| {
+        let marker =
+          (marked > 0).then(|| Cell::from(if resource.is_marked(c) { MARKER } else { "" }));
+        Row::new(
+          marker
+            .into_iter()
+            .chain(row.cells.iter().map(|cell| Cell::from(cell.as_str())))
+            .collect::<Vec<_>>(),
+        )
+        .style(health_style(resource.health(c), light_theme))
+      });
 
     let table = Table::new(rows)
-      .header(table_header_style(table_props.table_headers, light_theme))
+      .header(table_header_style(
+        headers.iter().map(String::as_str).collect(),
+        light_theme,
+      ))
       .block(block)
-      .highlight_style(style_highlight())
+      .highlight_style(style_highlight(light_theme))
       .highlight_symbol(HIGHLIGHT)
-      .widths(&table_props.column_widths);
-
-    f.render_stateful_widget(table, area, &mut table_props.resource.state);
+      .widths(&widths);
+
+    let mut state = TableState::default();
+    state.select(
+      resource
+        .state
+        .selected()
+        .and_then(|i| i.checked_sub(in_view.start)),
+    );
+    f.render_stateful_widget(table, area, &mut state);
   } else {
     loading(f, block, area, is_loading, light_theme);
   }
 }
 
-fn get_resource_row_style(status: &str, ready: (i32, i32), light: bool) -> Style {
-  if status == "Running" && ready.0 == ready.1 {
-    style_primary(light)
-  } else if status == "Completed" {
-    style_success(light)
-  } else if [
-    "ContainerCreating",
-    "PodInitializing",
-    "Pending",
-    "Initialized",
-  ]
-  .contains(&status)
-  {
-    style_secondary(light)
-  } else {
-    style_failure(light)
+/// column widths fitting the widest value of each column. When they don't fit, the widest
+/// columns are narrowed to the same width, but no column is narrowed below `MIN_COLUMN_WIDTH`
+fn content_column_widths(lengths: &[usize], available: usize) -> Vec<Constraint> {
+  let total = |cap: usize| -> usize { lengths.iter().map(|l| (*l).min(cap)).sum() };
+  let widest = lengths.iter().copied().max().unwrap_or(0);
+  let mut cap = widest;
+  if total(widest) > available {
+    // the widest cap that fits
+    let (mut low, mut high) = (MIN_COLUMN_WIDTH, widest);
+    while low < high {
+      let mid = low + (high - low).div_ceil(2);
+      if total(mid) <= available {
+        low = mid;
+      } else {
+        high = mid - 1;
+      }
+    }
+    cap = low;
+  }
+  lengths
+    .iter()
+    .map(|l| Constraint::Length((*l).min(cap) as u16))
+    .collect()
+}
+
+/// rows are colored by the health of their resource so problems stand out
+pub(super) fn health_style(health: RowHealth, light: bool) -> Style {
+  match health {
+    RowHealth::Healthy => style_primary(light),
+    RowHealth::Pending => style_warning(light),
+    RowHealth::Failed => style_failure(light),
+    RowHealth::Done => style_primary(light).add_modifier(Modifier::DIM),
   }
 }
 
@@ -1348,9 +1527,10 @@ fn title_with_ns(title: &str, ns: &str, length: usize) -> String {
   format!("{} (ns: {}) [{}]", title, ns, length)
 }
 
-fn get_describe_active<'a>(block: ActiveBlock) -> &'a str {
+fn get_describe_active<'a>(app: &App, block: ActiveBlock) -> &'a str {
   match block {
     ActiveBlock::Describe => DESCRIBE_ACTIVE,
+    _ if app.manifest_format == ManifestFormat::Json => JSON_ACTIVE,
     _ => YAML_ACTIVE,
   }
 }
@@ -1365,6 +1545,40 @@ mod tests {
     ui::utils::{COLOR_CYAN, COLOR_RED, COLOR_WHITE, COLOR_YELLOW},
   };
 
+  #[test]
+  fn test_highlight_matches() {
+    let style = Style::default();
+    let key = Style::default().add_modifier(Modifier::ITALIC);
+    let highlight = Style::default().add_modifier(Modifier::BOLD);
+    assert_eq!(
+      highlight_matches("Image: Web:1 (web)", &[(0, style)], "web", highlight),
+      Spans::from(vec![
+        Span::styled("Image: ", style),
+        Span::styled("Web", highlight),
+        Span::styled(":1 (", style),
+        Span::styled("web", highlight),
+        Span::styled(")", style),
+      ])
+    );
+    // matches are highlighted across styled segments
+    assert_eq!(
+      highlight_matches("name: api", &[(0, key), (4, style)], "e: a", highlight),
+      Spans::from(vec![
+        Span::styled("nam", key),
+        Span::styled("e", highlight),
+        Span::styled(": a", highlight),
+        Span::styled("pi", style),
+      ])
+    );
+    assert_eq!(
+      highlight_matches("name: api", &[(0, key), (4, style)], "", highlight),
+      Spans::from(vec![
+        Span::styled("name", key),
+        Span::styled(": api", style)
+      ])
+    );
+  }
+
   #[test]
   fn test_draw_resource_tabs_block() {
     let backend = TestBackend::new(100, 7);
@@ -1390,8 +1604,8 @@ mod tests {
         "│ Pods <1> │ Services <2> │ Nodes <3> │ ConfigMaps <4> │ StatefulSets <5> │ ReplicaSets <6> │ Deplo│",
         "│                                                                                                  │",
         "│ Pods (ns: all) [1] | Containers <enter> | describe <d> | yaml <y> ───────────────────────────────│",
-        "│   Namespace                Name                               Ready     Status    Restarts  A    │",
-        "│=> pod namespace test       pod name test                      0/2       Failed    0         6    │",
+        "│   Namespace          Name          Ready Status Restarts Disk Age                                │",
+        "│=> pod namespace test pod name test 0/2   Failed 0             6h52m                              │",
         "└──────────────────────────────────────────────────────────────────────────────────────────────────┘",
       ]);
     // set row styles
@@ -1512,6 +1726,17 @@ mod tests {
       pub data: i32,
       pub age: String,
     }
+    impl TableRow for RenderTest {
+      fn column_value(&self, column: &str) -> String {
+        match column {
+          "Namespace" => self.namespace.to_owned(),
+          "Name" => self.name.to_owned(),
+          "Data" => self.data.to_string(),
+          "Age" => self.age.to_owned(),
+          _ => String::default(),
+        }
+      }
+    }
     terminal
       .draw(|f| {
         let size = f.size();
@@ -1544,21 +1769,6 @@ mod tests {
             inline_help: "-> yaml <y>".into(),
             resource: &mut resource,
             table_headers: vec!["Namespace", "Name", "Data", "Age"],
-            column_widths: vec![
-              Constraint::Percentage(30),
-              Constraint::Percentage(40),
-              Constraint::Percentage(15),
-              Constraint::Percentage(15),
-            ],
-          },
-          |c| {
-            Row::new(vec![
-              Cell::from(c.namespace.to_owned()),
-              Cell::from(c.name.to_owned()),
-              Cell::from(c.data.to_string()),
-              Cell::from(c.age.to_owned()),
-            ])
-            .style(style_primary(false))
           },
           false,
           false,
@@ -1568,10 +1778,10 @@ mod tests {
 
     let mut expected = Buffer::with_lines(vec![
         "Test-> yaml <y>─────────────────────────────────────────────────────────────────────────────────────",
-        "   Namespace                      Name                                     Data            Age      ",
-        "=> Test ns                        Test 1                                   5               65h3m    ",
-        "   Test ns                        Test long name that should be truncated  3               65h3m    ",
-        "   Test ns long value check that  test_long_name_that_should_be_truncated_ 6               65h3m    ",
+        "   Namespace                                  Name                                       Data Age   ",
+        "=> Test ns                                    Test 1                                     5    65h3m ",
+        "   Test ns                                    Test long name that should be truncated fr 3    65h3m ",
+        "   Test ns long value check that should be tr test_long_name_that_should_be_truncated_fr 6    65h3m ",
         "                                                                                                    ",
       ]);
     // set row styles
@@ -1622,6 +1832,49 @@ mod tests {
     terminal.backend().assert_buffer(&expected);
   }
 
+  #[test]
+  fn test_content_column_widths() {
+    let lengths = [20, 6, 3, 40];
+    assert_eq!(
+      content_column_widths(&lengths, 100),
+      vec![
+        Constraint::Length(20),
+        Constraint::Length(6),
+        Constraint::Length(3),
+        Constraint::Length(40),
+      ]
+    );
+    // the widest columns are narrowed first
+    assert_eq!(
+      content_column_widths(&lengths, 50),
+      vec![
+        Constraint::Length(20),
+        Constraint::Length(6),
+        Constraint::Length(3),
+        Constraint::Length(21),
+      ]
+    );
+    assert_eq!(
+      content_column_widths(&lengths, 30),
+      vec![
+        Constraint::Length(10),
+        Constraint::Length(6),
+        Constraint::Length(3),
+        Constraint::Length(10),
+      ]
+    );
+    // but not below the minimum width
+    assert_eq!(
+      content_column_widths(&lengths, 10),
+      vec![
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(3),
+        Constraint::Length(8),
+      ]
+    );
+  }
+
   #[test]
   fn test_get_resource_title() {
     let app = App::default();
@@ -0,0 +1,155 @@
+use tui::{
+  backend::Backend,
+  layout::Rect,
+  text::{Span, Spans},
+  widgets::Paragraph,
+  Frame,
+};
+
+use super::utils::{style_help, style_secondary};
+use crate::{
+  app::{
+    key_binding::{key_bindings, KeyBinding},
+    ActiveBlock, App, RouteId,
+  },
+  event::Key,
+};
+
+/// one line with the most relevant keys for the focused block
+pub fn draw_status_bar<B: Backend>(f: &mut Frame<'_, B>, app: &App, area: Rect) {
+  let spans = key_hints(app)
+    .into_iter()
+    .flat_map(|(key, label)| {
+      [
+        Span::styled(format!(" {}", key), style_secondary(app.light_theme)),
+        Span::styled(format!(" {} ", label), style_help(app.light_theme)),
+      ]
+    })
+    .collect::<Vec<_>>();
+  f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+/// keys with a short label for the focused block, followed by the ones that work everywhere
+fn key_hints(app: &App) -> Vec<(Key, &'static str)> {
+  let kb = key_bindings();
+  let route = app.get_current_route();
+  let mut hints: Vec<(&KeyBinding, &'static str)> = match route.id {
+    RouteId::Home => match route.active_block {
+      ActiveBlock::Namespaces => vec![
+        (&kb.submit, "select"),
+        (&kb.select_all_namespace, "all"),
+        (&kb.pin_namespace, "pin"),
+        (&kb.esc, "back"),
+      ],
+      ActiveBlock::Logs => vec![
+        (&kb.log_auto_scroll, "auto-scroll"),
+        (&kb.copy_to_clipboard, "copy"),
+        (&kb.esc, "back"),
+      ],
+      ActiveBlock::Yaml => vec![
+        (&kb.search, "search"),
+        (&kb.toggle_json, "yaml/json"),
+        (&kb.copy_to_clipboard, "copy"),
+        (&kb.export_yaml, "save"),
+        (&kb.esc, "back"),
+      ],
+      ActiveBlock::Describe => vec![
+        (&kb.search, "search"),
+        (&kb.next_match, "next match"),
+        (&kb.copy_to_clipboard, "copy"),
+        (&kb.export_yaml, "save"),
+        (&kb.scroll_right, "scroll right"),
+        (&kb.esc, "back"),
+      ],
+      ActiveBlock::Columns => vec![(&kb.submit, "show/hide"), (&kb.esc, "back")],
+      ActiveBlock::More => vec![(&kb.submit, "select"), (&kb.esc, "back")],
+      ActiveBlock::HelmHistory => vec![
+        (&kb.helm_values, "values"),
+        (&kb.mark_row, "mark"),
+        (&kb.diff_last_applied, "diff"),
+        (&kb.helm_rollback, "rollback"),
+        (&kb.search, "filter"),
+        (&kb.cycle_sort, "sort"),
+        (&kb.esc, "back"),
+      ],
+      block => {
+        let mut hints = match block {
+          ActiveBlock::Pods => vec![(&kb.submit, "containers")],
+          ActiveBlock::Containers => vec![(&kb.submit, "logs"), (&kb.attach, "attach")],
+          ActiveBlock::Secrets => vec![(&kb.decode_secret, "decode")],
+          ActiveBlock::HelmReleases => {
+            vec![
+              (&kb.submit, "history"),
+              (&kb.helm_values, "values"),
+              (&kb.helm_uninstall, "uninstall"),
+            ]
+          }
+          ActiveBlock::Services => vec![(&kb.open_url, "open")],
+          _ => vec![],
+        };
+        hints.extend([
+          (&kb.describe_resource, "describe"),
+          (&kb.resource_yaml, "yaml"),
+          (&kb.search, "filter"),
+          (&kb.cycle_sort, "sort"),
+          (&kb.pick_columns, "columns"),
+          (&kb.mark_row, "mark"),
+          (&kb.copy_name, "copy name"),
+          (&kb.jump_to_namespace, "namespaces"),
+        ]);
+        hints
+      }
+    },
+    RouteId::Contexts => vec![(&kb.submit, "switch context")],
+    RouteId::Utilization => vec![(&kb.cycle_group_by, "group by")],
+    RouteId::HelpMenu => vec![(&kb.search, "search"), (&kb.esc, "back")],
+    RouteId::MetricsHistory => vec![(&kb.esc, "back")],
+    RouteId::Fleet => vec![
+      (&kb.submit, "switch context"),
+      (&kb.fleet_events, "warnings"),
+    ],
+  };
+  hints.extend([
+    (&kb.command_palette, "command"),
+    (&kb.help, "help"),
+    (&kb.quit, "quit"),
+  ]);
+  hints
+    .into_iter()
+    .filter(|(binding, _)| !(app.read_only && kb.is_mutating(binding)))
+    .map(|(binding, label)| (binding.key, label))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_key_hints() {
+    let mut app = App::default();
+    app.route_home();
+    let hints = key_hints(&app);
+    assert_eq!(hints[0], (Key::Enter, "containers"));
+    assert_eq!(hints[1], (Key::Char('d'), "describe"));
+    assert_eq!(hints.last(), Some(&(Key::Ctrl('c'), "quit")));
+
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Logs);
+    assert_eq!(
+      key_hints(&app),
+      vec![
+        (Key::Char('s'), "auto-scroll"),
+        (Key::Char('c'), "copy"),
+        (Key::Esc, "back"),
+        (Key::Char(':'), "command"),
+        (Key::Char('?'), "help"),
+        (Key::Ctrl('c'), "quit"),
+      ]
+    );
+
+    app.push_navigation_stack(RouteId::Home, ActiveBlock::Containers);
+    assert!(key_hints(&app).contains(&(Key::Char('I'), "attach")));
+    app.read_only = true;
+    assert!(!key_hints(&app).contains(&(Key::Char('I'), "attach")));
+  }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::OnceLock};
 
 use tui::{
   backend::Backend,
@@ -9,6 +9,11 @@ use tui::{
   widgets::{Block, Borders, Paragraph, Row},
   Frame,
 };
+
+use crate::{
+  app::models::TableSort,
+  config::{Palette, ThemeColors, ThemeConfig},
+};
 // Utils
 
 // default colors
@@ -27,6 +32,13 @@ pub const COLOR_BLUE: Color = Color::Rgb(0, 82, 163);
 pub const COLOR_GREEN_DARK: Color = Color::Rgb(20, 97, 73);
 pub const COLOR_RED_DARK: Color = Color::Rgb(173, 25, 20);
 pub const COLOR_ORANGE_DARK: Color = Color::Rgb(184, 49, 15);
+// color blind palette colors, from the Okabe-Ito palette
+pub const COLOR_SKY_BLUE: Color = Color::Rgb(86, 180, 233);
+pub const COLOR_VERMILLION: Color = Color::Rgb(230, 110, 40);
+pub const COLOR_AMBER: Color = Color::Rgb(240, 228, 66);
+pub const COLOR_BLUE_DARK: Color = Color::Rgb(0, 114, 178);
+pub const COLOR_VERMILLION_DARK: Color = Color::Rgb(213, 94, 0);
+pub const COLOR_PURPLE_DARK: Color = Color::Rgb(150, 70, 120);
 
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Styles {
@@ -39,9 +51,106 @@ pub enum Styles {
   Secondary,
   Help,
   Background,
+  Highlight,
+  Gauge,
+}
+
+/// theme colors from the config file, set once at startup
+static CUSTOM_THEME: OnceLock<ThemeConfig> = OnceLock::new();
+
+pub fn set_custom_theme(theme: ThemeConfig) {
+  let _ = CUSTOM_THEME.set(theme);
 }
 
 pub fn theme_styles(light: bool) -> BTreeMap<Styles, Style> {
+  let mut styles = default_theme_styles(light);
+  if let Some(theme) = CUSTOM_THEME.get() {
+    apply_palette(&mut styles, theme.palette, light);
+    if theme.palette != Palette::Monochrome {
+      apply_theme_colors(&mut styles, if light { &theme.light } else { &theme.dark });
+    }
+  }
+  styles
+}
+
+/// swap the colors that can't be told apart with red-green color blindness, or drop the colors
+/// for terminals without them
+fn apply_palette(styles: &mut BTreeMap<Styles, Style>, palette: Palette, light: bool) {
+  let (success, failure, warning) = match palette {
+    Palette::Default => return,
+    Palette::Monochrome => {
+      *styles = monochrome_styles();
+      return;
+    }
+    Palette::ColorBlind if light => (COLOR_BLUE_DARK, COLOR_VERMILLION_DARK, COLOR_PURPLE_DARK),
+    Palette::ColorBlind => (COLOR_SKY_BLUE, COLOR_VERMILLION, COLOR_AMBER),
+  };
+  for (style, color) in [
+    (Styles::Logo, success),
+    (Styles::Success, success),
+    (Styles::Failure, failure),
+    (Styles::Warning, warning),
+  ] {
+    styles.insert(style, Style::default().fg(color));
+  }
+}
+
+/// text attributes standing in for the colors
+fn monochrome_styles() -> BTreeMap<Styles, Style> {
+  let plain = Style::default();
+  let bold = plain.add_modifier(Modifier::BOLD);
+  BTreeMap::from([
+    (Styles::Default, plain),
+    (Styles::Logo, bold),
+    (Styles::Failure, bold.add_modifier(Modifier::REVERSED)),
+    (Styles::Warning, bold.add_modifier(Modifier::UNDERLINED)),
+    (Styles::Success, plain),
+    (Styles::Primary, bold),
+    (Styles::Secondary, plain.add_modifier(Modifier::UNDERLINED)),
+    (Styles::Help, plain),
+    (Styles::Background, plain),
+    (Styles::Highlight, plain.add_modifier(Modifier::REVERSED)),
+    (Styles::Gauge, plain),
+  ])
+}
+
+fn apply_theme_colors(styles: &mut BTreeMap<Styles, Style>, colors: &ThemeColors) {
+  let fg_colors = [
+    (Styles::Default, colors.default),
+    (Styles::Logo, colors.logo),
+    (Styles::Failure, colors.failure),
+    (Styles::Warning, colors.warning),
+    (Styles::Success, colors.success),
+    (Styles::Primary, colors.primary),
+    (Styles::Secondary, colors.secondary),
+    (Styles::Help, colors.help),
+    (Styles::Background, colors.default),
+    (Styles::Gauge, colors.gauge),
+  ];
+  for (style, color) in fg_colors {
+    if let Some(color) = color {
+      styles.entry(style).and_modify(|s| *s = s.fg(color.0));
+    }
+  }
+  if let Some(color) = colors.background {
+    styles
+      .entry(Styles::Background)
+      .and_modify(|s| *s = s.bg(color.0));
+  }
+  // a colored highlight replaces the reversed colors of the default one
+  if colors.highlight_fg.is_some() || colors.highlight_bg.is_some() {
+    let mut highlight = Style::default();
+    if let Some(color) = colors.highlight_fg {
+      highlight = highlight.fg(color.0);
+    }
+    if let Some(color) = colors.highlight_bg {
+      highlight = highlight.bg(color.0);
+    }
+    styles.insert(Styles::Highlight, highlight);
+  }
+}
+
+fn default_theme_styles(light: bool) -> BTreeMap<Styles, Style> {
   if light {
     BTreeMap::from([
       (Styles::Default, Style::default().fg(COLOR_GRAY)),
@@ -56,6 +165,11 @@ pub fn theme_styles(light: bool) -> BTreeMap<Styles, Style> {
         Styles::Background,
         Style::default().bg(COLOR_WHITE).fg(COLOR_GRAY),
       ),
+      (
+        Styles::Highlight,
+        Style::default().add_modifier(Modifier::REVERSED),
+      ),
+      (Styles::Gauge, Style::default().fg(COLOR_BLUE)),
     ])
   } else {
     BTreeMap::from([
@@ -71,6 +185,11 @@ pub fn theme_styles(light: bool) -> BTreeMap<Styles, Style> {
         Styles::Background,
         Style::default().bg(COLOR_TEAL).fg(COLOR_WHITE),
       ),
+      (
+        Styles::Highlight,
+        Style::default().add_modifier(Modifier::REVERSED),
+      ),
+      (Styles::Gauge, Style::default().fg(COLOR_CYAN)),
     ])
   }
 }
@@ -122,8 +241,12 @@ pub fn style_main_background(light: bool) -> Style {
   *theme_styles(light).get(&Styles::Background).unwrap()
 }
 
-pub fn style_highlight() -> Style {
-  Style::default().add_modifier(Modifier::REVERSED)
+pub fn style_highlight(light: bool) -> Style {
+  *theme_styles(light).get(&Styles::Highlight).unwrap()
+}
+
+pub fn style_gauge(light: bool) -> Style {
+  *theme_styles(light).get(&Styles::Gauge).unwrap()
 }
 
 pub fn get_gauge_style(enhanced_graphics: bool) -> symbols::line::Set {
@@ -138,6 +261,44 @@ pub fn table_header_style(cells: Vec<&str>, light: bool) -> Row<'_> {
   Row::new(cells).style(style_default(light)).bottom_margin(0)
 }
 
+/// header cells with an arrow next to the column the table is sorted by
+pub fn sorted_header_cells(cells: &[&str], sort: Option<&TableSort>) -> Vec<String> {
+  cells
+    .iter()
+    .map(|cell| match sort {
+      Some(sort) if sort.column == *cell => {
+        format!("{} {}", cell, if sort.descending { "↓" } else { "↑" })
+      }
+      _ => cell.to_string(),
+    })
+    .collect()
+}
+
+/// area of the rows of a table drawn in `inner`, the block's inner area, below the header row
+pub fn table_rows_area(inner: Rect) -> Rect {
+  Rect {
+    y: inner.y.saturating_add(1),
+    height: inner.height.saturating_sub(1),
+    ..inner
+  }
+}
+
+/// screen areas of tab titles the way the `Tabs` widget lays them out in `inner`,
+/// each title is padded by a space on both sides and followed by a one column divider
+pub fn tab_title_areas(titles: &[Spans<'_>], inner: Rect) -> Vec<Rect> {
+  let mut x = inner.left();
+  titles
+    .iter()
+    .map(|title| {
+      x = x.saturating_add(1).min(inner.right());
+      let width = (title.width() as u16).min(inner.right() - x);
+      let area = Rect::new(x, inner.top(), width, inner.height.min(1));
+      x = x.saturating_add(width).saturating_add(2);
+      area
+    })
+    .collect()
+}
+
 pub fn horizontal_chunks(constraints: Vec<Constraint>, size: Rect) -> Vec<Rect> {
   Layout::default()
     .constraints(<Vec<Constraint> as AsRef<[Constraint]>>::as_ref(
@@ -272,3 +433,62 @@ pub fn loading<B: Backend>(
     f.render_widget(block, area)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::ThemeColor;
+
+  #[test]
+  fn test_apply_theme_colors() {
+    let mut styles = default_theme_styles(false);
+    apply_theme_colors(
+      &mut styles,
+      &ThemeColors {
+        primary: Some(ThemeColor(Color::Blue)),
+        background: Some(ThemeColor(Color::Black)),
+        highlight_bg: Some(ThemeColor(Color::DarkGray)),
+        ..ThemeColors::default()
+      },
+    );
+    assert_eq!(styles[&Styles::Primary], Style::default().fg(Color::Blue));
+    assert_eq!(
+      styles[&Styles::Background],
+      Style::default().bg(Color::Black).fg(COLOR_WHITE)
+    );
+    assert_eq!(
+      styles[&Styles::Highlight],
+      Style::default().bg(Color::DarkGray)
+    );
+    // everything else keeps the defaults
+    assert_eq!(styles[&Styles::Failure], Style::default().fg(COLOR_RED));
+    assert_eq!(styles[&Styles::Gauge], Style::default().fg(COLOR_CYAN));
+  }
+
+  #[test]
+  fn test_apply_palette() {
+    let mut styles = default_theme_styles(true);
+    apply_palette(&mut styles, Palette::Default, true);
+    assert_eq!(styles, default_theme_styles(true));
+
+    apply_palette(&mut styles, Palette::ColorBlind, true);
+    assert_eq!(
+      styles[&Styles::Success],
+      Style::default().fg(COLOR_BLUE_DARK)
+    );
+    assert_eq!(
+      styles[&Styles::Failure],
+      Style::default().fg(COLOR_VERMILLION_DARK)
+    );
+    assert_eq!(styles[&Styles::Primary], Style::default().fg(COLOR_BLUE));
+
+    apply_palette(&mut styles, Palette::Monochrome, true);
+    assert!(styles
+      .values()
+      .all(|style| style.fg.is_none() && style.bg.is_none()));
+    assert_eq!(
+      styles[&Styles::Highlight],
+      Style::default().add_modifier(Modifier::REVERSED)
+    );
+  }
+}
@@ -0,0 +1,151 @@
+use tui::style::Style;
+
+use super::utils::{style_help, style_primary, style_secondary, style_success, style_warning};
+
+/// byte offsets where a style starts in a line of YAML or pretty printed JSON, covering the whole
+/// line. Each line is looked at on its own, so the contents of block scalars can get highlighted
+/// like mappings
+pub fn yaml_segments(line: &str, light: bool) -> Vec<(usize, Style)> {
+  let mut segments = vec![(0, style_primary(light))];
+  let mut start = line.len() - line.trim_start().len();
+  while line[start..].starts_with("- ") {
+    start += 2;
+  }
+  let rest = &line[start..];
+  if rest.starts_with('#') {
+    segments.push((start, style_help(light)));
+    return segments;
+  }
+
+  let key_end = if let Some(quoted) = rest.strip_prefix('"') {
+    // quoted keys like the ones of JSON
+    quoted
+      .find('"')
+      .map(|i| i + 2)
+      .filter(|&end| rest[end..].starts_with(':'))
+  } else if rest.starts_with('\'') {
+    None
+  } else {
+    rest
+      .find(": ")
+      .or_else(|| rest.strip_suffix(':').map(str::len))
+  };
+  let value_start = match key_end {
+    Some(end) => {
+      segments.push((start, style_secondary(light)));
+      segments.push((start + end, style_primary(light)));
+      start + end + 1
+    }
+    None => start,
+  };
+
+  let value = line[value_start..].trim_start();
+  if !value.is_empty() {
+    segments.push((line.len() - value.len(), value_style(value, light)));
+  }
+  segments
+}
+
+fn value_style(value: &str, light: bool) -> Style {
+  // JSON values end with a comma when more follow
+  let value = value.strip_suffix(',').unwrap_or(value);
+  match value {
+    "true" | "false" | "null" | "~" => style_warning(light),
+    _ if value.parse::<f64>().is_ok() => style_warning(light),
+    _ if value.starts_with('#') => style_help(light),
+    // block scalar indicators and empty flow collections
+    "|" | "|-" | "|+" | ">" | ">-" | ">+" | "[]" | "{}" | "[" | "{" | "]" | "}" => {
+      style_primary(light)
+    }
+    _ => style_success(light),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn styles(line: &str) -> Vec<(&str, Style)> {
+    let segments = yaml_segments(line, false);
+    segments
+      .iter()
+      .enumerate()
+      .map(|(i, (start, style))| {
+        let end = segments.get(i + 1).map_or(line.len(), |s| s.0);
+        (&line[*start..end], *style)
+      })
+      .filter(|(txt, _)| !txt.is_empty())
+      .collect()
+  }
+
+  #[test]
+  fn test_yaml_segments() {
+    let light = false;
+    assert_eq!(
+      styles("  name: web"),
+      vec![
+        ("  ", style_primary(light)),
+        ("name", style_secondary(light)),
+        (": ", style_primary(light)),
+        ("web", style_success(light)),
+      ]
+    );
+    assert_eq!(
+      styles("- containerPort: 8080"),
+      vec![
+        ("- ", style_primary(light)),
+        ("containerPort", style_secondary(light)),
+        (": ", style_primary(light)),
+        ("8080", style_warning(light)),
+      ]
+    );
+    assert_eq!(
+      styles("metadata:"),
+      vec![
+        ("metadata", style_secondary(light)),
+        (":", style_primary(light)),
+      ]
+    );
+    assert_eq!(
+      styles("  - \"a: b\""),
+      vec![
+        ("  - ", style_primary(light)),
+        ("\"a: b\"", style_success(light)),
+      ]
+    );
+    assert_eq!(
+      styles("  # comment: here"),
+      vec![
+        ("  ", style_primary(light)),
+        ("# comment: here", style_help(light)),
+      ]
+    );
+    assert_eq!(
+      styles("  ready: true"),
+      vec![
+        ("  ", style_primary(light)),
+        ("ready", style_secondary(light)),
+        (": ", style_primary(light)),
+        ("true", style_warning(light)),
+      ]
+    );
+    assert_eq!(
+      styles("  \"replicas\": 3,"),
+      vec![
+        ("  ", style_primary(light)),
+        ("\"replicas\"", style_secondary(light)),
+        (": ", style_primary(light)),
+        ("3,", style_warning(light)),
+      ]
+    );
+    assert_eq!(
+      styles("  \"metadata\": {"),
+      vec![
+        ("  ", style_primary(light)),
+        ("\"metadata\"", style_secondary(light)),
+        (": ", style_primary(light)),
+        ("{", style_primary(light)),
+      ]
+    );
+  }
+}